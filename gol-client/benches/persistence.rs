@@ -0,0 +1,127 @@
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use gol_client::persistence::{
+    batch_deserializer_local::BatchDeserializerLocal, batch_serializer::BatchIndexedSerializer,
+    batch_serializer_local::BatchSerializerLocal, load_board::HistoryHeader,
+};
+use gol_core::{Board, DecayLifeLikeStrategy, GridPoint2D, IndexedDataOwned, StandardBoardFactory};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+const SHAPE: usize = 256;
+
+const GENERATIONS: usize = 200;
+
+/// Same as the histories saved by the client.
+const BATCH_SIZE: usize = 100;
+
+type History = Vec<Vec<IndexedDataOwned<GridPoint2D<i64>, u8>>>;
+
+fn serializer_benchmark(c: &mut Criterion) {
+    let history = gen_history();
+    for (unit, elements) in [
+        ("Cells", SHAPE * SHAPE * GENERATIONS),
+        ("Generations", GENERATIONS),
+    ] {
+        let mut group = c.benchmark_group(format!("Serialize Soup 256x256 ({})", unit));
+        group.sample_size(10);
+        group.throughput(Throughput::Elements(elements as u64));
+        group.bench_function("Batch", |b| {
+            b.iter_batched(
+                || history.clone(),
+                |history| {
+                    let mut serializer =
+                        BatchIndexedSerializer::new(BATCH_SIZE).with_header(header());
+                    let bytes: Vec<_> = history
+                        .into_iter()
+                        .filter_map(|states| serializer.push(states))
+                        .collect();
+                    assert!(serializer.remaining().is_none());
+                    bytes
+                },
+                BatchSize::LargeInput,
+            )
+        });
+        group.finish();
+    }
+}
+
+fn replay_benchmark(c: &mut Criterion) {
+    let dir = std::env::temp_dir().join(format!("gol_replay_bench_{}", std::process::id()));
+    let dir = String::from(dir.to_str().unwrap());
+    {
+        let serializer = BatchIndexedSerializer::new(BATCH_SIZE).with_header(header());
+        let mut serializer = BatchSerializerLocal::new(&dir, serializer);
+        for states in gen_history() {
+            serializer.push(states);
+        }
+    }
+
+    for (unit, elements) in [
+        ("Cells", SHAPE * SHAPE * GENERATIONS),
+        ("Generations", GENERATIONS),
+    ] {
+        let mut group = c.benchmark_group(format!("Replay Soup 256x256 ({})", unit));
+        group.sample_size(10);
+        group.throughput(Throughput::Elements(elements as u64));
+        group.bench_function("Sequential", |b| {
+            b.iter(|| {
+                let deserializer: BatchDeserializerLocal<
+                    HistoryHeader,
+                    Vec<IndexedDataOwned<GridPoint2D<i64>, u8>>,
+                > = BatchDeserializerLocal::new(&dir);
+                // Replays look up the states of every generation by index.
+                (0..GENERATIONS)
+                    .map(|i| {
+                        let (_, states) = deserializer.get(i).unwrap();
+                        let lookup: HashMap<GridPoint2D<i64>, u8> =
+                            states.1.par_iter().cloned().collect();
+                        lookup.len()
+                    })
+                    .sum::<usize>()
+            })
+        });
+        group.finish();
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+criterion_group!(benches, serializer_benchmark, replay_benchmark);
+criterion_main!(benches);
+
+fn header() -> HistoryHeader {
+    HistoryHeader::new(vec![SHAPE, SHAPE], 2, 8, 64)
+}
+
+/// Non-trivial states of each generation of a random Game of Life soup.
+fn gen_history() -> History {
+    let mut rng = StdRng::seed_from_u64(11);
+    let half = (SHAPE / 2) as i64;
+    let mut initial_states = HashMap::new();
+    for x in 0..SHAPE as i64 {
+        for y in 0..SHAPE as i64 {
+            if rng.gen_bool(0.3) {
+                initial_states.insert(GridPoint2D::new(x - half, y - half), 1u8);
+            }
+        }
+    }
+    let mut board = StandardBoardFactory::new_standard_2d_grid(
+        (SHAPE, SHAPE),
+        0u8,
+        1,
+        initial_states,
+        Box::new(DecayLifeLikeStrategy::gol()),
+        Vec::new(),
+        true,
+    );
+    (0..GENERATIONS)
+        .map(|_| {
+            board
+                .advance_one_generation()
+                .par_iter()
+                .filter(|(_, state)| *state != 0)
+                .cloned()
+                .collect()
+        })
+        .collect()
+}
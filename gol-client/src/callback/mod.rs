@@ -2,17 +2,16 @@ mod delay;
 mod pause;
 mod terminate;
 
-pub fn standard_control_callbacks<T, U, I>(
+pub fn standard_control_callbacks<T, U>(
     is_paused: bool,
     delay_interval: std::time::Duration,
 ) -> (
-    Vec<gol_core::BoardCallback<T, U, I>>,
+    Vec<gol_core::BoardCallback<T, U>>,
     gol_renderer::renderer::keyboard_control::KeyboardControl,
 )
 where
     T: Send + Sync + Clone,
     U: Send + Sync + Clone,
-    I: rayon::iter::ParallelIterator<Item = gol_core::IndexedDataOwned<U, T>>,
 {
     use delay::Delay;
     use gol_core::BoardCallback;
@@ -1,3 +1,4 @@
 mod callback;
 pub mod persistence;
 pub mod replay;
+pub mod search;
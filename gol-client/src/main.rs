@@ -1,38 +1,13 @@
-use clap::{App, Arg};
-use gol_client::persistence::load_board::CellularAutomatonConfig;
+use clap::{App, Arg, ArgMatches, SubCommand};
+use gol_client::persistence::load_board::{example_jsons, CellularAutomatonConfig};
 use rayon::prelude::*;
-use serde_json;
 use std::collections::HashMap;
 use std::fs;
 
 fn main() {
-    let mut jsons = vec![
-        include_str!("../examples/tetris.json"),
-        include_str!("../examples/glider.json"),
-        include_str!("../examples/glider_gun.json"),
-        include_str!("../examples/glider_eater.json"),
-        include_str!("../examples/glider_gun_with_eater.json"),
-        include_str!("../examples/and_gate_00.json"),
-        include_str!("../examples/and_gate_01.json"),
-        include_str!("../examples/and_gate_10.json"),
-        include_str!("../examples/and_gate_11.json"),
-        include_str!("../examples/random.json"),
-        include_str!("../examples/random_gol.json"),
-        include_str!("../examples/and_gate_4_neighbors.json"),
-        include_str!("../examples/star_wars.json"),
-        include_str!("../examples/star_wars_donut.json"),
-        include_str!("../examples/brians_brain.json"),
-        include_str!("../examples/bombers.json"),
-        include_str!("../examples/bombers_255.json"),
-        include_str!("../examples/sedimental.json"),
-    ];
-
-    #[cfg(feature = "ascii")]
-    jsons.push(include_str!("../examples/tetris_ascii.json"));
-
-    let configs: Vec<CellularAutomatonConfig> = jsons
+    let configs: Vec<CellularAutomatonConfig> = example_jsons()
         .par_iter()
-        .map(|ele| serde_json::from_str(ele).unwrap())
+        .map(|ele| CellularAutomatonConfig::from_json(ele))
         .collect();
 
     let title_to_config: HashMap<String, CellularAutomatonConfig> = configs
@@ -110,8 +85,246 @@ if it does exist it must be empty, otherwise the program will terminate with err
                 .help("Convert the 2D square grid to triangular grid, only supported for configurations with non-extended Moore neighbor rule.")
                 .takes_value(false),
         )
+        .arg(
+            Arg::with_name("workers")
+                .short("w")
+                .long("workers")
+                .value_name("COUNT")
+                .help("Split 2D grid boards into strips evolved by this many worker processes.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("worker")
+                .long("worker")
+                .value_name("ADDR")
+                .help("Run as a worker of the coordinator at this address, started by \"-w\".")
+                .hidden(true)
+                .takes_value(true),
+        )
+        .subcommand(
+            SubCommand::with_name("search")
+                .about("Search random soups for rare objects without rendering, resumes from the results file.")
+                .arg(
+                    Arg::with_name("rule")
+                        .long("rule")
+                        .value_name("RULE")
+                        .help("Two-state life-like rule in B/S notation.")
+                        .default_value("B3/S23"),
+                )
+                .arg(
+                    Arg::with_name("soups")
+                        .short("n")
+                        .long("soups")
+                        .value_name("COUNT")
+                        .help("Total number of soups to search, including the ones in the results file.")
+                        .default_value("10000"),
+                )
+                .arg(
+                    Arg::with_name("soup-size")
+                        .long("soup-size")
+                        .value_name("SIZE")
+                        .help("Width and height of each soup.")
+                        .default_value("16"),
+                )
+                .arg(
+                    Arg::with_name("alive-ratio")
+                        .long("alive-ratio")
+                        .value_name("RATIO")
+                        .help("Chance of each cell of a soup being alive.")
+                        .default_value("0.5"),
+                )
+                .arg(
+                    Arg::with_name("max-generations")
+                        .long("max-generations")
+                        .value_name("COUNT")
+                        .help("Generations before a soup is given up as unstable.")
+                        .default_value("20000"),
+                )
+                .arg(
+                    Arg::with_name("batch-size")
+                        .long("batch-size")
+                        .value_name("COUNT")
+                        .help("Soups searched between saves of the results file.")
+                        .default_value("1000"),
+                )
+                .arg(
+                    Arg::with_name("results")
+                        .short("o")
+                        .long("results")
+                        .value_name("FILE")
+                        .help("JSON file with the results, created if it does not exist.")
+                        .default_value("soup_search.json"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("methuselah")
+                .about("Search small seeds that live long before they settle, writes the best as configs.")
+                .arg(
+                    Arg::with_name("rule")
+                        .long("rule")
+                        .value_name("RULE")
+                        .help("Two-state life-like rule in B/S notation.")
+                        .default_value("B3/S23"),
+                )
+                .arg(
+                    Arg::with_name("width")
+                        .long("width")
+                        .value_name("WIDTH")
+                        .help("Width of the bounding box of seeds.")
+                        .default_value("5"),
+                )
+                .arg(
+                    Arg::with_name("height")
+                        .long("height")
+                        .value_name("HEIGHT")
+                        .help("Height of the bounding box of seeds, at most 64 cells in total.")
+                        .default_value("5"),
+                )
+                .arg(
+                    Arg::with_name("max-population")
+                        .long("max-population")
+                        .value_name("COUNT")
+                        .help("Largest number of alive cells of seeds.")
+                        .default_value("5"),
+                )
+                .arg(
+                    Arg::with_name("max-generations")
+                        .long("max-generations")
+                        .value_name("COUNT")
+                        .help("Generations before a seed is given up as unstable.")
+                        .default_value("20000"),
+                )
+                .arg(
+                    Arg::with_name("rank")
+                        .long("rank")
+                        .value_name("RANK")
+                        .help("What seeds are ranked by.")
+                        .possible_values(&["lifespan", "max-population", "final-population"])
+                        .default_value("lifespan"),
+                )
+                .arg(
+                    Arg::with_name("top")
+                        .long("top")
+                        .value_name("COUNT")
+                        .help("Number of best seeds written as configs.")
+                        .default_value("10"),
+                )
+                .arg(
+                    Arg::with_name("samples")
+                        .long("samples")
+                        .value_name("COUNT")
+                        .help("Number of random seeds searched, all seeds are searched if not specified.")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("seed")
+                        .long("seed")
+                        .value_name("SEED")
+                        .help("Seed of the random samples.")
+                        .default_value("0"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .short("o")
+                        .long("output")
+                        .value_name("DIR")
+                        .help("Directory the configs are written to.")
+                        .default_value("methuselahs"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("pattern")
+                .about("Search oscillators and spaceships in a small box, writes them as configs and RLE.")
+                .arg(
+                    Arg::with_name("rule")
+                        .long("rule")
+                        .value_name("RULE")
+                        .help("Two-state life-like rule in B/S notation.")
+                        .default_value("B3/S23"),
+                )
+                .arg(
+                    Arg::with_name("period")
+                        .short("p")
+                        .long("period")
+                        .value_name("PERIOD")
+                        .help("Generations until the pattern repeats.")
+                        .default_value("2"),
+                )
+                .arg(
+                    Arg::with_name("dx")
+                        .long("dx")
+                        .value_name("DX")
+                        .help("Cells the pattern moves along x every period, 0 for oscillators.")
+                        .allow_hyphen_values(true)
+                        .default_value("0"),
+                )
+                .arg(
+                    Arg::with_name("dy")
+                        .long("dy")
+                        .value_name("DY")
+                        .help("Cells the pattern moves along y every period, 0 for oscillators.")
+                        .allow_hyphen_values(true)
+                        .default_value("0"),
+                )
+                .arg(
+                    Arg::with_name("width")
+                        .long("width")
+                        .value_name("WIDTH")
+                        .help("Width of the box all phases of the pattern fit in.")
+                        .default_value("5"),
+                )
+                .arg(
+                    Arg::with_name("height")
+                        .long("height")
+                        .value_name("HEIGHT")
+                        .help("Height of the box all phases of the pattern fit in.")
+                        .default_value("5"),
+                )
+                .arg(
+                    Arg::with_name("max-results")
+                        .long("max-results")
+                        .value_name("COUNT")
+                        .help("Number of distinct patterns after which the search stops.")
+                        .default_value("16"),
+                )
+                .arg(
+                    Arg::with_name("time-limit")
+                        .long("time-limit")
+                        .value_name("SECONDS")
+                        .help("Seconds after which the search stops and writes what it found.")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .short("o")
+                        .long("output")
+                        .value_name("DIR")
+                        .help("Directory the configs and RLE files are written to.")
+                        .default_value("patterns"),
+                ),
+        )
         .get_matches();
 
+    if let Some(search_matches) = matches.subcommand_matches("search") {
+        start_soup_search(search_matches);
+        return;
+    }
+
+    if let Some(search_matches) = matches.subcommand_matches("methuselah") {
+        start_methuselah_search(search_matches);
+        return;
+    }
+
+    if let Some(search_matches) = matches.subcommand_matches("pattern") {
+        start_pattern_search(search_matches);
+        return;
+    }
+
+    if let Some(coordinator_addr) = matches.value_of("worker") {
+        CellularAutomatonConfig::run_worker(coordinator_addr);
+        return;
+    }
+
     let is_triangular = matches.is_present("triangular");
 
     match matches.value_of("replay") {
@@ -119,6 +332,16 @@ if it does exist it must be empty, otherwise the program will terminate with err
         None => (),
     };
 
+    let worker_count: Option<usize> = matches.value_of("workers").map(|count| {
+        count
+            .parse()
+            .expect("Worker count must be a positive integer.")
+    });
+    let run = |config: &CellularAutomatonConfig, save_dir: Option<String>| match worker_count {
+        Some(count) => config.run_board_distributed(count, save_dir, is_triangular),
+        None => config.run_board(save_dir, is_triangular),
+    };
+
     let save_dir = match matches.value_of("save") {
         Some(save_dir) => Some(String::from(save_dir)),
         None => None,
@@ -127,7 +350,7 @@ if it does exist it must be empty, otherwise the program will terminate with err
     match matches.value_of("demo") {
         Some(demo_name) => {
             let board_config = title_to_config.get(&demo_name.to_lowercase()).unwrap();
-            board_config.run_board(save_dir.clone(), is_triangular);
+            run(board_config, save_dir.clone());
         }
         None => (),
     };
@@ -136,36 +359,248 @@ if it does exist it must be empty, otherwise the program will terminate with err
         Some(path) => {
             let content = fs::read_to_string(path).expect("Cannot read configuration file.");
             let config = CellularAutomatonConfig::from_json(content.as_str());
-            config.run_board(save_dir, is_triangular);
+            run(&config, save_dir);
         }
         None => (),
     };
 }
 
+fn start_soup_search(matches: &ArgMatches) {
+    use gol_client::search::soup_search::run_soup_search;
+    use gol_core::{ObjectCensus, SoupSearch};
+
+    let rule = matches.value_of("rule").unwrap();
+    let census = ObjectCensus::from_rule(rule)
+        .unwrap_or_else(|| panic!("Invalid rule \"{}\", expected e.g. \"B3/S23\".", rule));
+    let parse = |name: &str| -> u64 {
+        matches
+            .value_of(name)
+            .unwrap()
+            .parse()
+            .unwrap_or_else(|_| panic!("\"{}\" must be a positive integer.", name))
+    };
+    let alive_ratio = matches
+        .value_of("alive-ratio")
+        .unwrap()
+        .parse()
+        .ok()
+        .filter(|ratio: &f64| (0.0..=1.0).contains(ratio))
+        .expect("\"alive-ratio\" must be a number between 0 and 1.");
+    let search = SoupSearch::new(census)
+        .with_soup_size(parse("soup-size") as usize)
+        .with_alive_ratio(alive_ratio)
+        .with_max_generations(parse("max-generations") as usize);
+    run_soup_search(
+        &search,
+        matches.value_of("results").unwrap(),
+        parse("soups"),
+        parse("batch-size").max(1),
+    );
+}
+
+fn start_methuselah_search(matches: &ArgMatches) {
+    use gol_client::search::methuselah_search::run_methuselah_search;
+    use gol_core::{MethuselahRank, MethuselahSearch, ObjectCensus};
+
+    let rule = matches.value_of("rule").unwrap();
+    let census = ObjectCensus::from_rule(rule)
+        .unwrap_or_else(|| panic!("Invalid rule \"{}\", expected e.g. \"B3/S23\".", rule));
+    let parse = |name: &str| -> u64 {
+        matches
+            .value_of(name)
+            .unwrap()
+            .parse()
+            .unwrap_or_else(|_| panic!("\"{}\" must be a positive integer.", name))
+    };
+    let rank = match matches.value_of("rank").unwrap() {
+        "max-population" => MethuselahRank::MaxPopulation,
+        "final-population" => MethuselahRank::FinalPopulation,
+        _ => MethuselahRank::Lifespan,
+    };
+    let search = MethuselahSearch::new(census)
+        .with_bounding_box(parse("width") as usize, parse("height") as usize)
+        .with_max_population(parse("max-population") as usize)
+        .with_max_generations(parse("max-generations") as usize)
+        .with_rank(rank)
+        .with_top_count(parse("top") as usize);
+    let samples = if matches.is_present("samples") {
+        Some((parse("samples"), parse("seed")))
+    } else {
+        None
+    };
+    run_methuselah_search(&search, samples, matches.value_of("output").unwrap());
+}
+
+fn start_pattern_search(matches: &ArgMatches) {
+    use gol_client::search::pattern_search::run_pattern_search;
+    use gol_core::{ObjectCensus, PatternSearch};
+    use std::time::Duration;
+
+    let rule = matches.value_of("rule").unwrap();
+    let census = ObjectCensus::from_rule(rule)
+        .unwrap_or_else(|| panic!("Invalid rule \"{}\", expected e.g. \"B3/S23\".", rule));
+    let parse = |name: &str| -> i64 {
+        matches
+            .value_of(name)
+            .unwrap()
+            .parse()
+            .unwrap_or_else(|_| panic!("\"{}\" must be an integer.", name))
+    };
+    let search = PatternSearch::new(
+        census,
+        parse("period").max(1) as usize,
+        (parse("dx"), parse("dy")),
+    )
+    .with_bounding_box(
+        parse("width").max(0) as usize,
+        parse("height").max(0) as usize,
+    )
+    .with_max_results(parse("max-results").max(1) as usize);
+    let time_limit = matches.value_of("time-limit").map(|seconds| {
+        Duration::from_secs_f64(
+            seconds
+                .parse()
+                .expect("\"time-limit\" must be a number of seconds."),
+        )
+    });
+    run_pattern_search(&search, time_limit, matches.value_of("output").unwrap());
+}
+
 fn start_replay(local_path: &String, is_triangular: bool) {
+    use gol_client::persistence::{
+        batch_deserializer_local::read_header,
+        load_board::{HistoryHeader, LegacyHistoryHeader},
+    };
+    use gol_core::GridPoint2D;
+
+    let header = match read_header::<HistoryHeader>(local_path) {
+        Ok(Some(header)) if header.is_supported() => header,
+        Ok(None) => panic!("No history found in replay directory."),
+        // Histories saved before headers had a version.
+        _ => match read_header::<LegacyHistoryHeader>(local_path) {
+            Ok(Some(_)) => {
+                return start_replay_with_indices::<u8, GridPoint2D<i32>, LegacyHistoryHeader>(
+                    local_path,
+                    is_triangular,
+                    false,
+                    |(shape, state_count)| (vec![shape.width(), shape.height()], *state_count),
+                )
+            }
+            _ => panic!("Unsupported history version in {}.", local_path),
+        },
+    };
+    let dim_count = header.shape.len();
+    if dim_count == 1 {
+        eprintln!("Cannot replay graph or tiling boards, histories do not store the topology.");
+        return;
+    }
+    let idx_bits = header.idx_bits;
+    match header.state_bits {
+        8 => start_replay_with_states::<u8>(local_path, dim_count, idx_bits, is_triangular),
+        16 => start_replay_with_states::<u16>(local_path, dim_count, idx_bits, is_triangular),
+        32 => start_replay_with_states::<u32>(local_path, dim_count, idx_bits, is_triangular),
+        other => panic!("Unsupported state width {} in history header.", other),
+    }
+}
+
+fn start_replay_with_states<T>(
+    local_path: &String,
+    dim_count: usize,
+    idx_bits: usize,
+    is_triangular: bool,
+) where
+    T: 'static
+        + Send
+        + Sync
+        + Clone
+        + serde::de::DeserializeOwned
+        + num_traits::PrimInt
+        + num_traits::Unsigned,
+{
+    use gol_client::persistence::load_board::HistoryHeader;
+    use gol_core::{GridPoint2D, GridPoint3D, GridPointND};
+
+    let shape_of = |header: &HistoryHeader| (header.shape.clone(), header.state_count);
+
+    match (dim_count, idx_bits) {
+        (2, 32) => start_replay_with_indices::<T, GridPoint2D<i32>, _>(
+            local_path,
+            is_triangular,
+            false,
+            shape_of,
+        ),
+        (2, 64) => start_replay_with_indices::<T, GridPoint2D<i64>, _>(
+            local_path,
+            is_triangular,
+            false,
+            shape_of,
+        ),
+        (3, 32) => {
+            start_replay_with_indices::<T, GridPoint3D<i32>, _>(local_path, false, true, shape_of)
+        }
+        (3, 64) => {
+            start_replay_with_indices::<T, GridPoint3D<i64>, _>(local_path, false, true, shape_of)
+        }
+        (_, 32) => {
+            start_replay_with_indices::<T, GridPointND<i32>, _>(local_path, false, true, shape_of)
+        }
+        (_, 64) => {
+            start_replay_with_indices::<T, GridPointND<i64>, _>(local_path, false, true, shape_of)
+        }
+        (_, other) => panic!("Unsupported coordinate width {} in history header.", other),
+    }
+}
+
+/// Boards with more than two dimensions are shown as a 2D slice. `shape_of` gives the board shape
+/// and number of states in the header.
+fn start_replay_with_indices<T, CI, H>(
+    local_path: &String,
+    is_triangular: bool,
+    is_sliced: bool,
+    shape_of: fn(&H) -> (Vec<usize>, usize),
+) where
+    T: 'static
+        + Send
+        + Sync
+        + Clone
+        + serde::de::DeserializeOwned
+        + num_traits::PrimInt
+        + num_traits::Unsigned,
+    CI: 'static
+        + Send
+        + Sync
+        + Clone
+        + Eq
+        + std::hash::Hash
+        + serde::de::DeserializeOwned
+        + gol_renderer::RenderIndex2D<T>,
+    H: 'static + Send + Sync + Clone + serde::de::DeserializeOwned,
+{
     use gol_client::replay::replayer_local::ReplayerLocal;
-    use gol_core::{util::grid_util::Shape2D, GridPoint2D};
     use gol_renderer::{
         renderer::keyboard_control::KeyboardControl, CellularAutomatonRenderer,
-        DiscreteStateColorMap, GraphicalRendererGrid2D,
+        DiscreteStateColorMap, GraphicalRendererGrid2D, SliceView,
     };
 
     let control = KeyboardControl::new();
     let control_receiver = control.clone_receive_only();
 
-    let replayer: ReplayerLocal<u8, GridPoint2D<i32>, (Shape2D, usize)> =
-        ReplayerLocal::new(0, local_path).with_keyboard_control(control_receiver);
-    let (board_shape, num_states) = replayer.get_header();
+    let replayer: ReplayerLocal<T, CI, H> =
+        ReplayerLocal::new(T::zero(), local_path).with_keyboard_control(control_receiver);
+    let (board_shape, num_states) = shape_of(&replayer.get_header());
 
     let mut renderer = GraphicalRendererGrid2D::new(
-        board_shape.width(),
-        board_shape.height(),
+        board_shape[0],
+        board_shape[1],
         replayer.get_readonly_states(),
     )
     .ok()
     .unwrap()
     .with_keyboard_control(control);
 
+    if is_sliced {
+        renderer = renderer.with_slice_view(SliceView::new(board_shape.clone()));
+    }
     if is_triangular {
         renderer = renderer.with_triangles();
     }
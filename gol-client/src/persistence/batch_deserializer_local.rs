@@ -8,7 +8,7 @@ use flate2::read::GzDecoder;
 use rayon::prelude::*;
 use serde::de::DeserializeOwned;
 use std::collections::HashSet;
-use std::fs::{self, read_dir, File};
+use std::fs::{self, read_dir};
 use std::io::Read;
 use std::path::Path;
 use std::sync::Arc;
@@ -44,18 +44,7 @@ where
             return None;
         }
         let (start, end) = (self.idx_ranges[*key], self.idx_ranges[key + 1]);
-        let file_name = format!("{}_{}.{}", start, end, HISTORY_EXTENSION);
-        let file_path = Path::new(&self.path).join(&file_name);
-
-        let mut file = File::open(&file_path).expect("File not found.");
-        let metadata = fs::metadata(&file_path).expect("Cannot read file metadata.");
-        let mut buffer = vec![0; metadata.len() as usize];
-        file.read(&mut buffer).expect("Cannot read file.");
-        let mut decoder = GzDecoder::new(&buffer[..]);
-
-        // Uncompressed data should be larger, but good starting size.
-        let mut byte_data = Vec::with_capacity(metadata.len() as usize);
-        decoder.read_to_end(&mut byte_data).unwrap();
+        let byte_data = read_history_file(&self.path, start, end);
 
         let res: (Option<T>, Vec<(usize, U)>) =
             bincode::deserialize(&byte_data[..]).expect("Cannot deserialize data.");
@@ -141,6 +130,40 @@ impl<T, U> BatchDeserializerLocal<T, U> {
     }
 }
 
+/// Reads only the header of a history directory, so the state type can be decided before the
+/// states are deserialized. `None` if there is no history or it has no header, an error if the
+/// header cannot be deserialized as `T`.
+pub fn read_header<T>(path: &String) -> bincode::Result<Option<T>>
+where
+    T: DeserializeOwned,
+{
+    let expanded = shellexpand::full(path).unwrap();
+    let path = Path::new(expanded.as_ref());
+    let idx_ranges = match construct_ranges(path) {
+        Ok(val) => val,
+        Err(err) => panic!("{}", err),
+    };
+    if idx_ranges.len() < 2 {
+        return Ok(None);
+    }
+    let byte_data = read_history_file(path.to_str().unwrap(), idx_ranges[0], idx_ranges[1]);
+    // Header is serialized before the states, trailing bytes are ignored.
+    bincode::deserialize(&byte_data[..])
+}
+
+fn read_history_file(dir_path: &str, start: usize, end: usize) -> Vec<u8> {
+    let file_name = format!("{}_{}.{}", start, end, HISTORY_EXTENSION);
+    let file_path = Path::new(dir_path).join(&file_name);
+
+    let buffer = fs::read(&file_path).expect("Cannot read history file.");
+    let mut decoder = GzDecoder::new(&buffer[..]);
+
+    // Uncompressed data should be larger, but good starting size.
+    let mut byte_data = Vec::with_capacity(buffer.len());
+    decoder.read_to_end(&mut byte_data).unwrap();
+    byte_data
+}
+
 fn construct_ranges(path: &Path) -> Result<Vec<usize>, &'static str> {
     if !path.is_dir() {
         return Err("Path specified for deserialization is not a directory.");
@@ -162,3 +185,62 @@ fn construct_ranges(path: &Path) -> Result<Vec<usize>, &'static str> {
     res_vec.sort();
     Ok(res_vec)
 }
+
+#[cfg(test)]
+mod batch_deserializer_local_test {
+    use super::super::batch_serializer::BatchIndexedSerializer;
+    use super::super::batch_serializer_local::BatchSerializerLocal;
+    use super::super::load_board::{HistoryHeader, LegacyHistoryHeader};
+    use super::{read_header, BatchDeserializerLocal};
+    use gol_core::{util::grid_util::Shape2D, GridPoint2D, GridPoint3D};
+
+    #[test]
+    fn batch_deserializer_local_test_header() {
+        let dir = std::env::temp_dir().join(format!("gol_history_test_{}", std::process::id()));
+        let dir = String::from(dir.to_str().unwrap());
+        let header = (vec![3usize, 3, 3], 300usize, 16usize, 64usize);
+        {
+            let serializer = BatchIndexedSerializer::new(2).with_header(header.clone());
+            let mut serializer = BatchSerializerLocal::new(&dir, serializer);
+            for i in 0..3u16 {
+                serializer.push(vec![(GridPoint3D::new(0i64, 0, 1), 297 + i)]);
+            }
+        }
+
+        let res: (Vec<usize>, usize, usize, usize) = read_header(&dir).unwrap().unwrap();
+        assert_eq!(res.0, vec![3, 3, 3]);
+        assert_eq!(res.1, 300);
+        assert_eq!(res.2, 16);
+        assert_eq!(res.3, 64);
+
+        let deserializer: BatchDeserializerLocal<
+            (Vec<usize>, usize, usize, usize),
+            Vec<(GridPoint3D<i64>, u16)>,
+        > = BatchDeserializerLocal::new(&dir);
+        let (_, states) = deserializer.get(2).unwrap();
+        assert_eq!(states.1, vec![(GridPoint3D::new(0, 0, 1), 299)]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn batch_deserializer_local_test_legacy_header() {
+        let dir = std::env::temp_dir().join(format!("gol_legacy_test_{}", std::process::id()));
+        let dir = String::from(dir.to_str().unwrap());
+        let header: LegacyHistoryHeader = (Shape2D::new(200, 100), 2);
+        {
+            let serializer = BatchIndexedSerializer::new(2).with_header(header);
+            let mut serializer = BatchSerializerLocal::new(&dir, serializer);
+            for _ in 0..2 {
+                serializer.push(vec![(GridPoint2D::new(0i32, 1), 1u8)]);
+            }
+        }
+
+        let res = read_header::<HistoryHeader>(&dir).unwrap_or(None);
+        assert!(!res.map_or(false, |header| header.is_supported()));
+        let (shape, state_count) = read_header::<LegacyHistoryHeader>(&dir).unwrap().unwrap();
+        assert_eq!((shape.width(), shape.height(), state_count), (200, 100, 2));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
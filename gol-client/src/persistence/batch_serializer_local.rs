@@ -108,27 +108,31 @@ where
     }
 }
 
-impl<T, CI, I, S> BoardCallbackWithStates<T, CI, I> for BatchSerializerLocal<Vec<(CI, T)>, S>
+impl<T, CI, S> BoardCallbackWithStates<T, CI> for BatchSerializerLocal<Vec<(CI, T)>, S>
 where
-    T: Send + Sync + Serialize,
-    CI: Send + Sync + Serialize,
+    T: Send + Sync + Clone + Serialize,
+    CI: Send + Sync + Clone + Serialize,
     S: Send + Sync + Serialize,
-    I: rayon::iter::ParallelIterator<Item = IndexedDataOwned<CI, T>>,
 {
-    fn execute(&mut self, states: I) {
-        self.push(states.collect());
+    fn execute(&mut self, states: &[IndexedDataOwned<CI, T>]) {
+        self.push(states.to_vec());
     }
 }
 
-impl<T, CI, I, S> BoardCallbackWithStates<T, CI, I> for StateSerializerLocal<Vec<(CI, T)>, S, T>
+impl<T, CI, S> BoardCallbackWithStates<T, CI> for StateSerializerLocal<Vec<(CI, T)>, S, T>
 where
-    T: Send + Sync + Serialize + std::cmp::PartialEq,
-    CI: Send + Sync + Serialize,
+    T: Send + Sync + Clone + Serialize + std::cmp::PartialEq,
+    CI: Send + Sync + Clone + Serialize,
     S: Send + Sync + Serialize,
-    I: rayon::iter::ParallelIterator<Item = IndexedDataOwned<CI, T>>,
 {
-    fn execute(&mut self, states: I) {
-        self.serializer
-            .push(states.filter(|ele| ele.1 != self.trivial_state).collect());
+    fn execute(&mut self, states: &[IndexedDataOwned<CI, T>]) {
+        let trivial_state = &self.trivial_state;
+        self.serializer.push(
+            states
+                .par_iter()
+                .filter(|ele| &ele.1 != trivial_state)
+                .cloned()
+                .collect(),
+        );
     }
 }
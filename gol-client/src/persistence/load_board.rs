@@ -3,28 +3,363 @@ use crate::persistence::{
     batch_serializer_local::{BatchSerializerLocal, StateSerializerLocal},
 };
 use gol_core::{
-    util::grid_util::Shape2D, Board, BoardCallback, BoardNeighborManager, BoardSpaceManager,
-    BoardStateManager, BoardStrategyManager, DecayLifeLikeStrategy, Grid, GridFactory, GridPoint2D,
-    IndexedDataOwned, NeighborMoore, NeighborMooreDonut, NeighborMooreTriangle, NeighborsGridDonut,
-    NeighborsGridSurround, SharedStrategyManager, SparseStates, StandardBoard, StatesCallback,
+    util::grid_util::{Shape2D, Shape3D},
+    util::sync_util::ReadOnlyLock,
+    AvalancheHistogram, BitLife, Board, BoardCallback, BoardNeighborManager, BoardSpaceManager,
+    BoardStateManager, BoardStrategyManager, CellEraser, Census, CensusCallback,
+    DecayLifeLikeStrategy, DistributedBoard, DistributedWorker, EvolutionStrategy, GrainDropper,
+    Graph, GrayScottParams, GrayScottPreset, GrayScottState, GrayScottStrategy, Grid, GridFactory,
+    GridPoint2D, GridPoint3D, GridPointND, IndexedDataOwned, LatticeGasModel, LatticeGasStrategy,
+    NeighborHex, NeighborHexDonut, NeighborMoore, NeighborMooreDonut, NeighborMooreTriangle,
+    NeighborVonNeumann, NeighborsGraph, NeighborsGridDonut, NeighborsGridSurround,
+    NeighborsWeightedKernel, ObjectCensus, PackedSparseStates, SandpileStrategy,
+    SecondOrderStrategy, SharedStrategyManager, Spaceship, SpaceshipDetector, SparseStates,
+    StandardBoard, StatesCallback, StatesReadOnly, StripPartition, Tiling,
+    WeightedLifeLikeStrategy, WeightedStencil2D,
 };
 use gol_renderer::{
-    CellularAutomatonRenderer, DiscreteStateCharMap, DiscreteStateColorMap,
-    GraphicalRendererGrid2D, StateVisualMapping,
+    renderer::keyboard_control::KeyboardControl, CellularAutomatonRenderer, DiscreteStateCharMap,
+    DiscreteStateColorMap, GraphicalRendererGraph, GraphicalRendererGrid2D,
+    GraphicalRendererGrid3D, GraphicalRendererTiling, GrayScottCharMap, GrayScottColorMap,
+    LatticeGasCharMap, LatticeGasColorMap, RenderIndex2D, SandpileCharMap, SandpileColorMap,
+    SliceView, StateVisualMapping,
 };
 use num_cpus;
+use num_traits::{FromPrimitive, PrimInt, ToPrimitive, Unsigned};
 use rand::Rng;
 use rayon::prelude::*;
 use rgb::RGBA16;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json;
 use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::io;
 use std::iter::FromIterator;
+use std::net::TcpListener;
+use std::process::{Child, Command};
+use std::str::FromStr;
+use std::sync::OnceLock;
 use std::thread;
 use std::time::Duration;
 
-type IntIdx = i32;
-type IntState = u8;
+type IntIdx = i64;
+
+/// Start of the headers of saved histories, before the version of their layout.
+const HISTORY_MAGIC: [u8; 4] = *b"GOLH";
+
+const HISTORY_VERSION: u32 = 1;
+
+/// Header of saved histories.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HistoryHeader {
+    magic: [u8; 4],
+    version: u32,
+    /// Length of each board dimension.
+    pub shape: Vec<usize>,
+    pub state_count: usize,
+    /// Width of the state integer in bits.
+    pub state_bits: usize,
+    /// Width of the coordinate integer in bits.
+    pub idx_bits: usize,
+}
+
+impl HistoryHeader {
+    pub fn new(shape: Vec<usize>, state_count: usize, state_bits: usize, idx_bits: usize) -> Self {
+        Self {
+            magic: HISTORY_MAGIC,
+            version: HISTORY_VERSION,
+            shape,
+            state_count,
+            state_bits,
+            idx_bits,
+        }
+    }
+
+    /// Whether the header was saved with the current layout. Headers of other layouts can still
+    /// deserialize into garbage.
+    pub fn is_supported(&self) -> bool {
+        self.magic == HISTORY_MAGIC && self.version == HISTORY_VERSION
+    }
+}
+
+/// Header of histories saved before headers had a version, only for 2D grids with 8-bit states
+/// and 32-bit coordinates: board shape and number of states.
+pub type LegacyHistoryHeader = (Shape2D, usize);
+
+type BoardCallbacks<T, CI> = Vec<BoardCallback<T, CI>>;
+
+type Renderers<T> = (
+    Vec<Box<dyn CellularAutomatonRenderer<T, char>>>,
+    Vec<Box<dyn CellularAutomatonRenderer<T, RGBA16>>>,
+);
+
+/// Cell indices of configurable boards, grid points or graph node IDs.
+trait BoardIndex: 'static + Send + Sync + Clone + Eq + Hash + Debug + Serialize {
+    /// Index of the `i`-th cell of a centered board, the first dimension changes fastest.
+    fn from_flat_idx(i: usize, shape: &[usize]) -> Self;
+
+    fn initial_states_config(board: &BoardConfig) -> &InitialStatesConfig<Self>;
+
+    /// Slice shown by 2D renderers, `None` if the board is drawn as is.
+    fn slice_view(shape: &[usize]) -> Option<SliceView> {
+        Some(SliceView::new(shape.to_vec()))
+    }
+
+    /// Sparse state manager of boards with these indices.
+    fn state_manager<T>(
+        default_state: T,
+        initial_states: HashMap<Self, T>,
+    ) -> Box<dyn BoardStateManager<T, Self, rayon::vec::IntoIter<IndexedDataOwned<Self, T>>>>
+    where
+        T: 'static + Send + Sync + Clone + PartialEq,
+    {
+        Box::new(SparseStates::new(default_state, initial_states))
+    }
+
+    /// Renderer drawing each cell as a cube, `None` if the board is not 3D.
+    fn voxel_renderer<T>(
+        _shape: &[usize],
+        _states: StatesReadOnly<Self, T>,
+        _title: String,
+        _control: Option<KeyboardControl>,
+    ) -> Option<Box<dyn CellularAutomatonRenderer<T, RGBA16>>>
+    where
+        T: 'static + Send + Sync + Clone,
+    {
+        None
+    }
+
+    /// Callback taking a census of the last generation, `None` if the board is not a 2D grid.
+    fn census_callback<T>(
+        _census: ObjectCensus,
+        _dead_state: T,
+    ) -> Option<CensusCallbackWithResult<Self, T>>
+    where
+        T: 'static + Send + Sync + PartialEq,
+    {
+        None
+    }
+
+    /// Callback finding spaceships and the eraser removing them at the edge if `remove_at_edge`,
+    /// `None` if the board is not a 2D grid.
+    fn spaceship_callback<T>(
+        _census: ObjectCensus,
+        _dead_state: T,
+        _board: &BoardConfig,
+        _remove_at_edge: bool,
+    ) -> Option<SpaceshipCallback<Self, T>>
+    where
+        T: 'static + Send + Sync + Clone + PartialEq,
+    {
+        None
+    }
+}
+
+/// Spaceships found by a detector and the path they are written to.
+type FoundSpaceships = (ReadOnlyLock<Vec<Spaceship>>, Option<String>);
+
+/// Census callback and the census it takes.
+type CensusCallbackWithResult<CI, T> = (BoardCallback<T, CI>, ReadOnlyLock<Option<Census>>);
+
+/// Spaceship detector, the spaceships it found and the eraser it removes them with.
+type SpaceshipCallback<CI, T> = (
+    BoardCallback<T, CI>,
+    ReadOnlyLock<Vec<Spaceship>>,
+    Option<CellEraser<CI, T>>,
+);
+
+/// Parts of a board only some boards from configs have.
+struct BoardAddOns<CI, T> {
+    bit_life: Option<BitLife<CI, T>>,
+    grain_dropper: Option<GrainDropper<CI, T>>,
+}
+
+impl<CI, T> Default for BoardAddOns<CI, T> {
+    fn default() -> Self {
+        Self {
+            bit_life: None,
+            grain_dropper: None,
+        }
+    }
+}
+
+impl BoardIndex for GridPoint2D<IntIdx> {
+    fn from_flat_idx(i: usize, shape: &[usize]) -> Self {
+        let indices = flat_idx_to_indices(i, shape);
+        GridPoint2D::new(indices[0], indices[1])
+    }
+
+    fn initial_states_config(board: &BoardConfig) -> &InitialStatesConfig<Self> {
+        match board {
+            BoardConfig::Grid2D {
+                shape: _,
+                initial_states,
+            } => initial_states,
+            _ => panic!("Expected a 2D board."),
+        }
+    }
+
+    fn slice_view(_shape: &[usize]) -> Option<SliceView> {
+        None
+    }
+
+    fn state_manager<T>(
+        default_state: T,
+        initial_states: HashMap<Self, T>,
+    ) -> Box<dyn BoardStateManager<T, Self, rayon::vec::IntoIter<IndexedDataOwned<Self, T>>>>
+    where
+        T: 'static + Send + Sync + Clone + PartialEq,
+    {
+        Box::new(PackedSparseStates::new(default_state, initial_states))
+    }
+
+    fn census_callback<T>(
+        census: ObjectCensus,
+        dead_state: T,
+    ) -> Option<CensusCallbackWithResult<Self, T>>
+    where
+        T: 'static + Send + Sync + PartialEq,
+    {
+        let callback = CensusCallback::new(census, dead_state);
+        let res = callback.clone_read_only();
+        Some((BoardCallback::WithStates(Box::new(callback)), res))
+    }
+
+    fn spaceship_callback<T>(
+        census: ObjectCensus,
+        dead_state: T,
+        board: &BoardConfig,
+        remove_at_edge: bool,
+    ) -> Option<SpaceshipCallback<Self, T>>
+    where
+        T: 'static + Send + Sync + Clone + PartialEq,
+    {
+        let detector = SpaceshipDetector::new(census, dead_state.clone());
+        let (detector, eraser) = match board {
+            BoardConfig::Grid2D { shape, .. } if remove_at_edge => {
+                let eraser = CellEraser::new(dead_state);
+                (
+                    detector.with_edge_removal(shape.clone(), eraser.clone()),
+                    Some(eraser),
+                )
+            }
+            _ => (detector, None),
+        };
+        let res = detector.clone_read_only();
+        Some((BoardCallback::WithStates(Box::new(detector)), res, eraser))
+    }
+}
+
+impl BoardIndex for GridPoint3D<IntIdx> {
+    fn from_flat_idx(i: usize, shape: &[usize]) -> Self {
+        let indices = flat_idx_to_indices(i, shape);
+        GridPoint3D::new(indices[0], indices[1], indices[2])
+    }
+
+    fn initial_states_config(board: &BoardConfig) -> &InitialStatesConfig<Self> {
+        match board {
+            BoardConfig::Grid3D {
+                shape: _,
+                initial_states,
+            } => initial_states,
+            _ => panic!("Expected a 3D board."),
+        }
+    }
+
+    fn voxel_renderer<T>(
+        shape: &[usize],
+        states: StatesReadOnly<Self, T>,
+        title: String,
+        control: Option<KeyboardControl>,
+    ) -> Option<Box<dyn CellularAutomatonRenderer<T, RGBA16>>>
+    where
+        T: 'static + Send + Sync + Clone,
+    {
+        match GraphicalRendererGrid3D::new(shape[0], shape[1], shape[2], states) {
+            Ok(val) => {
+                let renderer = val.with_title(title);
+                Some(Box::new(match control {
+                    Some(control) => renderer.with_keyboard_control(control),
+                    None => renderer,
+                }))
+            }
+            Err(err) => {
+                eprintln!("Error creating voxel renderer: {:?}", err);
+                None
+            }
+        }
+    }
+}
+
+impl BoardIndex for GridPointND<IntIdx> {
+    fn from_flat_idx(i: usize, shape: &[usize]) -> Self {
+        GridPointND::new(flat_idx_to_indices(i, shape).iter())
+    }
+
+    fn initial_states_config(board: &BoardConfig) -> &InitialStatesConfig<Self> {
+        match board {
+            BoardConfig::GridND {
+                shape: _,
+                initial_states,
+            } => initial_states,
+            _ => panic!("Expected an N-dimensional board."),
+        }
+    }
+}
+
+impl BoardIndex for usize {
+    fn from_flat_idx(i: usize, _shape: &[usize]) -> Self {
+        i
+    }
+
+    fn initial_states_config(board: &BoardConfig) -> &InitialStatesConfig<Self> {
+        match board {
+            BoardConfig::Graph { initial_states, .. } => initial_states,
+            BoardConfig::Tiling { initial_states, .. } => initial_states,
+            _ => panic!("Expected a graph or tiling board."),
+        }
+    }
+
+    fn slice_view(_shape: &[usize]) -> Option<SliceView> {
+        None
+    }
+}
+
+/// Unsigned integer types that can be used as discrete cell states.
+trait IntState:
+    'static
+    + Send
+    + Sync
+    + Clone
+    + PartialEq
+    + Debug
+    + Serialize
+    + PrimInt
+    + Unsigned
+    + FromPrimitive
+    + ToPrimitive
+    + FromStr
+    + DeserializeOwned
+{
+}
+
+impl<T> IntState for T where
+    T: 'static
+        + Send
+        + Sync
+        + Clone
+        + PartialEq
+        + Debug
+        + Serialize
+        + PrimInt
+        + Unsigned
+        + FromPrimitive
+        + ToPrimitive
+        + FromStr
+        + DeserializeOwned
+{
+}
 
 // Visual
 
@@ -32,6 +367,8 @@ type IntState = u8;
 pub enum VisualStyle {
     Ascii,
     Graphical,
+    /// Cubes with an orbit camera, only for 3D boards.
+    Voxel,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -45,8 +382,26 @@ pub struct VisualConfig {
 #[derive(Serialize, Deserialize)]
 #[serde(tag = "type")]
 enum NeighborRuleConfig {
-    Moore { margin: usize },
-    MooreWrap { margin: usize },
+    Moore {
+        margin: usize,
+    },
+    MooreWrap {
+        margin: usize,
+    },
+    Hex,
+    HexWrap,
+    VonNeumann,
+    /// Weights of the cells around the center, only for 2D grid boards. Rows go along y.
+    Kernel {
+        weights: Vec<Vec<f64>>,
+    },
+    KernelWrap {
+        weights: Vec<Vec<f64>>,
+    },
+    /// Graph nodes or tiles sharing an edge, only for graph and tiling boards.
+    Edges,
+    /// Tiles sharing at least one corner, only for tiling boards.
+    Vertices,
 }
 
 // State
@@ -54,7 +409,13 @@ enum NeighborRuleConfig {
 #[derive(Serialize, Deserialize)]
 #[serde(tag = "type")]
 enum StateConfig {
-    UInt { count: usize },
+    UInt {
+        count: usize,
+        /// Width of the state integer, one of 8, 16 or 32. Inferred from `count` if not specified.
+        #[serde(default)]
+        bits: Option<usize>,
+    },
+    GrayScott,
 }
 
 // State
@@ -66,25 +427,115 @@ enum CellCount {
     Range(Vec<usize>),
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum WeightedSum {
+    Value(f64),
+    Range(Vec<f64>),
+}
+
+#[derive(Serialize, Deserialize)]
+enum LatticeGasModelConfig {
+    #[serde(rename = "HPP")]
+    Hpp,
+    #[serde(rename = "FHP")]
+    Fhp,
+}
+
+#[derive(Serialize, Deserialize)]
+enum LatticeGasColorConfig {
+    Density,
+    Momentum,
+}
+
+#[derive(Serialize, Deserialize)]
+enum GrayScottColorConfig {
+    U,
+    V,
+}
+
+#[derive(Serialize, Deserialize)]
+struct GrainDropConfig {
+    positions: Vec<GridPoint2D<IntIdx>>,
+    interval: usize,
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(tag = "type")]
 enum EvolutionRuleConfig {
     AliveCount {
         survive: Vec<CellCount>,
         born: Vec<CellCount>,
+        #[serde(default)]
+        second_order: bool,
+    },
+    /// Like `AliveCount`, but alive neighbors are summed with the weights of a kernel neighbor
+    /// rule.
+    WeightedSum {
+        survive: Vec<WeightedSum>,
+        born: Vec<WeightedSum>,
+    },
+    LatticeGas {
+        model: LatticeGasModelConfig,
+        color_by: LatticeGasColorConfig,
+    },
+    GrayScott {
+        preset: Option<GrayScottPreset>,
+        params: Option<GrayScottParams>,
+        color_by: GrayScottColorConfig,
+    },
+    Sandpile {
+        threshold: Option<usize>,
+        driver: Option<GrainDropConfig>,
     },
 }
 
 // Board
 
 #[derive(Serialize, Deserialize)]
-#[serde(tag = "type")]
-enum InitialStatesConfig {
+#[serde(tag = "type", bound(deserialize = "P: Deserialize<'de>"))]
+enum InitialStatesConfig<P> {
     Deterministic {
-        positions: HashMap<String, Vec<GridPoint2D<IntIdx>>>,
+        positions: HashMap<String, Vec<P>>,
     },
     Random {
         alive_ratio: f32,
+        /// Cells placed on top of the random states, e.g. obstacles.
+        #[serde(default)]
+        positions: HashMap<String, Vec<P>>,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+enum GraphConfig {
+    /// JSON file with a list of `[from, to]` node pairs.
+    EdgeListFile { path: String },
+    /// JSON file with the list of neighbor IDs of every node.
+    AdjacencyFile { path: String },
+    WattsStrogatz {
+        node_count: usize,
+        degree: usize,
+        rewire_probability: f64,
+        seed: u64,
+    },
+    ErdosRenyi {
+        node_count: usize,
+        edge_probability: f64,
+        seed: u64,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+enum TilingConfig {
+    /// Penrose rhombus tiling, every subdivision multiplies the number of tiles by about 2.6.
+    Penrose {
+        subdivisions: usize,
+    },
+    Voronoi {
+        tile_count: usize,
+        seed: u64,
     },
 }
 
@@ -93,8 +544,65 @@ enum InitialStatesConfig {
 enum BoardConfig {
     Grid2D {
         shape: Shape2D,
-        initial_states: InitialStatesConfig,
+        initial_states: InitialStatesConfig<GridPoint2D<IntIdx>>,
+    },
+    Grid3D {
+        shape: Shape3D,
+        initial_states: InitialStatesConfig<GridPoint3D<IntIdx>>,
     },
+    /// Positions are lists of indices, one per dimension.
+    GridND {
+        shape: Vec<usize>,
+        initial_states: InitialStatesConfig<GridPointND<IntIdx>>,
+    },
+    /// Positions are node IDs.
+    Graph {
+        graph: GraphConfig,
+        initial_states: InitialStatesConfig<usize>,
+        /// Built from `graph` on first use, so files are read and generators run only once.
+        #[serde(skip)]
+        loaded: OnceLock<Graph>,
+    },
+    /// Positions are tile IDs.
+    Tiling {
+        tiling: TilingConfig,
+        initial_states: InitialStatesConfig<usize>,
+        /// Generated from `tiling` on first use.
+        #[serde(skip)]
+        loaded: OnceLock<Tiling>,
+    },
+}
+
+// Census
+
+/// Census of the objects left when the board finishes, only for 2D grids of two-state
+/// `AliveCount` rules with the Moore neighborhood.
+#[derive(Serialize, Deserialize)]
+struct CensusConfig {
+    /// Largest distance between cells of the same object, 2 if not specified.
+    #[serde(default)]
+    distance: Option<usize>,
+    /// Longest period of oscillators and spaceships, 64 if not specified.
+    #[serde(default)]
+    max_period: Option<usize>,
+    /// JSON file the census is written to, printed if not specified.
+    #[serde(default)]
+    path: Option<String>,
+}
+
+/// Spaceships found while the board runs, with the same restrictions as `CensusConfig`.
+#[derive(Serialize, Deserialize)]
+struct SpaceshipsConfig {
+    /// Longest period of spaceships, 64 if not specified.
+    #[serde(default)]
+    max_period: Option<usize>,
+    /// Whether spaceships reaching the edge are removed, so they do not wrap around boards with
+    /// the "MooreWrap" neighbor rule.
+    #[serde(default)]
+    remove_at_edge: bool,
+    /// JSON file the spaceships are written to, printed if not specified.
+    #[serde(default)]
+    path: Option<String>,
 }
 
 // Cellular Automaton
@@ -111,11 +619,127 @@ pub struct CellularAutomatonConfig {
     state: StateConfig,
     evolution_rule: EvolutionRuleConfig,
     board: BoardConfig,
+    #[serde(default)]
+    census: Option<CensusConfig>,
+    #[serde(default)]
+    spaceships: Option<SpaceshipsConfig>,
 }
 
 impl CellularAutomatonConfig {
     pub fn from_json(json: &str) -> Self {
-        serde_json::from_str(json).unwrap()
+        let res: Self = serde_json::from_str(json).unwrap();
+        res.validate();
+        res
+    }
+
+    /// Panics on settings that cannot work together, so they fail when the config is loaded
+    /// instead of in the middle of a run.
+    fn validate(&self) {
+        if let EvolutionRuleConfig::Sandpile {
+            threshold: _,
+            driver: Some(driver),
+        } = &self.evolution_rule
+        {
+            let shape = match &self.board {
+                BoardConfig::Grid2D {
+                    shape,
+                    initial_states: _,
+                } => shape,
+                _ => panic!("Grains can only be dropped on 2D grid boards."),
+            };
+            for position in driver.positions.iter() {
+                assert!(
+                    (shape.x_idx_min()..=shape.x_idx_max()).contains(&position.x)
+                        && (shape.y_idx_min()..=shape.y_idx_max()).contains(&position.y),
+                    "Grain drop position ({}, {}) is not on the board.",
+                    position.x,
+                    position.y
+                );
+            }
+        }
+        if let EvolutionRuleConfig::LatticeGas { model, color_by: _ } = &self.evolution_rule {
+            match model {
+                LatticeGasModelConfig::Hpp => assert!(
+                    matches!(
+                        self.neighbor_rule,
+                        NeighborRuleConfig::VonNeumann
+                            | NeighborRuleConfig::Moore { margin: 1 }
+                            | NeighborRuleConfig::MooreWrap { margin: 1 }
+                    ),
+                    "The HPP lattice gas needs \"VonNeumann\" or margin 1 \"Moore\" neighbors."
+                ),
+                LatticeGasModelConfig::Fhp => assert!(
+                    self.is_hex(),
+                    "The FHP lattice gas needs \"Hex\" neighbors."
+                ),
+            }
+            let is_8_bit = match &self.state {
+                StateConfig::UInt { count, bits: _ } => *count <= 0x100 && self.state_bits() == 8,
+                StateConfig::GrayScott => false,
+            };
+            assert!(
+                is_8_bit,
+                "Lattice gas states are 8-bit, use at most 256 states and 8 bits."
+            );
+        }
+    }
+
+    /// Pattern of a two-state life-like rule on an otherwise empty wrapping 2D grid, paused at
+    /// the start and rendered graphically.
+    pub fn new_life_like(
+        title: String,
+        survive: &[usize],
+        born: &[usize],
+        shape: Shape2D,
+        cells: Vec<GridPoint2D<IntIdx>>,
+        max_iter: Option<usize>,
+    ) -> Self {
+        let counts = |counts: &[usize]| counts.iter().map(|c| CellCount::Integer(*c)).collect();
+        let mut positions = HashMap::new();
+        positions.insert(String::from("1"), cells);
+        Self {
+            title,
+            max_iter,
+            delay: 0.05,
+            pause_at_start: true,
+            enable_control: true,
+            visual: VisualConfig {
+                on: true,
+                styles: vec![VisualStyle::Graphical],
+            },
+            neighbor_rule: NeighborRuleConfig::MooreWrap { margin: 1 },
+            state: StateConfig::UInt {
+                count: 2,
+                bits: None,
+            },
+            evolution_rule: EvolutionRuleConfig::AliveCount {
+                survive: counts(survive),
+                born: counts(born),
+                second_order: false,
+            },
+            board: BoardConfig::Grid2D {
+                shape,
+                initial_states: InitialStatesConfig::Deterministic { positions },
+            },
+            census: None,
+            spaceships: None,
+        }
+    }
+
+    /// Removes spaceships reaching the edge of the board, so escaping ones do not wrap around and
+    /// hit the rest of the pattern again.
+    pub fn with_spaceship_removal(self) -> Self {
+        let mut res = self;
+        res.spaceships = Some(SpaceshipsConfig {
+            max_period: None,
+            remove_at_edge: true,
+            path: None,
+        });
+        res
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
     }
 
     pub fn title(&self) -> &String {
@@ -123,42 +747,394 @@ impl CellularAutomatonConfig {
     }
 
     pub fn run_board(&self, save_dir: Option<String>, is_triangular: bool) {
-        let max_iter = self.max_iter.clone();
-        let (mut char_renderers, mut color_renderers) = match self.board {
+        match self.state {
+            StateConfig::UInt { count: _, bits: _ } => match self.state_bits() {
+                8 => self.run_board_discrete::<u8>(save_dir, is_triangular),
+                16 => self.run_board_discrete::<u16>(save_dir, is_triangular),
+                32 => self.run_board_discrete::<u32>(save_dir, is_triangular),
+                other => panic!("Unsupported state width {}, use 8, 16 or 32.", other),
+            },
+            StateConfig::GrayScott => {
+                let max_iter = self.max_iter;
+                let space = self.gen_space_grid_2d().unwrap();
+                let neighbor = Box::new(self.gen_stencil_grid_2d().unwrap());
+                let state = self.gen_state_manager_grid_2d_gray_scott().unwrap();
+                let strat = self.gen_strat_grid_2d_gray_scott().unwrap();
+                let (callbacks, (char_renderers, color_renderers)) =
+                    self.gen_callbacks(save_dir, is_triangular, GrayScottState::default());
+                let mut board = StandardBoard::new(space, neighbor, state, strat, callbacks)
+                    .with_neighbor_table();
+                std::thread::spawn(move || {
+                    board.advance(max_iter);
+                });
+                self.run_renderers(
+                    char_renderers,
+                    color_renderers,
+                    || self.char_maps_gray_scott(),
+                    || self.color_maps_gray_scott(),
+                );
+            }
+        }
+    }
+
+    /// Same as `run_board`, but the board is split into strips evolved by `worker_count` copies of
+    /// this executable started with `--worker`. Only discrete 2D grid boards are supported.
+    pub fn run_board_distributed(
+        &self,
+        worker_count: usize,
+        save_dir: Option<String>,
+        is_triangular: bool,
+    ) {
+        match self.state {
+            StateConfig::UInt { count: _, bits: _ } => match self.state_bits() {
+                8 => {
+                    self.run_board_distributed_discrete::<u8>(worker_count, save_dir, is_triangular)
+                }
+                16 => self.run_board_distributed_discrete::<u16>(
+                    worker_count,
+                    save_dir,
+                    is_triangular,
+                ),
+                32 => self.run_board_distributed_discrete::<u32>(
+                    worker_count,
+                    save_dir,
+                    is_triangular,
+                ),
+                other => panic!("Unsupported state width {}, use 8, 16 or 32.", other),
+            },
+            StateConfig::GrayScott => panic!("Gray-Scott boards cannot be distributed."),
+        }
+    }
+
+    /// Evolves the strip assigned by the coordinator at `coordinator_addr` until it stops, with
+    /// the configuration the coordinator sent.
+    pub fn run_worker(coordinator_addr: &str) {
+        let worker = DistributedWorker::connect(coordinator_addr)
+            .expect("Cannot connect to the coordinator.");
+        let (config, is_triangular): (Self, bool) = serde_json::from_slice(worker.payload())
+            .expect("Invalid configuration from coordinator.");
+        match config.state_bits() {
+            8 => config.run_worker_discrete::<u8>(worker, is_triangular),
+            16 => config.run_worker_discrete::<u16>(worker, is_triangular),
+            32 => config.run_worker_discrete::<u32>(worker, is_triangular),
+            other => panic!("Unsupported state width {}, use 8, 16 or 32.", other),
+        }
+    }
+
+    fn run_board_distributed_discrete<T>(
+        &self,
+        worker_count: usize,
+        save_dir: Option<String>,
+        is_triangular: bool,
+    ) where
+        T: IntState,
+    {
+        let shape = match &self.board {
+            BoardConfig::Grid2D {
+                shape,
+                initial_states: _,
+            } => shape.clone(),
+            _ => panic!("Only 2D grid boards can be distributed."),
+        };
+        let (halo, is_donut) = self.halo_grid_2d();
+        let partition = StripPartition::new(shape, is_donut, halo, worker_count).unwrap();
+        // Everything that can fail is set up before workers are started, so they are not left
+        // waiting for a coordinator that gave up.
+        if self.gen_grain_dropper_grid_2d::<T>().is_some() {
+            panic!("Distributed boards cannot drop grains on sandpiles.");
+        }
+        self.gen_neighbor_grid_2d(is_triangular)
+            .expect("Neighbor rule is not supported by 2D grid boards.");
+        self.gen_strat_grid_2d_discrete::<T>()
+            .expect("Evolution rule is not supported by 2D grid boards.");
+        let max_iter = self.max_iter;
+        let payload = serde_json::to_vec(&(self, is_triangular)).unwrap();
+        let (mut callbacks, (char_renderers, color_renderers)) =
+            self.gen_callbacks_with(save_dir, T::zero(), |states, control| {
+                self.gen_grid_renderers(states, control, is_triangular)
+            });
+        let census = self.push_census_callback(&mut callbacks, T::zero());
+        let (spaceships, eraser) = self.push_spaceship_callback(&mut callbacks, T::zero());
+        if eraser.is_some() {
+            panic!("Distributed boards cannot remove spaceships at the edge.");
+        }
+        let has_renderer = !char_renderers.is_empty() || !color_renderers.is_empty();
+        let initial_states = self.gen_initial_states_discrete::<GridPoint2D<IntIdx>, T>();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let coordinator_addr = listener.local_addr().unwrap().to_string();
+        let executable = std::env::current_exe().unwrap();
+        let mut workers: Vec<Child> = (0..worker_count)
+            .map(|_| {
+                Command::new(&executable)
+                    .args(["--worker", coordinator_addr.as_str()])
+                    .spawn()
+                    .expect("Cannot start worker process.")
+            })
+            .collect();
+        let mut board = DistributedBoard::new(
+            &listener,
+            partition,
+            &payload,
+            T::zero(),
+            initial_states,
+            callbacks,
+            || {
+                for worker in workers.iter_mut() {
+                    if let Some(status) = worker.try_wait()? {
+                        return Err(io::Error::other(format!(
+                            "Worker exited before connecting with {}.",
+                            status
+                        )));
+                    }
+                }
+                Ok(())
+            },
+        )
+        .unwrap_or_else(|err| panic!("Cannot start distributed board: {}", err));
+        let handle = std::thread::spawn(move || {
+            board.advance(max_iter).unwrap();
+            if let Some((census, path)) = census {
+                write_census(&census, path);
+            }
+            if let Some((spaceships, path)) = spaceships {
+                write_spaceships(&spaceships, path);
+            }
+        });
+        self.run_renderers(
+            char_renderers,
+            color_renderers,
+            || self.char_maps_discrete(),
+            || self.color_maps_discrete(),
+        );
+        if !has_renderer {
+            handle.join().unwrap();
+            for worker in workers.iter_mut() {
+                worker.wait().unwrap();
+            }
+        }
+    }
+
+    fn run_worker_discrete<T>(&self, worker: DistributedWorker, is_triangular: bool)
+    where
+        T: IntState,
+    {
+        let res = worker.run::<T, IntIdx>(
+            self.gen_neighbor_grid_2d(is_triangular)
+                .expect("Neighbor rule is not supported by 2D grid boards."),
+            self.gen_strat_grid_2d_discrete::<T>().unwrap(),
+        );
+        match res {
+            // The coordinator exits without stopping workers when its renderers are closed.
+            Err(err) if err.kind() != std::io::ErrorKind::UnexpectedEof => {
+                panic!("Worker failed: {}", err)
+            }
+            _ => (),
+        }
+    }
+
+    /// Columns on each side a cell of a 2D grid needs to evolve, and whether the neighbors wrap
+    /// around the edges.
+    fn halo_grid_2d(&self) -> (usize, bool) {
+        match &self.neighbor_rule {
+            NeighborRuleConfig::Moore { margin } => (*margin, false),
+            NeighborRuleConfig::MooreWrap { margin } => (*margin, true),
+            NeighborRuleConfig::Hex | NeighborRuleConfig::VonNeumann => (1, false),
+            NeighborRuleConfig::HexWrap => (1, true),
+            NeighborRuleConfig::Kernel { weights } => (kernel_radius(weights), false),
+            NeighborRuleConfig::KernelWrap { weights } => (kernel_radius(weights), true),
+            NeighborRuleConfig::Edges | NeighborRuleConfig::Vertices => {
+                panic!("Only grid neighbor rules can be distributed.")
+            }
+        }
+    }
+
+    fn run_board_discrete<T>(&self, save_dir: Option<String>, is_triangular: bool)
+    where
+        T: IntState,
+    {
+        match &self.board {
             BoardConfig::Grid2D {
                 shape: _,
                 initial_states: _,
             } => {
                 let space = self.gen_space_grid_2d().unwrap();
-                let neighbor = self.gen_neighbor_grid_2d(is_triangular).unwrap();
-                match self.state {
-                    StateConfig::UInt { count: _ } => {
-                        let state = self.gen_state_manager_grid_2d_discrete().unwrap();
-                        let strat = self.gen_strat_grid_2d_discrete().unwrap();
-                        let (callbacks, char_renderers, color_renderers) =
-                            self.gen_callback_grid_2d_discrete_state(save_dir, is_triangular);
-                        let mut board =
-                            StandardBoard::new(space, neighbor, state, strat, callbacks);
-                        std::thread::spawn(move || {
-                            board.advance(max_iter);
-                        });
-                        (char_renderers, color_renderers)
-                    }
-                }
+                let bit_life = self.gen_bit_life_grid_2d::<T>(&*space, is_triangular);
+                self.run_board_discrete_with(
+                    space,
+                    self.gen_neighbor_grid_2d(is_triangular).unwrap(),
+                    self.gen_strat_grid_2d_discrete::<T>().unwrap(),
+                    BoardAddOns {
+                        bit_life,
+                        grain_dropper: self.gen_grain_dropper_grid_2d(),
+                    },
+                    save_dir,
+                    |states, control| self.gen_grid_renderers(states, control, is_triangular),
+                )
+            }
+            BoardConfig::Grid3D {
+                shape,
+                initial_states: _,
+            } => {
+                let shape_vec = vec![shape.width(), shape.height(), shape.depth()];
+                let neighbor: Box<
+                    dyn BoardNeighborManager<
+                        GridPoint3D<IntIdx>,
+                        std::vec::IntoIter<GridPoint3D<IntIdx>>,
+                    >,
+                > = match &self.neighbor_rule {
+                    NeighborRuleConfig::VonNeumann => Box::new(NeighborVonNeumann::new()),
+                    _ => self.gen_neighbor_grid_nd().unwrap(),
+                };
+                self.run_board_discrete_with(
+                    Box::new(Grid::<GridPoint3D<IntIdx>>::new(shape_vec.into_iter())),
+                    neighbor,
+                    self.gen_strat_discrete::<GridPoint3D<IntIdx>, T>().unwrap(),
+                    BoardAddOns::default(),
+                    save_dir,
+                    |states, control| self.gen_grid_renderers(states, control, false),
+                )
             }
+            BoardConfig::GridND {
+                shape,
+                initial_states: _,
+            } => self.run_board_discrete_with(
+                Box::new(Grid::<GridPointND<IntIdx>>::new(shape.clone().into_iter())),
+                self.gen_neighbor_grid_nd().unwrap(),
+                self.gen_strat_discrete::<GridPointND<IntIdx>, T>().unwrap(),
+                BoardAddOns::default(),
+                save_dir,
+                |states, control| self.gen_grid_renderers(states, control, false),
+            ),
+            BoardConfig::Graph { .. } => {
+                let graph = self.graph();
+                let neighbor: Box<dyn BoardNeighborManager<usize, std::vec::IntoIter<usize>>> =
+                    match &self.neighbor_rule {
+                        NeighborRuleConfig::Edges => Box::new(NeighborsGraph::new(graph.clone())),
+                        _ => panic!("Graph boards only support the \"Edges\" neighbor rule."),
+                    };
+                self.run_board_discrete_with(
+                    Box::new(graph.clone()),
+                    neighbor,
+                    self.gen_strat_discrete::<usize, T>().unwrap(),
+                    BoardAddOns::default(),
+                    save_dir,
+                    |states, control| self.gen_graph_renderers(states, control),
+                )
+            }
+            BoardConfig::Tiling { .. } => {
+                let tiling = self.tiling();
+                let graph = match &self.neighbor_rule {
+                    NeighborRuleConfig::Edges => tiling.edge_graph(),
+                    NeighborRuleConfig::Vertices => tiling.vertex_graph(),
+                    _ => panic!(
+                        "Tiling boards only support the \"Edges\" and \"Vertices\" neighbor rules."
+                    ),
+                };
+                self.run_board_discrete_with(
+                    Box::new(tiling.clone()),
+                    Box::new(NeighborsGraph::new(graph.clone())),
+                    self.gen_strat_discrete::<usize, T>().unwrap(),
+                    BoardAddOns::default(),
+                    save_dir,
+                    |states, control| self.gen_tiling_renderers(states, control),
+                )
+            }
+        }
+    }
+
+    fn run_board_discrete_with<CI, T, F>(
+        &self,
+        space: Box<dyn BoardSpaceManager<CI, std::vec::IntoIter<CI>, rayon::vec::IntoIter<CI>>>,
+        neighbor: Box<dyn BoardNeighborManager<CI, std::vec::IntoIter<CI>>>,
+        strat: Box<dyn BoardStrategyManager<CI, T, std::vec::IntoIter<IndexedDataOwned<CI, T>>>>,
+        add_ons: BoardAddOns<CI, T>,
+        save_dir: Option<String>,
+        gen_renderers: F,
+    ) where
+        CI: BoardIndex,
+        T: IntState,
+        F: FnOnce(&StatesReadOnly<CI, T>, &Option<KeyboardControl>) -> Renderers<T>,
+    {
+        let max_iter = self.max_iter;
+        let state = CI::state_manager(T::zero(), self.gen_initial_states_discrete::<CI, T>());
+        let (mut callbacks, (char_renderers, color_renderers)) =
+            self.gen_callbacks_with(save_dir, T::zero(), gen_renderers);
+        let histogram = match &self.evolution_rule {
+            EvolutionRuleConfig::Sandpile { .. } => {
+                let histogram_callback = AvalancheHistogram::new(self.sandpile_threshold());
+                let histogram = histogram_callback.clone_read_only();
+                callbacks.push(BoardCallback::WithStates(Box::new(histogram_callback)));
+                Some(histogram)
+            }
+            _ => None,
+        };
+        let census = self.push_census_callback(&mut callbacks, T::zero());
+        let (spaceships, eraser) = self.push_spaceship_callback(&mut callbacks, T::zero());
+        let has_renderer = !char_renderers.is_empty() || !color_renderers.is_empty();
+        let board = StandardBoard::new(space, neighbor, state, strat, callbacks);
+        let board = match eraser {
+            Some(eraser) => board.with_cell_eraser(eraser),
+            None => board,
         };
+        let board = match add_ons.grain_dropper {
+            Some(grain_dropper) => board.with_grain_dropper(grain_dropper),
+            None => board,
+        };
+        // Boards from configs are bounded, compile their neighbors once if they are still needed.
+        let mut board = match add_ons.bit_life {
+            Some(bit_life) => board.with_bit_life(bit_life),
+            None => board.with_neighbor_table(),
+        };
+        let handle = std::thread::spawn(move || {
+            board.advance(max_iter);
+            if let Some(histogram) = histogram {
+                println!("Avalanche size histogram (size: count):");
+                for (size, count) in histogram.try_read().unwrap().iter() {
+                    println!("{}: {}", size, count);
+                }
+            }
+            if let Some((census, path)) = census {
+                write_census(&census, path);
+            }
+            if let Some((spaceships, path)) = spaceships {
+                write_spaceships(&spaceships, path);
+            }
+        });
+        self.run_renderers(
+            char_renderers,
+            color_renderers,
+            || self.char_maps_discrete(),
+            || self.color_maps_discrete(),
+        );
+        if !has_renderer {
+            handle.join().unwrap();
+        }
+    }
 
+    fn run_renderers<T, C, R>(
+        &self,
+        char_renderers: Vec<Box<dyn CellularAutomatonRenderer<T, char>>>,
+        color_renderers: Vec<Box<dyn CellularAutomatonRenderer<T, RGBA16>>>,
+        char_maps: C,
+        color_maps: R,
+    ) where
+        T: 'static,
+        C: Fn() -> Box<dyn StateVisualMapping<T, char>>,
+        R: Fn() -> Box<dyn StateVisualMapping<T, RGBA16>>,
+    {
+        let (mut char_renderers, mut color_renderers) = (char_renderers, color_renderers);
         if char_renderers.len() + color_renderers.len() == 1 {
             match char_renderers.first() {
-                Some(_) => char_renderers[0].as_mut().run(self.char_maps_discrete()),
-                None => color_renderers[0].as_mut().run(self.color_maps_discrete()),
+                Some(_) => char_renderers[0].as_mut().run(char_maps()),
+                None => color_renderers[0].as_mut().run(color_maps()),
             }
         } else {
             let mut main_renderer = None;
             let mut handles = Vec::with_capacity(char_renderers.len() + color_renderers.len());
             while !char_renderers.is_empty() {
                 let mut cur = char_renderers.pop().unwrap();
-                let char_map = self.char_maps_discrete();
+                let char_map = char_maps();
                 handles.push(thread::spawn(move || cur.run(char_map)));
             }
             while !color_renderers.is_empty() {
@@ -166,12 +1142,12 @@ impl CellularAutomatonConfig {
                 if cur.need_run_on_main() {
                     main_renderer = Some(cur);
                 } else {
-                    let color_map = self.color_maps_discrete();
+                    let color_map = color_maps();
                     handles.push(thread::spawn(move || cur.run(color_map)));
                 }
             }
             match main_renderer {
-                Some(mut renderer) => renderer.run(self.color_maps_discrete()),
+                Some(mut renderer) => renderer.run(color_maps()),
                 None => {
                     for handle in handles {
                         handle.join().unwrap()
@@ -202,6 +1178,7 @@ impl CellularAutomatonConfig {
                 let space_manager = Grid::<GridPoint2D<IntIdx>>::new(shape_vec.into_iter());
                 Ok(Box::new(space_manager))
             }
+            _ => Err(()),
         }
     }
 
@@ -225,14 +1202,9 @@ impl CellularAutomatonConfig {
                 }
             }
             NeighborRuleConfig::MooreWrap { margin } => {
-                let shape = match &self.board {
-                    BoardConfig::Grid2D {
-                        shape,
-                        initial_states: _,
-                    } => shape,
-                };
+                let shape = self.slice_shape();
                 if margin == &1 {
-                    Ok(Box::new(NeighborMooreDonut::new(shape.clone())))
+                    Ok(Box::new(NeighborMooreDonut::new(shape)))
                 } else {
                     Ok(Box::new(NeighborsGridDonut::new(
                         margin.clone(),
@@ -240,101 +1212,342 @@ impl CellularAutomatonConfig {
                     )))
                 }
             }
+            NeighborRuleConfig::Hex => Ok(Box::new(NeighborHex::new())),
+            NeighborRuleConfig::VonNeumann => Ok(Box::new(NeighborVonNeumann::new())),
+            NeighborRuleConfig::HexWrap => {
+                let shape = self.slice_shape();
+                Ok(Box::new(NeighborHexDonut::new(shape)))
+            }
+            NeighborRuleConfig::Kernel { weights: _ }
+            | NeighborRuleConfig::KernelWrap { weights: _ } => {
+                Ok(Box::new(self.gen_kernel_grid_2d()?))
+            }
+            NeighborRuleConfig::Edges | NeighborRuleConfig::Vertices => Err(()),
         }
     }
 
-    fn gen_state_manager_grid_2d_discrete(
+    /// Bit-parallel kernel for two-state alive count rules with the Moore neighborhood of margin
+    /// 1, `None` for every other config.
+    fn gen_bit_life_grid_2d<T>(
         &self,
-    ) -> Result<
-        Box<
-            dyn BoardStateManager<
-                IntState,
-                GridPoint2D<IntIdx>,
-                rayon::vec::IntoIter<IndexedDataOwned<GridPoint2D<IntIdx>, IntState>>,
-            >,
+        space: &dyn BoardSpaceManager<
+            GridPoint2D<IntIdx>,
+            std::vec::IntoIter<GridPoint2D<IntIdx>>,
+            rayon::vec::IntoIter<GridPoint2D<IntIdx>>,
         >,
-        (),
-    > {
-        match &self.state {
-            StateConfig::UInt { count } => {
-                let init_states = match &self.board {
-                    BoardConfig::Grid2D {
-                        shape,
-                        initial_states,
-                    } => match initial_states {
-                        InitialStatesConfig::Deterministic { positions } => positions
-                            .par_iter()
-                            .map(|(key, val)| {
-                                let cur_map: HashMap<GridPoint2D<IntIdx>, IntState> = val
-                                    .par_iter()
-                                    .map(|ele| {
-                                        (
-                                            ele.clone(),
-                                            key.parse::<IntState>().expect(
-                                                "Discrete states must be unsigned integers.",
-                                            ),
-                                        )
-                                    })
-                                    .collect();
-                                cur_map
-                            })
-                            .reduce(|| HashMap::new(), |a, b| a.into_iter().chain(b).collect()),
-                        InitialStatesConfig::Random { alive_ratio } => {
-                            gen_2d_random_discrete_states(shape, alive_ratio, count)
-                        }
-                    },
-                };
-                Ok(Box::new(SparseStates::new(0, init_states)))
+        is_triangular: bool,
+    ) -> Option<BitLife<GridPoint2D<IntIdx>, T>>
+    where
+        T: IntState,
+    {
+        let is_donut = match &self.neighbor_rule {
+            NeighborRuleConfig::Moore { margin: 1 } => false,
+            NeighborRuleConfig::MooreWrap { margin: 1 } => true,
+            _ => return None,
+        };
+        let state_count = match &self.state {
+            StateConfig::UInt { count, bits: _ } => *count,
+            StateConfig::GrayScott => return None,
+        };
+        let strat = match &self.evolution_rule {
+            EvolutionRuleConfig::AliveCount {
+                survive,
+                born,
+                second_order: false,
+            } if !is_triangular => DecayLifeLikeStrategy::new(
+                state_count,
+                collect_cell_counts(survive),
+                collect_cell_counts(born),
+            ),
+            _ => return None,
+        };
+        let rule = EvolutionStrategy::<
+            GridPoint2D<IntIdx>,
+            T,
+            std::vec::IntoIter<IndexedDataOwned<GridPoint2D<IntIdx>, T>>,
+        >::two_state_rule(&strat)?;
+        let shape = self.slice_shape();
+        Some(BitLife::new(
+            space,
+            shape.width(),
+            shape.height(),
+            is_donut,
+            rule,
+        ))
+    }
+
+    fn gen_neighbor_grid_nd<CI>(
+        &self,
+    ) -> Result<Box<dyn BoardNeighborManager<CI, std::vec::IntoIter<CI>>>, ()>
+    where
+        CI: BoardIndex,
+        NeighborsGridSurround<usize>: BoardNeighborManager<CI, std::vec::IntoIter<CI>>,
+        NeighborsGridDonut<usize>: BoardNeighborManager<CI, std::vec::IntoIter<CI>>,
+    {
+        match &self.neighbor_rule {
+            NeighborRuleConfig::Moore { margin } => {
+                Ok(Box::new(NeighborsGridSurround::new(*margin)))
             }
+            NeighborRuleConfig::MooreWrap { margin } => Ok(Box::new(NeighborsGridDonut::new(
+                *margin,
+                self.board_shape().into_iter(),
+            ))),
+            _ => Err(()),
         }
     }
 
-    fn gen_strat_grid_2d_discrete(
-        &self,
-    ) -> Result<
+    fn gen_initial_states_discrete<CI, T>(&self) -> HashMap<CI, T>
+    where
+        CI: BoardIndex,
+        T: IntState,
+    {
+        let state_count = match &self.state {
+            StateConfig::UInt { count, bits: _ } => count,
+            StateConfig::GrayScott => unreachable!("Gray-Scott states are not discrete."),
+        };
+        match CI::initial_states_config(&self.board) {
+            InitialStatesConfig::Deterministic { positions } => parse_positions(positions),
+            InitialStatesConfig::Random {
+                alive_ratio,
+                positions,
+            } => {
+                let shape = self.board_shape();
+                let mut res = match &self.evolution_rule {
+                    EvolutionRuleConfig::LatticeGas { model, color_by: _ } => {
+                        gen_random_lattice_gas_states(&shape, alive_ratio, &model.to_model())
+                    }
+                    _ => gen_random_discrete_states(&shape, alive_ratio, state_count),
+                };
+                res.extend(parse_positions(positions));
+                res
+            }
+        }
+    }
+
+    /// Drops the grains of driven sandpiles through the board, so they follow its generations.
+    fn gen_grain_dropper_grid_2d<T>(&self) -> Option<GrainDropper<GridPoint2D<IntIdx>, T>>
+    where
+        T: IntState,
+    {
+        match &self.evolution_rule {
+            EvolutionRuleConfig::Sandpile {
+                threshold: _,
+                driver: Some(driver),
+            } => Some(GrainDropper::new(
+                driver.positions.iter().cloned(),
+                driver.interval,
+            )),
+            _ => None,
+        }
+    }
+
+    fn gen_strat_grid_2d_discrete<T>(
+        &self,
+    ) -> Result<
         Box<
             dyn BoardStrategyManager<
                 GridPoint2D<IntIdx>,
-                IntState,
-                std::vec::IntoIter<IndexedDataOwned<GridPoint2D<IntIdx>, IntState>>,
+                T,
+                std::vec::IntoIter<IndexedDataOwned<GridPoint2D<IntIdx>, T>>,
             >,
         >,
         (),
-    > {
+    >
+    where
+        T: IntState,
+    {
+        match &self.evolution_rule {
+            EvolutionRuleConfig::LatticeGas { model, color_by: _ } => Ok(Box::new(
+                SharedStrategyManager::new(Box::new(LatticeGasStrategy::new(model.to_model()))),
+            )),
+            // Grains are dropped by the board, see `gen_grain_dropper_grid_2d`.
+            EvolutionRuleConfig::Sandpile {
+                threshold: _,
+                driver: Some(_),
+            } => Ok(Box::new(SharedStrategyManager::new(Box::new(
+                SandpileStrategy::new(self.sandpile_threshold()),
+            )))),
+            EvolutionRuleConfig::WeightedSum { survive, born } => {
+                let state_count = match &self.state {
+                    StateConfig::UInt { count, bits: _ } => *count,
+                    StateConfig::GrayScott => return Err(()),
+                };
+                Ok(Box::new(SharedStrategyManager::new(Box::new(
+                    WeightedLifeLikeStrategy::new(
+                        state_count,
+                        self.gen_kernel_grid_2d()?,
+                        collect_weighted_sums(survive),
+                        collect_weighted_sums(born),
+                    ),
+                ))))
+            }
+            _ => self.gen_strat_discrete(),
+        }
+    }
+
+    /// Strategies that work with any grid index.
+    fn gen_strat_discrete<CI, T>(
+        &self,
+    ) -> Result<Box<dyn BoardStrategyManager<CI, T, std::vec::IntoIter<IndexedDataOwned<CI, T>>>>, ()>
+    where
+        CI: BoardIndex,
+        T: IntState,
+    {
         let state_count = match &self.state {
-            StateConfig::UInt { count } => count,
+            StateConfig::UInt { count, bits: _ } => count,
+            StateConfig::GrayScott => return Err(()),
         };
         match &self.evolution_rule {
-            EvolutionRuleConfig::AliveCount { survive, born } => Ok(Box::new(
-                SharedStrategyManager::new(Box::new(DecayLifeLikeStrategy::new(
+            EvolutionRuleConfig::AliveCount {
+                survive,
+                born,
+                second_order,
+            } => {
+                let strat = DecayLifeLikeStrategy::new(
                     state_count.clone(),
-                    collect_cell_counts(&survive),
-                    collect_cell_counts(&born),
-                ))),
-            )),
+                    collect_cell_counts(survive),
+                    collect_cell_counts(born),
+                );
+                Ok(Box::new(SharedStrategyManager::new(if *second_order {
+                    Box::new(SecondOrderStrategy::from_life_like(strat))
+                } else {
+                    Box::new(strat)
+                })))
+            }
+            EvolutionRuleConfig::Sandpile {
+                threshold: _,
+                driver: None,
+            } => Ok(Box::new(SharedStrategyManager::new(Box::new(
+                SandpileStrategy::new(self.sandpile_threshold()),
+            )))),
+            _ => Err(()),
+        }
+    }
+
+    fn gen_stencil_grid_2d(&self) -> Result<WeightedStencil2D, ()> {
+        let stencil = WeightedStencil2D::laplacian_9_point();
+        match &self.neighbor_rule {
+            NeighborRuleConfig::Moore { margin: 1 } => Ok(stencil),
+            NeighborRuleConfig::MooreWrap { margin: 1 } => {
+                let shape = self.slice_shape();
+                Ok(stencil.with_wrap(shape))
+            }
+            _ => Err(()),
+        }
+    }
+
+    fn gen_kernel_grid_2d(&self) -> Result<NeighborsWeightedKernel, ()> {
+        match &self.neighbor_rule {
+            NeighborRuleConfig::Kernel { weights } => {
+                Ok(NeighborsWeightedKernel::new(weights.clone()))
+            }
+            NeighborRuleConfig::KernelWrap { weights } => {
+                let shape = self.slice_shape();
+                Ok(NeighborsWeightedKernel::new(weights.clone()).with_wrap(shape))
+            }
+            _ => Err(()),
         }
     }
 
-    fn gen_callback_grid_2d_discrete_state(
+    fn gen_state_manager_grid_2d_gray_scott(
         &self,
-        save_dir: Option<String>,
-        is_triangular: bool,
-    ) -> (
-        Vec<
-            BoardCallback<
-                IntState,
+    ) -> Result<
+        Box<
+            dyn BoardStateManager<
+                GrayScottState,
                 GridPoint2D<IntIdx>,
-                rayon::vec::IntoIter<IndexedDataOwned<GridPoint2D<IntIdx>, IntState>>,
+                rayon::vec::IntoIter<IndexedDataOwned<GridPoint2D<IntIdx>, GrayScottState>>,
             >,
         >,
-        Vec<Box<dyn CellularAutomatonRenderer<IntState, char>>>,
-        Vec<Box<dyn CellularAutomatonRenderer<IntState, RGBA16>>>,
-    ) {
+        (),
+    > {
+        let init_states = match &self.board {
+            BoardConfig::Grid2D {
+                shape,
+                initial_states,
+            } => match initial_states {
+                InitialStatesConfig::Deterministic { positions } => {
+                    parse_gray_scott_positions(positions)
+                }
+                InitialStatesConfig::Random {
+                    alive_ratio,
+                    positions,
+                } => {
+                    let mut res = gen_2d_random_gray_scott_states(shape, alive_ratio);
+                    res.extend(parse_gray_scott_positions(positions));
+                    res
+                }
+            },
+            _ => return Err(()),
+        };
+        Ok(Box::new(PackedSparseStates::new(
+            GrayScottState::default(),
+            init_states,
+        )))
+    }
+
+    fn gen_strat_grid_2d_gray_scott(
+        &self,
+    ) -> Result<
+        Box<
+            dyn BoardStrategyManager<
+                GridPoint2D<IntIdx>,
+                GrayScottState,
+                std::vec::IntoIter<IndexedDataOwned<GridPoint2D<IntIdx>, GrayScottState>>,
+            >,
+        >,
+        (),
+    > {
+        match &self.evolution_rule {
+            EvolutionRuleConfig::GrayScott {
+                preset,
+                params,
+                color_by: _,
+            } => {
+                let params = match (params, preset) {
+                    (Some(params), _) => *params,
+                    (None, Some(preset)) => GrayScottParams::from_preset(*preset),
+                    (None, None) => return Err(()),
+                };
+                Ok(Box::new(SharedStrategyManager::new(Box::new(
+                    GrayScottStrategy::new(params, self.gen_stencil_grid_2d()?),
+                ))))
+            }
+            _ => Err(()),
+        }
+    }
+
+    fn gen_callbacks<CI, T>(
+        &self,
+        save_dir: Option<String>,
+        is_triangular: bool,
+        trivial_state: T,
+    ) -> (BoardCallbacks<T, CI>, Renderers<T>)
+    where
+        CI: BoardIndex + RenderIndex2D<T>,
+        T: 'static + Send + Sync + Clone + PartialEq + Serialize,
+    {
+        self.gen_callbacks_with(save_dir, trivial_state, |states, control| {
+            self.gen_grid_renderers(states, control, is_triangular)
+        })
+    }
+
+    /// Control, states and history callbacks, renderers reading the states come from
+    /// `gen_renderers`.
+    fn gen_callbacks_with<CI, T, F>(
+        &self,
+        save_dir: Option<String>,
+        trivial_state: T,
+        gen_renderers: F,
+    ) -> (BoardCallbacks<T, CI>, Renderers<T>)
+    where
+        CI: BoardIndex,
+        T: 'static + Send + Sync + Clone + PartialEq + Serialize,
+        F: FnOnce(&StatesReadOnly<CI, T>, &Option<KeyboardControl>) -> Renderers<T>,
+    {
         let mut callbacks = Vec::new();
-        let mut char_renderers: Vec<Box<dyn CellularAutomatonRenderer<IntState, char>>> =
-            Vec::new();
-        let mut color_renderers: Vec<Box<dyn CellularAutomatonRenderer<IntState, RGBA16>>> =
-            Vec::new();
+        let mut char_renderers: Vec<Box<dyn CellularAutomatonRenderer<T, char>>> = Vec::new();
+        let mut color_renderers: Vec<Box<dyn CellularAutomatonRenderer<T, RGBA16>>> = Vec::new();
 
         if self.visual.on && !self.visual.styles.is_empty() {
             let one_billion_nano_sec: f64 = 1_000_000_000f64;
@@ -350,69 +1563,14 @@ impl CellularAutomatonConfig {
             } else {
                 None
             };
-            let board_shape = match &self.board {
-                BoardConfig::Grid2D {
-                    shape,
-                    initial_states: _,
-                } => shape.clone(),
-            };
-            let states_callback: StatesCallback<GridPoint2D<IntIdx>, IntState> =
-                StatesCallback::new(0);
+            let states_callback: StatesCallback<CI, T> = StatesCallback::new(trivial_state.clone());
             let states_read_only = states_callback.clone_read_only();
             let states_callback = BoardCallback::WithStates(Box::new(states_callback));
             callbacks.push(states_callback);
 
-            for style in self.visual.styles.iter() {
-                match style {
-                    VisualStyle::Graphical => {
-                        let graphical_renderer = GraphicalRendererGrid2D::new(
-                            board_shape.width(),
-                            board_shape.height(),
-                            states_read_only.clone(),
-                        );
-
-                        match graphical_renderer {
-                            Ok(val) => {
-                                let mut real_gui_renderer = val.with_title(self.title.clone());
-                                if is_triangular {
-                                    real_gui_renderer = real_gui_renderer.with_triangles();
-                                }
-
-                                let res = match &keyboard_control {
-                                    Some(control) => {
-                                        real_gui_renderer.with_keyboard_control(control.clone())
-                                    }
-                                    None => real_gui_renderer,
-                                };
-                                color_renderers.push(Box::new(res));
-                            }
-                            Err(err) => eprintln!("Error creating graphical renderer: {:?}", err),
-                        };
-                    }
-                    VisualStyle::Ascii => {
-                        #[cfg(not(feature = "ascii"))]
-                        eprintln!("Cannot create ASCII renderer, please recompile with \"--features ascii\",");
-                        #[cfg(feature = "ascii")]
-                        {
-                            use gol_renderer::TextRendererGrid2D;
-
-                            let text_renderer = TextRendererGrid2D::new(
-                                board_shape.width(),
-                                board_shape.height(),
-                                states_read_only.clone(),
-                            )
-                            .with_title(self.title.clone());
-                            let res = match &keyboard_control {
-                                Some(control) => {
-                                    text_renderer.with_keyboard_control(control.clone())
-                                }
-                                None => text_renderer,
-                            };
-                            char_renderers.push(Box::new(res));
-                        }
-                    }
-                }
-            }
+            let (chars, colors) = gen_renderers(&states_read_only, &keyboard_control);
+            char_renderers = chars;
+            color_renderers = colors;
         }
 
         let mut found_must_main_thread = false;
@@ -439,42 +1597,491 @@ impl CellularAutomatonConfig {
 
         if save_dir.is_some() {
             let dir = save_dir.unwrap();
-            match &self.board {
-                BoardConfig::Grid2D {
-                    shape,
-                    initial_states: _,
-                } => {
-                    let num_states = match &self.state {
-                        StateConfig::UInt { count } => count,
-                    }
-                    .clone();
+            match &self.state {
+                StateConfig::UInt { count, bits: _ } => {
                     let serializer: BatchIndexedSerializer<
-                        Vec<IndexedDataOwned<GridPoint2D<IntIdx>, IntState>>,
-                        (Shape2D, usize), // Header with shape and number of states.
-                    > = BatchIndexedSerializer::new(100).with_header((shape.clone(), num_states));
+                        Vec<IndexedDataOwned<CI, T>>,
+                        HistoryHeader,
+                    > = BatchIndexedSerializer::new(100).with_header(HistoryHeader::new(
+                        self.board_shape(),
+                        *count,
+                        self.state_bits(),
+                        std::mem::size_of::<IntIdx>() * 8,
+                    ));
                     let serializer = BatchSerializerLocal::new(&dir, serializer);
-                    let serializer = StateSerializerLocal::new(serializer, 0);
+                    let serializer = StateSerializerLocal::new(serializer, trivial_state);
                     callbacks.push(BoardCallback::WithStates(Box::new(serializer)));
                 }
+                StateConfig::GrayScott => {
+                    eprintln!("Saving history is only supported for discrete states.")
+                }
+            }
+        }
+
+        (callbacks, (char_renderers, color_renderers))
+    }
+
+    fn gen_grid_renderers<CI, T>(
+        &self,
+        states_read_only: &StatesReadOnly<CI, T>,
+        keyboard_control: &Option<KeyboardControl>,
+        is_triangular: bool,
+    ) -> Renderers<T>
+    where
+        CI: BoardIndex + RenderIndex2D<T>,
+        T: 'static + Send + Sync + Clone,
+    {
+        // Only text renderers are char renderers, they need the ascii feature.
+        #[cfg_attr(not(feature = "ascii"), allow(unused_mut))]
+        let mut char_renderers: Vec<Box<dyn CellularAutomatonRenderer<T, char>>> = Vec::new();
+        let mut color_renderers: Vec<Box<dyn CellularAutomatonRenderer<T, RGBA16>>> = Vec::new();
+        let board_shape = self.slice_shape();
+        let slice_view = CI::slice_view(&self.board_shape());
+
+        for style in self.visual.styles.iter() {
+            match style {
+                VisualStyle::Graphical => {
+                    let graphical_renderer = GraphicalRendererGrid2D::new(
+                        board_shape.width(),
+                        board_shape.height(),
+                        states_read_only.clone(),
+                    );
+
+                    match graphical_renderer {
+                        Ok(val) => {
+                            let mut real_gui_renderer = val.with_title(self.title.clone());
+                            if let Some(view) = &slice_view {
+                                real_gui_renderer = real_gui_renderer.with_slice_view(view.clone());
+                            }
+                            if self.is_hex() {
+                                real_gui_renderer = real_gui_renderer.with_hex_offset_rows();
+                            } else if is_triangular {
+                                real_gui_renderer = real_gui_renderer.with_triangles();
+                            }
+
+                            let res = match &keyboard_control {
+                                Some(control) => {
+                                    real_gui_renderer.with_keyboard_control(control.clone())
+                                }
+                                None => real_gui_renderer,
+                            };
+                            color_renderers.push(Box::new(res));
+                        }
+                        Err(err) => eprintln!("Error creating graphical renderer: {:?}", err),
+                    };
+                }
+                VisualStyle::Voxel => {
+                    if self.board_shape().len() != 3 {
+                        eprintln!("Voxel renderer only supports 3D boards.");
+                    } else if let Some(renderer) = CI::voxel_renderer(
+                        &self.board_shape(),
+                        states_read_only.clone(),
+                        self.title.clone(),
+                        keyboard_control.clone(),
+                    ) {
+                        color_renderers.push(renderer);
+                    }
+                }
+                VisualStyle::Ascii => {
+                    #[cfg(not(feature = "ascii"))]
+                    eprintln!(
+                        "Cannot create ASCII renderer, please recompile with \"--features ascii\","
+                    );
+                    #[cfg(feature = "ascii")]
+                    {
+                        use gol_renderer::TextRendererGrid2D;
+
+                        let mut text_renderer = TextRendererGrid2D::new(
+                            board_shape.width(),
+                            board_shape.height(),
+                            states_read_only.clone(),
+                        )
+                        .with_title(self.title.clone());
+                        if let Some(view) = &slice_view {
+                            text_renderer = text_renderer.with_slice_view(view.clone());
+                        }
+                        let res = match &keyboard_control {
+                            Some(control) => text_renderer.with_keyboard_control(control.clone()),
+                            None => text_renderer,
+                        };
+                        char_renderers.push(Box::new(res));
+                    }
+                }
             }
         }
 
-        (callbacks, char_renderers, color_renderers)
+        (char_renderers, color_renderers)
+    }
+
+    fn gen_graph_renderers<T>(
+        &self,
+        states_read_only: &StatesReadOnly<usize, T>,
+        keyboard_control: &Option<KeyboardControl>,
+    ) -> Renderers<T>
+    where
+        T: 'static + Send + Sync + Clone,
+    {
+        let mut color_renderers: Vec<Box<dyn CellularAutomatonRenderer<T, RGBA16>>> = Vec::new();
+        for style in self.visual.styles.iter() {
+            match style {
+                VisualStyle::Graphical => {
+                    match GraphicalRendererGraph::new(
+                        self.graph().clone(),
+                        states_read_only.clone(),
+                    ) {
+                        Ok(val) => {
+                            let renderer = val.with_title(self.title.clone());
+                            color_renderers.push(Box::new(match keyboard_control {
+                                Some(control) => renderer.with_keyboard_control(control.clone()),
+                                None => renderer,
+                            }));
+                        }
+                        Err(err) => eprintln!("Error creating graph renderer: {:?}", err),
+                    }
+                }
+                _ => eprintln!("Graph boards can only be rendered with the graphical style."),
+            }
+        }
+        (Vec::new(), color_renderers)
     }
 
-    fn char_maps_discrete(&self) -> Box<dyn StateVisualMapping<IntState, char>> {
+    fn gen_tiling_renderers<T>(
+        &self,
+        states_read_only: &StatesReadOnly<usize, T>,
+        keyboard_control: &Option<KeyboardControl>,
+    ) -> Renderers<T>
+    where
+        T: 'static + Send + Sync + Clone,
+    {
+        let mut color_renderers: Vec<Box<dyn CellularAutomatonRenderer<T, RGBA16>>> = Vec::new();
+        for style in self.visual.styles.iter() {
+            match style {
+                VisualStyle::Graphical => {
+                    match GraphicalRendererTiling::new(
+                        self.tiling().clone(),
+                        states_read_only.clone(),
+                    ) {
+                        Ok(val) => {
+                            let renderer = val.with_title(self.title.clone());
+                            color_renderers.push(Box::new(match keyboard_control {
+                                Some(control) => renderer.with_keyboard_control(control.clone()),
+                                None => renderer,
+                            }));
+                        }
+                        Err(err) => eprintln!("Error creating tiling renderer: {:?}", err),
+                    }
+                }
+                _ => eprintln!("Tiling boards can only be rendered with the graphical style."),
+            }
+        }
+        (Vec::new(), color_renderers)
+    }
+
+    /// Graph of a graph board, loaded or generated on first call.
+    fn graph(&self) -> &Graph {
+        match &self.board {
+            BoardConfig::Graph { graph, loaded, .. } => loaded.get_or_init(|| graph.to_graph()),
+            _ => panic!("Expected a graph board."),
+        }
+    }
+
+    /// Tiling of a tiling board, generated on first call.
+    fn tiling(&self) -> &Tiling {
+        match &self.board {
+            BoardConfig::Tiling { tiling, loaded, .. } => loaded.get_or_init(|| tiling.to_tiling()),
+            _ => panic!("Expected a tiling board."),
+        }
+    }
+
+    /// Length of each board dimension.
+    fn board_shape(&self) -> Vec<usize> {
+        match &self.board {
+            BoardConfig::Grid2D {
+                shape,
+                initial_states: _,
+            } => vec![shape.width(), shape.height()],
+            BoardConfig::Grid3D {
+                shape,
+                initial_states: _,
+            } => vec![shape.width(), shape.height(), shape.depth()],
+            BoardConfig::GridND {
+                shape,
+                initial_states: _,
+            } => shape.clone(),
+            BoardConfig::Graph { .. } => vec![self.graph().node_count()],
+            BoardConfig::Tiling { .. } => vec![self.tiling().tile_count()],
+        }
+    }
+
+    /// Shape of the board, or of the rendered slice for boards with more than two dimensions.
+    fn slice_shape(&self) -> Shape2D {
+        let shape = self.board_shape();
+        assert!(
+            shape.len() >= 2,
+            "Boards must have at least two dimensions."
+        );
+        Shape2D::new(shape[0], shape[1])
+    }
+
+    fn is_hex(&self) -> bool {
+        matches!(
+            &self.neighbor_rule,
+            NeighborRuleConfig::Hex | NeighborRuleConfig::HexWrap
+        )
+    }
+
+    /// Width of discrete states, the narrowest supported integer that fits all states if not
+    /// specified in the configuration.
+    fn state_bits(&self) -> usize {
+        match &self.state {
+            StateConfig::UInt { count, bits } => {
+                let min_bits = match count {
+                    0..=0x100 => 8,
+                    0x101..=0x1_0000 => 16,
+                    _ => 32,
+                };
+                match bits {
+                    Some(bits) => {
+                        assert!(
+                            bits >= &min_bits,
+                            "{} states do not fit in {} bits.",
+                            count,
+                            bits
+                        );
+                        *bits
+                    }
+                    None => min_bits,
+                }
+            }
+            StateConfig::GrayScott => unreachable!("Gray-Scott states are not discrete."),
+        }
+    }
+
+    /// Adds the census callback if a census is configured, returns the census of the last
+    /// generation and the path it is written to.
+    fn push_census_callback<CI, T>(
+        &self,
+        callbacks: &mut Vec<BoardCallback<T, CI>>,
+        dead_state: T,
+    ) -> Option<(ReadOnlyLock<Option<Census>>, Option<String>)>
+    where
+        CI: BoardIndex,
+        T: IntState,
+    {
+        let census = self.gen_census()?;
+        let (callback, census) = CI::census_callback(census, dead_state)
+            .expect("Census is only supported by 2D grid boards.");
+        callbacks.push(callback);
+        let path = self.census.as_ref().unwrap().path.clone();
+        Some((census, path))
+    }
+
+    /// Adds the spaceship detector if configured, returns the spaceships it finds, the path they
+    /// are written to and the eraser of the board.
+    fn push_spaceship_callback<CI, T>(
+        &self,
+        callbacks: &mut Vec<BoardCallback<T, CI>>,
+        dead_state: T,
+    ) -> (Option<FoundSpaceships>, Option<CellEraser<CI, T>>)
+    where
+        CI: BoardIndex,
+        T: IntState,
+    {
+        let config = match &self.spaceships {
+            Some(config) => config,
+            None => return (None, None),
+        };
+        let census = self.gen_object_census("Spaceship detection");
+        let census = match config.max_period {
+            Some(max_period) => census.with_max_period(max_period),
+            None => census,
+        };
+        let (callback, spaceships, eraser) =
+            CI::spaceship_callback(census, dead_state, &self.board, config.remove_at_edge)
+                .expect("Spaceship detection is only supported by 2D grid boards.");
+        callbacks.push(callback);
+        (Some((spaceships, config.path.clone())), eraser)
+    }
+
+    /// Census of the config, `None` if not configured.
+    fn gen_census(&self) -> Option<ObjectCensus> {
+        let config = self.census.as_ref()?;
+        let census = self.gen_object_census("Census");
+        let census = match config.distance {
+            Some(distance) => census.with_distance(distance),
+            None => census,
+        };
+        Some(match config.max_period {
+            Some(max_period) => census.with_max_period(max_period),
+            None => census,
+        })
+    }
+
+    /// Census of the rule, `feature` is the name of the analysis in the panic message of
+    /// unsupported rules.
+    fn gen_object_census(&self, feature: &str) -> ObjectCensus {
+        let is_moore = matches!(
+            self.neighbor_rule,
+            NeighborRuleConfig::Moore { margin: 1 } | NeighborRuleConfig::MooreWrap { margin: 1 }
+        );
+        match (&self.state, &self.evolution_rule) {
+            (
+                StateConfig::UInt { count: 2, bits: _ },
+                EvolutionRuleConfig::AliveCount {
+                    survive,
+                    born,
+                    second_order: false,
+                },
+            ) if is_moore => {
+                ObjectCensus::new(&collect_cell_counts(survive), &collect_cell_counts(born))
+            }
+            _ => panic!(
+                "{} is only supported by two-state \"AliveCount\" rules with the Moore neighbor rule.",
+                feature
+            ),
+        }
+    }
+
+    fn sandpile_threshold(&self) -> usize {
+        match &self.evolution_rule {
+            EvolutionRuleConfig::Sandpile {
+                threshold: Some(threshold),
+                driver: _,
+            } => *threshold,
+            _ => SandpileStrategy::default().threshold(),
+        }
+    }
+
+    fn char_maps_discrete<T>(&self) -> Box<dyn StateVisualMapping<T, char>>
+    where
+        T: IntState,
+    {
+        match &self.evolution_rule {
+            EvolutionRuleConfig::LatticeGas { model, color_by: _ } => {
+                return Box::new(LatticeGasCharMap::new(model.to_model()))
+            }
+            EvolutionRuleConfig::Sandpile { .. } => {
+                return Box::new(SandpileCharMap::new(self.sandpile_threshold()))
+            }
+            _ => (),
+        }
         match &self.state {
-            StateConfig::UInt { count } => Box::new(DiscreteStateCharMap::new(*count)),
+            StateConfig::UInt { count, bits: _ } => Box::new(DiscreteStateCharMap::new(*count)),
+            StateConfig::GrayScott => unreachable!("Gray-Scott states are not discrete."),
         }
     }
 
-    fn color_maps_discrete(&self) -> Box<dyn StateVisualMapping<IntState, RGBA16>> {
+    fn color_maps_discrete<T>(&self) -> Box<dyn StateVisualMapping<T, RGBA16>>
+    where
+        T: IntState,
+    {
+        match &self.evolution_rule {
+            EvolutionRuleConfig::LatticeGas { model, color_by } => {
+                let color_map = LatticeGasColorMap::new(model.to_model());
+                return Box::new(match color_by {
+                    LatticeGasColorConfig::Density => color_map,
+                    LatticeGasColorConfig::Momentum => color_map.with_momentum(),
+                });
+            }
+            EvolutionRuleConfig::Sandpile { .. } => {
+                return Box::new(SandpileColorMap::new(self.sandpile_threshold()))
+            }
+            _ => (),
+        }
         match &self.state {
-            StateConfig::UInt { count } => Box::new(DiscreteStateColorMap::new(*count)),
+            StateConfig::UInt { count, bits: _ } => Box::new(DiscreteStateColorMap::new(*count)),
+            StateConfig::GrayScott => unreachable!("Gray-Scott states are not discrete."),
+        }
+    }
+
+    fn char_maps_gray_scott(&self) -> Box<dyn StateVisualMapping<GrayScottState, char>> {
+        match &self.evolution_rule {
+            EvolutionRuleConfig::GrayScott {
+                preset: _,
+                params: _,
+                color_by: GrayScottColorConfig::U,
+            } => Box::new(GrayScottCharMap::new().with_u_concentration()),
+            _ => Box::new(GrayScottCharMap::new()),
+        }
+    }
+
+    fn color_maps_gray_scott(&self) -> Box<dyn StateVisualMapping<GrayScottState, RGBA16>> {
+        match &self.evolution_rule {
+            EvolutionRuleConfig::GrayScott {
+                preset: _,
+                params: _,
+                color_by: GrayScottColorConfig::U,
+            } => Box::new(GrayScottColorMap::new().with_u_concentration()),
+            _ => Box::new(GrayScottColorMap::new()),
+        }
+    }
+}
+
+impl GraphConfig {
+    fn to_graph(&self) -> Graph {
+        match self {
+            GraphConfig::EdgeListFile { path } => {
+                Graph::from_edge_list_json(&read_graph_file(path)).expect("Invalid edge list.")
+            }
+            GraphConfig::AdjacencyFile { path } => {
+                Graph::from_adjacency_json(&read_graph_file(path)).expect("Invalid adjacency list.")
+            }
+            GraphConfig::WattsStrogatz {
+                node_count,
+                degree,
+                rewire_probability,
+                seed,
+            } => Graph::watts_strogatz(*node_count, *degree, *rewire_probability, *seed),
+            GraphConfig::ErdosRenyi {
+                node_count,
+                edge_probability,
+                seed,
+            } => Graph::erdos_renyi(*node_count, *edge_probability, *seed),
         }
     }
 }
 
+impl TilingConfig {
+    fn to_tiling(&self) -> Tiling {
+        match self {
+            TilingConfig::Penrose { subdivisions } => Tiling::penrose_rhombus(*subdivisions),
+            TilingConfig::Voronoi { tile_count, seed } => Tiling::voronoi(*tile_count, *seed),
+        }
+    }
+}
+
+fn read_graph_file(path: &String) -> String {
+    std::fs::read_to_string(path)
+        .unwrap_or_else(|err| panic!("Cannot read graph file {}: {}", path, err))
+}
+
+impl LatticeGasModelConfig {
+    fn to_model(&self) -> LatticeGasModel {
+        match self {
+            Self::Hpp => LatticeGasModel::Hpp,
+            Self::Fhp => LatticeGasModel::Fhp,
+        }
+    }
+}
+
+/// Writes the census as JSON to the path, or prints it if there is no path.
+fn write_census(census: &ReadOnlyLock<Option<Census>>, path: Option<String>) {
+    let json = census.try_read().unwrap().as_ref().unwrap().to_json();
+    match path {
+        Some(path) => std::fs::write(&path, json).unwrap(),
+        None => println!("{}", json),
+    }
+}
+
+/// Writes the spaceships as JSON to the path, or prints them if there is no path.
+fn write_spaceships(spaceships: &ReadOnlyLock<Vec<Spaceship>>, path: Option<String>) {
+    let json = serde_json::to_string_pretty(&*spaceships.try_read().unwrap()).unwrap();
+    match path {
+        Some(path) => std::fs::write(&path, json).unwrap(),
+        None => println!("{}", json),
+    }
+}
+
 fn collect_cell_counts(counts: &Vec<CellCount>) -> HashSet<usize> {
     counts
         .par_iter()
@@ -487,6 +2094,57 @@ fn collect_cell_counts(counts: &Vec<CellCount>) -> HashSet<usize> {
         .reduce(|| HashSet::new(), |a, b| a.union(&b).cloned().collect())
 }
 
+fn collect_weighted_sums(sums: &[WeightedSum]) -> Vec<(f64, f64)> {
+    sums.iter()
+        .map(|ele| match ele {
+            WeightedSum::Value(val) => (*val, *val),
+            WeightedSum::Range(range) => (*range.first().unwrap(), *range.last().unwrap()),
+        })
+        .collect()
+}
+
+/// Cells on each side of the center a kernel reaches, in either direction.
+fn kernel_radius(weights: &[Vec<f64>]) -> usize {
+    let row_len = weights.iter().map(|row| row.len()).max().unwrap_or(0);
+    weights.len().max(row_len) / 2
+}
+
+fn parse_positions<CI, T>(positions: &HashMap<String, Vec<CI>>) -> HashMap<CI, T>
+where
+    CI: BoardIndex,
+    T: IntState,
+{
+    positions
+        .par_iter()
+        .map(|(key, val)| {
+            let cur_map: HashMap<CI, T> = val
+                .par_iter()
+                .map(|ele| {
+                    (
+                        ele.clone(),
+                        key.parse::<T>()
+                            .ok()
+                            .expect("Discrete states must be unsigned integers within range."),
+                    )
+                })
+                .collect();
+            cur_map
+        })
+        .reduce(HashMap::new, |a, b| a.into_iter().chain(b).collect())
+}
+
+fn flat_idx_to_indices(i: usize, shape: &[usize]) -> Vec<IntIdx> {
+    let mut cur = i;
+    shape
+        .iter()
+        .map(|len| {
+            let res = (cur % len) as i64 - (*len as i64) / 2;
+            cur /= len;
+            res as IntIdx
+        })
+        .collect()
+}
+
 fn gen_random_usize(len: &usize, alive_ratio: &f32) -> HashSet<usize> {
     let core_count = num_cpus::get();
     let num_indices_per_thread = len / core_count + 1;
@@ -510,11 +2168,78 @@ fn gen_random_usize(len: &usize, alive_ratio: &f32) -> HashSet<usize> {
         .reduce(|| HashSet::new(), |a, b| a.union(&b).cloned().collect())
 }
 
-fn gen_2d_random_discrete_states(
-    board_shape: &Shape2D,
+fn gen_random_discrete_states<CI, T>(
+    board_shape: &[usize],
     alive_ratio: &f32,
     state_count: &usize,
-) -> HashMap<GridPoint2D<IntIdx>, IntState> {
+) -> HashMap<CI, T>
+where
+    CI: BoardIndex,
+    T: IntState,
+{
+    let res = gen_random_usize(&board_shape.iter().product(), alive_ratio);
+    res.into_par_iter()
+        .map(|ele| {
+            (
+                CI::from_flat_idx(ele, board_shape),
+                T::from_usize(state_count - 1).unwrap(),
+            )
+        })
+        .collect()
+}
+
+fn gen_random_lattice_gas_states<CI, T>(
+    board_shape: &[usize],
+    alive_ratio: &f32,
+    model: &LatticeGasModel,
+) -> HashMap<CI, T>
+where
+    CI: BoardIndex,
+    T: IntState,
+{
+    let channel_count = model.channel_count();
+    let volume: usize = board_shape.iter().product();
+    let res = gen_random_usize(&(volume * channel_count), alive_ratio);
+    let mut states: HashMap<CI, T> = HashMap::new();
+    for ele in res.into_iter() {
+        let (cell, channel) = (ele / channel_count, ele % channel_count);
+        let state = states
+            .entry(CI::from_flat_idx(cell, board_shape))
+            .or_insert_with(T::zero);
+        *state = *state | T::one() << channel;
+    }
+    states
+}
+
+fn parse_gray_scott_positions(
+    positions: &HashMap<String, Vec<GridPoint2D<IntIdx>>>,
+) -> HashMap<GridPoint2D<IntIdx>, GrayScottState> {
+    positions
+        .iter()
+        .flat_map(|(key, val)| {
+            let concentrations: Vec<f64> = key
+                .split(',')
+                .map(|ele| {
+                    ele.trim()
+                        .parse::<f64>()
+                        .expect("Gray-Scott states must be formatted as \"u,v\".")
+                })
+                .collect();
+            assert_eq!(
+                concentrations.len(),
+                2,
+                "Gray-Scott states must be formatted as \"u,v\"."
+            );
+            let state = GrayScottState::new(concentrations[0], concentrations[1]);
+            val.iter().map(move |ele| (ele.clone(), state))
+        })
+        .collect()
+}
+
+fn gen_2d_random_gray_scott_states(
+    board_shape: &Shape2D,
+    alive_ratio: &f32,
+) -> HashMap<GridPoint2D<IntIdx>, GrayScottState> {
     let res = gen_random_usize(&board_shape.volume(), alive_ratio);
     res.into_par_iter()
         .map(|ele| {
@@ -522,8 +2247,103 @@ fn gen_2d_random_discrete_states(
             let y = (ele / board_shape.width()) as i64 + board_shape.y_idx_min();
             (
                 GridPoint2D::new(x as IntIdx, y as IntIdx),
-                (state_count - 1) as IntState,
+                GrayScottState::new(0.5, 0.25),
             )
         })
         .collect()
 }
+
+/// Configs of the bundled demos.
+pub fn example_jsons() -> Vec<&'static str> {
+    #[cfg_attr(not(feature = "ascii"), allow(unused_mut))]
+    let mut res = vec![
+        include_str!("../../examples/tetris.json"),
+        include_str!("../../examples/glider.json"),
+        include_str!("../../examples/glider_gun.json"),
+        include_str!("../../examples/glider_eater.json"),
+        include_str!("../../examples/glider_gun_with_eater.json"),
+        include_str!("../../examples/and_gate_00.json"),
+        include_str!("../../examples/and_gate_01.json"),
+        include_str!("../../examples/and_gate_10.json"),
+        include_str!("../../examples/and_gate_11.json"),
+        include_str!("../../examples/random.json"),
+        include_str!("../../examples/random_gol.json"),
+        include_str!("../../examples/and_gate_4_neighbors.json"),
+        include_str!("../../examples/star_wars.json"),
+        include_str!("../../examples/star_wars_donut.json"),
+        include_str!("../../examples/brians_brain.json"),
+        include_str!("../../examples/bombers.json"),
+        include_str!("../../examples/bombers_255.json"),
+        include_str!("../../examples/sedimental.json"),
+        include_str!("../../examples/lattice_gas_hpp.json"),
+        include_str!("../../examples/lattice_gas_fhp.json"),
+        include_str!("../../examples/gray_scott_spots.json"),
+        include_str!("../../examples/gray_scott_stripes.json"),
+        include_str!("../../examples/gray_scott_mitosis.json"),
+        include_str!("../../examples/second_order_gol.json"),
+        include_str!("../../examples/sandpile_fractal.json"),
+        include_str!("../../examples/sandpile_driven.json"),
+        include_str!("../../examples/life_3d_4555.json"),
+        include_str!("../../examples/brians_brain_3d.json"),
+        include_str!("../../examples/clouds_3d.json"),
+        include_str!("../../examples/life_4d.json"),
+        include_str!("../../examples/small_world_waves.json"),
+        include_str!("../../examples/penrose_life.json"),
+        include_str!("../../examples/voronoi_life.json"),
+        include_str!("../../examples/weighted_life.json"),
+        include_str!("../../examples/gaussian_life.json"),
+    ];
+
+    #[cfg(feature = "ascii")]
+    res.push(include_str!("../../examples/tetris_ascii.json"));
+    res
+}
+
+#[cfg(test)]
+mod load_board_test {
+    use super::{example_jsons, CellularAutomatonConfig};
+
+    /// Example config changed by `edit` before it is loaded.
+    fn load_edited<F>(example: &str, edit: F) -> CellularAutomatonConfig
+    where
+        F: FnOnce(&mut serde_json::Value),
+    {
+        let mut json: serde_json::Value = serde_json::from_str(example).unwrap();
+        edit(&mut json);
+        CellularAutomatonConfig::from_json(&json.to_string())
+    }
+
+    #[test]
+    fn load_board_test_examples() {
+        for json in example_jsons() {
+            CellularAutomatonConfig::from_json(json);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "needs \"Hex\" neighbors")]
+    fn load_board_test_lattice_gas_neighbor_rule() {
+        load_edited(
+            include_str!("../../examples/lattice_gas_fhp.json"),
+            |json| json["neighbor_rule"] = serde_json::json!({ "type": "VonNeumann" }),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Lattice gas states are 8-bit")]
+    fn load_board_test_lattice_gas_state_width() {
+        load_edited(
+            include_str!("../../examples/lattice_gas_hpp.json"),
+            |json| json["state"]["count"] = 300.into(),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "is not on the board")]
+    fn load_board_test_grain_drop_off_board() {
+        load_edited(
+            include_str!("../../examples/sandpile_driven.json"),
+            |json| json["evolution_rule"]["driver"]["positions"][0]["x"] = 26.into(),
+        );
+    }
+}
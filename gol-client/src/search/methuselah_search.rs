@@ -0,0 +1,83 @@
+use crate::persistence::load_board::CellularAutomatonConfig;
+use gol_core::{util::grid_util::Shape2D, GridPoint2D, Methuselah, MethuselahSearch};
+use std::path::Path;
+
+/// Smallest width and height of the boards of the configs.
+const MIN_BOARD_SIZE: usize = 64;
+
+/// Generations the configs keep running after the seed settled.
+const LIFESPAN_MARGIN: usize = 100;
+
+/// Runs the search and writes the best seeds as configs `methuselah_<rank>.json` to the
+/// directory, all seeds in the bounding box if `samples` is `None`, otherwise that many random
+/// seeds from the RNG seed.
+pub fn run_methuselah_search(search: &MethuselahSearch, samples: Option<(u64, u64)>, dir: &str) {
+    let best = match samples {
+        Some((sample_count, rng_seed)) => {
+            println!("Sampling {} seeds.", sample_count);
+            search.sample(sample_count, rng_seed)
+        }
+        None => {
+            println!(
+                "Searching {} seeds, leaving out rotations, reflections and translations.",
+                search.seed_count()
+            );
+            search.enumerate()
+        }
+    };
+
+    std::fs::create_dir_all(dir).expect("Cannot create the output directory.");
+    let census = search.census();
+    for (i, methuselah) in best.iter().enumerate() {
+        let title = format!(
+            "Methuselah {} of {}: lifespan {}, max population {}, final population {}",
+            i + 1,
+            census.rule(),
+            methuselah.lifespan(),
+            methuselah.max_population(),
+            methuselah.final_population()
+        );
+        println!("{}", title);
+        let (shape, cells) = board_of(methuselah);
+        let config = CellularAutomatonConfig::new_life_like(
+            title,
+            &census.survive_counts(),
+            &census.born_counts(),
+            shape,
+            cells,
+            Some(methuselah.lifespan() + LIFESPAN_MARGIN),
+        )
+        .with_spaceship_removal();
+        let path = Path::new(dir).join(format!("methuselah_{}.json", i + 1));
+        std::fs::write(&path, config.to_json())
+            .unwrap_or_else(|err| panic!("Cannot write {}: {}", path.display(), err));
+    }
+}
+
+/// Board with room for the seed to grow, and the cells of the seed moved to its center. Spaceships
+/// escaping the seed are removed at the edge by the config instead of wrapping around.
+fn board_of(methuselah: &Methuselah) -> (Shape2D, Vec<GridPoint2D<i64>>) {
+    let width = methuselah
+        .cells()
+        .iter()
+        .map(|c| c.x + 1)
+        .max()
+        .unwrap_or(0);
+    let height = methuselah
+        .cells()
+        .iter()
+        .map(|c| c.y + 1)
+        .max()
+        .unwrap_or(0);
+    let margin = methuselah.lifespan() / 2 + MIN_BOARD_SIZE / 2;
+    let shape = Shape2D::new(
+        (width as usize + margin).max(MIN_BOARD_SIZE),
+        (height as usize + margin).max(MIN_BOARD_SIZE),
+    );
+    let cells = methuselah
+        .cells()
+        .iter()
+        .map(|c| GridPoint2D::new(c.x - width / 2, c.y - height / 2))
+        .collect();
+    (shape, cells)
+}
@@ -0,0 +1,3 @@
+pub mod methuselah_search;
+pub mod pattern_search;
+pub mod soup_search;
@@ -0,0 +1,67 @@
+use crate::persistence::load_board::CellularAutomatonConfig;
+use gol_core::{to_rle, util::grid_util::Shape2D, GridPoint2D, PatternSearch};
+use std::path::Path;
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::time::Duration;
+
+/// Width and height of the boards of the configs, enough for spaceships to travel a while before
+/// they wrap around.
+const BOARD_SIZE: usize = 64;
+
+/// Runs the search, cancelling it after the time limit, and writes every pattern found as
+/// `<code>.json` config and `<code>.rle` to the directory.
+pub fn run_pattern_search(search: &PatternSearch, time_limit: Option<Duration>, dir: &str) {
+    if let Some(time_limit) = time_limit {
+        let cancel_flag = search.cancel_flag();
+        thread::spawn(move || {
+            thread::sleep(time_limit);
+            cancel_flag.store(true, Ordering::Relaxed);
+        });
+    }
+    let found = search.search(|progress| {
+        println!(
+            "{} nodes, {:.2}% searched, {} found.",
+            progress.nodes,
+            progress.fraction * 100.0,
+            progress.found
+        );
+    });
+    if search.is_cancelled() {
+        println!("Search stopped after the time limit.");
+    }
+
+    std::fs::create_dir_all(dir).expect("Cannot create the output directory.");
+    let census = search.census();
+    let rule = census.rule();
+    for object in found.iter() {
+        let title = match object.name() {
+            Some(name) => format!("{} {} ({})", object.code(), name, rule),
+            None => format!("{} ({})", object.code(), rule),
+        };
+        println!("{}", title);
+        let width = object.cells().iter().map(|c| c.x + 1).max().unwrap_or(0);
+        let height = object.cells().iter().map(|c| c.y + 1).max().unwrap_or(0);
+        let cells: Vec<GridPoint2D<i64>> = object
+            .cells()
+            .iter()
+            .map(|c| GridPoint2D::new(c.x - width / 2, c.y - height / 2))
+            .collect();
+        let config = CellularAutomatonConfig::new_life_like(
+            title,
+            &census.survive_counts(),
+            &census.born_counts(),
+            Shape2D::new(BOARD_SIZE, BOARD_SIZE),
+            cells,
+            None,
+        );
+        let path = Path::new(dir).join(object.code());
+        let write = |extension: &str, contents: String| {
+            let path = path.with_extension(extension);
+            std::fs::write(&path, contents)
+                .unwrap_or_else(|err| panic!("Cannot write {}: {}", path.display(), err));
+        };
+        write("json", config.to_json());
+        write("rle", to_rle(object.cells(), &rule));
+    }
+}
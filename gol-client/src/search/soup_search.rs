@@ -0,0 +1,38 @@
+use gol_core::{SearchResults, SoupSearch};
+
+/// Searches soups in batches until `soup_count` soups were searched in total, saving the results
+/// after every batch. Searches continue from the results already saved at the path.
+pub fn run_soup_search(search: &SoupSearch, path: &str, soup_count: u64, batch_size: u64) {
+    let mut results = match SearchResults::load_or_new(path, search) {
+        Ok(results) => results,
+        Err(err) => panic!("Cannot resume search from \"{}\": {}", path, err),
+    };
+    if results.soup_count() > 0 {
+        println!("Resuming from soup {}.", results.soup_count());
+    }
+    while results.soup_count() < soup_count {
+        let batch = batch_size.min(soup_count - results.soup_count());
+        search.search(&mut results, batch);
+        results.save(path).expect("Cannot save search results.");
+        println!(
+            "{} soups, {} objects, {} rare finds, {} unstable soups.",
+            results.soup_count(),
+            results.census().total(),
+            results.rare_finds().len(),
+            results.unstable_seeds().len()
+        );
+    }
+
+    let mut entries: Vec<_> = results.census().entries().iter().collect();
+    entries.sort_by(|a, b| b.1.count().cmp(&a.1.count()).then_with(|| a.0.cmp(b.0)));
+    println!("Objects (code: count):");
+    for (code, entry) in entries {
+        match entry.name() {
+            Some(name) => println!("{} ({}): {}", code, name, entry.count()),
+            None => println!("{}: {}", code, entry.count()),
+        }
+    }
+    for find in results.rare_finds() {
+        println!("Rare find {} in soup {}.", find.code, find.seed);
+    }
+}
@@ -0,0 +1,67 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use gol_core::{
+    util::grid_util::Shape2D, Board, DecayLifeLikeStrategy, Grid, GridFactory, GridPoint2D,
+    NeighborMooreDonut, SharedStrategyManager, SparseStates, StandardBoard, StandardBoardFactory,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::HashMap;
+
+const SHAPES_2D: [usize; 6] = [64, 128, 256, 512, 1024, 2048];
+
+type LifeBoard = StandardBoard<u8, GridPoint2D<i32>, std::vec::IntoIter<GridPoint2D<i32>>>;
+
+fn bit_life_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("2D Life Generation");
+    group.sample_size(10);
+
+    for shape in SHAPES_2D.iter() {
+        let (mut generic, mut bit) = gen_boards(*shape);
+        assert!(generic.bit_life().is_none());
+        assert!(bit.bit_life().is_some());
+        group.bench_with_input(BenchmarkId::new("Generic", shape), shape, |b, _| {
+            b.iter(|| generic.advance_one_generation())
+        });
+        group.bench_with_input(BenchmarkId::new("Bit-Parallel", shape), shape, |b, _| {
+            b.iter(|| bit.advance_one_generation())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bit_life_benchmark);
+criterion_main!(benches);
+
+fn gen_boards(shape: usize) -> (LifeBoard, LifeBoard) {
+    let mut rng = StdRng::seed_from_u64(1);
+    let half = (shape / 2) as i32;
+    let mut initial_states = HashMap::new();
+    for x in 0..shape as i32 {
+        for y in 0..shape as i32 {
+            if rng.gen_bool(0.3) {
+                initial_states.insert(GridPoint2D::new(x - half, y - half), 1u8);
+            }
+        }
+    }
+
+    let generic = StandardBoard::new(
+        Box::new(Grid::<GridPoint2D<i32>>::new(
+            vec![shape, shape].into_iter(),
+        )),
+        Box::new(NeighborMooreDonut::new(Shape2D::new(shape, shape))),
+        Box::new(SparseStates::new(0u8, initial_states.clone())),
+        Box::new(SharedStrategyManager::new(Box::new(
+            DecayLifeLikeStrategy::gol(),
+        ))),
+        Vec::new(),
+    );
+    let bit = StandardBoardFactory::new_standard_2d_grid(
+        (shape, shape),
+        0u8,
+        1,
+        initial_states,
+        Box::new(DecayLifeLikeStrategy::gol()),
+        Vec::new(),
+        true,
+    );
+    (generic, bit)
+}
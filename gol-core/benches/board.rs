@@ -0,0 +1,373 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use gol_core::{
+    util::grid_util::Shape2D, Board, BoardCallback, BoardCallbackWithStates,
+    BoardCallbackWithoutStates, BoardStateManager, ChunkedStates, DecayLifeLikeStrategy, Grid,
+    GridFactory, GridPoint2D, GridPoint3D, IndexedDataOwned, NeighborMoore, SharedStrategyManager,
+    SparseStates, StandardBoard, StandardBoardFactory,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+/// Generations evolved by every call to `Board::advance`.
+const GENERATIONS: usize = 4;
+
+const CALLBACK_COUNT: usize = 8;
+
+type BoxedBoard<T, CI> = Box<dyn Board<T, CI, std::vec::IntoIter<CI>>>;
+
+/// Builds a board in its initial states.
+type NewBoard<'a, T, CI> = Box<dyn Fn() -> BoxedBoard<T, CI> + 'a>;
+
+/// Gosper glider gun, y going down.
+const GLIDER_GUN: [(i32, i32); 36] = [
+    (24, 0),
+    (22, 1),
+    (24, 1),
+    (12, 2),
+    (13, 2),
+    (20, 2),
+    (21, 2),
+    (34, 2),
+    (35, 2),
+    (11, 3),
+    (15, 3),
+    (20, 3),
+    (21, 3),
+    (34, 3),
+    (35, 3),
+    (0, 4),
+    (1, 4),
+    (10, 4),
+    (16, 4),
+    (20, 4),
+    (21, 4),
+    (0, 5),
+    (1, 5),
+    (10, 5),
+    (14, 5),
+    (16, 5),
+    (17, 5),
+    (22, 5),
+    (24, 5),
+    (10, 6),
+    (16, 6),
+    (24, 6),
+    (11, 7),
+    (15, 7),
+    (12, 8),
+    (13, 8),
+];
+
+struct NoOpWithStates;
+
+impl<T, CI> BoardCallbackWithStates<T, CI> for NoOpWithStates
+where
+    T: Send + Sync,
+    CI: Send + Sync,
+{
+    fn execute(&mut self, _: &[IndexedDataOwned<CI, T>]) {}
+}
+
+struct NoOpWithoutStates;
+
+impl<T, CI> BoardCallbackWithoutStates<T, CI> for NoOpWithoutStates
+where
+    T: Send + Sync,
+    CI: Send + Sync,
+{
+    fn execute(&mut self) {}
+}
+
+fn random_soup_benchmark(c: &mut Criterion) {
+    let shape = 1024;
+    let initial_states = random_states_2d(shape, 2, 0.3);
+    let new_generic = || {
+        new_board_2d(
+            shape,
+            initial_states.clone(),
+            DecayLifeLikeStrategy::gol(),
+            Vec::new(),
+        )
+    };
+    let new_bit = || {
+        StandardBoardFactory::new_standard_2d_grid(
+            (shape, shape),
+            0u8,
+            1,
+            initial_states.clone(),
+            Box::new(DecayLifeLikeStrategy::gol()),
+            Vec::new(),
+            false,
+        )
+    };
+    bench_advance(
+        c,
+        "Random Soup 1024x1024",
+        shape * shape,
+        vec![
+            ("Generic", Box::new(|| Box::new(new_generic()))),
+            (
+                "Neighbor Table",
+                Box::new(|| Box::new(new_generic().with_neighbor_table())),
+            ),
+            ("Bit-Parallel", Box::new(|| Box::new(new_bit()))),
+        ],
+    );
+}
+
+fn glider_gun_benchmark(c: &mut Criterion) {
+    let shape = 1024;
+    let offset = -(shape as i32) / 4;
+    let initial_states: HashMap<GridPoint2D<i32>, u8> = GLIDER_GUN
+        .iter()
+        .map(|(x, y)| (GridPoint2D::new(offset + x, offset - y), 1))
+        .collect();
+    let new_sparse = || {
+        new_board_2d(
+            shape,
+            initial_states.clone(),
+            DecayLifeLikeStrategy::gol(),
+            Vec::new(),
+        )
+    };
+    let new_chunked = || {
+        StandardBoard::new(
+            Box::new(Grid::<GridPoint2D<i32>>::new(
+                vec![shape, shape].into_iter(),
+            )),
+            Box::new(NeighborMoore::new()),
+            Box::new(ChunkedStates::new_bounded(
+                0u8,
+                initial_states.clone(),
+                1,
+                Shape2D::new(shape, shape),
+                false,
+            )),
+            Box::new(SharedStrategyManager::new(Box::new(
+                DecayLifeLikeStrategy::gol(),
+            ))),
+            Vec::new(),
+        )
+    };
+    bench_advance(
+        c,
+        "Glider Gun 1024x1024",
+        shape * shape,
+        vec![
+            ("Sparse", Box::new(|| Box::new(new_sparse()))),
+            ("Chunked", Box::new(|| Box::new(new_chunked()))),
+        ],
+    );
+}
+
+fn brians_brain_benchmark(c: &mut Criterion) {
+    let shape = 512;
+    let strat = || DecayLifeLikeStrategy::new(3, Default::default(), vec![2].into_iter().collect());
+    let initial_states = random_states_2d(shape, 3, 0.3);
+    let new_generic = || new_board_2d(shape, initial_states.clone(), strat(), Vec::new());
+    bench_advance(
+        c,
+        "Brian's Brain 512x512",
+        shape * shape,
+        vec![
+            ("Generic", Box::new(|| Box::new(new_generic()))),
+            (
+                "Neighbor Table",
+                Box::new(|| Box::new(new_generic().with_neighbor_table())),
+            ),
+        ],
+    );
+}
+
+fn grid_3d_benchmark(c: &mut Criterion) {
+    let shape = 64;
+    let mut rng = StdRng::seed_from_u64(3);
+    let half = (shape / 2) as i32;
+    let mut initial_states = HashMap::new();
+    for x in 0..shape as i32 {
+        for y in 0..shape as i32 {
+            for z in 0..shape as i32 {
+                if rng.gen_bool(0.2) {
+                    initial_states.insert(GridPoint3D::new(x - half, y - half, z - half), 1u8);
+                }
+            }
+        }
+    }
+    let new_board = || {
+        StandardBoardFactory::new_standard_3d_grid(
+            (shape, shape, shape),
+            0u8,
+            1,
+            initial_states.clone(),
+            Box::new(DecayLifeLikeStrategy::new(
+                2,
+                vec![4, 5].into_iter().collect(),
+                vec![5].into_iter().collect(),
+            )),
+            Vec::new(),
+            true,
+        )
+    };
+    bench_advance(
+        c,
+        "Life 3D 4555 64x64x64",
+        shape * shape * shape,
+        vec![
+            ("Generic", Box::new(|| Box::new(new_board()))),
+            (
+                "Neighbor Table",
+                Box::new(|| Box::new(new_board().with_neighbor_table())),
+            ),
+        ],
+    );
+}
+
+fn callback_benchmark(c: &mut Criterion) {
+    let shape = 256;
+    let initial_states = random_states_2d(shape, 2, 0.3);
+    let new_board = |callbacks| {
+        new_board_2d(
+            shape,
+            initial_states.clone(),
+            DecayLifeLikeStrategy::gol(),
+            callbacks,
+        )
+        .with_neighbor_table()
+    };
+    let with_states = || {
+        (0..CALLBACK_COUNT)
+            .map(|_| BoardCallback::WithStates(Box::new(NoOpWithStates)))
+            .collect()
+    };
+    let without_states = || {
+        (0..CALLBACK_COUNT)
+            .map(|_| BoardCallback::WithoutStates(Box::new(NoOpWithoutStates)))
+            .collect()
+    };
+    bench_advance(
+        c,
+        "Callbacks 256x256",
+        shape * shape,
+        vec![
+            ("None", Box::new(|| Box::new(new_board(Vec::new())))),
+            (
+                "Without States",
+                Box::new(|| Box::new(new_board(without_states()))),
+            ),
+            (
+                "With States",
+                Box::new(|| Box::new(new_board(with_states()))),
+            ),
+        ],
+    );
+}
+
+fn sparse_states_benchmark(c: &mut Criterion) {
+    let shape = 1024;
+    let initial_states = random_states_2d(shape, 2, 0.3);
+    let mut board = new_board_2d(
+        shape,
+        initial_states.clone(),
+        DecayLifeLikeStrategy::gol(),
+        Vec::new(),
+    );
+    let next_states = board.advance_one_generation().to_vec();
+    let mut states = SparseStates::new(0u8, initial_states);
+
+    let mut group = c.benchmark_group("SparseStates Update 1024x1024");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(next_states.len() as u64));
+    group.bench_function("Borrowed", |b| {
+        b.iter(|| states.update_cell_states(&next_states))
+    });
+    group.bench_function("Parallel Iterator", |b| {
+        b.iter(|| states.update_cell_states_from_par_iter(next_states.clone().into_par_iter()))
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    random_soup_benchmark,
+    glider_gun_benchmark,
+    brians_brain_benchmark,
+    grid_3d_benchmark,
+    callback_benchmark,
+    sparse_states_benchmark
+);
+criterion_main!(benches);
+
+/// Benchmarks `Board::advance` of each board twice, reporting cells per second and generations
+/// per second. Every sample starts from a new board, which is not measured.
+fn bench_advance<T, CI>(
+    c: &mut Criterion,
+    name: &str,
+    cell_count: usize,
+    boards: Vec<(&str, NewBoard<T, CI>)>,
+) where
+    T: 'static + Send + Sync + Clone,
+    CI: 'static + Send + Sync + Clone,
+{
+    for (unit, elements) in [
+        ("Cells", cell_count * GENERATIONS),
+        ("Generations", GENERATIONS),
+    ] {
+        let mut group = c.benchmark_group(format!("{} ({})", name, unit));
+        group.sample_size(10);
+        group.throughput(Throughput::Elements(elements as u64));
+        for (label, new_board) in boards.iter() {
+            group.bench_with_input(BenchmarkId::new(*label, cell_count), &cell_count, |b, _| {
+                b.iter_batched(
+                    new_board,
+                    |mut board| {
+                        // The last generation passed to `advance` is the initial one.
+                        board.advance(Some(GENERATIONS + 1));
+                        board
+                    },
+                    BatchSize::LargeInput,
+                )
+            });
+        }
+        group.finish();
+    }
+}
+
+fn new_board_2d(
+    shape: usize,
+    initial_states: HashMap<GridPoint2D<i32>, u8>,
+    strat: DecayLifeLikeStrategy,
+    callbacks: Vec<BoardCallback<u8, GridPoint2D<i32>>>,
+) -> StandardBoard<u8, GridPoint2D<i32>, std::vec::IntoIter<GridPoint2D<i32>>> {
+    StandardBoard::new(
+        Box::new(Grid::<GridPoint2D<i32>>::new(
+            vec![shape, shape].into_iter(),
+        )),
+        Box::new(NeighborMoore::new()),
+        Box::new(SparseStates::new(0u8, initial_states)),
+        Box::new(SharedStrategyManager::new(Box::new(strat))),
+        callbacks,
+    )
+}
+
+/// Random non-zero states of a centered square board, any of them as likely.
+fn random_states_2d(
+    shape: usize,
+    state_count: u8,
+    alive_ratio: f64,
+) -> HashMap<GridPoint2D<i32>, u8> {
+    let mut rng = StdRng::seed_from_u64(7);
+    let half = (shape / 2) as i32;
+    let mut res = HashMap::new();
+    for x in 0..shape as i32 {
+        for y in 0..shape as i32 {
+            if rng.gen_bool(alive_ratio) {
+                res.insert(
+                    GridPoint2D::new(x - half, y - half),
+                    rng.gen_range(1..state_count),
+                );
+            }
+        }
+    }
+    res
+}
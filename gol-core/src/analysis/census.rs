@@ -0,0 +1,716 @@
+use crate::{DecayLifeLikeStrategy, GridPoint2D, IndexedDataOwned};
+use num_traits::ToPrimitive;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::OnceLock;
+
+pub(crate) type Cell = (i64, i64);
+
+/// Cells at most this far apart share a neighbor, so objects further apart evolve independently.
+const DEFAULT_DISTANCE: usize = 2;
+
+const DEFAULT_MAX_PERIOD: usize = 64;
+
+const LIFE_SURVIVE_MASK: u16 = 1 << 2 | 1 << 3;
+
+const LIFE_BORN_MASK: u16 = 1 << 3;
+
+const WECHSLER_DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// Common objects of Conway's Game of Life, rows from top to bottom with `o` for alive cells.
+const KNOWN_OBJECTS: [(&str, &str); 25] = [
+    ("block", "oo/oo"),
+    ("beehive", ".oo./o..o/.oo."),
+    ("loaf", ".oo./o..o/.o.o/..o."),
+    ("boat", "oo./o.o/.o."),
+    ("ship", "oo./o.o/.oo"),
+    ("tub", ".o./o.o/.o."),
+    ("pond", ".oo./o..o/o..o/.oo."),
+    ("barge", ".o../o.o./.o.o/..o."),
+    ("long boat", "oo../o.o./.o.o/..o."),
+    ("long ship", "oo../o.o./.o.o/..oo"),
+    ("eater 1", "oo../o.o./..o./..oo"),
+    ("snake", "oo.o/o.oo"),
+    ("aircraft carrier", "oo../o..o/..oo"),
+    ("mango", ".oo../o..o./.o..o/..oo."),
+    ("bi-block", "oo.oo/oo.oo"),
+    ("blinker", "ooo"),
+    ("toad", ".ooo/ooo."),
+    ("beacon", "oo../oo../..oo/..oo"),
+    ("clock", "..o./o.o./.o.o/.o.."),
+    ("pulsar", "..ooo...ooo../............./o....o.o....o/o....o.o....o/o....o.o....o/..ooo...ooo../............./..ooo...ooo../o....o.o....o/o....o.o....o/o....o.o....o/............./..ooo...ooo.."),
+    ("pentadecathlon", "..o....o../oo.oooo.oo/..o....o.."),
+    ("glider", ".o./..o/ooo"),
+    ("lightweight spaceship", ".o..o/o..../o...o/oooo."),
+    ("middleweight spaceship", "...o../.o...o/o...../o....o/ooooo."),
+    ("heavyweight spaceship", "...oo../.o....o/o....../o.....o/oooooo."),
+];
+
+/// How an object behaves when evolved on its own.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ObjectKind {
+    StillLife,
+    Oscillator,
+    Spaceship,
+    /// Does not repeat within the maximum period, e.g. still evolving or dying out.
+    Unstable,
+}
+
+/// Island of alive cells, classified by evolving it in isolation.
+#[derive(Clone, Debug)]
+pub struct CensusObject {
+    code: String,
+    name: Option<&'static str>,
+    kind: ObjectKind,
+    period: usize,
+    displacement: (i64, i64),
+    cells: Vec<GridPoint2D<i64>>,
+}
+
+impl CensusObject {
+    /// Identifier of the shape in all phases and orientations, in the format of apgcodes, e.g.
+    /// `xs4_33` for blocks and `xq4_153` for gliders. `unstable` for unstable objects.
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// Common name of the object, `None` if it is not in the built-in table.
+    pub fn name(&self) -> Option<&'static str> {
+        self.name
+    }
+
+    pub fn kind(&self) -> ObjectKind {
+        self.kind
+    }
+
+    /// Generations until the object repeats, 0 for unstable objects.
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Distance the object moved in x and y after one period, zero unless it is a spaceship.
+    pub fn displacement(&self) -> (i64, i64) {
+        self.displacement
+    }
+
+    /// Alive cells of the object on the board.
+    pub fn cells(&self) -> &[GridPoint2D<i64>] {
+        &self.cells
+    }
+}
+
+/// Splits alive cells of a two-state life-like board into islands and identifies each of them.
+pub struct ObjectCensus {
+    survive_mask: u16,
+    born_mask: u16,
+    distance: usize,
+    max_period: usize,
+}
+
+impl ObjectCensus {
+    /// Census of the life-like rule with the Moore neighborhood. Rules with cells born without
+    /// neighbors have no finite objects and are not supported.
+    pub fn new(survive_counts: &HashSet<usize>, born_counts: &HashSet<usize>) -> Self {
+        assert!(
+            !born_counts.contains(&0),
+            "Rules with cells born without neighbors have no finite objects."
+        );
+        let to_mask = |counts: &HashSet<usize>| {
+            counts
+                .iter()
+                .filter(|count| **count <= 8)
+                .fold(0u16, |res, count| res | 1 << count)
+        };
+        Self {
+            survive_mask: to_mask(survive_counts),
+            born_mask: to_mask(born_counts),
+            distance: DEFAULT_DISTANCE,
+            max_period: DEFAULT_MAX_PERIOD,
+        }
+    }
+
+    /// Census of the rule of the strategy, `None` if it has more than two states.
+    pub fn from_strategy(strategy: &DecayLifeLikeStrategy) -> Option<Self> {
+        if strategy.state_count() != 2 {
+            return None;
+        }
+        Some(Self::new(strategy.survive_counts(), strategy.born_counts()))
+    }
+
+    /// Census of a rule in B/S notation, e.g. `B3/S23`, `None` if it cannot be parsed or has
+    /// cells born without neighbors.
+    pub fn from_rule(rule: &str) -> Option<Self> {
+        let mut born = None;
+        let mut survive = None;
+        for part in rule.to_uppercase().split('/') {
+            let counts = || {
+                part[1..]
+                    .chars()
+                    .map(|c| c.to_digit(9).map(|count| count as usize))
+                    .collect::<Option<HashSet<usize>>>()
+            };
+            match part.chars().next() {
+                Some('B') if born.is_none() => born = Some(counts()?),
+                Some('S') if survive.is_none() => survive = Some(counts()?),
+                _ => return None,
+            }
+        }
+        let (born, survive) = (born?, survive?);
+        if born.contains(&0) {
+            return None;
+        }
+        Some(Self::new(&survive, &born))
+    }
+
+    /// Rule of the census in B/S notation, e.g. `B3/S23`.
+    pub fn rule(&self) -> String {
+        let digits = |counts: Vec<usize>| -> String {
+            counts
+                .iter()
+                .map(|count| std::char::from_digit(*count as u32, 10).unwrap())
+                .collect()
+        };
+        format!(
+            "B{}/S{}",
+            digits(self.born_counts()),
+            digits(self.survive_counts())
+        )
+    }
+
+    /// Numbers of alive neighbors alive cells survive with.
+    pub fn survive_counts(&self) -> Vec<usize> {
+        (0..=8)
+            .filter(|count| self.survive_mask >> count & 1 == 1)
+            .collect()
+    }
+
+    /// Numbers of alive neighbors dead cells are born with.
+    pub fn born_counts(&self) -> Vec<usize> {
+        (0..=8)
+            .filter(|count| self.born_mask >> count & 1 == 1)
+            .collect()
+    }
+
+    /// Largest distance along x or y between cells of the same island, 2 by default. Larger
+    /// distances keep objects with gaps together, but also merge objects close to each other.
+    pub fn with_distance(self, distance: usize) -> Self {
+        let mut res = self;
+        res.distance = distance.max(1);
+        res
+    }
+
+    /// Longest period objects are evolved for before they are considered unstable, 64 by default.
+    pub fn with_max_period(self, max_period: usize) -> Self {
+        let mut res = self;
+        res.max_period = max_period;
+        res
+    }
+
+    pub(crate) fn max_period(&self) -> usize {
+        self.max_period
+    }
+
+    /// Tally of the objects of the board, cells with any state other than `dead_state` are alive.
+    pub fn census<U, T>(
+        &self,
+        states: &[IndexedDataOwned<GridPoint2D<U>, T>],
+        dead_state: &T,
+    ) -> Census
+    where
+        U: Sync + ToPrimitive,
+        T: Sync + PartialEq,
+    {
+        let cells: Vec<GridPoint2D<i64>> = states
+            .par_iter()
+            .filter(|(_, state)| state != dead_state)
+            .map(|(idx, _)| GridPoint2D::new(idx.x.to_i64().unwrap(), idx.y.to_i64().unwrap()))
+            .collect();
+        let mut res = Census::new();
+        for object in self.objects(&cells) {
+            res.add(&object);
+        }
+        res
+    }
+
+    /// Identifies every island of the alive cells.
+    pub fn objects(&self, cells: &[GridPoint2D<i64>]) -> Vec<CensusObject> {
+        self.islands(cells)
+            .into_par_iter()
+            .map(|island| self.classify(&island))
+            .collect()
+    }
+
+    /// Groups of alive cells connected through cells at most the distance apart, sorted by their
+    /// smallest cell.
+    pub fn islands(&self, cells: &[GridPoint2D<i64>]) -> Vec<Vec<GridPoint2D<i64>>> {
+        let d = self.distance as i64;
+        let mut remaining: HashSet<Cell> = cells.iter().map(|cell| (cell.x, cell.y)).collect();
+        let mut sorted: Vec<Cell> = remaining.iter().cloned().collect();
+        sorted.sort_unstable();
+        let mut res = Vec::new();
+        for start in sorted {
+            if !remaining.remove(&start) {
+                continue;
+            }
+            let mut island = vec![start];
+            let mut i = 0;
+            while i < island.len() {
+                let (x, y) = island[i];
+                for dx in -d..=d {
+                    for dy in -d..=d {
+                        if remaining.remove(&(x + dx, y + dy)) {
+                            island.push((x + dx, y + dy));
+                        }
+                    }
+                }
+                i += 1;
+            }
+            island.sort_unstable();
+            res.push(
+                island
+                    .into_iter()
+                    .map(|(x, y)| GridPoint2D::new(x, y))
+                    .collect(),
+            );
+        }
+        res
+    }
+
+    /// Evolves the island on an otherwise empty board until it repeats, possibly somewhere else.
+    /// Objects are only named with the rules of Conway's Game of Life.
+    pub fn classify(&self, island: &[GridPoint2D<i64>]) -> CensusObject {
+        let mut res = self.classify_unnamed(island);
+        if self.survive_mask == LIFE_SURVIVE_MASK && self.born_mask == LIFE_BORN_MASK {
+            res.name = known_objects().get(&res.code).cloned();
+        }
+        res
+    }
+
+    fn classify_unnamed(&self, island: &[GridPoint2D<i64>]) -> CensusObject {
+        let cells: HashSet<Cell> = island.iter().map(|cell| (cell.x, cell.y)).collect();
+        let (shape, origin) = normalize(&cells);
+        let mut phases = vec![shape.clone()];
+        let mut cur = cells;
+        let mut repeat = None;
+        for period in 1..=self.max_period {
+            cur = self.step(&cur);
+            if cur.is_empty() {
+                break;
+            }
+            let (cur_shape, cur_origin) = normalize(&cur);
+            if cur_shape == shape {
+                repeat = Some((period, (cur_origin.0 - origin.0, cur_origin.1 - origin.1)));
+                break;
+            }
+            phases.push(cur_shape);
+        }
+
+        let (kind, period, displacement) = match repeat {
+            Some((1, (0, 0))) => (ObjectKind::StillLife, 1, (0, 0)),
+            Some((period, (0, 0))) => (ObjectKind::Oscillator, period, (0, 0)),
+            Some((period, displacement)) => (ObjectKind::Spaceship, period, displacement),
+            None => (ObjectKind::Unstable, 0, (0, 0)),
+        };
+        let code = match kind {
+            ObjectKind::StillLife => format!("xs{}_{}", island.len(), canonical_wechsler(&phases)),
+            ObjectKind::Oscillator => format!("xp{}_{}", period, canonical_wechsler(&phases)),
+            ObjectKind::Spaceship => format!("xq{}_{}", period, canonical_wechsler(&phases)),
+            ObjectKind::Unstable => String::from("unstable"),
+        };
+        CensusObject {
+            name: None,
+            code,
+            kind,
+            period,
+            displacement,
+            cells: island.to_vec(),
+        }
+    }
+
+    /// Next generation of the cells on an otherwise empty board.
+    pub(crate) fn step(&self, cells: &HashSet<Cell>) -> HashSet<Cell> {
+        let mut counts: HashMap<Cell, usize> = HashMap::with_capacity(cells.len() * 9);
+        for (x, y) in cells.iter() {
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    if dx != 0 || dy != 0 {
+                        *counts.entry((x + dx, y + dy)).or_default() += 1;
+                    }
+                }
+            }
+        }
+        counts
+            .into_iter()
+            .filter(|(cell, count)| {
+                let mask = if cells.contains(cell) {
+                    self.survive_mask
+                } else {
+                    self.born_mask
+                };
+                mask >> count & 1 == 1
+            })
+            .map(|(cell, _)| cell)
+            .collect()
+    }
+}
+
+/// Number of objects of every kind, by code.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Census {
+    objects: BTreeMap<String, CensusEntry>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CensusEntry {
+    name: Option<String>,
+    kind: ObjectKind,
+    count: usize,
+}
+
+impl CensusEntry {
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn kind(&self) -> ObjectKind {
+        self.kind
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+impl Census {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, object: &CensusObject) {
+        self.objects
+            .entry(object.code.clone())
+            .or_insert_with(|| CensusEntry {
+                name: object.name.map(String::from),
+                kind: object.kind,
+                count: 0,
+            })
+            .count += 1;
+    }
+
+    /// Adds the counts of another census, e.g. of another run.
+    pub fn merge(&mut self, other: &Census) {
+        for (code, entry) in other.objects.iter() {
+            self.objects
+                .entry(code.clone())
+                .or_insert_with(|| CensusEntry {
+                    count: 0,
+                    ..entry.clone()
+                })
+                .count += entry.count;
+        }
+    }
+
+    /// Number of objects with the code.
+    pub fn count(&self, code: &str) -> usize {
+        match self.objects.get(code) {
+            Some(entry) => entry.count,
+            None => 0,
+        }
+    }
+
+    /// Number of objects of all kinds.
+    pub fn total(&self) -> usize {
+        self.objects.values().map(|entry| entry.count).sum()
+    }
+
+    /// Entries by code, in the order of the codes.
+    pub fn entries(&self) -> &BTreeMap<String, CensusEntry> {
+        &self.objects
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+/// Codes of the built-in objects, classified with the rules of Conway's Game of Life.
+pub(crate) fn known_objects() -> &'static HashMap<String, &'static str> {
+    static KNOWN: OnceLock<HashMap<String, &'static str>> = OnceLock::new();
+    KNOWN.get_or_init(|| {
+        let census = ObjectCensus::from_strategy(&DecayLifeLikeStrategy::gol()).unwrap();
+        KNOWN_OBJECTS
+            .iter()
+            .map(|(name, rows)| {
+                let object = census.classify_unnamed(&parse_rows(rows));
+                (object.code, *name)
+            })
+            .collect()
+    })
+}
+
+/// Cells of rows separated by `/`, with `o` for alive cells and y growing downwards.
+pub(crate) fn parse_rows(rows: &str) -> Vec<GridPoint2D<i64>> {
+    rows.split('/')
+        .enumerate()
+        .flat_map(|(y, row)| {
+            row.chars()
+                .enumerate()
+                .filter(|(_, c)| *c == 'o')
+                .map(move |(x, _)| GridPoint2D::new(x as i64, y as i64))
+        })
+        .collect()
+}
+
+/// Sorted cells moved so the smallest x and y are zero, and the smallest x and y before moving.
+pub(crate) fn normalize(cells: &HashSet<Cell>) -> (Vec<Cell>, Cell) {
+    let min_x = cells.iter().map(|cell| cell.0).min().unwrap_or(0);
+    let min_y = cells.iter().map(|cell| cell.1).min().unwrap_or(0);
+    let mut res: Vec<Cell> = cells.iter().map(|(x, y)| (x - min_x, y - min_y)).collect();
+    res.sort_unstable();
+    (res, (min_x, min_y))
+}
+
+/// Rotations and reflections of a cell around the origin, the first one keeps it unchanged.
+pub(crate) const ORIENTATIONS: [fn(Cell) -> Cell; 8] = [
+    |(x, y)| (x, y),
+    |(x, y)| (-x, y),
+    |(x, y)| (x, -y),
+    |(x, y)| (-x, -y),
+    |(x, y)| (y, x),
+    |(x, y)| (-y, x),
+    |(x, y)| (y, -x),
+    |(x, y)| (-y, -x),
+];
+
+/// Shortest, then alphabetically first, extended Wechsler encoding of the phases in all eight
+/// orientations.
+fn canonical_wechsler(phases: &[Vec<Cell>]) -> String {
+    phases
+        .iter()
+        .flat_map(|phase| {
+            ORIENTATIONS.iter().map(move |orient| {
+                let cells: HashSet<Cell> = phase.iter().map(|cell| orient(*cell)).collect();
+                wechsler(&normalize(&cells).0)
+            })
+        })
+        .min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))
+        .unwrap()
+}
+
+/// Extended Wechsler format: strips of 5 rows separated by `z`, each column of a strip a base 32
+/// digit with the top row as the lowest bit, and runs of zeros shortened.
+fn wechsler(cells: &[Cell]) -> String {
+    let width = cells.iter().map(|cell| cell.0 + 1).max().unwrap_or(0);
+    let height = cells.iter().map(|cell| cell.1 + 1).max().unwrap_or(0);
+    let alive: HashSet<&Cell> = cells.iter().collect();
+    let strips: Vec<String> = (0..(height + 4) / 5)
+        .map(|strip| {
+            let mut digits: Vec<usize> = (0..width)
+                .map(|x| {
+                    (0..5)
+                        .filter(|row| alive.contains(&(x, strip * 5 + row)))
+                        .fold(0, |res, row| res | 1 << row)
+                })
+                .collect();
+            while digits.last() == Some(&0) {
+                digits.pop();
+            }
+            compress_zeros(&digits)
+        })
+        .collect();
+    strips.join("z")
+}
+
+fn compress_zeros(digits: &[usize]) -> String {
+    let mut res = String::new();
+    let mut zeros = 0;
+    let flush = |res: &mut String, zeros: &mut usize| {
+        while *zeros >= 4 {
+            let run = (*zeros).min(39);
+            res.push('y');
+            res.push(WECHSLER_DIGITS[run - 4] as char);
+            *zeros -= run;
+        }
+        res.push_str(["", "0", "w", "x"][*zeros]);
+        *zeros = 0;
+    };
+    for digit in digits.iter() {
+        if *digit == 0 {
+            zeros += 1;
+        } else {
+            flush(&mut res, &mut zeros);
+            res.push(WECHSLER_DIGITS[*digit] as char);
+        }
+    }
+    flush(&mut res, &mut zeros);
+    res
+}
+
+#[cfg(test)]
+mod object_census_test {
+    use super::parse_rows;
+    use crate::{
+        Board, DecayLifeLikeStrategy, GridPoint2D, ObjectCensus, ObjectKind, StandardBoardFactory,
+    };
+    use std::collections::HashMap;
+
+    fn classify(rows: &str) -> (String, Option<&'static str>, ObjectKind) {
+        let census = ObjectCensus::from_strategy(&DecayLifeLikeStrategy::gol()).unwrap();
+        let object = census.classify(&parse_rows(rows));
+        (String::from(object.code()), object.name(), object.kind())
+    }
+
+    fn shift(cells: Vec<GridPoint2D<i64>>, dx: i64, dy: i64) -> Vec<GridPoint2D<i64>> {
+        cells
+            .into_iter()
+            .map(|cell| GridPoint2D::new(cell.x + dx, cell.y + dy))
+            .collect()
+    }
+
+    #[test]
+    fn object_census_test_codes() {
+        assert_eq!(
+            classify("oo/oo"),
+            (String::from("xs4_33"), Some("block"), ObjectKind::StillLife)
+        );
+        assert_eq!(classify(".oo./o..o/.oo.").0, "xs6_696");
+        assert_eq!(classify("o/o/o").0, "xp2_7");
+        assert_eq!(classify(".ooo/ooo.").0, "xp2_7e");
+        assert_eq!(
+            classify("ooo/o../.o."),
+            (
+                String::from("xq4_153"),
+                Some("glider"),
+                ObjectKind::Spaceship
+            )
+        );
+        assert_eq!(classify(".o..o/o..../o...o/oooo.").0, "xq4_6frc");
+        // Rotated and reflected phases get the same code.
+        assert_eq!(classify("..o/o.o/.oo").0, "xq4_153");
+        assert_eq!(classify("oo/o.o/.o").1, Some("boat"));
+        assert_eq!(classify("oo.o/o.oo").1, Some("snake"));
+    }
+
+    #[test]
+    fn object_census_test_periods() {
+        let census = ObjectCensus::from_strategy(&DecayLifeLikeStrategy::gol()).unwrap();
+        let glider = census.classify(&parse_rows(".o./..o/ooo"));
+        assert_eq!(glider.period(), 4);
+        assert_eq!(glider.displacement(), (1, 1));
+        let pentadecathlon = census.classify(&parse_rows("..o....o../oo.oooo.oo/..o....o.."));
+        assert_eq!(pentadecathlon.kind(), ObjectKind::Oscillator);
+        assert_eq!(pentadecathlon.period(), 15);
+        assert_eq!(pentadecathlon.name(), Some("pentadecathlon"));
+
+        // R-pentomino keeps evolving for more than a thousand generations.
+        let r_pentomino = census.classify(&parse_rows(".oo/oo./.o."));
+        assert_eq!(r_pentomino.kind(), ObjectKind::Unstable);
+        assert_eq!(r_pentomino.code(), "unstable");
+        let census = census.with_max_period(10);
+        let pentadecathlon = census.classify(&parse_rows("..o....o../oo.oooo.oo/..o....o.."));
+        assert_eq!(pentadecathlon.kind(), ObjectKind::Unstable);
+    }
+
+    #[test]
+    fn object_census_test_islands() {
+        let census = ObjectCensus::from_strategy(&DecayLifeLikeStrategy::gol()).unwrap();
+        let mut cells = parse_rows("oo/oo");
+        cells.extend(shift(parse_rows("ooo"), 3, 0));
+        cells.extend(shift(parse_rows(".oo./o..o/.oo."), -10, 10));
+        assert_eq!(census.islands(&cells).len(), 2);
+        assert_eq!(census.with_distance(1).islands(&cells).len(), 3);
+
+        let census = ObjectCensus::from_strategy(&DecayLifeLikeStrategy::gol()).unwrap();
+        let mut cells = parse_rows("oo/oo");
+        cells.extend(shift(parse_rows("oo/oo"), 20, 0));
+        cells.extend(shift(parse_rows("ooo"), 0, 20));
+        cells.extend(shift(parse_rows(".oo./o..o/.oo."), -10, 10));
+        let objects = census.objects(&cells);
+        assert_eq!(objects.len(), 4);
+        assert_eq!(
+            objects
+                .iter()
+                .map(|object| object.cells().len())
+                .sum::<usize>(),
+            17
+        );
+    }
+
+    #[test]
+    fn object_census_test_board() {
+        let mut initial_states = HashMap::new();
+        for (x, y) in [
+            (-10i32, -10i32),
+            (-10, -9),
+            (-9, -10),
+            (-9, -9),
+            (5, 5),
+            (6, 5),
+            (7, 5),
+        ] {
+            initial_states.insert(GridPoint2D::new(x, y), 1u8);
+        }
+        let mut board = StandardBoardFactory::new_standard_2d_grid(
+            (40usize, 40),
+            0u8,
+            1,
+            initial_states,
+            Box::new(DecayLifeLikeStrategy::gol()),
+            Vec::new(),
+            false,
+        );
+        let states = board.advance_one_generation();
+        let census = ObjectCensus::from_strategy(&DecayLifeLikeStrategy::gol())
+            .unwrap()
+            .census(&states, &0);
+        assert_eq!(census.count("xs4_33"), 1);
+        assert_eq!(census.count("xp2_7"), 1);
+        assert_eq!(census.total(), 2);
+        assert_eq!(census.entries()["xp2_7"].name(), Some("blinker"));
+
+        let mut merged = census.clone();
+        merged.merge(&census);
+        assert_eq!(merged.count("xs4_33"), 2);
+        let json = merged.to_json();
+        assert!(json.contains("\"xs4_33\""));
+        assert_eq!(
+            serde_json::from_str::<crate::Census>(&json).unwrap(),
+            merged
+        );
+    }
+
+    #[test]
+    fn object_census_test_known_objects() {
+        let census = ObjectCensus::from_strategy(&DecayLifeLikeStrategy::gol()).unwrap();
+        for (name, rows) in super::KNOWN_OBJECTS.iter() {
+            let object = census.classify(&parse_rows(rows));
+            assert_ne!(object.kind(), ObjectKind::Unstable, "{}", name);
+            assert_eq!(object.name(), Some(*name));
+        }
+
+        // Blocks are still lifes of HighLife, but names are only given with Life.
+        let high_life = ObjectCensus::new(
+            &vec![2, 3].into_iter().collect(),
+            &vec![3, 6].into_iter().collect(),
+        );
+        let block = high_life.classify(&parse_rows("oo/oo"));
+        assert_eq!(block.code(), "xs4_33");
+        assert_eq!(block.name(), None);
+    }
+
+    #[test]
+    fn object_census_test_rule() {
+        let census = ObjectCensus::from_rule("B3/S23").unwrap();
+        assert_eq!(census.rule(), "B3/S23");
+        assert_eq!(census.classify(&parse_rows("oo/oo")).name(), Some("block"));
+        assert_eq!(
+            ObjectCensus::from_rule("b36/s23").unwrap().rule(),
+            "B36/S23"
+        );
+        assert_eq!(ObjectCensus::from_rule("B2/S").unwrap().rule(), "B2/S");
+        assert!(ObjectCensus::from_rule("B3").is_none());
+        assert!(ObjectCensus::from_rule("B39/S23").is_none());
+        assert!(ObjectCensus::from_rule("B03/S23").is_none());
+    }
+}
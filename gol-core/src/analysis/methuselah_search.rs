@@ -0,0 +1,389 @@
+use crate::analysis::census::{Cell, ORIENTATIONS};
+use crate::analysis::soup_search::settle;
+use crate::{GridPoint2D, ObjectCensus};
+use rand::{rngs::StdRng, seq::index, Rng, SeedableRng};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+const DEFAULT_SIZE: usize = 5;
+
+const DEFAULT_MAX_POPULATION: usize = 5;
+
+const DEFAULT_MAX_GENERATIONS: usize = 20000;
+
+const DEFAULT_TOP_COUNT: usize = 10;
+
+/// Seeds evolved in parallel at a time, only the best of them are kept before the next ones.
+const CHUNK_SIZE: usize = 4096;
+
+/// What seeds are ranked by first, the other two break ties.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MethuselahRank {
+    Lifespan,
+    MaxPopulation,
+    FinalPopulation,
+}
+
+/// Seed evolved until it settled.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Methuselah {
+    cells: Vec<GridPoint2D<i64>>,
+    lifespan: usize,
+    max_population: usize,
+    final_population: usize,
+    is_stable: bool,
+}
+
+impl Methuselah {
+    /// Alive cells of the seed, with the smallest x and y zero.
+    pub fn cells(&self) -> &[GridPoint2D<i64>] {
+        &self.cells
+    }
+
+    /// Generation the seed settled into still lifes, oscillators and escaping spaceships, or
+    /// the maximum number of generations.
+    pub fn lifespan(&self) -> usize {
+        self.lifespan
+    }
+
+    pub fn max_population(&self) -> usize {
+        self.max_population
+    }
+
+    /// Alive cells after the seed settled, including escaped spaceships.
+    pub fn final_population(&self) -> usize {
+        self.final_population
+    }
+
+    /// Whether the seed settled within the maximum number of generations.
+    pub fn is_stable(&self) -> bool {
+        self.is_stable
+    }
+}
+
+/// Searches small seeds that take long to settle, either all of them or random samples. Seeds
+/// are evolved on an unbounded plane, and seeds that are rotations, reflections or translations
+/// of each other are evolved only once.
+pub struct MethuselahSearch {
+    census: ObjectCensus,
+    width: usize,
+    height: usize,
+    max_population: usize,
+    max_generations: usize,
+    rank: MethuselahRank,
+    top_count: usize,
+}
+
+impl MethuselahSearch {
+    pub fn new(census: ObjectCensus) -> Self {
+        Self {
+            census,
+            width: DEFAULT_SIZE,
+            height: DEFAULT_SIZE,
+            max_population: DEFAULT_MAX_POPULATION,
+            max_generations: DEFAULT_MAX_GENERATIONS,
+            rank: MethuselahRank::Lifespan,
+            top_count: DEFAULT_TOP_COUNT,
+        }
+    }
+
+    pub fn census(&self) -> &ObjectCensus {
+        &self.census
+    }
+
+    /// Box all seeds fit in, 5 by 5 by default. It has at most 64 cells.
+    pub fn with_bounding_box(self, width: usize, height: usize) -> Self {
+        assert!(
+            width > 0 && height > 0 && width * height <= 64,
+            "Bounding box of {}x{} does not have between 1 and 64 cells.",
+            width,
+            height
+        );
+        let mut res = self;
+        res.width = width;
+        res.height = height;
+        res
+    }
+
+    /// Largest number of alive cells of seeds, 5 by default.
+    pub fn with_max_population(self, max_population: usize) -> Self {
+        let mut res = self;
+        res.max_population = max_population;
+        res
+    }
+
+    /// Generations a seed evolves for at most, 20000 by default. Seeds still changing after
+    /// that are not ranked.
+    pub fn with_max_generations(self, max_generations: usize) -> Self {
+        let mut res = self;
+        res.max_generations = max_generations;
+        res
+    }
+
+    /// What seeds are ranked by, lifespan by default.
+    pub fn with_rank(self, rank: MethuselahRank) -> Self {
+        let mut res = self;
+        res.rank = rank;
+        res
+    }
+
+    /// Number of best seeds kept, 10 by default.
+    pub fn with_top_count(self, top_count: usize) -> Self {
+        let mut res = self;
+        res.top_count = top_count;
+        res
+    }
+
+    /// Number of seeds in the bounding box within the population limit, before leaving out
+    /// rotations, reflections and translations.
+    pub fn seed_count(&self) -> u128 {
+        let cell_count = (self.width * self.height) as u128;
+        let mut res = 0;
+        let mut choices = 1u128;
+        for population in 1..=self.max_population.min(cell_count as usize) as u128 {
+            choices = choices * (cell_count - population + 1) / population;
+            res += choices;
+        }
+        res
+    }
+
+    /// Evolves the seed until it settles.
+    pub fn run(&self, cells: &[GridPoint2D<i64>]) -> Methuselah {
+        let settled = settle(
+            &self.census,
+            cells.iter().map(|c| (c.x, c.y)).collect(),
+            self.max_generations,
+        );
+        let escaped_population: usize = settled.escaped.iter().map(|s| s.cells().len()).sum();
+        Methuselah {
+            cells: cells.to_vec(),
+            lifespan: settled.lifespan,
+            max_population: settled.max_population,
+            final_population: settled.cells.len() + escaped_population,
+            is_stable: settled.is_stable,
+        }
+    }
+
+    /// Best seeds of all in the bounding box, best first.
+    pub fn enumerate(&self) -> Vec<Methuselah> {
+        let cell_count = self.width * self.height;
+        let masks = (1..=self.max_population.min(cell_count))
+            .flat_map(move |population| Combinations::new(cell_count, population))
+            .filter(|mask| self.canonical_mask(*mask) == *mask);
+        self.best_of(masks)
+    }
+
+    /// Best seeds of `sample_count` random ones, best first. The population of each seed is
+    /// random too, and the samples are the same for every run with the same `rng_seed`.
+    pub fn sample(&self, sample_count: u64, rng_seed: u64) -> Vec<Methuselah> {
+        let cell_count = self.width * self.height;
+        let max_population = self.max_population.clamp(1, cell_count);
+        let mut rng = StdRng::seed_from_u64(rng_seed);
+        let mut seen = HashSet::new();
+        let masks = (0..sample_count)
+            .map(move |_| {
+                let population = rng.gen_range(1..=max_population);
+                index::sample(&mut rng, cell_count, population)
+                    .iter()
+                    .fold(0u64, |res, i| res | 1 << i)
+            })
+            .map(|mask| self.canonical_mask(mask))
+            .filter(move |mask| seen.insert(*mask));
+        self.best_of(masks)
+    }
+
+    fn best_of<I>(&self, masks: I) -> Vec<Methuselah>
+    where
+        I: Iterator<Item = u64>,
+    {
+        let mut res: Vec<(u64, Methuselah)> = Vec::new();
+        let mut masks = masks.peekable();
+        while masks.peek().is_some() {
+            let chunk: Vec<u64> = masks.by_ref().take(CHUNK_SIZE).collect();
+            let found: Vec<(u64, Methuselah)> = chunk
+                .into_par_iter()
+                .map(|mask| (mask, self.run(&self.mask_cells(mask))))
+                .filter(|(_, methuselah)| methuselah.is_stable)
+                .collect();
+            res.extend(found);
+            res.sort_by(|a, b| self.compare(a, b));
+            res.truncate(self.top_count);
+        }
+        res.into_iter().map(|(_, methuselah)| methuselah).collect()
+    }
+
+    /// Better seeds first, ties go to seeds with fewer cells and then to the smaller mask.
+    fn compare(&self, a: &(u64, Methuselah), b: &(u64, Methuselah)) -> Ordering {
+        let key = |m: &Methuselah| match self.rank {
+            MethuselahRank::Lifespan => (m.lifespan, m.max_population, m.final_population),
+            MethuselahRank::MaxPopulation => (m.max_population, m.lifespan, m.final_population),
+            MethuselahRank::FinalPopulation => (m.final_population, m.lifespan, m.max_population),
+        };
+        key(&b.1)
+            .cmp(&key(&a.1))
+            .then_with(|| a.1.cells.len().cmp(&b.1.cells.len()))
+            .then_with(|| a.0.cmp(&b.0))
+    }
+
+    /// Cells of the bit mask, bit `x + y * width` is the cell at (x, y).
+    fn mask_cells(&self, mask: u64) -> Vec<GridPoint2D<i64>> {
+        (0..self.width * self.height)
+            .filter(|i| mask >> i & 1 == 1)
+            .map(|i| GridPoint2D::new((i % self.width) as i64, (i / self.width) as i64))
+            .collect()
+    }
+
+    /// Smallest mask of the seed moved, rotated or reflected within the bounding box.
+    fn canonical_mask(&self, mask: u64) -> u64 {
+        let cells: Vec<Cell> = self
+            .mask_cells(mask)
+            .iter()
+            .map(|cell| (cell.x, cell.y))
+            .collect();
+        ORIENTATIONS
+            .iter()
+            .filter_map(|orient| {
+                let oriented: Vec<Cell> = cells.iter().map(|cell| orient(*cell)).collect();
+                let min_x = oriented.iter().map(|cell| cell.0).min()?;
+                let min_y = oriented.iter().map(|cell| cell.1).min()?;
+                oriented.iter().try_fold(0u64, |res, (x, y)| {
+                    let (x, y) = ((x - min_x) as usize, (y - min_y) as usize);
+                    if x < self.width && y < self.height {
+                        Some(res | 1 << (x + y * self.width))
+                    } else {
+                        None
+                    }
+                })
+            })
+            .min()
+            .unwrap_or(mask)
+    }
+}
+
+/// Bit masks of `len` bits with `count` of them set, in increasing order.
+struct Combinations {
+    next: Option<u128>,
+    len: usize,
+}
+
+impl Combinations {
+    fn new(len: usize, count: usize) -> Self {
+        Self {
+            next: if count <= len {
+                Some((1u128 << count) - 1)
+            } else {
+                None
+            },
+            len,
+        }
+    }
+}
+
+impl Iterator for Combinations {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let res = self.next?;
+        // Gosper's hack: move the lowest block of set bits up by one.
+        self.next = if res == 0 {
+            None
+        } else {
+            let lowest = res & res.wrapping_neg();
+            let ripple = res + lowest;
+            let next = (((ripple ^ res) >> 2) / lowest) | ripple;
+            if next >> self.len == 0 {
+                Some(next)
+            } else {
+                None
+            }
+        };
+        Some(res as u64)
+    }
+}
+
+#[cfg(test)]
+mod methuselah_search_test {
+    use super::Combinations;
+    use crate::{GridPoint2D, MethuselahRank, MethuselahSearch, ObjectCensus};
+
+    fn new_search() -> MethuselahSearch {
+        MethuselahSearch::new(ObjectCensus::from_rule("B3/S23").unwrap())
+    }
+
+    fn r_pentomino_cells() -> Vec<GridPoint2D<i64>> {
+        [(1, 0), (0, 1), (1, 1), (2, 1), (0, 2)]
+            .iter()
+            .map(|(x, y)| GridPoint2D::new(*x, *y))
+            .collect()
+    }
+
+    #[test]
+    fn methuselah_search_test_combinations() {
+        let masks: Vec<u64> = Combinations::new(4, 2).collect();
+        assert_eq!(masks, vec![0b0011, 0b0101, 0b0110, 0b1001, 0b1010, 0b1100]);
+        assert_eq!(Combinations::new(64, 64).count(), 1);
+        assert_eq!(Combinations::new(3, 4).count(), 0);
+        assert_eq!(new_search().with_bounding_box(3, 3).seed_count(), 381);
+    }
+
+    #[test]
+    fn methuselah_search_test_canonical() {
+        let search = new_search().with_bounding_box(3, 2);
+        // The same domino in every position and orientation that fits.
+        let dominoes = [
+            0b000_011, 0b000_110, 0b011_000, 0b110_000, 0b001_001, 0b100_100,
+        ];
+        for mask in dominoes.iter() {
+            assert_eq!(search.canonical_mask(*mask), 0b000_011);
+        }
+        // Vertical triominoes do not fit, so the ones in both rows stay apart from them.
+        assert_eq!(search.canonical_mask(0b111_000), 0b000_111);
+    }
+
+    #[test]
+    fn methuselah_search_test_r_pentomino() {
+        let search = new_search();
+        let r_pentomino = search.run(&r_pentomino_cells());
+        assert!(r_pentomino.is_stable());
+        assert_eq!(r_pentomino.lifespan(), 1103);
+        assert_eq!(r_pentomino.max_population(), 319);
+        assert_eq!(r_pentomino.final_population(), 116);
+
+        let blinker = [(0, 1), (1, 1), (2, 1)];
+        let blinker: Vec<_> = blinker
+            .iter()
+            .map(|(x, y)| GridPoint2D::new(*x, *y))
+            .collect();
+        let blinker = search.run(&blinker);
+        assert!(blinker.is_stable());
+        assert_eq!(blinker.lifespan(), 0);
+        assert_eq!(blinker.final_population(), 3);
+    }
+
+    #[test]
+    fn methuselah_search_test_enumerate() {
+        let search = new_search().with_bounding_box(3, 3).with_top_count(3);
+        let best = search.enumerate();
+        // A seed turning into the R-pentomino, then the R-pentomino itself.
+        let lifespans: Vec<_> = best.iter().map(|m| m.lifespan()).collect();
+        assert_eq!(lifespans, vec![1104, 1103, 11]);
+        assert_eq!(best[1].cells(), r_pentomino_cells().as_slice());
+
+        let search = search.with_rank(MethuselahRank::MaxPopulation);
+        assert_eq!(search.enumerate()[0].max_population(), 319);
+    }
+
+    #[test]
+    fn methuselah_search_test_sample() {
+        let search = new_search().with_bounding_box(3, 3).with_top_count(3);
+        let best = search.sample(50, 7);
+        assert_eq!(best, search.sample(50, 7));
+        assert!(best.len() <= 3);
+        assert!(best.windows(2).all(|w| w[0].lifespan() >= w[1].lifespan()));
+        assert!(best
+            .iter()
+            .all(|m| m.cells().iter().all(|c| c.x < 3 && c.y < 3)));
+    }
+}
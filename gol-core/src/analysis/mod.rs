@@ -0,0 +1,5 @@
+pub mod census;
+pub mod methuselah_search;
+pub mod pattern_search;
+pub mod rle;
+pub mod soup_search;
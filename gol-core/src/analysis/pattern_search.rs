@@ -0,0 +1,348 @@
+use crate::{CensusObject, GridPoint2D, ObjectCensus};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+const DEFAULT_SIZE: usize = 5;
+
+const DEFAULT_MAX_RESULTS: usize = 16;
+
+/// Search nodes visited between progress reports.
+const PROGRESS_INTERVAL: u64 = 1 << 16;
+
+const UNKNOWN: i8 = -1;
+
+/// How far a pattern search got, reported while it runs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PatternSearchProgress {
+    /// Cells decided so far, counting every time a cell was tried as dead or alive.
+    pub nodes: u64,
+    /// Estimated part of the search space already covered, between 0 and 1.
+    pub fraction: f64,
+    /// Distinct patterns found so far.
+    pub found: usize,
+}
+
+/// Searches oscillators and spaceships of a two-state life-like rule that fit in a small box:
+/// patterns that return to themselves moved by the displacement after the period. Cells of every
+/// phase are decided one by one depth-first, and branches that cannot satisfy the rule are cut
+/// as soon as neighborhoods are known well enough.
+pub struct PatternSearch {
+    census: ObjectCensus,
+    survive_mask: u16,
+    born_mask: u16,
+    period: usize,
+    displacement: (i64, i64),
+    width: usize,
+    height: usize,
+    max_results: usize,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl PatternSearch {
+    /// Search of patterns with the period, moving by the displacement in x and y every period.
+    /// Oscillators have no displacement.
+    pub fn new(census: ObjectCensus, period: usize, displacement: (i64, i64)) -> Self {
+        assert!(period > 0, "Patterns have a period of at least 1.");
+        let to_mask = |counts: Vec<usize>| counts.iter().fold(0u16, |res, c| res | 1 << c);
+        let survive_mask = to_mask(census.survive_counts());
+        let born_mask = to_mask(census.born_counts());
+        let max_period = census.max_period().max(period);
+        Self {
+            census: census.with_max_period(max_period),
+            survive_mask,
+            born_mask,
+            period,
+            displacement,
+            width: DEFAULT_SIZE,
+            height: DEFAULT_SIZE,
+            max_results: DEFAULT_MAX_RESULTS,
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn census(&self) -> &ObjectCensus {
+        &self.census
+    }
+
+    /// Box all phases of the patterns fit in, 5 by 5 by default. Spaceships move within the box
+    /// during a period, so it needs room for that too.
+    pub fn with_bounding_box(self, width: usize, height: usize) -> Self {
+        let mut res = self;
+        res.width = width;
+        res.height = height;
+        res
+    }
+
+    /// Number of distinct patterns after which the search stops, 16 by default.
+    pub fn with_max_results(self, max_results: usize) -> Self {
+        let mut res = self;
+        res.max_results = max_results;
+        res
+    }
+
+    /// Flag stopping the search as soon as it is set, e.g. from another thread.
+    pub fn cancel_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.cancelled)
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Runs the search until it covered all patterns in the box, found the maximum number of
+    /// results or was cancelled. Found patterns are single objects of exactly the period and
+    /// displacement, each in one phase and orientation only.
+    pub fn search<F>(&self, on_progress: F) -> Vec<CensusObject>
+    where
+        F: FnMut(&PatternSearchProgress),
+    {
+        let mut dfs = Dfs {
+            search: self,
+            cells: vec![UNKNOWN; self.period * self.width * self.height],
+            path: Vec::new(),
+            nodes: 0,
+            codes: HashSet::new(),
+            found: Vec::new(),
+            on_progress,
+        };
+        dfs.extend(0);
+        dfs.found
+    }
+
+    fn is_done(&self, found: usize) -> bool {
+        found >= self.max_results || self.is_cancelled()
+    }
+}
+
+struct Dfs<'a, F> {
+    search: &'a PatternSearch,
+    /// States of all cells of all phases in the box, phase by phase and row by row.
+    cells: Vec<i8>,
+    /// Whether the first or second state was tried for each decided cell.
+    path: Vec<u8>,
+    nodes: u64,
+    codes: HashSet<String>,
+    found: Vec<CensusObject>,
+    on_progress: F,
+}
+
+impl<'a, F> Dfs<'a, F>
+where
+    F: FnMut(&PatternSearchProgress),
+{
+    /// Decides cells in rows, every phase of a cell before the next cell.
+    fn extend(&mut self, depth: usize) {
+        if self.search.is_done(self.found.len()) {
+            return;
+        }
+        if self.is_moved_copy(depth) {
+            return;
+        }
+        if depth == self.cells.len() {
+            self.check_pattern();
+            return;
+        }
+        let (phase, x, y) = self.cell_at(depth);
+        let i = self.index(phase, x, y);
+        for (branch, state) in [0i8, 1].iter().enumerate() {
+            self.nodes += 1;
+            if self.nodes.is_multiple_of(PROGRESS_INTERVAL) {
+                self.report_progress();
+            }
+            self.cells[i] = *state;
+            self.path.push(branch as u8);
+            if self.is_consistent(phase, x, y) {
+                self.extend(depth + 1);
+            }
+            self.path.pop();
+        }
+        self.cells[i] = UNKNOWN;
+    }
+
+    fn cell_at(&self, depth: usize) -> (usize, i64, i64) {
+        let period = self.search.period;
+        let cell = depth / period;
+        let x = cell % self.search.width;
+        let y = cell / self.search.width;
+        (depth % period, x as i64, y as i64)
+    }
+
+    fn index(&self, phase: usize, x: i64, y: i64) -> usize {
+        let (width, height) = (self.search.width, self.search.height);
+        phase * width * height + y as usize * width + x as usize
+    }
+
+    /// State of the cell in the phase, the phase after the last one is the first one moved by
+    /// the displacement. Cells outside the box are dead.
+    fn state(&self, phase: usize, x: i64, y: i64) -> i8 {
+        if phase == self.search.period {
+            let (dx, dy) = self.search.displacement;
+            return self.state(0, x - dx, y - dy);
+        }
+        if x < 0 || y < 0 || x >= self.search.width as i64 || y >= self.search.height as i64 {
+            return 0;
+        }
+        self.cells[self.index(phase, x, y)]
+    }
+
+    /// Whether all neighborhoods the cell was just decided in can still evolve as required.
+    fn is_consistent(&self, phase: usize, x: i64, y: i64) -> bool {
+        let around = (-1..=1).all(|dy| (-1..=1).all(|dx| self.can_evolve(phase, x + dx, y + dy)));
+        let from = if phase > 0 {
+            self.can_evolve(phase - 1, x, y)
+        } else {
+            let (dx, dy) = self.search.displacement;
+            self.can_evolve(self.search.period - 1, x + dx, y + dy)
+        };
+        around && from
+    }
+
+    /// Whether some states of the unknown cells of the neighborhood in the phase give the
+    /// state of its center in the next phase.
+    fn can_evolve(&self, phase: usize, x: i64, y: i64) -> bool {
+        let next = self.state(phase + 1, x, y);
+        if next == UNKNOWN {
+            return true;
+        }
+        let (mut alive, mut unknown) = (0, 0);
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                if dx == 0 && dy == 0 {
+                    continue;
+                }
+                match self.state(phase, x + dx, y + dy) {
+                    UNKNOWN => unknown += 1,
+                    1 => alive += 1,
+                    _ => (),
+                }
+            }
+        }
+        let counts = ((1u16 << (unknown + 1)) - 1) << alive;
+        let center = self.state(phase, x, y);
+        [(0, self.search.born_mask), (1, self.search.survive_mask)]
+            .iter()
+            .filter(|(state, _)| center == UNKNOWN || center == *state)
+            .any(|(_, mask)| {
+                let alive_next = mask & counts;
+                if next == 1 {
+                    alive_next != 0
+                } else {
+                    alive_next != counts
+                }
+            })
+    }
+
+    /// Whether the pattern stays away from the top or left of the box in all phases, in which
+    /// case it is found again moved there. Checked as soon as the top row or left column is
+    /// decided.
+    fn is_moved_copy(&self, depth: usize) -> bool {
+        let (width, height) = (self.search.width as i64, self.search.height as i64);
+        let period = self.search.period;
+        let edge: Vec<(i64, i64)> = if depth == width as usize * period {
+            (0..width).map(|x| (x, 0)).collect()
+        } else if depth == ((height - 1) * width) as usize * period + period {
+            (0..height).map(|y| (0, y)).collect()
+        } else {
+            return false;
+        };
+        edge.iter()
+            .all(|(x, y)| (0..period).all(|phase| self.state(phase, *x, *y) == 0))
+    }
+
+    /// Keeps the decided pattern if it is a new single object with exactly the period and
+    /// displacement searched for.
+    fn check_pattern(&mut self) {
+        let (width, height) = (self.search.width as i64, self.search.height as i64);
+        let cells: Vec<GridPoint2D<i64>> = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .filter(|(x, y)| self.state(0, *x, *y) == 1)
+            .map(|(x, y)| GridPoint2D::new(x, y))
+            .collect();
+        if cells.is_empty() || self.search.census.islands(&cells).len() != 1 {
+            return;
+        }
+        let object = self.search.census.classify(&cells);
+        if object.period() != self.search.period
+            || object.displacement() != self.search.displacement
+        {
+            return;
+        }
+        if self.codes.insert(object.code().to_string()) {
+            self.found.push(object);
+            self.report_progress();
+        }
+    }
+
+    fn report_progress(&mut self) {
+        let fraction = self
+            .path
+            .iter()
+            .take(52)
+            .enumerate()
+            .map(|(depth, branch)| *branch as f64 / (2u64 << depth) as f64)
+            .sum();
+        (self.on_progress)(&PatternSearchProgress {
+            nodes: self.nodes,
+            fraction,
+            found: self.found.len(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod pattern_search_test {
+    use crate::{ObjectCensus, ObjectKind, PatternSearch};
+
+    fn new_search(period: usize, displacement: (i64, i64)) -> PatternSearch {
+        PatternSearch::new(
+            ObjectCensus::from_rule("B3/S23").unwrap(),
+            period,
+            displacement,
+        )
+    }
+
+    #[test]
+    fn pattern_search_test_oscillator() {
+        let search = new_search(2, (0, 0)).with_bounding_box(4, 4);
+        let mut reports = 0;
+        let found = search.search(|_| reports += 1);
+        let mut names: Vec<_> = found.iter().map(|o| o.name().unwrap()).collect();
+        names.sort_unstable();
+        assert_eq!(names, vec!["beacon", "blinker", "clock", "toad"]);
+        assert!(found.iter().all(|o| o.kind() == ObjectKind::Oscillator));
+        assert_eq!(reports, found.len());
+        assert!(!search.is_cancelled());
+    }
+
+    #[test]
+    fn pattern_search_test_still_life() {
+        let found = new_search(1, (0, 0))
+            .with_bounding_box(3, 3)
+            .with_max_results(3)
+            .search(|_| ());
+        assert_eq!(found.len(), 3);
+        assert!(found.iter().all(|o| o.kind() == ObjectKind::StillLife));
+    }
+
+    #[test]
+    fn pattern_search_test_spaceship() {
+        let found = new_search(4, (1, 1))
+            .with_bounding_box(4, 4)
+            .with_max_results(1)
+            .search(|_| ());
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name(), Some("glider"));
+        assert_eq!(found[0].displacement(), (1, 1));
+    }
+
+    #[test]
+    fn pattern_search_test_cancel() {
+        let search = new_search(2, (0, 0)).with_bounding_box(6, 6);
+        search
+            .cancel_flag()
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+        assert!(search.search(|_| ()).is_empty());
+        assert!(search.is_cancelled());
+    }
+}
@@ -0,0 +1,89 @@
+use crate::GridPoint2D;
+
+/// Longest line of the encoded cells, as in files written by Golly.
+const MAX_LINE_LENGTH: usize = 70;
+
+/// Alive cells in the run length encoded format most pattern collections use, with the rule in
+/// the header, e.g. `B3/S23`.
+pub fn to_rle(cells: &[GridPoint2D<i64>], rule: &str) -> String {
+    let min_x = cells.iter().map(|c| c.x).min().unwrap_or(0);
+    let min_y = cells.iter().map(|c| c.y).min().unwrap_or(0);
+    let width = cells.iter().map(|c| c.x - min_x + 1).max().unwrap_or(0);
+    let height = cells.iter().map(|c| c.y - min_y + 1).max().unwrap_or(0);
+    let mut rows = vec![Vec::new(); height as usize];
+    for cell in cells.iter() {
+        rows[(cell.y - min_y) as usize].push(cell.x - min_x);
+    }
+
+    // Runs of dead cells `b`, alive cells `o` and row ends `$`, trailing dead cells left out.
+    let mut runs: Vec<(usize, char)> = Vec::new();
+    let mut push = |count: usize, tag: char| match runs.last_mut() {
+        Some((last_count, last_tag)) if *last_tag == tag => *last_count += count,
+        _ => runs.push((count, tag)),
+    };
+    let row_count = rows.len();
+    for (y, row) in rows.iter_mut().enumerate() {
+        row.sort_unstable();
+        let mut next_x = 0;
+        for x in row.iter() {
+            if *x > next_x {
+                push((x - next_x) as usize, 'b');
+            }
+            push(1, 'o');
+            next_x = x + 1;
+        }
+        if y + 1 < row_count {
+            push(1, '$');
+        }
+    }
+
+    let mut res = format!("x = {}, y = {}, rule = {}\n", width, height, rule);
+    let mut line = String::new();
+    for (count, tag) in runs.iter() {
+        let run = match count {
+            1 => tag.to_string(),
+            _ => format!("{}{}", count, tag),
+        };
+        if line.len() + run.len() > MAX_LINE_LENGTH {
+            res.push_str(&line);
+            res.push('\n');
+            line.clear();
+        }
+        line.push_str(&run);
+    }
+    res.push_str(&line);
+    res.push_str("!\n");
+    res
+}
+
+#[cfg(test)]
+mod rle_test {
+    use super::to_rle;
+    use crate::GridPoint2D;
+
+    #[test]
+    fn rle_test_glider() {
+        let glider: Vec<_> = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]
+            .iter()
+            .map(|(x, y)| GridPoint2D::new(x + 10, y - 5))
+            .collect();
+        assert_eq!(
+            to_rle(&glider, "B3/S23"),
+            "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n"
+        );
+    }
+
+    #[test]
+    fn rle_test_empty_rows_and_long_lines() {
+        let cells = vec![GridPoint2D::new(0, 0), GridPoint2D::new(0, 3)];
+        assert_eq!(
+            to_rle(&cells, "B3/S23"),
+            "x = 1, y = 4, rule = B3/S23\no3$o!\n"
+        );
+
+        let cells: Vec<_> = (0..100).map(|x| GridPoint2D::new(x * 2, 0)).collect();
+        let rle = to_rle(&cells, "B3/S23");
+        assert!(rle.lines().all(|line| line.len() <= 70));
+        assert_eq!(rle.matches('o').count(), 100);
+    }
+}
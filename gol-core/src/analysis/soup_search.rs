@@ -0,0 +1,547 @@
+use crate::analysis::census::{known_objects, Cell};
+use crate::{Census, CensusObject, GridPoint2D, ObjectCensus, ObjectKind};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::path::Path;
+
+const DEFAULT_SOUP_SIZE: usize = 16;
+
+const DEFAULT_ALIVE_RATIO: f64 = 0.5;
+
+const DEFAULT_MAX_GENERATIONS: usize = 20000;
+
+/// Generations between checks for spaceships leaving the soup.
+const ESCAPE_INTERVAL: usize = 64;
+
+/// Smallest gap between a spaceship and the rest of the soup before it counts as escaped.
+const ESCAPE_DISTANCE: i64 = 16;
+
+/// Generations kept beyond the longest period, so cycles that started before the last spaceship
+/// was taken out are still traced back to where they started.
+const HISTORY_MARGIN: usize = 4 * ESCAPE_INTERVAL;
+
+/// Objects found in almost every soup of Conway's Game of Life, everything else is rare.
+const COMMON_NAMES: [&str; 14] = [
+    "block",
+    "beehive",
+    "loaf",
+    "boat",
+    "ship",
+    "tub",
+    "pond",
+    "barge",
+    "long boat",
+    "long ship",
+    "blinker",
+    "toad",
+    "beacon",
+    "glider",
+];
+
+/// Evolution of a soup until it settles.
+pub struct SoupResult {
+    census: Census,
+    generations: usize,
+    is_stable: bool,
+}
+
+impl SoupResult {
+    /// Objects left when the soup settled, including spaceships that escaped before.
+    pub fn census(&self) -> &Census {
+        &self.census
+    }
+
+    /// Generations until the soup repeated, or the maximum number of generations.
+    pub fn generations(&self) -> usize {
+        self.generations
+    }
+
+    /// Whether the soup repeated within the maximum number of generations.
+    pub fn is_stable(&self) -> bool {
+        self.is_stable
+    }
+}
+
+/// Soup with an object that is not common.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RareFind {
+    pub seed: u64,
+    pub code: String,
+    pub name: Option<String>,
+}
+
+/// Results of all soups searched so far, saved after every batch so searches can be resumed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SearchResults {
+    rule: String,
+    soup_size: usize,
+    alive_ratio: f64,
+    next_seed: u64,
+    census: Census,
+    rare_finds: Vec<RareFind>,
+    /// Soups still changing after the maximum number of generations.
+    unstable_seeds: Vec<u64>,
+}
+
+impl SearchResults {
+    pub fn new(search: &SoupSearch) -> Self {
+        Self {
+            rule: search.census.rule(),
+            soup_size: search.soup_size,
+            alive_ratio: search.alive_ratio,
+            next_seed: 0,
+            census: Census::new(),
+            rare_finds: Vec::new(),
+            unstable_seeds: Vec::new(),
+        }
+    }
+
+    /// Results saved at the path, or new ones if there are none yet. Fails if the saved results
+    /// are from a search with other parameters.
+    pub fn load_or_new<P>(path: P, search: &SoupSearch) -> std::io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let res: Self = match std::fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json)?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Self::new(search)),
+            Err(err) => return Err(err),
+        };
+        let expected = Self::new(search);
+        if (&res.rule, res.soup_size, res.alive_ratio)
+            != (&expected.rule, expected.soup_size, expected.alive_ratio)
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "Results are from a search of {} soups of {}x{} with alive ratio {}.",
+                    res.rule, res.soup_size, res.soup_size, res.alive_ratio
+                ),
+            ));
+        }
+        Ok(res)
+    }
+
+    /// Writes the results to a temporary file first, so an interrupted search never leaves a
+    /// broken file behind.
+    pub fn save<P>(&self, path: P) -> std::io::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let mut tmp_path = path.as_os_str().to_owned();
+        tmp_path.push(".tmp");
+        std::fs::write(&tmp_path, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(&tmp_path, path)
+    }
+
+    /// Number of soups searched, also the seed of the next soup.
+    pub fn soup_count(&self) -> u64 {
+        self.next_seed
+    }
+
+    pub fn census(&self) -> &Census {
+        &self.census
+    }
+
+    pub fn rare_finds(&self) -> &[RareFind] {
+        &self.rare_finds
+    }
+
+    pub fn unstable_seeds(&self) -> &[u64] {
+        &self.unstable_seeds
+    }
+}
+
+/// Searches seeded random soups for rare objects, the same way as apgsearch. Soups evolve on an
+/// unbounded plane until they repeat, spaceships leaving the soup are taken out on the way.
+pub struct SoupSearch {
+    census: ObjectCensus,
+    soup_size: usize,
+    alive_ratio: f64,
+    max_generations: usize,
+    common_codes: HashSet<String>,
+}
+
+impl SoupSearch {
+    pub fn new(census: ObjectCensus) -> Self {
+        Self {
+            census,
+            soup_size: DEFAULT_SOUP_SIZE,
+            alive_ratio: DEFAULT_ALIVE_RATIO,
+            max_generations: DEFAULT_MAX_GENERATIONS,
+            common_codes: known_objects()
+                .iter()
+                .filter(|(_, name)| COMMON_NAMES.contains(name))
+                .map(|(code, _)| code.clone())
+                .collect(),
+        }
+    }
+
+    /// Width and height of the random square each soup starts as, 16 by default.
+    pub fn with_soup_size(self, soup_size: usize) -> Self {
+        let mut res = self;
+        res.soup_size = soup_size;
+        res
+    }
+
+    /// Chance of each cell of the soup being alive, 0.5 by default.
+    pub fn with_alive_ratio(self, alive_ratio: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&alive_ratio),
+            "Alive ratio must be between 0 and 1."
+        );
+        let mut res = self;
+        res.alive_ratio = alive_ratio;
+        res
+    }
+
+    /// Generations a soup evolves for at most before it is given up as unstable, 20000 by
+    /// default.
+    pub fn with_max_generations(self, max_generations: usize) -> Self {
+        let mut res = self;
+        res.max_generations = max_generations;
+        res
+    }
+
+    /// Codes of objects that are not recorded as rare finds. Defaults to the most common still
+    /// lifes, oscillators and the glider of Conway's Game of Life.
+    pub fn with_common_codes(self, common_codes: HashSet<String>) -> Self {
+        let mut res = self;
+        res.common_codes = common_codes;
+        res
+    }
+
+    pub fn is_rare(&self, code: &str) -> bool {
+        code != "unstable" && !self.common_codes.contains(code)
+    }
+
+    /// Alive cells of the soup with the seed, the same for every run.
+    pub fn soup(&self, seed: u64) -> Vec<GridPoint2D<i64>> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let size = self.soup_size as i64;
+        (0..size)
+            .flat_map(|y| (0..size).map(move |x| GridPoint2D::new(x, y)))
+            .filter(|_| rng.gen_bool(self.alive_ratio))
+            .collect()
+    }
+
+    /// Evolves the soup with the seed until it repeats and takes a census of it.
+    pub fn run_soup(&self, seed: u64) -> SoupResult {
+        let cells = self.soup(seed).iter().map(|c| (c.x, c.y)).collect();
+        let settled = settle(&self.census, cells, self.max_generations);
+        let cells: Vec<GridPoint2D<i64>> = settled
+            .cells
+            .iter()
+            .map(|(x, y)| GridPoint2D::new(*x, *y))
+            .collect();
+        let mut census = Census::new();
+        for object in settled
+            .escaped
+            .iter()
+            .chain(self.census.objects(&cells).iter())
+        {
+            census.add(object);
+        }
+        SoupResult {
+            census,
+            generations: settled.generations,
+            is_stable: settled.is_stable,
+        }
+    }
+
+    /// Runs the next `soup_count` soups in parallel and adds them to the results.
+    pub fn search(&self, results: &mut SearchResults, soup_count: u64) {
+        let first_seed = results.next_seed;
+        let soups: Vec<(u64, SoupResult)> = (first_seed..first_seed + soup_count)
+            .into_par_iter()
+            .map(|seed| (seed, self.run_soup(seed)))
+            .collect();
+        for (seed, soup) in soups {
+            results.census.merge(&soup.census);
+            if !soup.is_stable {
+                results.unstable_seeds.push(seed);
+            }
+            for (code, entry) in soup.census.entries() {
+                if self.is_rare(code) {
+                    results.rare_finds.push(RareFind {
+                        seed,
+                        code: code.clone(),
+                        name: entry.name().map(String::from),
+                    });
+                }
+            }
+        }
+        results.next_seed = first_seed + soup_count;
+    }
+}
+
+/// Cells on an unbounded plane evolved until they repeat.
+pub(crate) struct Settled {
+    /// Cells when the repeat was found, without escaped spaceships.
+    pub(crate) cells: HashSet<Cell>,
+    /// Spaceships taken out after leaving the rest of the cells.
+    pub(crate) escaped: Vec<CensusObject>,
+    /// Generations until the repeat was found, or the maximum number of generations.
+    pub(crate) generations: usize,
+    /// First generation of the cycle the cells settled in, escaped spaceships aside. Cycles are
+    /// only traced back as far as the generations kept, which covers spaceships escaping
+    /// quickly.
+    pub(crate) lifespan: usize,
+    /// Largest number of alive cells in any generation, including escaped spaceships.
+    pub(crate) max_population: usize,
+    pub(crate) is_stable: bool,
+}
+
+/// Evolves the cells until they repeat, taking out spaceships leaving the rest of the cells so
+/// patterns emitting them still settle.
+pub(crate) fn settle(
+    census: &ObjectCensus,
+    cells: HashSet<Cell>,
+    max_generations: usize,
+) -> Settled {
+    let max_period = census.max_period();
+    let mut res = Settled {
+        max_population: cells.len(),
+        cells,
+        escaped: Vec::new(),
+        generations: max_generations,
+        lifespan: max_generations,
+        is_stable: false,
+    };
+    let mut escaped_population = 0;
+    let mut history: VecDeque<(usize, u64, HashSet<Cell>)> = VecDeque::new();
+    history.push_back((0, hash_cells(&res.cells), res.cells.clone()));
+    for generation in 1..=max_generations {
+        res.cells = census.step(&res.cells);
+        res.max_population = res.max_population.max(res.cells.len() + escaped_population);
+        if generation % ESCAPE_INTERVAL == 0 {
+            let removed = remove_escaped(census, &mut res.cells);
+            for object in removed.iter() {
+                // Take the spaceship out of the earlier generations too, so cycles started before
+                // it left are still found. Generations before it was whole are of no use anymore.
+                let mut first_kept = 0;
+                for (i, (prev_generation, hash, prev)) in history.iter_mut().enumerate().rev() {
+                    let ship = past_cells(census, object, generation - *prev_generation);
+                    if !ship.is_subset(prev) {
+                        first_kept = i + 1;
+                        break;
+                    }
+                    for cell in ship.iter() {
+                        prev.remove(cell);
+                    }
+                    *hash = hash_cells(prev);
+                }
+                history.drain(..first_kept);
+                escaped_population += object.cells().len();
+            }
+            res.escaped.extend(removed);
+        }
+        let hash = hash_cells(&res.cells);
+        let repeat = history
+            .iter()
+            .rev()
+            .find(|(_, h, prev)| *h == hash && *prev == res.cells);
+        if let Some((prev_generation, _, _)) = repeat {
+            let period = generation - prev_generation;
+            let first_generation = history[0].0;
+            let state = |generation: usize| &history[generation - first_generation];
+            res.lifespan = *prev_generation;
+            while res.lifespan > first_generation {
+                let (_, hash, cells) = state(res.lifespan - 1);
+                let (_, later_hash, later_cells) = state(res.lifespan - 1 + period);
+                if hash != later_hash || cells != later_cells {
+                    break;
+                }
+                res.lifespan -= 1;
+            }
+            res.generations = generation;
+            res.is_stable = true;
+            break;
+        }
+        history.push_back((generation, hash, res.cells.clone()));
+        if history.len() > max_period + HISTORY_MARGIN {
+            history.pop_front();
+        }
+    }
+    res
+}
+
+/// Cells of the spaceship the number of generations before it was classified.
+fn past_cells(
+    census: &ObjectCensus,
+    spaceship: &CensusObject,
+    generations: usize,
+) -> HashSet<Cell> {
+    let period = spaceship.period();
+    let periods = generations.div_ceil(period) as i64;
+    let (dx, dy) = spaceship.displacement();
+    let mut res: HashSet<Cell> = spaceship.cells().iter().map(|c| (c.x, c.y)).collect();
+    for _ in 0..(periods as usize * period - generations) {
+        res = census.step(&res);
+    }
+    res.into_iter()
+        .map(|(x, y)| (x - periods * dx, y - periods * dy))
+        .collect()
+}
+
+/// Takes out spaceships far from the rest of the cells and moving away from them.
+fn remove_escaped(census: &ObjectCensus, cells: &mut HashSet<Cell>) -> Vec<CensusObject> {
+    let all: Vec<GridPoint2D<i64>> = cells
+        .iter()
+        .map(|(x, y)| GridPoint2D::new(*x, *y))
+        .collect();
+    let islands = census.islands(&all);
+    let bounds: Vec<Bounds> = islands.iter().map(|island| Bounds::new(island)).collect();
+    let mut res = Vec::new();
+    for (i, island) in islands.iter().enumerate() {
+        let rest = bounds
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .map(|(_, bounds)| *bounds)
+            .reduce(Bounds::union);
+        let gap = match &rest {
+            Some(rest) => rest.gap(&bounds[i]),
+            None => i64::MAX,
+        };
+        if gap <= ESCAPE_DISTANCE {
+            continue;
+        }
+        let object = census.classify(island);
+        let (dx, dy) = object.displacement();
+        let is_leaving = match &rest {
+            Some(rest) => rest.gap(&bounds[i].shift(dx, dy)) > gap,
+            None => true,
+        };
+        if object.kind() != ObjectKind::Spaceship || !is_leaving {
+            continue;
+        }
+        for cell in island.iter() {
+            cells.remove(&(cell.x, cell.y));
+        }
+        res.push(object);
+    }
+    res
+}
+
+#[derive(Clone, Copy)]
+struct Bounds {
+    min: Cell,
+    max: Cell,
+}
+
+impl Bounds {
+    fn new(cells: &[GridPoint2D<i64>]) -> Self {
+        cells.iter().fold(
+            Self {
+                min: (i64::MAX, i64::MAX),
+                max: (i64::MIN, i64::MIN),
+            },
+            |res, cell| Self {
+                min: (res.min.0.min(cell.x), res.min.1.min(cell.y)),
+                max: (res.max.0.max(cell.x), res.max.1.max(cell.y)),
+            },
+        )
+    }
+
+    fn union(self, other: Self) -> Self {
+        Self {
+            min: (self.min.0.min(other.min.0), self.min.1.min(other.min.1)),
+            max: (self.max.0.max(other.max.0), self.max.1.max(other.max.1)),
+        }
+    }
+
+    fn shift(&self, dx: i64, dy: i64) -> Self {
+        Self {
+            min: (self.min.0 + dx, self.min.1 + dy),
+            max: (self.max.0 + dx, self.max.1 + dy),
+        }
+    }
+
+    /// Largest distance along x or y between the boxes, 0 if they overlap.
+    fn gap(&self, other: &Self) -> i64 {
+        let gap_x = (other.min.0 - self.max.0).max(self.min.0 - other.max.0);
+        let gap_y = (other.min.1 - self.max.1).max(self.min.1 - other.max.1);
+        gap_x.max(gap_y).max(0)
+    }
+}
+
+/// Hash of the cells independent of their order.
+fn hash_cells(cells: &HashSet<Cell>) -> u64 {
+    cells.iter().fold(cells.len() as u64, |res, (x, y)| {
+        // SplitMix64 finalizer of both coordinates.
+        let mut z = (*x as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ (*y as u64);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        res.wrapping_add(z ^ (z >> 31))
+    })
+}
+
+#[cfg(test)]
+mod soup_search_test {
+    use crate::{ObjectCensus, SearchResults, SoupSearch};
+
+    fn new_search() -> SoupSearch {
+        SoupSearch::new(ObjectCensus::from_rule("B3/S23").unwrap())
+    }
+
+    #[test]
+    fn soup_search_test_soup() {
+        let search = new_search().with_soup_size(8);
+        assert_eq!(search.soup(3), search.soup(3));
+        assert_ne!(search.soup(3), search.soup(4));
+        assert!(search
+            .soup(3)
+            .iter()
+            .all(|c| (0..8).contains(&c.x) && (0..8).contains(&c.y)));
+        assert!(search.with_alive_ratio(0.0).soup(3).is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn soup_search_test_alive_ratio_out_of_range() {
+        new_search().with_alive_ratio(1.5);
+    }
+
+    #[test]
+    fn soup_search_test_run_soup() {
+        let search = new_search().with_soup_size(8);
+        for seed in 0..4 {
+            let soup = search.run_soup(seed);
+            assert!(soup.is_stable());
+            assert_eq!(soup.census().count("unstable"), 0);
+        }
+
+        let search = new_search().with_max_generations(3);
+        assert!(!search.run_soup(0).is_stable());
+        assert!(search.is_rare("xp15_4r4z4r4"));
+        assert!(!search.is_rare("xs4_33"));
+        assert!(!search.is_rare("unstable"));
+    }
+
+    #[test]
+    fn soup_search_test_resume() {
+        let search = new_search().with_soup_size(8);
+        let path =
+            std::env::temp_dir().join(format!("gol_soup_search_{}.json", std::process::id()));
+        let mut results = SearchResults::load_or_new(&path, &search).unwrap();
+        search.search(&mut results, 2);
+        results.save(&path).unwrap();
+
+        let mut resumed = SearchResults::load_or_new(&path, &search).unwrap();
+        assert_eq!(resumed, results);
+        search.search(&mut resumed, 2);
+        let mut all = SearchResults::new(&search);
+        search.search(&mut all, 4);
+        assert_eq!(resumed, all);
+        assert_eq!(all.soup_count(), 4);
+        assert!(all.census().total() > 0);
+
+        let other = new_search().with_soup_size(10);
+        assert!(SearchResults::load_or_new(&path, &other).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
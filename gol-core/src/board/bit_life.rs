@@ -0,0 +1,436 @@
+use crate::{BoardSpaceManager, BoardStateManager, IndexedDataOwned};
+use rayon::prelude::*;
+use std::collections::HashSet;
+
+/// Two-state outer-totalistic rule with the Moore neighborhood of margin 1 on a bounded 2D grid,
+/// evolved with bit-sliced adders that count neighbors for 64 cells at once.
+///
+/// Rows of the bit board are the cells sharing the same x, in the order of the space manager, so
+/// the board is transposed compared to the grid. The Moore neighborhood is symmetric and does not
+/// care. Cells outside of a board that does not wrap are dead.
+pub struct BitLife<CI, T> {
+    indices: Vec<CI>,
+    layout: BitLayout,
+    rule: TwoStateRule<T>,
+}
+
+/// Bit-packed 2D board, one bit per cell. Each row is stored in whole `u64` words with column `i`
+/// at bit `i % 64` of word `i / 64`, rows follow each other without padding.
+#[derive(Clone, Debug)]
+pub(crate) struct BitLayout {
+    row_count: usize,
+    col_count: usize,
+    words_per_row: usize,
+    is_donut: bool,
+}
+
+/// Two-state outer-totalistic rule, with the alive neighbor counts as bit masks.
+pub struct TwoStateRule<T> {
+    survive_mask: u16,
+    born_mask: u16,
+    dead: T,
+    alive: T,
+    is_alive: fn(&T) -> bool,
+}
+
+impl<T> TwoStateRule<T> {
+    pub fn new(
+        survive_counts: &HashSet<usize>,
+        born_counts: &HashSet<usize>,
+        dead: T,
+        alive: T,
+        is_alive: fn(&T) -> bool,
+    ) -> Self {
+        let to_mask = |counts: &HashSet<usize>| {
+            counts
+                .iter()
+                .filter(|count| **count <= 8)
+                .fold(0u16, |res, count| res | 1 << count)
+        };
+        Self {
+            survive_mask: to_mask(survive_counts),
+            born_mask: to_mask(born_counts),
+            dead,
+            alive,
+            is_alive,
+        }
+    }
+
+    pub(crate) fn is_alive(&self, state: &T) -> bool {
+        (self.is_alive)(state)
+    }
+
+    pub(crate) fn state(&self, is_alive: bool) -> T
+    where
+        T: Clone,
+    {
+        if is_alive {
+            self.alive.clone()
+        } else {
+            self.dead.clone()
+        }
+    }
+}
+
+impl<CI, T> BitLife<CI, T>
+where
+    CI: Send + Sync + Clone,
+    T: Send + Sync + Clone,
+{
+    /// Indices of the space manager must be in the order of `Grid`, y changing faster than x.
+    pub fn new<I>(
+        space_manager: &dyn BoardSpaceManager<CI, I, rayon::vec::IntoIter<CI>>,
+        width: usize,
+        height: usize,
+        is_donut: bool,
+        rule: TwoStateRule<T>,
+    ) -> Self
+    where
+        I: Iterator<Item = CI>,
+    {
+        let indices: Vec<CI> = space_manager.indices_iter().collect();
+        assert_eq!(indices.len(), width * height);
+        Self {
+            indices,
+            layout: BitLayout::new(width, height, is_donut),
+            rule,
+        }
+    }
+
+    /// Evolves `packed`, the last generation packed, which is looked up in the state manager if it
+    /// is empty. `packed` is replaced by the next generation.
+    pub fn next_states(
+        &self,
+        states: &dyn BoardStateManager<T, CI, rayon::vec::IntoIter<IndexedDataOwned<CI, T>>>,
+        packed: &mut Vec<u64>,
+        res: &mut Vec<IndexedDataOwned<CI, T>>,
+    ) {
+        let layout = &self.layout;
+        if packed.is_empty() {
+            self.pack_with(packed, |i| {
+                self.rule.is_alive(&states.get_cell_state(&self.indices[i]))
+            });
+        }
+        let mut next = vec![0u64; packed.len()];
+        layout.next_rows(packed, 0, &mut next, &self.rule);
+        self.indices
+            .par_iter()
+            .enumerate()
+            .map(|(i, idx)| {
+                let (row, col) = (i / layout.col_count, i % layout.col_count);
+                (idx.clone(), self.rule.state(layout.get(&next, row, col)))
+            })
+            .collect_into_vec(res);
+        *packed = next;
+    }
+
+    /// Packs states in the order of the space manager again, e.g. after the board changed some
+    /// of the ones `next_states` returned.
+    pub fn repack(&self, states: &[IndexedDataOwned<CI, T>], packed: &mut Vec<u64>) {
+        self.pack_with(packed, |i| self.rule.is_alive(&states[i].1));
+    }
+
+    /// Packs the states of the cells, `is_alive` gets their positions in the space manager.
+    fn pack_with<F>(&self, res: &mut Vec<u64>, is_alive: F)
+    where
+        F: Fn(usize) -> bool + Sync,
+    {
+        let layout = &self.layout;
+        res.clear();
+        res.resize(layout.word_count(), 0);
+        res.par_chunks_mut(layout.words_per_row)
+            .enumerate()
+            .for_each(|(row, words)| {
+                for col in 0..layout.col_count {
+                    if is_alive(row * layout.col_count + col) {
+                        words[col / 64] |= 1 << (col % 64);
+                    }
+                }
+            });
+    }
+}
+
+impl BitLayout {
+    pub(crate) fn new(row_count: usize, col_count: usize, is_donut: bool) -> Self {
+        Self {
+            row_count,
+            col_count,
+            words_per_row: col_count.div_ceil(64),
+            is_donut,
+        }
+    }
+
+    pub(crate) fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    pub(crate) fn is_donut(&self) -> bool {
+        self.is_donut
+    }
+
+    pub(crate) fn words_per_row(&self) -> usize {
+        self.words_per_row
+    }
+
+    /// Number of words of the whole board.
+    pub(crate) fn word_count(&self) -> usize {
+        self.row_count * self.words_per_row
+    }
+
+    pub(crate) fn get(&self, board: &[u64], row: usize, col: usize) -> bool {
+        board[row * self.words_per_row + col / 64] >> (col % 64) & 1 == 1
+    }
+
+    pub(crate) fn set(&self, board: &mut [u64], row: usize, col: usize, is_alive: bool) {
+        let (word, bit) = (&mut board[row * self.words_per_row + col / 64], col % 64);
+        if is_alive {
+            *word |= 1 << bit;
+        } else {
+            *word &= !(1 << bit);
+        }
+    }
+
+    /// Evolves the rows starting at `first_row` that fit in `res`, `board` is the whole board.
+    pub(crate) fn next_rows<T>(
+        &self,
+        board: &[u64],
+        first_row: usize,
+        res: &mut [u64],
+        rule: &TwoStateRule<T>,
+    ) where
+        T: Sync,
+    {
+        res.par_chunks_mut(self.words_per_row)
+            .enumerate()
+            .for_each(|(i, res)| self.next_row(board, first_row + i, res, rule));
+    }
+
+    fn row<'a>(&self, board: &'a [u64], row: isize) -> Option<&'a [u64]> {
+        let row_count = self.row_count as isize;
+        let row = if row < 0 || row >= row_count {
+            if !self.is_donut {
+                return None;
+            }
+            (row + row_count) % row_count
+        } else {
+            row
+        };
+        let start = row as usize * self.words_per_row;
+        Some(&board[start..start + self.words_per_row])
+    }
+
+    fn next_row<T>(&self, board: &[u64], row: usize, res: &mut [u64], rule: &TwoStateRule<T>) {
+        let row = row as isize;
+        let (above, center, below) = (
+            self.row(board, row - 1),
+            self.row(board, row).unwrap(),
+            self.row(board, row + 1),
+        );
+        let last_word = self.words_per_row - 1;
+        let last_bit = (self.col_count - 1) % 64;
+        let last_mask = u64::MAX >> (63 - last_bit);
+        for (w, word) in res.iter_mut().enumerate() {
+            let mut neighbors = [0u64; 8];
+            let mut n = 0;
+            for (cur_row, include_center) in [(above, true), (Some(center), false), (below, true)] {
+                let cur_row = match cur_row {
+                    Some(cur_row) => cur_row,
+                    None => {
+                        n += if include_center { 3 } else { 2 };
+                        continue;
+                    }
+                };
+                neighbors[n] = self.west(cur_row, w, last_word, last_bit);
+                neighbors[n + 1] = self.east(cur_row, w, last_word, last_bit);
+                n += 2;
+                if include_center {
+                    neighbors[n] = cur_row[w];
+                    n += 1;
+                }
+            }
+            let counts = count_bits(&neighbors);
+            let mut next = 0u64;
+            for count in 0..=8 {
+                let survive = rule.survive_mask >> count & 1 == 1;
+                let born = rule.born_mask >> count & 1 == 1;
+                if !survive && !born {
+                    continue;
+                }
+                let is_count = (0..4).fold(u64::MAX, |res, bit| {
+                    res & if count >> bit & 1 == 1 {
+                        counts[bit]
+                    } else {
+                        !counts[bit]
+                    }
+                });
+                next |= is_count
+                    & match (survive, born) {
+                        (true, true) => u64::MAX,
+                        (true, false) => center[w],
+                        _ => !center[w],
+                    };
+            }
+            *word = if w == last_word {
+                next & last_mask
+            } else {
+                next
+            };
+        }
+    }
+
+    /// Cells one column before, i.e. bit `i` holds the cell at column `i - 1` of word `w`.
+    fn west(&self, row: &[u64], w: usize, last_word: usize, last_bit: usize) -> u64 {
+        let carry = if w > 0 {
+            row[w - 1] >> 63
+        } else if self.is_donut {
+            row[last_word] >> last_bit & 1
+        } else {
+            0
+        };
+        row[w] << 1 | carry
+    }
+
+    /// Cells one column after, i.e. bit `i` holds the cell at column `i + 1` of word `w`.
+    fn east(&self, row: &[u64], w: usize, last_word: usize, last_bit: usize) -> u64 {
+        let carry = if w < last_word {
+            row[w + 1] << 63
+        } else if self.is_donut {
+            (row[0] & 1) << last_bit
+        } else {
+            0
+        };
+        row[w] >> 1 | carry
+    }
+}
+
+fn full_add(a: u64, b: u64, c: u64) -> (u64, u64) {
+    (a ^ b ^ c, a & b | c & (a ^ b))
+}
+
+/// Bits of the number of set neighbors of every cell, least significant first.
+fn count_bits(neighbors: &[u64; 8]) -> [u64; 4] {
+    let (s0, c0) = full_add(neighbors[0], neighbors[1], neighbors[2]);
+    let (s1, c1) = full_add(neighbors[3], neighbors[4], neighbors[5]);
+    let (s2, c2) = (neighbors[6] ^ neighbors[7], neighbors[6] & neighbors[7]);
+    let (ones, c3) = full_add(s0, s1, s2);
+    let (t0, d0) = full_add(c0, c1, c2);
+    let (twos, d1) = (t0 ^ c3, t0 & c3);
+    [ones, twos, d0 ^ d1, d0 & d1]
+}
+
+#[cfg(test)]
+mod bit_life_test {
+    use crate::{
+        util::grid_util::Shape2D, Board, CellEraser, DecayLifeLikeStrategy, Grid, GridFactory,
+        GridPoint2D, NeighborMoore, NeighborMooreDonut, SharedStrategyManager, SparseStates,
+        StandardBoard, StandardBoardFactory,
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::collections::{HashMap, HashSet};
+
+    fn random_states(width: usize, height: usize) -> HashMap<GridPoint2D<i32>, u8> {
+        let mut rng = StdRng::seed_from_u64(7);
+        let (x_half, y_half) = ((width / 2) as i32, (height / 2) as i32);
+        let mut res = HashMap::new();
+        for x in 0..width as i32 {
+            for y in 0..height as i32 {
+                if rng.gen_bool(0.4) {
+                    res.insert(GridPoint2D::new(x - x_half, y - y_half), 1);
+                }
+            }
+        }
+        res
+    }
+
+    fn assert_same_generations(
+        width: usize,
+        height: usize,
+        is_donut: bool,
+        strat: fn() -> DecayLifeLikeStrategy,
+    ) {
+        let initial_states = random_states(width, height);
+        // Both boards erase the same cells, the packed states must not keep them.
+        let erased: Vec<GridPoint2D<i32>> = initial_states.keys().take(10).cloned().collect();
+        let (eraser, eraser_bit) = (CellEraser::new(0u8), CellEraser::new(0u8));
+        eraser.erase(5, erased.clone().into_iter());
+        eraser_bit.erase(5, erased.into_iter());
+        let mut board = StandardBoard::new(
+            Box::new(Grid::<GridPoint2D<i32>>::new(
+                vec![width, height].into_iter(),
+            )),
+            if is_donut {
+                Box::new(NeighborMooreDonut::new(Shape2D::new(width, height)))
+            } else {
+                Box::new(NeighborMoore::new())
+            },
+            Box::new(SparseStates::new(0u8, initial_states.clone())),
+            Box::new(SharedStrategyManager::new(Box::new(strat()))),
+            Vec::new(),
+        )
+        .with_cell_eraser(eraser);
+        let mut board_bit = StandardBoardFactory::new_standard_2d_grid(
+            (width, height),
+            0u8,
+            1,
+            initial_states,
+            Box::new(strat()),
+            Vec::new(),
+            is_donut,
+        )
+        .with_cell_eraser(eraser_bit);
+        assert!(board_bit.bit_life().is_some());
+        for i in 0..20 {
+            // The packed states are of the wrong generation once time is reversed.
+            if i == 10 {
+                board.reverse();
+                board_bit.reverse();
+            }
+            let mut states = board.advance_one_generation().to_vec();
+            let mut states_bit = board_bit.advance_one_generation().to_vec();
+            states.sort_by_key(|(idx, _)| (idx.x, idx.y));
+            states_bit.sort_by_key(|(idx, _)| (idx.x, idx.y));
+            assert_eq!(states, states_bit);
+        }
+    }
+
+    #[test]
+    fn bit_life_test_gol() {
+        assert_same_generations(10, 7, false, DecayLifeLikeStrategy::gol);
+        assert_same_generations(10, 7, true, DecayLifeLikeStrategy::gol);
+    }
+
+    #[test]
+    fn bit_life_test_multiple_words() {
+        assert_same_generations(5, 130, false, DecayLifeLikeStrategy::gol);
+        assert_same_generations(130, 64, true, DecayLifeLikeStrategy::gol);
+    }
+
+    #[test]
+    fn bit_life_test_born_without_neighbors() {
+        let strat = || {
+            let survive: HashSet<usize> = vec![0, 8].into_iter().collect();
+            let born: HashSet<usize> = vec![0, 1, 2, 6].into_iter().collect();
+            DecayLifeLikeStrategy::new(2, survive, born)
+        };
+        assert_same_generations(66, 9, false, strat);
+        assert_same_generations(66, 9, true, strat);
+    }
+
+    #[test]
+    fn bit_life_test_more_states() {
+        let board = StandardBoardFactory::new_standard_2d_grid(
+            (5usize, 5),
+            0u8,
+            1,
+            HashMap::<GridPoint2D<i32>, u8>::new(),
+            Box::new(DecayLifeLikeStrategy::new(
+                3,
+                vec![2, 3].into_iter().collect(),
+                vec![3].into_iter().collect(),
+            )),
+            Vec::new(),
+            false,
+        );
+        assert!(board.bit_life().is_none());
+    }
+}
@@ -1,8 +1,10 @@
 use crate::{
-    BoardCallbackManager, BoardNeighborManager, BoardSpaceManager, BoardStateManager,
-    BoardStrategyManager, IndexedDataOwned,
+    BitLife, BoardCallbackManager, BoardNeighborManager, BoardSpaceManager, BoardStateManager,
+    BoardStrategyManager, CellEraser, GenerationBuffers, GenerationStates, GrainDropper,
+    IndexedDataOwned, NeighborTable, StateChunk,
 };
 
+use rayon::iter::Either;
 use rayon::prelude::*;
 pub trait Board<T, CI, I>: Send + Sync
 where
@@ -25,18 +27,52 @@ where
         &self,
     ) -> &dyn BoardStrategyManager<CI, T, std::vec::IntoIter<IndexedDataOwned<CI, T>>>;
 
-    fn callback_manager(
-        &mut self,
-    ) -> &mut BoardCallbackManager<T, CI, rayon::vec::IntoIter<IndexedDataOwned<CI, T>>>;
+    fn callback_manager(&mut self) -> &mut BoardCallbackManager<T, CI>;
+
+    fn generation_buffers(&mut self) -> &mut GenerationBuffers<CI, T>;
+
+    /// Precomputed neighbors used instead of the neighbor manager when available.
+    fn neighbor_table(&self) -> Option<&NeighborTable<CI>> {
+        None
+    }
+
+    /// Bit-parallel kernel used instead of the strategy and neighbor managers when available.
+    fn bit_life(&self) -> Option<&BitLife<CI, T>> {
+        None
+    }
+
+    /// Cells killed in the generations they are requested for, e.g. objects removed by callbacks.
+    fn cell_eraser(&self) -> Option<&CellEraser<CI, T>> {
+        None
+    }
+
+    /// Grains dropped on sources in the generations they are due, for driven sandpiles.
+    fn grain_dropper(&self) -> Option<&GrainDropper<CI, T>> {
+        None
+    }
 
     fn advance(&mut self, max_iter: Option<usize>) {
         let mut cur_iter = 0usize;
+        let mut cur_states = self.generation_buffers().take_spare();
         let state_manager = self.state_manager();
-        let cur_states = self
-            .space_manager()
-            .indices_par_iter()
-            .map(|idx| (idx.clone(), state_manager.get_cell_state(&idx)))
-            .collect();
+        match state_manager.chunks() {
+            Some(chunks) => cur_states.par_extend(chunks.into_par_iter().flat_map_iter(|chunk| {
+                let indices = match chunk {
+                    StateChunk::Awake(indices) => indices,
+                    StateChunk::Asleep(indices, _) => indices,
+                };
+                indices
+                    .iter()
+                    .map(move |idx| (idx.clone(), state_manager.get_cell_state(idx)))
+            })),
+            None => self
+                .space_manager()
+                .indices()
+                .par_iter()
+                .map(|idx| (idx.clone(), state_manager.get_cell_state(idx)))
+                .collect_into_vec(&mut cur_states),
+        }
+        let cur_states = self.generation_buffers().push(cur_states);
 
         self.callback_manager().setup_all();
         self.callback_manager().call(cur_states);
@@ -59,33 +95,173 @@ where
         self.callback_manager().cleanup_all();
     }
 
-    fn advance_one_generation(&mut self) -> Vec<IndexedDataOwned<CI, T>> {
-        let states = self.state_manager();
-        let strat = self.strategy_manager();
-        let neighbor_manager = self.neighbor_manager();
+    /// Evolves the board by one generation. The returned states are shared with the board, which
+    /// writes a later generation into the same memory once nothing else holds them.
+    fn advance_one_generation(&mut self) -> GenerationStates<CI, T> {
+        let mut next_states = self.generation_buffers().take_spare();
+        let mut dense = self.generation_buffers().take_dense();
+        let mut packed = self.generation_buffers().take_packed();
+        let mut table_cell_count = None;
+        if let Some(bit_life) = self.bit_life() {
+            bit_life.next_states(self.state_manager(), &mut packed, &mut next_states);
+        } else if let Some(table) = self.neighbor_table() {
+            advance_with_table(
+                table,
+                self.state_manager(),
+                self.strategy_manager(),
+                &mut dense,
+                &mut next_states,
+            );
+            table_cell_count = Some(table.cell_count());
+        } else {
+            let states = self.state_manager();
+            let strat = self.strategy_manager();
+            let neighbor_manager = self.neighbor_manager();
 
-        let next_states: Vec<IndexedDataOwned<CI, T>> = self
-            .space_manager()
-            .indices_par_iter()
-            .map(|idx| {
-                let cur_state = states.get_cell_state(&idx.clone());
+            let evolve = |idx: &CI| {
+                let cur_state = states.get_cell_state(idx);
+                let previous_state = states.get_cell_previous_state(idx);
                 let neighbors: Vec<IndexedDataOwned<CI, T>> = neighbor_manager
-                    .get_neighbors_idx(&idx)
-                    .map(|neighbor_idx| (idx.clone(), states.get_cell_state(&neighbor_idx)))
+                    .get_neighbors_idx(idx)
+                    .map(|neighbor_idx| {
+                        let neighbor_state = states.get_cell_state(&neighbor_idx);
+                        (neighbor_idx, neighbor_state)
+                    })
                     .collect();
                 (
                     idx.clone(),
-                    strat.get_strategy_at_index(idx.clone()).next_state(
+                    strat
+                        .get_strategy_at_index(idx.clone())
+                        .next_state_with_previous(
+                            idx.clone(),
+                            cur_state,
+                            previous_state,
+                            neighbors.into_iter(),
+                        ),
+                )
+            };
+
+            // Sleeping chunks keep their states, only the awake ones are evolved.
+            match states.chunks() {
+                Some(chunks) => next_states.par_extend(chunks.into_par_iter().flat_map_iter(
+                    |chunk| match chunk {
+                        StateChunk::Awake(indices) => Either::Left(indices.iter().map(&evolve)),
+                        StateChunk::Asleep(indices, chunk_states) => {
+                            Either::Right(indices.iter().cloned().zip(chunk_states.iter().cloned()))
+                        }
+                    },
+                )),
+                None => self
+                    .space_manager()
+                    .indices()
+                    .par_iter()
+                    .map(evolve)
+                    .collect_into_vec(&mut next_states),
+            }
+        }
+
+        if let Some(eraser) = self.cell_eraser() {
+            eraser.apply(&mut next_states);
+        }
+        if let Some(dropper) = self.grain_dropper() {
+            dropper.apply(&mut next_states);
+        }
+        if let Some(bit_life) = self.bit_life() {
+            // Packed states must see the cells changed after the kernel.
+            if self.cell_eraser().is_some() || self.grain_dropper().is_some() {
+                bit_life.repack(&next_states, &mut packed);
+            }
+        }
+        self.state_manager_mut().update_cell_states(&next_states);
+        // Cells of the board come first in table order, keep their states for the next generation.
+        if let Some(cell_count) = table_cell_count {
+            dense.clear();
+            dense.par_extend(
+                next_states[..cell_count]
+                    .par_iter()
+                    .map(|(_, state)| state.clone()),
+            );
+        }
+        let res = self.generation_buffers().push(next_states);
+        if table_cell_count.is_some() {
+            self.generation_buffers().set_dense(dense);
+        }
+        if !packed.is_empty() {
+            self.generation_buffers().set_packed(packed);
+        }
+        res
+    }
+
+    /// Reverses the direction of time by swapping the current and previous generations. Boards
+    /// with second-order strategies advance backwards exactly after this.
+    fn reverse(&mut self) {
+        self.state_manager_mut().swap_generations();
+        // The dense and packed states are of the generation that is the previous one now.
+        self.generation_buffers().take_dense();
+        self.generation_buffers().take_packed();
+    }
+}
+
+/// Same as the generic path of `advance_one_generation`, but neighbors are read by ID instead of
+/// being generated and hashed for every cell. States of the cells on the board are read from
+/// `dense`, the last generation by ID, and only looked up if it is empty. Previous states are
+/// only looked up for strategies using them. Strategies still get the neighbors of each cell in
+/// a vector of their own, since they take them as an owned iterator.
+fn advance_with_table<T, CI>(
+    table: &NeighborTable<CI>,
+    states: &dyn BoardStateManager<T, CI, rayon::vec::IntoIter<IndexedDataOwned<CI, T>>>,
+    strat: &dyn BoardStrategyManager<CI, T, std::vec::IntoIter<IndexedDataOwned<CI, T>>>,
+    dense: &mut Vec<T>,
+    res: &mut Vec<IndexedDataOwned<CI, T>>,
+) where
+    T: 'static + Send + Sync + Clone,
+    CI: 'static + Send + Sync + Clone,
+{
+    let cell_count = table.cell_count();
+    if dense.len() != cell_count {
+        dense.clear();
+        (0..cell_count)
+            .into_par_iter()
+            .map(|id| states.get_cell_state(table.idx(id)))
+            .collect_into_vec(dense);
+    }
+    // Neighbors outside of the board are not evolved, but may have states of their own.
+    dense.par_extend(
+        (cell_count..table.index_count())
+            .into_par_iter()
+            .map(|id| states.get_cell_state(table.idx(id))),
+    );
+    let cur_states = &*dense;
+    let uses_previous_state = strat.uses_previous_state();
+    (0..cell_count)
+        .into_par_iter()
+        .map(|id| {
+            let idx = table.idx(id).clone();
+            let cur_state = cur_states[id].clone();
+            let previous_state = if uses_previous_state {
+                states.get_cell_previous_state(&idx)
+            } else {
+                cur_state.clone()
+            };
+            let neighbor_ids = table.neighbors(id);
+            let mut neighbors = Vec::with_capacity(neighbor_ids.len());
+            neighbors.extend(neighbor_ids.iter().map(|neighbor_id| {
+                (
+                    table.idx(*neighbor_id).clone(),
+                    cur_states[*neighbor_id].clone(),
+                )
+            }));
+            (
+                idx.clone(),
+                strat
+                    .get_strategy_at_index(idx.clone())
+                    .next_state_with_previous(
                         idx,
                         cur_state,
+                        previous_state,
                         neighbors.into_iter(),
                     ),
-                )
-            })
-            .collect();
-
-        self.state_manager_mut()
-            .update_cell_states_from_par_iter(next_states.clone().into_par_iter());
-        next_states
-    }
+            )
+        })
+        .collect_into_vec(res);
 }
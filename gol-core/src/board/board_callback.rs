@@ -1,4 +1,4 @@
-use crate::IndexedDataOwned;
+use crate::{GenerationStates, IndexedDataOwned};
 use rayon::prelude::*;
 use std::sync::{Arc, Mutex};
 use std::thread::{self, JoinHandle};
@@ -13,40 +13,37 @@ where
     fn cleanup(&mut self) {}
 }
 
-pub trait BoardCallbackWithStates<T, CI, I>: Send + Sync
+pub trait BoardCallbackWithStates<T, CI>: Send + Sync
 where
     T: Send + Sync,
     CI: Send + Sync,
-    I: ParallelIterator<Item = IndexedDataOwned<CI, T>>,
 {
     fn setup(&mut self) {}
-    fn execute(&mut self, states: I);
+    /// Borrows the states of the generation, shared by all callbacks.
+    fn execute(&mut self, states: &[IndexedDataOwned<CI, T>]);
     fn cleanup(&mut self) {}
 }
 
-pub enum BoardCallback<T, CI, I> {
+pub enum BoardCallback<T, CI> {
     WithoutStates(Box<dyn BoardCallbackWithoutStates<T, CI>>),
-    WithStates(Box<dyn BoardCallbackWithStates<T, CI, I>>),
+    WithStates(Box<dyn BoardCallbackWithStates<T, CI>>),
 }
 
-pub struct BoardCallbackManager<T, CI, I>
+pub struct BoardCallbackManager<T, CI>
 where
     T: Send + Sync,
     CI: Send + Sync,
-    I: ParallelIterator<Item = IndexedDataOwned<CI, T>>,
 {
-    callbacks: Arc<Mutex<Vec<BoardCallback<T, CI, I>>>>,
+    callbacks: Arc<Mutex<Vec<BoardCallback<T, CI>>>>,
     callback_handle: Arc<Mutex<Option<JoinHandle<()>>>>,
 }
 
-impl<T, CI> BoardCallbackManager<T, CI, rayon::vec::IntoIter<IndexedDataOwned<CI, T>>>
+impl<T, CI> BoardCallbackManager<T, CI>
 where
     T: 'static + Send + Sync + Clone,
     CI: 'static + Send + Sync + Clone,
 {
-    pub fn new(
-        callbacks: Vec<BoardCallback<T, CI, rayon::vec::IntoIter<IndexedDataOwned<CI, T>>>>,
-    ) -> Self {
+    pub fn new(callbacks: Vec<BoardCallback<T, CI>>) -> Self {
         Self {
             callbacks: Arc::new(Mutex::new(callbacks)),
             callback_handle: Arc::new(Mutex::new(None)),
@@ -64,7 +61,9 @@ where
             });
     }
 
-    pub fn call(&self, next_states: Vec<IndexedDataOwned<CI, T>>) {
+    /// Runs all callbacks in the background after the ones of the last generation finished. The
+    /// states are released once they are done.
+    pub fn call(&self, next_states: GenerationStates<CI, T>) {
         self.block_until_finish();
         debug_assert!(self.callback_handle.lock().unwrap().is_none());
 
@@ -77,9 +76,7 @@ where
                 .par_iter_mut()
                 .for_each(|ele| match ele {
                     BoardCallback::WithoutStates(val) => val.execute(),
-                    BoardCallback::WithStates(val) => {
-                        val.execute(next_states.clone().into_par_iter())
-                    }
+                    BoardCallback::WithStates(val) => val.execute(&next_states),
                 });
         }));
     }
@@ -9,4 +9,7 @@ where
 {
     fn indices_iter(&self) -> I1;
     fn indices_par_iter(&self) -> I2;
+
+    /// All indices in the same order as the iterators, borrowed instead of cloned.
+    fn indices(&self) -> &[CI];
 }
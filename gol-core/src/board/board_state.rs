@@ -8,5 +8,30 @@ where
     I: ParallelIterator<Item = IndexedDataOwned<CI, T>>,
 {
     fn get_cell_state(&self, idx: &CI) -> T;
+
+    /// State of the cell one generation before the current one.
+    fn get_cell_previous_state(&self, idx: &CI) -> T;
+
     fn update_cell_states_from_par_iter(&mut self, new_states: I);
+
+    /// Same as `update_cell_states_from_par_iter`, but borrows the new states so the board can
+    /// share them with callbacks afterwards.
+    fn update_cell_states(&mut self, new_states: &[IndexedDataOwned<CI, T>]);
+
+    /// Swaps the current and previous generations, used to run second-order rules backwards.
+    fn swap_generations(&mut self);
+
+    /// Cells of the next generation in chunks that are evolved in parallel, used instead of the
+    /// indices of the space manager. `None` if the state manager does not keep chunks.
+    fn chunks(&self) -> Option<Vec<StateChunk<'_, CI, T>>> {
+        None
+    }
+}
+
+/// Cells of a state manager that are evolved together.
+pub enum StateChunk<'a, CI, T> {
+    /// Cells that may change in the next generation.
+    Awake(&'a [CI]),
+    /// Cells that keep their current states, in the same order as the indices.
+    Asleep(&'a [CI], &'a [T]),
 }
@@ -2,4 +2,9 @@ use crate::EvolutionStrategy;
 
 pub trait BoardStrategyManager<CI, T, I>: Send + Sync {
     fn get_strategy_at_index(&self, idx: CI) -> &dyn EvolutionStrategy<CI, T, I>;
+
+    /// Whether any of the strategies depends on the previous state of cells.
+    fn uses_previous_state(&self) -> bool {
+        false
+    }
 }
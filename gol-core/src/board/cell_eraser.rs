@@ -0,0 +1,128 @@
+use crate::IndexedDataOwned;
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+
+/// Cells to kill by generation.
+type PendingCells<CI> = HashMap<usize, HashSet<CI>>;
+
+/// Cells killed by the board in the generations they are requested for, e.g. by callbacks
+/// removing objects. Generations are counted like callbacks see them, the states the board
+/// starts with are generation 0. Clones share the same cells.
+pub struct CellEraser<CI, T> {
+    dead_state: T,
+    /// Last generation evolved, and the cells to kill in later ones.
+    pending: Arc<Mutex<(usize, PendingCells<CI>)>>,
+    /// `HashSet::contains`, kept so the board does not need to know the index is hashable.
+    contains: fn(&HashSet<CI>, &CI) -> bool,
+}
+
+impl<CI, T> Clone for CellEraser<CI, T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            dead_state: self.dead_state.clone(),
+            pending: Arc::clone(&self.pending),
+            contains: self.contains,
+        }
+    }
+}
+
+impl<CI, T> CellEraser<CI, T> {
+    pub fn new(dead_state: T) -> Self
+    where
+        CI: Hash + Eq,
+    {
+        Self {
+            dead_state,
+            pending: Arc::new(Mutex::new((0, HashMap::new()))),
+            contains: HashSet::contains,
+        }
+    }
+
+    /// Kills the cells in the generation, which must not be evolved yet. Callbacks of a
+    /// generation run while the board evolves the one after, so the earliest generation they
+    /// can erase cells in is two after theirs.
+    pub fn erase<I>(&self, generation: usize, cells: I)
+    where
+        I: Iterator<Item = CI>,
+        CI: Hash + Eq,
+    {
+        let mut pending = self.pending.lock().unwrap();
+        assert!(
+            generation > pending.0,
+            "Cannot erase cells in generation {}, generation {} is already evolved.",
+            generation,
+            pending.0
+        );
+        pending.1.entry(generation).or_default().extend(cells);
+    }
+
+    /// Kills the cells requested for the generation the states are of, the one after the
+    /// generation this was last called for.
+    pub fn apply(&self, states: &mut [IndexedDataOwned<CI, T>])
+    where
+        CI: Send + Sync,
+        T: Send + Sync + Clone,
+    {
+        let pending = {
+            let mut pending = self.pending.lock().unwrap();
+            pending.0 += 1;
+            let generation = pending.0;
+            match pending.1.remove(&generation) {
+                Some(cells) => cells,
+                None => return,
+            }
+        };
+        let contains = self.contains;
+        states
+            .par_iter_mut()
+            .filter(|(idx, _)| contains(&pending, idx))
+            .for_each(|(_, state)| *state = self.dead_state.clone());
+    }
+}
+
+#[cfg(test)]
+mod cell_eraser_test {
+    use crate::{
+        Board, CellEraser, DecayLifeLikeStrategy, GridPoint2D, StandardBoard, StandardBoardFactory,
+    };
+    use std::collections::HashMap;
+
+    #[test]
+    fn cell_eraser_test_next_generation() {
+        let mut initial_states = HashMap::new();
+        for (x, y) in [(0i32, 0i32), (0, 1), (1, 0), (1, 1), (5, 5), (6, 5), (7, 5)] {
+            initial_states.insert(GridPoint2D::new(x, y), 1u8);
+        }
+        let eraser = CellEraser::new(0u8);
+        let board: StandardBoard<_, _, _> = StandardBoardFactory::new_standard_2d_grid(
+            (20usize, 20),
+            0u8,
+            1,
+            initial_states,
+            Box::new(DecayLifeLikeStrategy::gol()),
+            Vec::new(),
+            false,
+        );
+        let mut board = board.with_cell_eraser(eraser.clone());
+
+        // The blinker is vertical in generation 1 and horizontal in generation 2.
+        let vertical = [(6, 4), (6, 5), (6, 6)];
+        let horizontal = [(5, 5), (6, 5), (7, 5)];
+        eraser.erase(2, horizontal.iter().map(|(x, y)| GridPoint2D::new(*x, *y)));
+        eraser.erase(2, vertical.iter().map(|(x, y)| GridPoint2D::new(*x, *y)));
+        let states = board.advance_one_generation();
+        assert_eq!(states.iter().filter(|(_, state)| *state == 1).count(), 7);
+
+        let states = board.advance_one_generation();
+        assert_eq!(states.iter().filter(|(_, state)| *state == 1).count(), 4);
+
+        // Erased cells are only killed once.
+        let states = board.advance_one_generation();
+        assert_eq!(states.iter().filter(|(_, state)| *state == 1).count(), 4);
+    }
+}
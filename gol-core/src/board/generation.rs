@@ -0,0 +1,107 @@
+use crate::IndexedDataOwned;
+use std::sync::Arc;
+
+/// States of every cell in one generation. The board shares it with its callbacks, which borrow
+/// it instead of receiving their own copies.
+pub type GenerationStates<CI, T> = Arc<Vec<IndexedDataOwned<CI, T>>>;
+
+/// Two generations used in turns. A new generation is written into the memory of the one before
+/// the last, which callbacks are done with by then.
+pub struct GenerationBuffers<CI, T> {
+    last: Option<GenerationStates<CI, T>>,
+    spare: Option<GenerationStates<CI, T>>,
+    /// States of the last generation by neighbor table ID, empty if they are not known.
+    dense: Vec<T>,
+    /// Last generation packed by the bit-parallel kernel, empty if it is not known.
+    packed: Vec<u64>,
+}
+
+impl<CI, T> GenerationBuffers<CI, T> {
+    pub fn new() -> Self {
+        Self {
+            last: None,
+            spare: None,
+            dense: Vec::new(),
+            packed: Vec::new(),
+        }
+    }
+
+    /// Empty buffer for the next generation, only allocates if the spare generation is still
+    /// shared.
+    pub fn take_spare(&mut self) -> Vec<IndexedDataOwned<CI, T>> {
+        let mut res = self
+            .spare
+            .take()
+            .and_then(|spare| Arc::try_unwrap(spare).ok())
+            .unwrap_or_default();
+        res.clear();
+        res
+    }
+
+    /// Makes the states the last generation, forgetting the dense and packed states of the one
+    /// before.
+    pub fn push(&mut self, states: Vec<IndexedDataOwned<CI, T>>) -> GenerationStates<CI, T> {
+        self.dense.clear();
+        self.packed.clear();
+        let res = Arc::new(states);
+        self.spare = self.last.replace(Arc::clone(&res));
+        res
+    }
+
+    /// States of the last generation by neighbor table ID, empty if they are not known.
+    pub fn take_dense(&mut self) -> Vec<T> {
+        std::mem::take(&mut self.dense)
+    }
+
+    /// Keeps the states of the generation just pushed by neighbor table ID for the next one.
+    pub fn set_dense(&mut self, dense: Vec<T>) {
+        self.dense = dense;
+    }
+
+    /// Last generation packed by the bit-parallel kernel, empty if it is not known.
+    pub fn take_packed(&mut self) -> Vec<u64> {
+        std::mem::take(&mut self.packed)
+    }
+
+    /// Keeps the generation just pushed packed for the next one.
+    pub fn set_packed(&mut self, packed: Vec<u64>) {
+        self.packed = packed;
+    }
+}
+
+impl<CI, T> Default for GenerationBuffers<CI, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod generation_buffers_test {
+    use crate::GenerationBuffers;
+    use std::sync::Arc;
+
+    #[test]
+    fn generation_buffers_test_reuse() {
+        let mut buffers = GenerationBuffers::new();
+        let mut first = buffers.take_spare();
+        first.extend((0..100).map(|i| (i, 0u8)));
+        let first_ptr = first.as_ptr();
+        drop(buffers.push(first));
+        let mut second = buffers.take_spare();
+        second.push((0, 1u8));
+        drop(buffers.push(second));
+        let third = buffers.take_spare();
+        assert!(third.is_empty());
+        assert_eq!(third.as_ptr(), first_ptr);
+    }
+
+    #[test]
+    fn generation_buffers_test_shared() {
+        let mut buffers = GenerationBuffers::new();
+        let first = buffers.push(vec![(0, 0u8)]);
+        buffers.push(vec![(0, 1u8)]);
+        let third = buffers.take_spare();
+        assert!(third.capacity() == 0);
+        assert_eq!(Arc::strong_count(&first), 1);
+    }
+}
@@ -0,0 +1,144 @@
+use crate::IndexedDataOwned;
+use num_traits::PrimInt;
+use rayon::prelude::*;
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Drives a sandpile by dropping one grain on each source cell every `interval` generations the
+/// board evolves, starting from the first one. Generations are counted like callbacks see them,
+/// the states the board starts with are generation 0. Sources must be cells of the board.
+pub struct GrainDropper<CI, T> {
+    sources: HashSet<CI>,
+    interval: usize,
+    /// Last generation evolved.
+    generation: AtomicUsize,
+    /// `drop_grains`, kept so the board does not need to know the index is hashable or the state
+    /// is an integer.
+    drop_grains: fn(&HashSet<CI>, &mut Vec<IndexedDataOwned<CI, T>>),
+    /// `HashSet::contains`, for the same reason.
+    contains: fn(&HashSet<CI>, &CI) -> bool,
+}
+
+impl<CI, T> GrainDropper<CI, T> {
+    pub fn new<I>(sources: I, interval: usize) -> Self
+    where
+        I: Iterator<Item = CI>,
+        CI: Send + Sync + Clone + Hash + Eq,
+        T: Send + Sync + PrimInt,
+    {
+        assert!(interval > 0);
+        Self {
+            sources: sources.collect(),
+            interval,
+            generation: AtomicUsize::new(0),
+            drop_grains,
+            contains: HashSet::contains,
+        }
+    }
+
+    pub fn interval(&self) -> usize {
+        self.interval
+    }
+
+    /// Whether all sources are among the indices.
+    pub fn is_within(&self, indices: &[CI]) -> bool {
+        let contains = self.contains;
+        indices
+            .iter()
+            .filter(|idx| contains(&self.sources, idx))
+            .count()
+            == self.sources.len()
+    }
+
+    /// Drops the grains of the generation the states are of, the one after the generation this
+    /// was last called for.
+    pub fn apply(&self, states: &mut Vec<IndexedDataOwned<CI, T>>) {
+        let generation = self.generation.fetch_add(1, Ordering::Relaxed) + 1;
+        if (generation - 1).is_multiple_of(self.interval) {
+            (self.drop_grains)(&self.sources, states);
+        }
+    }
+}
+
+/// Sources missing from the states, e.g. in chunks freed while empty, are added with one grain.
+fn drop_grains<CI, T>(sources: &HashSet<CI>, states: &mut Vec<IndexedDataOwned<CI, T>>)
+where
+    CI: Send + Sync + Clone + Hash + Eq,
+    T: Send + Sync + PrimInt,
+{
+    let dropped: HashSet<CI> = states
+        .par_iter_mut()
+        .filter(|(idx, _)| sources.contains(idx))
+        .map(|(idx, state)| {
+            *state = state
+                .checked_add(&T::one())
+                .expect("Sandpile state overflow, try using a wider state type.");
+            idx.clone()
+        })
+        .collect();
+    states.extend(
+        sources
+            .difference(&dropped)
+            .map(|idx| (idx.clone(), T::one())),
+    );
+}
+
+#[cfg(test)]
+mod grain_dropper_test {
+    use crate::{
+        Board, ChunkedStates, GrainDropper, Grid, GridFactory, GridPoint2D, NeighborVonNeumann,
+        SandpileStrategy, SharedStrategyManager, SparseStates, StandardBoard,
+    };
+    use std::collections::HashMap;
+
+    #[test]
+    fn grain_dropper_test_interval() {
+        let source = GridPoint2D::new(0i32, 0i32);
+        let board = StandardBoard::new(
+            Box::new(Grid::<GridPoint2D<i32>>::new(
+                vec![200usize, 200].into_iter(),
+            )),
+            Box::new(NeighborVonNeumann::new()),
+            Box::new(ChunkedStates::new_bounded(
+                0u8,
+                HashMap::new(),
+                1,
+                crate::util::grid_util::Shape2D::new(200, 200),
+                false,
+            )),
+            Box::new(SharedStrategyManager::new(Box::new(
+                SandpileStrategy::default(),
+            ))),
+            Vec::new(),
+        );
+        let mut board =
+            board.with_grain_dropper(GrainDropper::new(vec![source.clone()].into_iter(), 3));
+        let grains = |board: &mut StandardBoard<_, _, _>| {
+            let states = board.advance_one_generation();
+            states.iter().map(|ele| ele.1 as usize).sum::<usize>()
+        };
+        // Generations 1, 4 and 7 get a grain, also while the chunk of the source sleeps.
+        let counts: Vec<usize> = (0..9).map(|_| grains(&mut board)).collect();
+        assert_eq!(counts, vec![1, 1, 1, 2, 2, 2, 3, 3, 3]);
+        assert_eq!(board.state_manager().get_cell_state(&source), 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn grain_dropper_test_source_off_board() {
+        let board = StandardBoard::new(
+            Box::new(Grid::<GridPoint2D<i32>>::new(vec![5usize, 5].into_iter())),
+            Box::new(NeighborVonNeumann::new()),
+            Box::new(SparseStates::new(0u8, HashMap::new())),
+            Box::new(SharedStrategyManager::new(Box::new(
+                SandpileStrategy::default(),
+            ))),
+            Vec::new(),
+        );
+        board.with_grain_dropper(GrainDropper::new(
+            vec![GridPoint2D::new(10, 0)].into_iter(),
+            3,
+        ));
+    }
+}
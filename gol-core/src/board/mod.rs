@@ -1,7 +1,11 @@
+pub mod bit_life;
 pub mod board;
 pub mod board_callback;
 pub mod board_neighbor;
 pub mod board_space;
 pub mod board_state;
 pub mod board_strategy;
+pub mod cell_eraser;
+pub mod generation;
+pub mod grain_dropper;
 pub mod standard;
@@ -1,10 +1,10 @@
 use crate::neighbors::util::{MarginPrimInt, PointPrimInt};
 use crate::{
-    util::grid_util::Shape2D, Board, BoardCallback, BoardCallbackManager, BoardNeighborManager,
-    BoardSpaceManager, BoardStateManager, BoardStrategyManager, EvolutionStrategy, Grid,
-    GridFactory, GridPoint1D, GridPoint2D, GridPoint3D, GridPointND, IndexedDataOwned,
-    NeighborMoore, NeighborMooreDonut, NeighborsGridDonut, NeighborsGridSurround,
-    SharedStrategyManager, SparseStates,
+    util::grid_util::Shape2D, BitLife, Board, BoardCallback, BoardCallbackManager,
+    BoardNeighborManager, BoardSpaceManager, BoardStateManager, BoardStrategyManager, CellEraser,
+    EvolutionStrategy, GenerationBuffers, GrainDropper, Grid, GridFactory, GridPoint1D,
+    GridPoint2D, GridPoint3D, GridPointND, IndexedDataOwned, NeighborMoore, NeighborMooreDonut,
+    NeighborTable, NeighborsGridDonut, NeighborsGridSurround, SharedStrategyManager, SparseStates,
 };
 use num_traits::{CheckedDiv, FromPrimitive, PrimInt, Unsigned};
 use rayon;
@@ -24,7 +24,12 @@ where
     state_manager: Box<dyn BoardStateManager<T, CI, rayon::vec::IntoIter<IndexedDataOwned<CI, T>>>>,
     strategy_manager:
         Box<dyn BoardStrategyManager<CI, T, std::vec::IntoIter<IndexedDataOwned<CI, T>>>>,
-    callback_manager: BoardCallbackManager<T, CI, rayon::vec::IntoIter<IndexedDataOwned<CI, T>>>,
+    callback_manager: BoardCallbackManager<T, CI>,
+    generation_buffers: GenerationBuffers<CI, T>,
+    neighbor_table: Option<NeighborTable<CI>>,
+    bit_life: Option<BitLife<CI, T>>,
+    cell_eraser: Option<CellEraser<CI, T>>,
+    grain_dropper: Option<GrainDropper<CI, T>>,
 }
 
 impl<T, CI, I> Board<T, CI, I> for StandardBoard<T, CI, I>
@@ -59,11 +64,29 @@ where
         &*self.strategy_manager
     }
 
-    fn callback_manager(
-        &mut self,
-    ) -> &mut BoardCallbackManager<T, CI, rayon::vec::IntoIter<IndexedDataOwned<CI, T>>> {
+    fn callback_manager(&mut self) -> &mut BoardCallbackManager<T, CI> {
         &mut self.callback_manager
     }
+
+    fn generation_buffers(&mut self) -> &mut GenerationBuffers<CI, T> {
+        &mut self.generation_buffers
+    }
+
+    fn neighbor_table(&self) -> Option<&NeighborTable<CI>> {
+        self.neighbor_table.as_ref()
+    }
+
+    fn bit_life(&self) -> Option<&BitLife<CI, T>> {
+        self.bit_life.as_ref()
+    }
+
+    fn cell_eraser(&self) -> Option<&CellEraser<CI, T>> {
+        self.cell_eraser.as_ref()
+    }
+
+    fn grain_dropper(&self) -> Option<&GrainDropper<CI, T>> {
+        self.grain_dropper.as_ref()
+    }
 }
 
 impl<T, CI, I> StandardBoard<T, CI, I>
@@ -81,16 +104,65 @@ where
         strategy_manager: Box<
             dyn BoardStrategyManager<CI, T, std::vec::IntoIter<IndexedDataOwned<CI, T>>>,
         >,
-        callbacks: Vec<BoardCallback<T, CI, rayon::vec::IntoIter<IndexedDataOwned<CI, T>>>>,
+        callbacks: Vec<BoardCallback<T, CI>>,
     ) -> Self {
+        assert!(
+            state_manager.chunks().is_none() || !strategy_manager.uses_previous_state(),
+            "States evolved in chunks cannot be used with strategies depending on previous states."
+        );
         Self {
             space_manager,
             neighbor_manager,
             state_manager,
             strategy_manager,
             callback_manager: BoardCallbackManager::new(callbacks),
+            generation_buffers: GenerationBuffers::new(),
+            neighbor_table: None,
+            bit_life: None,
+            cell_eraser: None,
+            grain_dropper: None,
         }
     }
+
+    /// Compiles the neighbors of every cell into a table once, so generations do not need the
+    /// neighbor manager anymore. Only for bounded boards whose neighbors never change.
+    pub fn with_neighbor_table(self) -> Self
+    where
+        CI: Hash + Eq,
+    {
+        let mut res = self;
+        res.neighbor_table = Some(NeighborTable::new(
+            &*res.space_manager,
+            &*res.neighbor_manager,
+        ));
+        res
+    }
+
+    /// Evolves the board with a bit-parallel kernel instead of the strategy manager.
+    pub fn with_bit_life(self, bit_life: BitLife<CI, T>) -> Self {
+        let mut res = self;
+        res.bit_life = Some(bit_life);
+        res
+    }
+
+    /// Kills the cells passed to the eraser, or its clones, in the generations they are for.
+    pub fn with_cell_eraser(self, cell_eraser: CellEraser<CI, T>) -> Self {
+        let mut res = self;
+        res.cell_eraser = Some(cell_eraser);
+        res
+    }
+
+    /// Drops grains on the sources of the dropper every interval, counting the generations this
+    /// board evolves.
+    pub fn with_grain_dropper(self, grain_dropper: GrainDropper<CI, T>) -> Self {
+        assert!(
+            grain_dropper.is_within(self.space_manager.indices()),
+            "Grain sources must be cells of the board."
+        );
+        let mut res = self;
+        res.grain_dropper = Some(grain_dropper);
+        res
+    }
 }
 
 impl StandardBoardFactory {
@@ -106,13 +178,7 @@ impl StandardBoardFactory {
                 std::vec::IntoIter<IndexedDataOwned<GridPointND<U>, T>>,
             >,
         >,
-        callbacks: Vec<
-            BoardCallback<
-                T,
-                GridPointND<U>,
-                rayon::vec::IntoIter<IndexedDataOwned<GridPointND<U>, T>>,
-            >,
-        >,
+        callbacks: Vec<BoardCallback<T, GridPointND<U>>>,
         is_donut: bool,
     ) -> StandardBoard<T, GridPointND<U>, std::vec::IntoIter<GridPointND<U>>>
     where
@@ -153,13 +219,7 @@ impl StandardBoardFactory {
                 std::vec::IntoIter<IndexedDataOwned<GridPoint3D<U>, T>>,
             >,
         >,
-        callbacks: Vec<
-            BoardCallback<
-                T,
-                GridPoint3D<U>,
-                rayon::vec::IntoIter<IndexedDataOwned<GridPoint3D<U>, T>>,
-            >,
-        >,
+        callbacks: Vec<BoardCallback<T, GridPoint3D<U>>>,
         is_donut: bool,
     ) -> StandardBoard<T, GridPoint3D<U>, std::vec::IntoIter<GridPoint3D<U>>>
     where
@@ -199,13 +259,7 @@ impl StandardBoardFactory {
                 std::vec::IntoIter<IndexedDataOwned<GridPoint2D<U>, T>>,
             >,
         >,
-        callbacks: Vec<
-            BoardCallback<
-                T,
-                GridPoint2D<U>,
-                rayon::vec::IntoIter<IndexedDataOwned<GridPoint2D<U>, T>>,
-            >,
-        >,
+        callbacks: Vec<BoardCallback<T, GridPoint2D<U>>>,
         is_donut: bool,
     ) -> StandardBoard<T, GridPoint2D<U>, std::vec::IntoIter<GridPoint2D<U>>>
     where
@@ -216,6 +270,16 @@ impl StandardBoardFactory {
         let (width, height) = shape;
         let shape = vec![width, height];
         let space_manager = Grid::<GridPoint2D<U>>::new(shape.clone().into_iter());
+        let bit_life = match strategy.two_state_rule() {
+            Some(rule) if neighbor_margin == S::one() => Some(BitLife::new(
+                &space_manager,
+                width.to_usize().unwrap(),
+                height.to_usize().unwrap(),
+                is_donut,
+                rule,
+            )),
+            _ => None,
+        };
         let neighbor_manager = if is_donut {
             if neighbor_margin == S::one() {
                 Box::new(NeighborMooreDonut::new(Shape2D::new(
@@ -246,13 +310,17 @@ impl StandardBoardFactory {
         };
         let state_manager = SparseStates::new(default_state, initial_states);
         let strategy_manger = SharedStrategyManager::new(strategy);
-        StandardBoard::new(
+        let board = StandardBoard::new(
             Box::new(space_manager),
             neighbor_manager,
             Box::new(state_manager),
             Box::new(strategy_manger),
             callbacks,
-        )
+        );
+        match bit_life {
+            Some(bit_life) => board.with_bit_life(bit_life),
+            None => board,
+        }
     }
 
     pub fn new_standard_1d_grid<T, U, S>(
@@ -267,13 +335,7 @@ impl StandardBoardFactory {
                 std::vec::IntoIter<IndexedDataOwned<GridPoint1D<U>, T>>,
             >,
         >,
-        callbacks: Vec<
-            BoardCallback<
-                T,
-                GridPoint1D<U>,
-                rayon::vec::IntoIter<IndexedDataOwned<GridPoint1D<U>, T>>,
-            >,
-        >,
+        callbacks: Vec<BoardCallback<T, GridPoint1D<U>>>,
         is_donut: bool,
     ) -> StandardBoard<T, GridPoint1D<U>, std::vec::IntoIter<GridPoint1D<U>>>
     where
@@ -0,0 +1,501 @@
+use crate::{
+    util::grid_util::Shape2D, BoardStateManager, GridPoint2D, IndexedDataOwned, StateChunk,
+};
+use num_traits::{FromPrimitive, PrimInt, ToPrimitive};
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+/// Number of cells along each side of a chunk.
+pub const CHUNK_SIZE: usize = 64;
+
+type ChunkKey = (i64, i64);
+type ChunkRuns<'a, U, T> = HashMap<ChunkKey, Vec<&'a [IndexedDataOwned<GridPoint2D<U>, T>]>>;
+
+/// Cells of a chunk, x changing slower than y. Chunks at the edges of bounded boards are cut off
+/// at the edges.
+struct Chunk<T, U> {
+    x_min: i64,
+    y_min: i64,
+    height: usize,
+    indices: Vec<GridPoint2D<U>>,
+    states: Vec<T>,
+    previous_states: Vec<T>,
+    is_awake: bool,
+}
+
+impl<T, U> Chunk<T, U>
+where
+    U: ToPrimitive,
+{
+    fn offset(&self, idx: &GridPoint2D<U>) -> usize {
+        let x = idx.x.to_i64().unwrap() - self.x_min;
+        let y = idx.y.to_i64().unwrap() - self.y_min;
+        x as usize * self.height + y as usize
+    }
+}
+
+struct ChunkBounds {
+    shape: Shape2D,
+    is_donut: bool,
+}
+
+/// Stores a 2D grid in square chunks of `CHUNK_SIZE` cells per side, dense inside. Only chunks next
+/// to one that changed in the last generation are awake and evolved, the others keep their states.
+/// Chunks that are asleep with only default states are freed, so boards without bounds only keep
+/// the area around non-default cells.
+///
+/// Sleeping assumes the next state of a cell only depends on its current state and the ones of
+/// neighbors at most `neighbor_margin` cells away, and that default cells surrounded by default
+/// cells stay default. Boards reject strategies that use previous states with it.
+pub struct ChunkedStates<T, U> {
+    default_state: T,
+    bounds: Option<ChunkBounds>,
+    /// Chunks on each side woken up around a changed one.
+    chunk_reach: i64,
+    chunks: HashMap<ChunkKey, Chunk<T, U>>,
+}
+
+impl<T, U> ChunkedStates<T, U>
+where
+    T: Send + Sync + Clone + PartialEq,
+    U: Send + Sync + PrimInt + FromPrimitive + ToPrimitive,
+{
+    /// Board without bounds, for neighbors at most `neighbor_margin` cells away in x and y.
+    pub fn new(
+        default_state: T,
+        initial_states: HashMap<GridPoint2D<U>, T>,
+        neighbor_margin: usize,
+    ) -> Self {
+        Self::new_with_bounds(default_state, initial_states, neighbor_margin, None)
+    }
+
+    /// Board with the shape and origin of a centered `Grid`, neighbors of the chunks at the edges
+    /// wrap around if `is_donut` is set. Initial states out of the bounds are dropped.
+    pub fn new_bounded(
+        default_state: T,
+        initial_states: HashMap<GridPoint2D<U>, T>,
+        neighbor_margin: usize,
+        shape: Shape2D,
+        is_donut: bool,
+    ) -> Self {
+        Self::new_with_bounds(
+            default_state,
+            initial_states,
+            neighbor_margin,
+            Some(ChunkBounds { shape, is_donut }),
+        )
+    }
+
+    fn new_with_bounds(
+        default_state: T,
+        initial_states: HashMap<GridPoint2D<U>, T>,
+        neighbor_margin: usize,
+        bounds: Option<ChunkBounds>,
+    ) -> Self {
+        let chunk_reach = neighbor_margin.max(1).div_ceil(CHUNK_SIZE) as i64;
+        let mut res = Self {
+            default_state,
+            bounds,
+            chunk_reach,
+            chunks: HashMap::new(),
+        };
+        let mut changed = HashSet::new();
+        for (idx, state) in initial_states {
+            let key = match res.key(&idx) {
+                Some(key) => key,
+                None => continue,
+            };
+            if !res.chunks.contains_key(&key) {
+                let chunk = res.new_chunk(key);
+                res.chunks.insert(key, chunk);
+            }
+            let chunk = res.chunks.get_mut(&key).unwrap();
+            let offset = chunk.offset(&idx);
+            chunk.states[offset] = state;
+            changed.insert(key);
+        }
+        res.wake_up_around(&changed);
+        res
+    }
+
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    pub fn awake_chunk_count(&self) -> usize {
+        self.chunks.values().filter(|chunk| chunk.is_awake).count()
+    }
+
+    fn origin(&self) -> (i64, i64) {
+        match &self.bounds {
+            Some(bounds) => (bounds.shape.x_idx_min(), bounds.shape.y_idx_min()),
+            None => (0, 0),
+        }
+    }
+
+    /// `None` if the index is out of bounds.
+    fn key(&self, idx: &GridPoint2D<U>) -> Option<ChunkKey> {
+        let (x, y) = (idx.x.to_i64().unwrap(), idx.y.to_i64().unwrap());
+        if let Some(bounds) = &self.bounds {
+            let shape = &bounds.shape;
+            if x < shape.x_idx_min()
+                || x > shape.x_idx_max()
+                || y < shape.y_idx_min()
+                || y > shape.y_idx_max()
+            {
+                return None;
+            }
+        }
+        let (x_origin, y_origin) = self.origin();
+        let size = CHUNK_SIZE as i64;
+        Some((
+            (x - x_origin).div_euclid(size),
+            (y - y_origin).div_euclid(size),
+        ))
+    }
+
+    fn new_chunk(&self, key: ChunkKey) -> Chunk<T, U> {
+        let (x_origin, y_origin) = self.origin();
+        let size = CHUNK_SIZE as i64;
+        let (x_min, y_min) = (x_origin + key.0 * size, y_origin + key.1 * size);
+        let (width, height) = match &self.bounds {
+            Some(bounds) => (
+                size.min(bounds.shape.x_idx_max() + 1 - x_min) as usize,
+                size.min(bounds.shape.y_idx_max() + 1 - y_min) as usize,
+            ),
+            None => (CHUNK_SIZE, CHUNK_SIZE),
+        };
+        let mut indices = Vec::with_capacity(width * height);
+        for x in x_min..x_min + width as i64 {
+            for y in y_min..y_min + height as i64 {
+                indices.push(GridPoint2D::new(
+                    U::from_i64(x).unwrap(),
+                    U::from_i64(y).unwrap(),
+                ));
+            }
+        }
+        Chunk {
+            x_min,
+            y_min,
+            height,
+            indices,
+            states: vec![self.default_state.clone(); width * height],
+            previous_states: vec![self.default_state.clone(); width * height],
+            is_awake: false,
+        }
+    }
+
+    /// The chunk itself and the ones within reach around it, wrapping around the edges of donut
+    /// boards.
+    fn neighbor_keys(&self, key: ChunkKey) -> Vec<ChunkKey> {
+        let counts = self.bounds.as_ref().map(|bounds| {
+            let size = CHUNK_SIZE as i64;
+            let width = bounds.shape.width() as i64;
+            let height = bounds.shape.height() as i64;
+            (
+                (width + size - 1) / size,
+                (height + size - 1) / size,
+                bounds.is_donut,
+            )
+        });
+        let reach = self.chunk_reach;
+        let mut res = Vec::with_capacity(((2 * reach + 1) * (2 * reach + 1)) as usize);
+        for dx in -reach..=reach {
+            for dy in -reach..=reach {
+                let (x, y) = (key.0 + dx, key.1 + dy);
+                match counts {
+                    None => res.push((x, y)),
+                    Some((x_count, y_count, is_donut)) => {
+                        if is_donut {
+                            res.push((x.rem_euclid(x_count), y.rem_euclid(y_count)));
+                        } else if x >= 0 && x < x_count && y >= 0 && y < y_count {
+                            res.push((x, y));
+                        }
+                    }
+                }
+            }
+        }
+        res
+    }
+
+    /// Wakes up the chunks around the changed ones and puts all others to sleep, freeing the
+    /// sleeping ones with only default states.
+    fn wake_up_around(&mut self, changed: &HashSet<ChunkKey>) {
+        let awake: HashSet<ChunkKey> = changed
+            .iter()
+            .flat_map(|key| self.neighbor_keys(*key))
+            .collect();
+        for key in awake.iter() {
+            if !self.chunks.contains_key(key) {
+                let chunk = self.new_chunk(*key);
+                self.chunks.insert(*key, chunk);
+            }
+        }
+        let default_state = &self.default_state;
+        self.chunks.retain(|key, chunk| {
+            chunk.is_awake = awake.contains(key);
+            chunk.is_awake || chunk.states.iter().any(|state| state != default_state)
+        });
+    }
+}
+
+impl<T, U>
+    BoardStateManager<T, GridPoint2D<U>, rayon::vec::IntoIter<IndexedDataOwned<GridPoint2D<U>, T>>>
+    for ChunkedStates<T, U>
+where
+    T: Send + Sync + Clone + PartialEq,
+    U: Send + Sync + PrimInt + FromPrimitive + ToPrimitive,
+{
+    fn get_cell_state(&self, idx: &GridPoint2D<U>) -> T {
+        match self.key(idx).and_then(|key| self.chunks.get(&key)) {
+            Some(chunk) => chunk.states[chunk.offset(idx)].clone(),
+            None => self.default_state.clone(),
+        }
+    }
+
+    fn get_cell_previous_state(&self, idx: &GridPoint2D<U>) -> T {
+        match self.key(idx).and_then(|key| self.chunks.get(&key)) {
+            Some(chunk) => chunk.previous_states[chunk.offset(idx)].clone(),
+            None => self.default_state.clone(),
+        }
+    }
+
+    fn update_cell_states_from_par_iter(
+        &mut self,
+        new_states: rayon::vec::IntoIter<IndexedDataOwned<GridPoint2D<U>, T>>,
+    ) {
+        let new_states: Vec<IndexedDataOwned<GridPoint2D<U>, T>> = new_states.collect();
+        self.update_cell_states(&new_states);
+    }
+
+    fn update_cell_states(&mut self, new_states: &[IndexedDataOwned<GridPoint2D<U>, T>]) {
+        // Boards pass the cells of each chunk in one run, split them without hashing every cell.
+        let mut runs: ChunkRuns<U, T> = HashMap::new();
+        let mut start = 0;
+        while start < new_states.len() {
+            let key = self.key(&new_states[start].0);
+            let mut end = start + 1;
+            while end < new_states.len() && self.key(&new_states[end].0) == key {
+                end += 1;
+            }
+            if let Some(key) = key {
+                runs.entry(key).or_default().push(&new_states[start..end]);
+            }
+            start = end;
+        }
+        for key in runs.keys() {
+            if !self.chunks.contains_key(key) {
+                let chunk = self.new_chunk(*key);
+                self.chunks.insert(*key, chunk);
+            }
+        }
+
+        // Cells not in the new states go back to the default state.
+        let default_state = &self.default_state;
+        let changed: HashSet<ChunkKey> = self
+            .chunks
+            .par_iter_mut()
+            .filter_map(|(key, chunk)| {
+                let mut next = std::mem::take(&mut chunk.previous_states);
+                next.clear();
+                next.resize(chunk.indices.len(), default_state.clone());
+                for run in runs.get(key).into_iter().flatten() {
+                    for (idx, state) in run.iter() {
+                        next[chunk.offset(idx)] = state.clone();
+                    }
+                }
+                let is_changed = next != chunk.states;
+                chunk.previous_states = std::mem::replace(&mut chunk.states, next);
+                if is_changed {
+                    Some(*key)
+                } else {
+                    None
+                }
+            })
+            .collect();
+        self.wake_up_around(&changed);
+    }
+
+    fn swap_generations(&mut self) {
+        for chunk in self.chunks.values_mut() {
+            std::mem::swap(&mut chunk.states, &mut chunk.previous_states);
+            chunk.is_awake = true;
+        }
+    }
+
+    fn chunks(&self) -> Option<Vec<StateChunk<'_, GridPoint2D<U>, T>>> {
+        Some(
+            self.chunks
+                .values()
+                .map(|chunk| {
+                    if chunk.is_awake {
+                        StateChunk::Awake(&chunk.indices)
+                    } else {
+                        StateChunk::Asleep(&chunk.indices, &chunk.states)
+                    }
+                })
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod chunked_state_manager_test {
+    use crate::{
+        util::grid_util::Shape2D, Board, BoardStateManager, ChunkedStates, DecayLifeLikeStrategy,
+        Grid, GridFactory, GridPoint2D, NeighborMoore, NeighborMooreDonut, SecondOrderStrategy,
+        SharedStrategyManager, SparseStates, StandardBoard,
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::collections::{HashMap, HashSet};
+
+    type Point = GridPoint2D<i32>;
+
+    fn alive(states: &[(Point, u8)]) -> HashSet<Point> {
+        states
+            .iter()
+            .filter(|ele| ele.1 == 1)
+            .map(|ele| ele.0.clone())
+            .collect()
+    }
+
+    fn glider(x: i32, y: i32) -> HashMap<Point, u8> {
+        vec![(0, -1), (1, 0), (-1, 1), (0, 1), (1, 1)]
+            .into_iter()
+            .map(|(dx, dy)| (GridPoint2D::new(x + dx, y + dy), 1))
+            .collect()
+    }
+
+    #[test]
+    fn chunked_state_test_1() {
+        let mut initial_states = HashMap::new();
+        initial_states.insert(GridPoint2D::new(-70, 3), 1u8);
+        initial_states.insert(GridPoint2D::new(1000, -1000), 2u8);
+        let states = ChunkedStates::new(0, initial_states, 1);
+        assert_eq!(states.get_cell_state(&GridPoint2D::new(-70, 3)), 1);
+        assert_eq!(states.get_cell_state(&GridPoint2D::new(1000, -1000)), 2);
+        assert_eq!(states.get_cell_state(&GridPoint2D::new(1000, -999)), 0);
+        assert_eq!(states.get_cell_state(&GridPoint2D::<i32>::new(5000, 0)), 0);
+        // Both chunks and the ones around them.
+        assert_eq!(states.chunk_count(), 18);
+        assert_eq!(states.awake_chunk_count(), 18);
+    }
+
+    #[test]
+    fn chunked_state_test_same_as_sparse() {
+        let (width, height) = (150usize, 70usize);
+        let mut rng = StdRng::seed_from_u64(5);
+        let initial_states: HashMap<Point, u8> = (0..width as i32)
+            .flat_map(|x| (0..height as i32).map(move |y| (x, y)))
+            .filter(|_| rng.gen_bool(0.3))
+            .map(|(x, y)| {
+                let idx = GridPoint2D::new(x - width as i32 / 2, y - height as i32 / 2);
+                (idx, 1)
+            })
+            .collect();
+        for is_donut in [false, true].iter().cloned() {
+            let new_board = |state_manager| {
+                StandardBoard::new(
+                    Box::new(Grid::<Point>::new(vec![width, height].into_iter())),
+                    if is_donut {
+                        Box::new(NeighborMooreDonut::new(Shape2D::new(width, height)))
+                    } else {
+                        Box::new(NeighborMoore::new())
+                    },
+                    state_manager,
+                    Box::new(SharedStrategyManager::new(Box::new(
+                        DecayLifeLikeStrategy::gol(),
+                    ))),
+                    Vec::new(),
+                )
+            };
+            let mut board = new_board(Box::new(SparseStates::new(0u8, initial_states.clone())));
+            let mut board_chunked = new_board(Box::new(ChunkedStates::new_bounded(
+                0u8,
+                initial_states.clone(),
+                1,
+                Shape2D::new(width, height),
+                is_donut,
+            )));
+            for _ in 0..40 {
+                let states = board.advance_one_generation();
+                let states_chunked = board_chunked.advance_one_generation();
+                assert_eq!(alive(&states), alive(&states_chunked));
+            }
+        }
+    }
+
+    #[test]
+    fn chunked_state_test_unbounded_sleep() {
+        // Crosses into the next chunk.
+        let mut initial_states = glider(60, 60);
+        // Block far away, a still life.
+        for (x, y) in [(-300, -300), (-299, -300), (-300, -299), (-299, -299)].iter() {
+            initial_states.insert(GridPoint2D::new(*x, *y), 1);
+        }
+        let mut board = StandardBoard::new(
+            Box::new(Grid::<Point>::new(vec![3usize, 3].into_iter())),
+            Box::new(NeighborMoore::new()),
+            Box::new(ChunkedStates::new(0u8, initial_states, 1)),
+            Box::new(SharedStrategyManager::new(Box::new(
+                DecayLifeLikeStrategy::gol(),
+            ))),
+            Vec::new(),
+        );
+        let generations = 4 * 8;
+        let mut states = board.advance_one_generation();
+        for _ in 1..generations {
+            states = board.advance_one_generation();
+        }
+        let mut expected = glider(68, 68);
+        for (x, y) in [(-300, -300), (-299, -300), (-300, -299), (-299, -299)].iter() {
+            expected.insert(GridPoint2D::new(*x, *y), 1);
+        }
+        assert_eq!(alive(&states), expected.keys().cloned().collect());
+    }
+
+    #[test]
+    fn chunked_state_test_sleep_and_free() {
+        let mut initial_states = glider(10, 10);
+        initial_states.insert(GridPoint2D::new(-300, -300), 1);
+        let mut states = ChunkedStates::new(0u8, initial_states, 1);
+        assert_eq!(states.chunk_count(), 18);
+
+        // The lonely cell dies, the glider moves inside its chunk.
+        let mut next: Vec<(Point, u8)> = glider(11, 11).into_iter().collect();
+        states.update_cell_states(&next);
+        assert_eq!(states.get_cell_state(&GridPoint2D::new(-300, -300)), 0);
+        assert_eq!(states.awake_chunk_count(), 18);
+
+        // Nothing changes any more, every chunk goes to sleep and the empty ones are freed.
+        next.sort_by_key(|ele| (ele.0.x, ele.0.y));
+        states.update_cell_states(&next);
+        assert_eq!(states.awake_chunk_count(), 0);
+        assert_eq!(states.chunk_count(), 1);
+        assert_eq!(states.get_cell_state(&GridPoint2D::new(11, 12)), 1);
+        assert_eq!(states.get_cell_previous_state(&GridPoint2D::new(11, 12)), 1);
+    }
+
+    #[test]
+    fn chunked_state_test_neighbor_margin() {
+        let initial_states = glider(10, 10);
+        // Neighbors up to 100 cells away reach two chunks on each side.
+        let states = ChunkedStates::new(0u8, initial_states, 100);
+        assert_eq!(states.chunk_count(), 25);
+        assert_eq!(states.awake_chunk_count(), 25);
+    }
+
+    #[test]
+    #[should_panic]
+    fn chunked_state_test_reject_second_order() {
+        StandardBoard::new(
+            Box::new(Grid::<Point>::new(vec![3usize, 3].into_iter())),
+            Box::new(NeighborMoore::new()),
+            Box::new(ChunkedStates::new(0u8, glider(0, 0), 1)),
+            Box::new(SharedStrategyManager::new(Box::new(
+                SecondOrderStrategy::from_life_like(DecayLifeLikeStrategy::gol()),
+            ))),
+            Vec::new(),
+        );
+    }
+}
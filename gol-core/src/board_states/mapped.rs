@@ -0,0 +1,451 @@
+use crate::{
+    board::bit_life::BitLayout, util::grid_util::Shape2D, BoardStateManager, GridPoint2D,
+    IndexedDataOwned, TwoStateRule,
+};
+use memmap2::MmapMut;
+use num_traits::{PrimInt, ToPrimitive};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::fs::OpenOptions;
+use std::io;
+use std::marker::PhantomData;
+use std::path::Path;
+
+const MAGIC: &[u8; 8] = b"GOLBITS1";
+
+/// Written in native byte order, words of files from machines with another byte order would be
+/// read wrong.
+const BYTE_ORDER_MARK: u64 = 0x0102_0304_0506_0708;
+
+/// Magic, width, height, whether the board wraps, generation, half of the file holding it and
+/// byte order mark, padded so the words stay aligned.
+const HEADER_LEN: usize = 64;
+
+/// Words evolved at once if the band size is not specified, 32 MiB.
+const DEFAULT_BAND_WORDS: usize = 1 << 22;
+
+/// Dense states of a two-state 2D grid, kept in a memory-mapped file so boards can be larger than
+/// the memory. The file holds the current and previous generations in the bit-packed layout of
+/// `BitLife`, and doubles as a checkpoint: reopening it continues from the last generation.
+///
+/// `advance` evolves the board in bands of rows without ever holding the cells as states, use it
+/// instead of a `Board` for boards that do not fit in memory.
+pub struct MappedBitStates<T, U> {
+    map: MmapMut,
+    shape: Shape2D,
+    layout: BitLayout,
+    rule: TwoStateRule<T>,
+    band_rows: usize,
+    phantom: PhantomData<U>,
+}
+
+impl<T, U> MappedBitStates<T, U>
+where
+    T: Send + Sync + Clone,
+    U: PrimInt + ToPrimitive,
+{
+    /// Creates the file, replacing it if it exists. Cells outside of the shape are ignored.
+    pub fn create<P: AsRef<Path>>(
+        path: P,
+        shape: Shape2D,
+        is_donut: bool,
+        rule: TwoStateRule<T>,
+        initial_states: HashMap<GridPoint2D<U>, T>,
+    ) -> io::Result<Self> {
+        let layout = BitLayout::new(shape.width(), shape.height(), is_donut);
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len((HEADER_LEN + 2 * layout.word_count() * 8) as u64)?;
+        let map = unsafe { MmapMut::map_mut(&file)? };
+        let mut res = Self::with_map(map, shape, layout, rule);
+        res.map[..8].copy_from_slice(MAGIC);
+        res.write_header(1, res.shape.width() as u64);
+        res.write_header(2, res.shape.height() as u64);
+        res.write_header(3, is_donut as u64);
+        res.map[48..56].copy_from_slice(&BYTE_ORDER_MARK.to_ne_bytes());
+
+        let (shape, layout, rule) = (&res.shape, &res.layout, &res.rule);
+        let board = split_generations(&mut res.map, 0, layout.word_count()).0;
+        for (idx, state) in initial_states.iter() {
+            if let Some((row, col)) = position(shape, idx) {
+                layout.set(board, row, col, rule.is_alive(state));
+            }
+        }
+        Ok(res)
+    }
+
+    /// Opens a file written by `create`, at the generation it was last advanced to.
+    pub fn open<P: AsRef<Path>>(path: P, rule: TwoStateRule<T>) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        let map = unsafe { MmapMut::map_mut(&file)? };
+        if map.len() < HEADER_LEN || &map[..8] != MAGIC {
+            return Err(invalid_data("Not a bit-packed board file."));
+        }
+        if map[48..56] != BYTE_ORDER_MARK.to_ne_bytes() {
+            return Err(invalid_data(
+                "Board file was written with another byte order.",
+            ));
+        }
+        let read = |i: usize| u64::from_le_bytes(map[i * 8..(i + 1) * 8].try_into().unwrap());
+        let shape = Shape2D::new(read(1) as usize, read(2) as usize);
+        let layout = BitLayout::new(shape.width(), shape.height(), read(3) == 1);
+        if map.len() != HEADER_LEN + 2 * layout.word_count() * 8 {
+            return Err(invalid_data("Board file is truncated."));
+        }
+        Ok(Self::with_map(map, shape, layout, rule))
+    }
+
+    /// Number of rows, i.e. columns of the grid, evolved at once by `advance`. Larger bands keep
+    /// more threads busy, smaller ones keep fewer pages of the file in memory.
+    pub fn with_band_rows(self, band_rows: usize) -> Self {
+        let mut res = self;
+        res.band_rows = band_rows.max(1);
+        res
+    }
+
+    pub fn shape(&self) -> &Shape2D {
+        &self.shape
+    }
+
+    pub fn is_donut(&self) -> bool {
+        self.layout.is_donut()
+    }
+
+    /// Number of generations evolved since the file was created.
+    pub fn generation(&self) -> usize {
+        self.read_header(4) as usize
+    }
+
+    pub fn alive_count(&self) -> usize {
+        let (cur, _) = self.generations();
+        cur.par_iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Evolves the board by one generation with the rule, band by band. Finished bands are
+    /// written back in the background, so memory is only needed for the bands being evolved.
+    pub fn advance(&mut self) -> io::Result<()> {
+        let (row_count, words_per_row) = (self.layout.row_count(), self.layout.words_per_row());
+        let (current, word_count) = (self.current(), self.layout.word_count());
+        let mut first_row = 0;
+        while first_row < row_count {
+            let last_row = (first_row + self.band_rows).min(row_count);
+            let (cur, next) = split_generations(&mut self.map, current, word_count);
+            let band = &mut next[first_row * words_per_row..last_row * words_per_row];
+            self.layout.next_rows(cur, first_row, band, &self.rule);
+            let offset = (1 - current) * word_count + first_row * words_per_row;
+            self.map.flush_async_range(
+                HEADER_LEN + offset * 8,
+                (last_row - first_row) * words_per_row * 8,
+            )?;
+            first_row = last_row;
+        }
+        self.finish_generation();
+        Ok(())
+    }
+
+    /// Writes everything to the file, which can be reopened from here on.
+    pub fn flush(&self) -> io::Result<()> {
+        self.map.flush()
+    }
+
+    fn with_map(map: MmapMut, shape: Shape2D, layout: BitLayout, rule: TwoStateRule<T>) -> Self {
+        let band_rows = (DEFAULT_BAND_WORDS / layout.words_per_row()).max(1);
+        Self {
+            map,
+            shape,
+            layout,
+            rule,
+            band_rows,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Half of the file holding the current generation.
+    fn current(&self) -> usize {
+        self.read_header(5) as usize
+    }
+
+    fn finish_generation(&mut self) {
+        let (current, generation) = (self.current(), self.generation());
+        self.write_header(5, 1 - current as u64);
+        self.write_header(4, generation as u64 + 1);
+    }
+
+    /// Current and previous generations.
+    fn generations(&self) -> (&[u64], &[u64]) {
+        let word_count = self.layout.word_count();
+        // The map is page aligned and the header keeps the words aligned.
+        let words = unsafe {
+            std::slice::from_raw_parts(
+                self.map[HEADER_LEN..].as_ptr() as *const u64,
+                2 * word_count,
+            )
+        };
+        let (first, second) = words.split_at(word_count);
+        if self.current() == 0 {
+            (first, second)
+        } else {
+            (second, first)
+        }
+    }
+
+    fn read_header(&self, field: usize) -> u64 {
+        u64::from_le_bytes(self.map[field * 8..(field + 1) * 8].try_into().unwrap())
+    }
+
+    fn write_header(&mut self, field: usize, val: u64) {
+        self.map[field * 8..(field + 1) * 8].copy_from_slice(&val.to_le_bytes());
+    }
+
+    fn cell_state(&self, board: &[u64], idx: &GridPoint2D<U>) -> T {
+        let is_alive = match position(&self.shape, idx) {
+            Some((row, col)) => self.layout.get(board, row, col),
+            None => false,
+        };
+        self.rule.state(is_alive)
+    }
+}
+
+impl<T, U>
+    BoardStateManager<T, GridPoint2D<U>, rayon::vec::IntoIter<IndexedDataOwned<GridPoint2D<U>, T>>>
+    for MappedBitStates<T, U>
+where
+    T: Send + Sync + Clone,
+    U: Send + Sync + PrimInt + ToPrimitive,
+{
+    fn get_cell_state(&self, idx: &GridPoint2D<U>) -> T {
+        self.cell_state(self.generations().0, idx)
+    }
+
+    fn get_cell_previous_state(&self, idx: &GridPoint2D<U>) -> T {
+        self.cell_state(self.generations().1, idx)
+    }
+
+    fn update_cell_states_from_par_iter(
+        &mut self,
+        new_states: rayon::vec::IntoIter<IndexedDataOwned<GridPoint2D<U>, T>>,
+    ) {
+        let new_states: Vec<IndexedDataOwned<GridPoint2D<U>, T>> = new_states.collect();
+        self.update_cell_states(&new_states);
+    }
+
+    fn update_cell_states(&mut self, new_states: &[IndexedDataOwned<GridPoint2D<U>, T>]) {
+        let (shape, layout, rule) = (&self.shape, &self.layout, &self.rule);
+        let current = self.read_header(5) as usize;
+        let next = split_generations(&mut self.map, current, layout.word_count()).1;
+        next.par_iter_mut().for_each(|word| *word = 0);
+        for (idx, state) in new_states.iter() {
+            if let Some((row, col)) = position(shape, idx) {
+                layout.set(next, row, col, rule.is_alive(state));
+            }
+        }
+        self.finish_generation();
+    }
+
+    fn swap_generations(&mut self) {
+        let current = self.current();
+        self.write_header(5, 1 - current as u64);
+    }
+}
+
+/// Current generation and the other half of the file, with `current` the half holding it.
+fn split_generations(
+    map: &mut MmapMut,
+    current: usize,
+    word_count: usize,
+) -> (&mut [u64], &mut [u64]) {
+    // The map is page aligned and the header keeps the words aligned.
+    let words = unsafe {
+        std::slice::from_raw_parts_mut(map[HEADER_LEN..].as_mut_ptr() as *mut u64, 2 * word_count)
+    };
+    let (first, second) = words.split_at_mut(word_count);
+    if current == 0 {
+        (first, second)
+    } else {
+        (second, first)
+    }
+}
+
+/// Row and column of the cell in the bit layout, rows going along x like `BitLife`.
+fn position<U>(shape: &Shape2D, idx: &GridPoint2D<U>) -> Option<(usize, usize)>
+where
+    U: ToPrimitive,
+{
+    let row = idx.x.to_i64()? - shape.x_idx_min();
+    let col = idx.y.to_i64()? - shape.y_idx_min();
+    if row < 0 || col < 0 || row >= shape.width() as i64 || col >= shape.height() as i64 {
+        None
+    } else {
+        Some((row as usize, col as usize))
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod mapped_bit_state_manager_test {
+    use crate::{
+        util::grid_util::Shape2D, Board, BoardStateManager, DecayLifeLikeStrategy, Grid,
+        GridFactory, GridPoint2D, MappedBitStates, NeighborMoore, NeighborMooreDonut,
+        SharedStrategyManager, SparseStates, StandardBoard, TwoStateRule,
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    type Point = GridPoint2D<i64>;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("gol_mapped_{}_{}", name, std::process::id()))
+    }
+
+    fn gol_rule() -> TwoStateRule<u8> {
+        TwoStateRule::new(
+            &vec![2, 3].into_iter().collect(),
+            &vec![3].into_iter().collect(),
+            0,
+            1,
+            |state| *state == 1,
+        )
+    }
+
+    fn random_states(shape: &Shape2D) -> HashMap<Point, u8> {
+        let mut rng = StdRng::seed_from_u64(5);
+        (shape.x_idx_min()..=shape.x_idx_max())
+            .flat_map(|x| (shape.y_idx_min()..=shape.y_idx_max()).map(move |y| (x, y)))
+            .filter(|_| rng.gen_bool(0.4))
+            .map(|(x, y)| (GridPoint2D::new(x, y), 1))
+            .collect()
+    }
+
+    fn assert_same_as_standard(name: &str, shape: Shape2D, is_donut: bool) {
+        let path = temp_path(name);
+        let initial_states = random_states(&shape);
+        let mut states = MappedBitStates::create(
+            &path,
+            shape.clone(),
+            is_donut,
+            gol_rule(),
+            initial_states.clone(),
+        )
+        .unwrap()
+        .with_band_rows(7);
+        let mut board = StandardBoard::new(
+            Box::new(Grid::<Point>::new(
+                vec![shape.width(), shape.height()].into_iter(),
+            )),
+            if is_donut {
+                Box::new(NeighborMooreDonut::new(shape.clone()))
+            } else {
+                Box::new(NeighborMoore::new())
+            },
+            Box::new(SparseStates::new(0u8, initial_states)),
+            Box::new(SharedStrategyManager::new(Box::new(
+                DecayLifeLikeStrategy::gol(),
+            ))),
+            Vec::new(),
+        );
+        for _ in 0..10 {
+            states.advance().unwrap();
+            for (idx, state) in board.advance_one_generation().iter() {
+                assert_eq!(&states.get_cell_state(idx), state);
+            }
+        }
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn mapped_bit_state_test_same_as_standard() {
+        assert_same_as_standard("bounded", Shape2D::new(41, 70), false);
+        assert_same_as_standard("donut", Shape2D::new(30, 130), true);
+    }
+
+    #[test]
+    fn mapped_bit_state_test_checkpoint() {
+        let shape = Shape2D::new(50, 66);
+        let (path, path_continuous) = (temp_path("checkpoint"), temp_path("continuous"));
+        let initial_states = random_states(&shape);
+        let mut continuous: MappedBitStates<u8, i64> = MappedBitStates::create(
+            &path_continuous,
+            shape.clone(),
+            true,
+            gol_rule(),
+            initial_states.clone(),
+        )
+        .unwrap();
+        let mut states =
+            MappedBitStates::create(&path, shape.clone(), true, gol_rule(), initial_states)
+                .unwrap();
+        for _ in 0..5 {
+            states.advance().unwrap();
+        }
+        states.flush().unwrap();
+        drop(states);
+
+        let mut states: MappedBitStates<u8, i64> =
+            MappedBitStates::open(&path, gol_rule()).unwrap();
+        assert_eq!(states.generation(), 5);
+        assert!(states.is_donut());
+        for _ in 0..5 {
+            states.advance().unwrap();
+        }
+        for _ in 0..10 {
+            continuous.advance().unwrap();
+        }
+        assert_eq!(states.alive_count(), continuous.alive_count());
+        for x in shape.x_idx_min()..=shape.x_idx_max() {
+            for y in shape.y_idx_min()..=shape.y_idx_max() {
+                let idx = GridPoint2D::new(x, y);
+                assert_eq!(states.get_cell_state(&idx), continuous.get_cell_state(&idx));
+                assert_eq!(
+                    states.get_cell_previous_state(&idx),
+                    continuous.get_cell_previous_state(&idx)
+                );
+            }
+        }
+        std::fs::remove_file(path).unwrap();
+        std::fs::remove_file(path_continuous).unwrap();
+    }
+
+    #[test]
+    fn mapped_bit_state_test_board() {
+        let shape = Shape2D::new(20, 20);
+        let path = temp_path("board");
+        let initial_states = random_states(&shape);
+        let new_board = |state_manager| {
+            StandardBoard::new(
+                Box::new(Grid::<Point>::new(vec![20usize, 20].into_iter())),
+                Box::new(NeighborMoore::new()),
+                state_manager,
+                Box::new(SharedStrategyManager::new(Box::new(
+                    DecayLifeLikeStrategy::gol(),
+                ))),
+                Vec::new(),
+            )
+        };
+        let mapped =
+            MappedBitStates::create(&path, shape, false, gol_rule(), initial_states.clone())
+                .unwrap();
+        let mut board = new_board(Box::new(SparseStates::new(0u8, initial_states)));
+        let mut board_mapped = new_board(Box::new(mapped));
+        for _ in 0..5 {
+            let states = board.advance_one_generation();
+            let states_mapped = board_mapped.advance_one_generation();
+            assert_eq!(states.as_slice(), states_mapped.as_slice());
+        }
+        assert_eq!(
+            board_mapped
+                .state_manager()
+                .get_cell_state(&GridPoint2D::new(100, 0)),
+            0
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+}
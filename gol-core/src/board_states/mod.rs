@@ -1 +1,5 @@
+pub mod chunked;
+pub mod mapped;
+pub mod packed_sparse;
+pub mod quadtree;
 pub mod sparse;
@@ -0,0 +1,160 @@
+use crate::{BoardStateManager, IndexedDataOwned, PackedIndex};
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+/// Same as `SparseStates`, but keyed by the packed indices, which are cheaper to hash and keep
+/// cells close to each other close in the lookup.
+pub struct PackedSparseStates<T, CI>
+where
+    CI: PackedIndex,
+{
+    default_state: T,
+    lookup: HashMap<CI::Packed, T>,
+    previous_lookup: HashMap<CI::Packed, T>,
+}
+
+impl<T, CI> PackedSparseStates<T, CI>
+where
+    CI: PackedIndex,
+{
+    pub fn new(default_state: T, initial_states: HashMap<CI, T>) -> Self
+    where
+        T: PartialEq + Clone,
+    {
+        Self {
+            default_state,
+            lookup: pack_keys(initial_states),
+            previous_lookup: HashMap::new(),
+        }
+    }
+
+    /// Sets the generation before the initial states, all default states if not specified.
+    pub fn with_previous_states(self, previous_states: HashMap<CI, T>) -> Self {
+        let mut res = self;
+        res.previous_lookup = pack_keys(previous_states);
+        res
+    }
+}
+
+impl<T, CI> BoardStateManager<T, CI, rayon::vec::IntoIter<IndexedDataOwned<CI, T>>>
+    for PackedSparseStates<T, CI>
+where
+    T: Send + Sync + Clone + PartialEq,
+    CI: Send + Sync + Clone + PackedIndex,
+{
+    fn get_cell_state(&self, idx: &CI) -> T {
+        match self.lookup.get(&idx.pack()) {
+            Some(val) => val.clone(),
+            None => self.default_state.clone(),
+        }
+    }
+
+    fn get_cell_previous_state(&self, idx: &CI) -> T {
+        match self.previous_lookup.get(&idx.pack()) {
+            Some(val) => val.clone(),
+            None => self.default_state.clone(),
+        }
+    }
+
+    fn update_cell_states_from_par_iter(
+        &mut self,
+        new_states: rayon::vec::IntoIter<IndexedDataOwned<CI, T>>,
+    ) {
+        let next_lookup = new_states
+            .filter(|ele| ele.1 != self.default_state)
+            .map(|ele| (ele.0.pack(), ele.1))
+            .collect();
+        self.previous_lookup = std::mem::replace(&mut self.lookup, next_lookup);
+    }
+
+    fn update_cell_states(&mut self, new_states: &[IndexedDataOwned<CI, T>]) {
+        // The lookup two generations back is not needed anymore, reuse its memory.
+        let default_state = &self.default_state;
+        let mut next_lookup = std::mem::take(&mut self.previous_lookup);
+        next_lookup.clear();
+        next_lookup.par_extend(
+            new_states
+                .par_iter()
+                .filter(|ele| &ele.1 != default_state)
+                .map(|ele| (ele.0.pack(), ele.1.clone())),
+        );
+        self.previous_lookup = std::mem::replace(&mut self.lookup, next_lookup);
+    }
+
+    fn swap_generations(&mut self) {
+        std::mem::swap(&mut self.lookup, &mut self.previous_lookup);
+    }
+}
+
+fn pack_keys<CI, T>(states: HashMap<CI, T>) -> HashMap<CI::Packed, T>
+where
+    CI: PackedIndex,
+{
+    states
+        .into_iter()
+        .map(|(idx, state)| (idx.pack(), state))
+        .collect()
+}
+
+#[cfg(test)]
+mod packed_sparse_state_manager_test {
+    use crate::{
+        Board, BoardStateManager, DecayLifeLikeStrategy, Grid, GridFactory, GridPoint2D,
+        NeighborMoore, PackedSparseStates, SharedStrategyManager, SparseStates, StandardBoard,
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use rayon::prelude::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn packed_sparse_state_test_1() {
+        let mut initial_maps = HashMap::new();
+        initial_maps.insert(GridPoint2D::new(0i64, 0), 1u8);
+        initial_maps.insert(GridPoint2D::new(i64::MAX, -(1 << 40)), 2u8);
+        let mut states = PackedSparseStates::new(0, initial_maps);
+        assert_eq!(states.get_cell_state(&GridPoint2D::new(0, 0)), 1);
+        assert_eq!(
+            states.get_cell_state(&GridPoint2D::new(i64::MAX, -(1 << 40))),
+            2
+        );
+        assert_eq!(states.get_cell_state(&GridPoint2D::new(1, -5)), 0);
+
+        let next = vec![(GridPoint2D::new(1, 0), 2u8), (GridPoint2D::new(2, 0), 0u8)];
+        states.update_cell_states_from_par_iter(next.into_par_iter());
+        assert_eq!(states.get_cell_state(&GridPoint2D::new(0, 0)), 0);
+        assert_eq!(states.get_cell_state(&GridPoint2D::new(1, 0)), 2);
+        assert_eq!(states.get_cell_previous_state(&GridPoint2D::new(0, 0)), 1);
+
+        states.swap_generations();
+        assert_eq!(states.get_cell_state(&GridPoint2D::new(0, 0)), 1);
+        assert_eq!(states.get_cell_previous_state(&GridPoint2D::new(1, 0)), 2);
+    }
+
+    #[test]
+    fn packed_sparse_state_test_same_as_sparse() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let initial_states: HashMap<GridPoint2D<i32>, u8> = (-15..15)
+            .flat_map(|x| (-15..15).map(move |y| GridPoint2D::new(x, y)))
+            .filter(|_| rng.gen_bool(0.3))
+            .map(|idx| (idx, 1))
+            .collect();
+        let new_board = |state_manager| {
+            StandardBoard::new(
+                Box::new(Grid::<GridPoint2D<i32>>::new(vec![30usize, 30].into_iter())),
+                Box::new(NeighborMoore::new()),
+                state_manager,
+                Box::new(SharedStrategyManager::new(Box::new(
+                    DecayLifeLikeStrategy::gol(),
+                ))),
+                Vec::new(),
+            )
+        };
+        let mut board = new_board(Box::new(SparseStates::new(0u8, initial_states.clone())));
+        let mut board_packed = new_board(Box::new(PackedSparseStates::new(0u8, initial_states)));
+        for _ in 0..10 {
+            let states = board.advance_one_generation();
+            let states_packed = board_packed.advance_one_generation();
+            assert_eq!(states.as_slice(), states_packed.as_slice());
+        }
+    }
+}
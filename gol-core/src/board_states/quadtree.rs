@@ -0,0 +1,458 @@
+use crate::{
+    cell::packed::{deinterleave_64, interleave_64, to_unsigned_64},
+    BoardStateManager, GridPoint2D, IndexedDataOwned,
+};
+use num_traits::{FromPrimitive, PrimInt, ToPrimitive};
+use rayon::prelude::*;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+/// Side length of the root quadrant as a power of two, it covers all 64-bit coordinates.
+const ROOT_LEVEL: u32 = 64;
+
+type Entry<U, T> = (u128, GridPoint2D<U>, T);
+
+/// Candidate of a nearest cell search, ordered by the squared distance to the searched cell first.
+/// Holds the level, the smallest corner and the range of entries of a quadrant.
+type Candidate = Reverse<(u128, u32, u64, u64, usize, usize)>;
+
+struct Generation<U, T> {
+    /// Non-default cells sorted by the Z-order of their coordinates, so every quadrant of the tree
+    /// is a contiguous range of entries.
+    entries: Vec<Entry<U, T>>,
+    bounding_box: Option<(GridPoint2D<U>, GridPoint2D<U>)>,
+}
+
+/// Stores the non-default cells of a 2D grid in a linear quadtree, taking memory proportional to
+/// their count no matter how far apart they are. Besides the state of a cell it answers which
+/// cells are in a rectangle, the bounding box of all cells and which cell is the nearest to a
+/// point, without going through all cells.
+pub struct QuadtreeStates<T, U> {
+    default_state: T,
+    current: Generation<U, T>,
+    previous: Generation<U, T>,
+}
+
+impl<T, U> QuadtreeStates<T, U>
+where
+    T: Send + Sync + Clone + PartialEq,
+    U: Send + Sync + PrimInt + FromPrimitive + ToPrimitive,
+{
+    pub fn new(default_state: T, initial_states: HashMap<GridPoint2D<U>, T>) -> Self {
+        let mut res = Self {
+            default_state,
+            current: Generation::new(),
+            previous: Generation::new(),
+        };
+        res.current = res.generation_from(initial_states.into_iter().collect());
+        res
+    }
+
+    /// Sets the generation before the initial states, all default states if not specified.
+    pub fn with_previous_states(self, previous_states: HashMap<GridPoint2D<U>, T>) -> Self {
+        let mut res = self;
+        res.previous = res.generation_from(previous_states.into_iter().collect());
+        res
+    }
+
+    /// Number of non-default cells.
+    pub fn len(&self) -> usize {
+        self.current.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.current.entries.is_empty()
+    }
+
+    /// Smallest and largest coordinates of the non-default cells, `None` if there are none.
+    pub fn bounding_box(&self) -> Option<(GridPoint2D<U>, GridPoint2D<U>)> {
+        self.current.bounding_box.clone()
+    }
+
+    /// Non-default cells with coordinates between the two corners, both included, in no particular
+    /// order.
+    pub fn states_in_rect(
+        &self,
+        min: &GridPoint2D<U>,
+        max: &GridPoint2D<U>,
+    ) -> Vec<IndexedDataOwned<GridPoint2D<U>, T>> {
+        let rect = (
+            to_key(&min.x),
+            to_key(&min.y),
+            to_key(&max.x),
+            to_key(&max.y),
+        );
+        let mut res = Vec::new();
+        if rect.0 <= rect.2 && rect.1 <= rect.3 {
+            collect_in_rect(&self.current.entries, ROOT_LEVEL, 0, 0, &rect, &mut res);
+        }
+        res
+    }
+
+    /// One of the non-default cells closest to the index by Euclidean distance, the index itself
+    /// if it is not default. `None` if all cells are default.
+    pub fn nearest(&self, idx: &GridPoint2D<U>) -> Option<IndexedDataOwned<GridPoint2D<U>, T>> {
+        let entries = &self.current.entries;
+        let point = (to_key(&idx.x), to_key(&idx.y));
+        let mut candidates: BinaryHeap<Candidate> = BinaryHeap::new();
+        candidates.push(Reverse((0, ROOT_LEVEL, 0, 0, 0, entries.len())));
+
+        // Quadrants are visited closest first, so the first single cell found is the nearest.
+        while let Some(Reverse((_, level, x_min, y_min, start, end))) = candidates.pop() {
+            if start == end {
+                continue;
+            }
+            if level == 0 {
+                let (_, idx, state) = &entries[start];
+                return Some((idx.clone(), state.clone()));
+            }
+            if end - start == 1 {
+                let (code, _, _) = &entries[start];
+                let (x, y) = deinterleave_64(*code);
+                let dist = squared_dist(point, (x, y, 0));
+                candidates.push(Reverse((dist, 0, x, y, start, end)));
+                continue;
+            }
+            for (x_min, y_min, range) in split(&entries[start..end], level, x_min, y_min) {
+                let dist = squared_dist(point, (x_min, y_min, level - 1));
+                candidates.push(Reverse((
+                    dist,
+                    level - 1,
+                    x_min,
+                    y_min,
+                    start + range.start,
+                    start + range.end,
+                )));
+            }
+        }
+        None
+    }
+
+    fn generation_from(
+        &self,
+        states: Vec<IndexedDataOwned<GridPoint2D<U>, T>>,
+    ) -> Generation<U, T> {
+        let mut res = Generation::new();
+        res.fill(&self.default_state, states.par_iter());
+        res
+    }
+
+    fn get_state(&self, generation: &Generation<U, T>, idx: &GridPoint2D<U>) -> T {
+        let code = to_code(idx);
+        match generation
+            .entries
+            .binary_search_by_key(&code, |entry| entry.0)
+        {
+            Ok(i) => generation.entries[i].2.clone(),
+            Err(_) => self.default_state.clone(),
+        }
+    }
+}
+
+impl<U, T> Generation<U, T>
+where
+    T: Send + Sync + Clone + PartialEq,
+    U: Send + Sync + PrimInt + FromPrimitive + ToPrimitive,
+{
+    fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            bounding_box: None,
+        }
+    }
+
+    /// Replaces the cells, keeping the memory of the entries.
+    fn fill<'a, I>(&mut self, default_state: &T, states: I)
+    where
+        I: ParallelIterator<Item = &'a IndexedDataOwned<GridPoint2D<U>, T>>,
+        T: 'a,
+        U: 'a,
+    {
+        self.entries.clear();
+        self.entries.par_extend(
+            states
+                .filter(|ele| &ele.1 != default_state)
+                .map(|(idx, state)| (to_code(idx), idx.clone(), state.clone())),
+        );
+        self.entries.par_sort_unstable_by_key(|entry| entry.0);
+        self.bounding_box = self
+            .entries
+            .par_iter()
+            .map(|(_, idx, _)| (idx.x, idx.y, idx.x, idx.y))
+            .reduce_with(|a, b| (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3)))
+            .map(|(x_min, y_min, x_max, y_max)| {
+                (
+                    GridPoint2D::new(x_min, y_min),
+                    GridPoint2D::new(x_max, y_max),
+                )
+            });
+    }
+}
+
+impl<T, U>
+    BoardStateManager<T, GridPoint2D<U>, rayon::vec::IntoIter<IndexedDataOwned<GridPoint2D<U>, T>>>
+    for QuadtreeStates<T, U>
+where
+    T: Send + Sync + Clone + PartialEq,
+    U: Send + Sync + PrimInt + FromPrimitive + ToPrimitive,
+{
+    fn get_cell_state(&self, idx: &GridPoint2D<U>) -> T {
+        self.get_state(&self.current, idx)
+    }
+
+    fn get_cell_previous_state(&self, idx: &GridPoint2D<U>) -> T {
+        self.get_state(&self.previous, idx)
+    }
+
+    fn update_cell_states_from_par_iter(
+        &mut self,
+        new_states: rayon::vec::IntoIter<IndexedDataOwned<GridPoint2D<U>, T>>,
+    ) {
+        let new_states: Vec<IndexedDataOwned<GridPoint2D<U>, T>> = new_states.collect();
+        self.update_cell_states(&new_states);
+    }
+
+    fn update_cell_states(&mut self, new_states: &[IndexedDataOwned<GridPoint2D<U>, T>]) {
+        // The generation two generations back is not needed anymore, reuse its memory.
+        self.previous
+            .fill(&self.default_state, new_states.par_iter());
+        std::mem::swap(&mut self.current, &mut self.previous);
+    }
+
+    fn swap_generations(&mut self) {
+        std::mem::swap(&mut self.current, &mut self.previous);
+    }
+}
+
+fn to_key<U: ToPrimitive>(val: &U) -> u64 {
+    to_unsigned_64(val.to_i64().unwrap())
+}
+
+fn to_code<U: ToPrimitive>(idx: &GridPoint2D<U>) -> u128 {
+    interleave_64(to_key(&idx.x), to_key(&idx.y))
+}
+
+/// Largest offset from the smallest corner inside a quadrant of side `2^level`.
+fn side_mask(level: u32) -> u64 {
+    if level == 0 {
+        0
+    } else {
+        u64::MAX >> (64 - level)
+    }
+}
+
+/// Squared distance from a point to the quadrant of side `2^level` with the given smallest
+/// corner, saturating for points very far apart.
+fn squared_dist(point: (u64, u64), quadrant: (u64, u64, u32)) -> u128 {
+    let (x_min, y_min, level) = quadrant;
+    let dist_1d = |val: u64, min: u64| {
+        let max = min + side_mask(level);
+        (min.saturating_sub(val) + val.saturating_sub(max)) as u128
+    };
+    let dx = dist_1d(point.0, x_min);
+    let dy = dist_1d(point.1, y_min);
+    (dx * dx).saturating_add(dy * dy)
+}
+
+/// The four child quadrants of a quadrant with its entries, as smallest corners and ranges in the
+/// entries.
+fn split<U, T>(
+    entries: &[Entry<U, T>],
+    level: u32,
+    x_min: u64,
+    y_min: u64,
+) -> [(u64, u64, std::ops::Range<usize>); 4] {
+    let prefix = interleave_64(x_min, y_min);
+    let quarter = 1u128 << (2 * (level - 1));
+    let bounds: Vec<usize> = (1..4u128)
+        .map(|i| entries.partition_point(|entry| entry.0 < prefix + i * quarter))
+        .collect();
+    let half = 1u64 << (level - 1);
+    [
+        (x_min, y_min, 0..bounds[0]),
+        (x_min + half, y_min, bounds[0]..bounds[1]),
+        (x_min, y_min + half, bounds[1]..bounds[2]),
+        (x_min + half, y_min + half, bounds[2]..entries.len()),
+    ]
+}
+
+fn collect_in_rect<U, T>(
+    entries: &[Entry<U, T>],
+    level: u32,
+    x_min: u64,
+    y_min: u64,
+    rect: &(u64, u64, u64, u64),
+    res: &mut Vec<IndexedDataOwned<GridPoint2D<U>, T>>,
+) where
+    U: Clone,
+    T: Clone,
+{
+    if entries.is_empty() {
+        return;
+    }
+    let (x_max, y_max) = (x_min + side_mask(level), y_min + side_mask(level));
+    if x_max < rect.0 || x_min > rect.2 || y_max < rect.1 || y_min > rect.3 {
+        return;
+    }
+    if x_min >= rect.0 && x_max <= rect.2 && y_min >= rect.1 && y_max <= rect.3 {
+        res.extend(
+            entries
+                .iter()
+                .map(|(_, idx, state)| (idx.clone(), state.clone())),
+        );
+        return;
+    }
+    // Single cells are either in or out of the rectangle, so the level is above 0 here.
+    for (x_min, y_min, range) in split(entries, level, x_min, y_min) {
+        collect_in_rect(&entries[range], level - 1, x_min, y_min, rect, res);
+    }
+}
+
+#[cfg(test)]
+mod quadtree_state_manager_test {
+    use crate::{
+        Board, BoardStateManager, DecayLifeLikeStrategy, Grid, GridFactory, GridPoint2D,
+        NeighborMoore, QuadtreeStates, SharedStrategyManager, SparseStates, StandardBoard,
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::collections::{HashMap, HashSet};
+
+    type Point = GridPoint2D<i64>;
+
+    fn random_states(count: usize, spread: i64) -> HashMap<Point, u8> {
+        let mut rng = StdRng::seed_from_u64(11);
+        (0..count)
+            .map(|_| {
+                let idx = GridPoint2D::new(
+                    rng.gen_range(-spread..spread),
+                    rng.gen_range(-spread..spread),
+                );
+                (idx, rng.gen_range(1..4))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn quadtree_state_test_1() {
+        let mut initial_states = HashMap::new();
+        initial_states.insert(GridPoint2D::new(0, 0), 1u8);
+        initial_states.insert(GridPoint2D::new(-5_000_000, 3), 2u8);
+        initial_states.insert(GridPoint2D::new(i64::MAX, i64::MIN), 3u8);
+        let states = QuadtreeStates::new(0, initial_states);
+        assert_eq!(states.len(), 3);
+        assert_eq!(states.get_cell_state(&GridPoint2D::new(0, 0)), 1);
+        assert_eq!(states.get_cell_state(&GridPoint2D::new(-5_000_000, 3)), 2);
+        assert_eq!(
+            states.get_cell_state(&GridPoint2D::new(i64::MAX, i64::MIN)),
+            3
+        );
+        assert_eq!(states.get_cell_state(&GridPoint2D::new(0, 1)), 0);
+        assert_eq!(
+            states.bounding_box(),
+            Some((
+                GridPoint2D::new(-5_000_000, i64::MIN),
+                GridPoint2D::new(i64::MAX, 3)
+            ))
+        );
+    }
+
+    #[test]
+    fn quadtree_state_test_rect() {
+        let initial_states = random_states(5000, 1000);
+        let states = QuadtreeStates::new(0u8, initial_states.clone());
+        let rects = [
+            (-1000, -1000, 999, 999),
+            (-3, 250, 400, 251),
+            (7, 7, 7, 7),
+            (5, 0, 4, 0),
+        ];
+        for (x_min, y_min, x_max, y_max) in rects.iter().cloned() {
+            let res: HashMap<Point, u8> = states
+                .states_in_rect(
+                    &GridPoint2D::new(x_min, y_min),
+                    &GridPoint2D::new(x_max, y_max),
+                )
+                .into_iter()
+                .collect();
+            let expected: HashMap<Point, u8> = initial_states
+                .iter()
+                .filter(|(idx, _)| {
+                    idx.x >= x_min && idx.x <= x_max && idx.y >= y_min && idx.y <= y_max
+                })
+                .map(|(idx, state)| (idx.clone(), *state))
+                .collect();
+            assert_eq!(res, expected);
+        }
+    }
+
+    #[test]
+    fn quadtree_state_test_nearest() {
+        let initial_states = random_states(300, 1_000_000);
+        let states = QuadtreeStates::new(0u8, initial_states.clone());
+        let dist = |a: &Point, b: &Point| {
+            let (dx, dy) = ((a.x - b.x) as i128, (a.y - b.y) as i128);
+            dx * dx + dy * dy
+        };
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..100 {
+            let idx = GridPoint2D::new(
+                rng.gen_range(-2_000_000..2_000_000),
+                rng.gen_range(-2_000_000..2_000_000),
+            );
+            let (nearest, state) = states.nearest(&idx).unwrap();
+            assert_eq!(initial_states[&nearest], state);
+            let expected = initial_states.keys().map(|ele| dist(ele, &idx)).min();
+            assert_eq!(Some(dist(&nearest, &idx)), expected);
+        }
+        let (idx, _) = initial_states.iter().next().unwrap();
+        assert_eq!(&states.nearest(idx).unwrap().0, idx);
+        assert!(QuadtreeStates::<u8, i64>::new(0, HashMap::new())
+            .nearest(&GridPoint2D::new(0, 0))
+            .is_none());
+    }
+
+    #[test]
+    fn quadtree_state_test_same_as_sparse() {
+        let alive = |states: &[(GridPoint2D<i32>, u8)]| -> HashSet<GridPoint2D<i32>> {
+            states
+                .iter()
+                .filter(|ele| ele.1 == 1)
+                .map(|ele| ele.0.clone())
+                .collect()
+        };
+        let mut rng = StdRng::seed_from_u64(7);
+        let initial_states: HashMap<GridPoint2D<i32>, u8> = (-20..20)
+            .flat_map(|x| (-20..20).map(move |y| GridPoint2D::new(x, y)))
+            .filter(|_| rng.gen_bool(0.3))
+            .map(|idx| (idx, 1))
+            .collect();
+        let new_board = |state_manager| {
+            StandardBoard::new(
+                Box::new(Grid::<GridPoint2D<i32>>::new(vec![40usize, 40].into_iter())),
+                Box::new(NeighborMoore::new()),
+                state_manager,
+                Box::new(SharedStrategyManager::new(Box::new(
+                    DecayLifeLikeStrategy::gol(),
+                ))),
+                Vec::new(),
+            )
+        };
+        let mut board = new_board(Box::new(SparseStates::new(0u8, initial_states.clone())));
+        let mut board_quadtree = new_board(Box::new(QuadtreeStates::new(0u8, initial_states)));
+        for _ in 0..20 {
+            let states = board.advance_one_generation();
+            let states_quadtree = board_quadtree.advance_one_generation();
+            assert_eq!(alive(&states), alive(&states_quadtree));
+        }
+        board_quadtree.reverse();
+        board.reverse();
+        assert_eq!(
+            board
+                .state_manager()
+                .get_cell_state(&GridPoint2D::new(0, 0)),
+            board_quadtree
+                .state_manager()
+                .get_cell_state(&GridPoint2D::new(0, 0))
+        );
+    }
+}
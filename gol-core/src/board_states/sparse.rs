@@ -5,6 +5,7 @@ use std::collections::HashMap;
 pub struct SparseStates<T, CI> {
     default_state: T,
     lookup: HashMap<CI, T>,
+    previous_lookup: HashMap<CI, T>,
 }
 
 impl<T, CI> SparseStates<T, CI> {
@@ -16,8 +17,16 @@ impl<T, CI> SparseStates<T, CI> {
         Self {
             default_state,
             lookup: initial_states,
+            previous_lookup: HashMap::new(),
         }
     }
+
+    /// Sets the generation before the initial states, all default states if not specified.
+    pub fn with_previous_states(self, previous_states: HashMap<CI, T>) -> Self {
+        let mut res = self;
+        res.previous_lookup = previous_states;
+        res
+    }
 }
 
 impl<T, CI> BoardStateManager<T, CI, rayon::vec::IntoIter<IndexedDataOwned<CI, T>>>
@@ -33,20 +42,47 @@ where
         }
     }
 
+    fn get_cell_previous_state(&self, idx: &CI) -> T {
+        match self.previous_lookup.get(idx) {
+            Some(val) => val.clone(),
+            None => self.default_state.clone(),
+        }
+    }
+
     fn update_cell_states_from_par_iter(
         &mut self,
         new_states: rayon::vec::IntoIter<IndexedDataOwned<CI, T>>,
     ) {
-        self.lookup = new_states
+        let next_lookup = new_states
             .filter(|ele| ele.1 != self.default_state)
             .map(|ele| (ele.0.clone(), ele.1.clone()))
             .collect();
+        self.previous_lookup = std::mem::replace(&mut self.lookup, next_lookup);
+    }
+
+    fn update_cell_states(&mut self, new_states: &[IndexedDataOwned<CI, T>]) {
+        // The lookup two generations back is not needed anymore, reuse its memory.
+        let default_state = &self.default_state;
+        let mut next_lookup = std::mem::take(&mut self.previous_lookup);
+        next_lookup.clear();
+        next_lookup.par_extend(
+            new_states
+                .par_iter()
+                .filter(|ele| &ele.1 != default_state)
+                .cloned(),
+        );
+        self.previous_lookup = std::mem::replace(&mut self.lookup, next_lookup);
+    }
+
+    fn swap_generations(&mut self) {
+        std::mem::swap(&mut self.lookup, &mut self.previous_lookup);
     }
 }
 
 #[cfg(test)]
 mod sparse_state_manager_test {
     use crate::{BoardStateManager, GridPoint2D, SparseStates};
+    use rayon::prelude::*;
     use std::collections::HashMap;
 
     #[test]
@@ -58,4 +94,51 @@ mod sparse_state_manager_test {
         assert_eq!(states.get_cell_state(&GridPoint2D { x: 1, y: 0 }), 0);
         assert_eq!(states.get_cell_state(&GridPoint2D { x: 1, y: -5 }), 0);
     }
+
+    #[test]
+    fn sparse_state_test_previous_generation() {
+        let mut initial_maps = HashMap::new();
+        initial_maps.insert(GridPoint2D { x: 0, y: 0 }, 1u8);
+        let mut states = SparseStates::new(0, initial_maps);
+        assert_eq!(
+            states.get_cell_previous_state(&GridPoint2D { x: 0, y: 0 }),
+            0
+        );
+
+        let next = vec![(GridPoint2D { x: 1, y: 0 }, 2u8)];
+        states.update_cell_states_from_par_iter(next.into_par_iter());
+        assert_eq!(states.get_cell_state(&GridPoint2D { x: 0, y: 0 }), 0);
+        assert_eq!(states.get_cell_state(&GridPoint2D { x: 1, y: 0 }), 2);
+        assert_eq!(
+            states.get_cell_previous_state(&GridPoint2D { x: 0, y: 0 }),
+            1
+        );
+
+        states.swap_generations();
+        assert_eq!(states.get_cell_state(&GridPoint2D { x: 0, y: 0 }), 1);
+        assert_eq!(
+            states.get_cell_previous_state(&GridPoint2D { x: 1, y: 0 }),
+            2
+        );
+    }
+
+    #[test]
+    fn sparse_state_test_update_from_slice() {
+        let mut initial_maps = HashMap::new();
+        initial_maps.insert(GridPoint2D { x: 0, y: 0 }, 1u8);
+        let mut states = SparseStates::new(0, initial_maps);
+        for i in 1..4 {
+            let next = vec![
+                (GridPoint2D { x: i, y: 0 }, i as u8),
+                (GridPoint2D { x: 0, y: 0 }, 0u8),
+            ];
+            states.update_cell_states(&next);
+            assert_eq!(states.get_cell_state(&GridPoint2D { x: i, y: 0 }), i as u8);
+            assert_eq!(states.get_cell_state(&GridPoint2D { x: i - 1, y: 0 }), 0);
+            assert_eq!(
+                states.get_cell_previous_state(&GridPoint2D { x: i - 1, y: 0 }),
+                (i as u8 - 1).max(1)
+            );
+        }
+    }
 }
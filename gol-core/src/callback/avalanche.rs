@@ -0,0 +1,95 @@
+use crate::util::sync_util::ReadOnlyLock;
+use crate::{BoardCallbackWithStates, IndexedDataOwned};
+use num_traits::{FromPrimitive, PrimInt};
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+/// Collects avalanche sizes of a sandpile. An avalanche starts when any cell reaches the toppling
+/// threshold and ends at the first generation without unstable cells, its size is the total number
+/// of topplings in between. Grains should be dropped slower than avalanches relax, otherwise
+/// consecutive avalanches are merged.
+pub struct AvalancheHistogram {
+    threshold: usize,
+    cur_size: usize,
+    histogram: Arc<RwLock<BTreeMap<usize, usize>>>,
+}
+
+impl AvalancheHistogram {
+    pub fn new(threshold: usize) -> Self {
+        Self {
+            threshold,
+            cur_size: 0,
+            histogram: Arc::new(RwLock::new(BTreeMap::new())),
+        }
+    }
+
+    /// Map from avalanche size to the number of avalanches with that size.
+    pub fn clone_read_only(&self) -> ReadOnlyLock<BTreeMap<usize, usize>> {
+        ReadOnlyLock::from(Arc::clone(&self.histogram))
+    }
+
+    fn finish_avalanche(&mut self) {
+        if self.cur_size > 0 {
+            *self
+                .histogram
+                .write()
+                .unwrap()
+                .entry(self.cur_size)
+                .or_insert(0) += 1;
+            self.cur_size = 0;
+        }
+    }
+}
+
+impl<T, CI> BoardCallbackWithStates<T, CI> for AvalancheHistogram
+where
+    T: Send + Sync + PrimInt + FromPrimitive,
+    CI: Send + Sync,
+{
+    fn execute(&mut self, states: &[IndexedDataOwned<CI, T>]) {
+        let threshold = T::from_usize(self.threshold).unwrap();
+        let unstable_count = states.par_iter().filter(|ele| ele.1 >= threshold).count();
+        if unstable_count == 0 {
+            self.finish_avalanche();
+        } else {
+            self.cur_size += unstable_count;
+        }
+    }
+
+    fn cleanup(&mut self) {
+        self.finish_avalanche();
+    }
+}
+
+#[cfg(test)]
+mod avalanche_histogram_test {
+    use crate::{AvalancheHistogram, BoardCallbackWithStates, GridPoint1D};
+
+    fn execute(callback: &mut AvalancheHistogram, states: Vec<u16>) {
+        let states: Vec<(GridPoint1D<i32>, u16)> = states
+            .into_iter()
+            .enumerate()
+            .map(|(i, ele)| (GridPoint1D::new(i as i32), ele))
+            .collect();
+        callback.execute(&states);
+    }
+
+    #[test]
+    fn avalanche_histogram_test_1() {
+        let mut callback = AvalancheHistogram::new(4);
+        let histogram = callback.clone_read_only();
+        execute(&mut callback, vec![0, 3, 1]);
+        execute(&mut callback, vec![4, 3, 5]);
+        execute(&mut callback, vec![1, 4, 1]);
+        execute(&mut callback, vec![2, 0, 2]);
+        execute(&mut callback, vec![2, 4, 2]);
+        execute(&mut callback, vec![2, 0, 2]);
+        execute(&mut callback, vec![3, 4, 3]);
+        BoardCallbackWithStates::<u16, GridPoint1D<i32>>::cleanup(&mut callback);
+        let res = histogram.try_read().unwrap();
+        assert_eq!(res.get(&3), Some(&1));
+        assert_eq!(res.get(&1), Some(&2));
+        assert_eq!(res.len(), 2);
+    }
+}
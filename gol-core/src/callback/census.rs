@@ -0,0 +1,90 @@
+use crate::util::sync_util::ReadOnlyLock;
+use crate::{BoardCallbackWithStates, Census, GridPoint2D, IndexedDataOwned, ObjectCensus};
+use num_traits::ToPrimitive;
+use rayon::prelude::*;
+use std::sync::{Arc, RwLock};
+
+/// Takes a census of the objects left on a two-state board when it finishes.
+pub struct CensusCallback<T> {
+    census: ObjectCensus,
+    dead_state: T,
+    cells: Vec<GridPoint2D<i64>>,
+    res: Arc<RwLock<Option<Census>>>,
+}
+
+impl<T> CensusCallback<T> {
+    pub fn new(census: ObjectCensus, dead_state: T) -> Self {
+        Self {
+            census,
+            dead_state,
+            cells: Vec::new(),
+            res: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Census of the last generation, `None` until the board finishes.
+    pub fn clone_read_only(&self) -> ReadOnlyLock<Option<Census>> {
+        ReadOnlyLock::from(Arc::clone(&self.res))
+    }
+}
+
+impl<T, U> BoardCallbackWithStates<T, GridPoint2D<U>> for CensusCallback<T>
+where
+    T: Send + Sync + PartialEq,
+    U: Send + Sync + ToPrimitive,
+{
+    fn execute(&mut self, states: &[IndexedDataOwned<GridPoint2D<U>, T>]) {
+        let dead_state = &self.dead_state;
+        self.cells = states
+            .par_iter()
+            .filter(|(_, state)| state != dead_state)
+            .map(|(idx, _)| GridPoint2D::new(idx.x.to_i64().unwrap(), idx.y.to_i64().unwrap()))
+            .collect();
+    }
+
+    fn cleanup(&mut self) {
+        let mut census = Census::new();
+        for object in self.census.objects(&self.cells) {
+            census.add(&object);
+        }
+        *self.res.write().unwrap() = Some(census);
+    }
+}
+
+#[cfg(test)]
+mod census_callback_test {
+    use crate::{
+        BoardCallbackWithStates, CensusCallback, DecayLifeLikeStrategy, GridPoint2D, ObjectCensus,
+    };
+
+    #[test]
+    fn census_callback_test_last_generation() {
+        let census = ObjectCensus::from_strategy(&DecayLifeLikeStrategy::gol()).unwrap();
+        let mut callback = CensusCallback::new(census, 0u8);
+        let res = callback.clone_read_only();
+        let states = |cells: &[(i32, i32)]| -> Vec<(GridPoint2D<i32>, u8)> {
+            cells
+                .iter()
+                .map(|(x, y)| (GridPoint2D::new(*x, *y), 1))
+                .chain(std::iter::once((GridPoint2D::new(9, 9), 0)))
+                .collect()
+        };
+        callback.execute(&states(&[(0, 0), (1, 0), (2, 0)]));
+        callback.execute(&states(&[
+            (1, -1),
+            (1, 0),
+            (1, 1),
+            (5, 5),
+            (5, 6),
+            (6, 5),
+            (6, 6),
+        ]));
+        assert!(res.try_read().unwrap().is_none());
+        BoardCallbackWithStates::<u8, GridPoint2D<i32>>::cleanup(&mut callback);
+        let census = res.try_read().unwrap();
+        let census = census.as_ref().unwrap();
+        assert_eq!(census.count("xp2_7"), 1);
+        assert_eq!(census.count("xs4_33"), 1);
+        assert_eq!(census.total(), 2);
+    }
+}
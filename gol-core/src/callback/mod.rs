@@ -1 +1,4 @@
+pub mod avalanche;
+pub mod census;
 pub mod model_states;
+pub mod spaceship;
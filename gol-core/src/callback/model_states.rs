@@ -49,6 +49,8 @@ where
 {
     trivial_state: T,
     non_trivial_lookup: Arc<RwLock<(usize, HashMap<CI, T>)>>,
+    /// Lookup of the generation before the shared one, refilled in place to avoid allocating.
+    spare_lookup: HashMap<CI, T>,
 }
 
 impl<CI, T> StatesCallback<CI, T>
@@ -59,6 +61,7 @@ where
         Self {
             trivial_state,
             non_trivial_lookup: Arc::new(RwLock::new((0, HashMap::new()))),
+            spare_lookup: HashMap::new(),
         }
     }
 
@@ -68,6 +71,12 @@ where
         *lookup_unlocked = (iter_count, lookup);
     }
 
+    fn swap_in_spare_lookup(&mut self) {
+        let mut lookup_unlocked = self.non_trivial_lookup.write().unwrap();
+        lookup_unlocked.0 += 1;
+        std::mem::swap(&mut lookup_unlocked.1, &mut self.spare_lookup);
+    }
+
     pub fn clone_read_only(&self) -> StatesReadOnly<CI, T>
     where
         T: Clone,
@@ -79,18 +88,21 @@ where
     }
 }
 
-impl<T, CI, I> BoardCallbackWithStates<T, CI, I> for StatesCallback<CI, T>
+impl<T, CI> BoardCallbackWithStates<T, CI> for StatesCallback<CI, T>
 where
-    T: Send + Sync + Clone + Eq,
+    T: Send + Sync + Clone + PartialEq,
     CI: Send + Sync + Clone + Eq + Hash,
-    I: ParallelIterator<Item = IndexedDataOwned<CI, T>>,
 {
-    fn execute(&mut self, states: I) {
-        let new_lookup: HashMap<CI, T> = states
-            .filter(|ele| &ele.1 != &self.trivial_state)
-            .map(|ele| (ele.0, ele.1))
-            .collect();
-        self.set_non_trivial_lookup(new_lookup);
+    fn execute(&mut self, states: &[IndexedDataOwned<CI, T>]) {
+        let trivial_state = &self.trivial_state;
+        self.spare_lookup.clear();
+        self.spare_lookup.par_extend(
+            states
+                .par_iter()
+                .filter(|ele| &ele.1 != trivial_state)
+                .cloned(),
+        );
+        self.swap_in_spare_lookup();
     }
 
     fn cleanup(&mut self) {
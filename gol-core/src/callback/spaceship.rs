@@ -0,0 +1,426 @@
+use crate::analysis::census::{normalize, Cell};
+use crate::util::grid_util::Shape2D;
+use crate::util::sync_util::ReadOnlyLock;
+use crate::{
+    BoardCallbackWithStates, CellEraser, GridPoint2D, IndexedDataOwned, ObjectCensus, ObjectKind,
+};
+use num_traits::{FromPrimitive, ToPrimitive};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::sync::{Arc, RwLock};
+
+/// Direction a spaceship travels in relative to the grid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpaceshipDirection {
+    Orthogonal,
+    Diagonal,
+    Oblique,
+}
+
+/// Spaceship found on the board.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Spaceship {
+    code: String,
+    name: Option<String>,
+    period: usize,
+    displacement: (i64, i64),
+    velocity: String,
+    direction: SpaceshipDirection,
+    first_seen: usize,
+    removed_at: Option<usize>,
+}
+
+impl Spaceship {
+    /// Code of the spaceship in the format of apgcodes, same as in a census.
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Distance moved in x and y after one period.
+    pub fn displacement(&self) -> (i64, i64) {
+        self.displacement
+    }
+
+    /// Speed as a fraction of the speed of light, e.g. `c/4` for gliders.
+    pub fn velocity(&self) -> &str {
+        &self.velocity
+    }
+
+    pub fn direction(&self) -> SpaceshipDirection {
+        self.direction
+    }
+
+    /// First generation the spaceship was on its own, one period before it was recognized.
+    pub fn first_seen(&self) -> usize {
+        self.first_seen
+    }
+
+    /// Generation the spaceship was removed at the edge of the board, if it was.
+    pub fn removed_at(&self) -> Option<usize> {
+        self.removed_at
+    }
+}
+
+/// Finds spaceships on two-state 2D grids by following islands of alive cells. An island that
+/// shows up again with the same shape somewhere else within the maximum period of the census is
+/// checked in isolation, and followed from then on until it hits another object.
+pub struct SpaceshipDetector<T, U> {
+    census: ObjectCensus,
+    dead_state: T,
+    generation: usize,
+    /// Origins of the untracked islands of the last generations by shape, the newest last.
+    history: VecDeque<HashMap<Vec<Cell>, Vec<Cell>>>,
+    /// Spaceships followed and their cells in the last generation.
+    tracked: Vec<(usize, HashSet<Cell>)>,
+    /// Cells of spaceships removed at the edge in the next generation, which the board still
+    /// evolves before the eraser kills them.
+    removing: Vec<HashSet<Cell>>,
+    edge: Option<(Shape2D, CellEraser<GridPoint2D<U>, T>)>,
+    spaceships: Arc<RwLock<Vec<Spaceship>>>,
+}
+
+impl<T, U> SpaceshipDetector<T, U> {
+    pub fn new(census: ObjectCensus, dead_state: T) -> Self {
+        Self {
+            census,
+            dead_state,
+            generation: 0,
+            history: VecDeque::new(),
+            tracked: Vec::new(),
+            removing: Vec::new(),
+            edge: None,
+            spaceships: Arc::new(RwLock::new(Vec::new())),
+        }
+    }
+
+    /// Removes spaceships reaching the edge of a board with the shape through the eraser of the
+    /// board, so they do not wrap around boards with `NeighborMooreDonut`.
+    pub fn with_edge_removal(self, shape: Shape2D, eraser: CellEraser<GridPoint2D<U>, T>) -> Self {
+        let mut res = self;
+        res.edge = Some((shape, eraser));
+        res
+    }
+
+    /// Spaceships found so far, in the order they were found.
+    pub fn clone_read_only(&self) -> ReadOnlyLock<Vec<Spaceship>> {
+        ReadOnlyLock::from(Arc::clone(&self.spaceships))
+    }
+
+    /// Smallest number of generations ago the shape was somewhere else, at most as far as light
+    /// travels.
+    fn translated_repeat(&self, shape: &[Cell], origin: Cell) -> Option<usize> {
+        (1..=self.history.len()).find(|period| {
+            let p = *period as i64;
+            match self.history[self.history.len() - period].get(shape) {
+                Some(origins) => origins.iter().any(|(x, y)| {
+                    let (dx, dy) = (origin.0 - x, origin.1 - y);
+                    (dx, dy) != (0, 0) && dx.abs() <= p && dy.abs() <= p
+                }),
+                None => false,
+            }
+        })
+    }
+
+    fn push_spaceship(&mut self, island: &[GridPoint2D<i64>], repeat: usize) -> Option<usize> {
+        let object = self.census.classify(island);
+        if object.kind() != ObjectKind::Spaceship {
+            return None;
+        }
+        let (dx, dy) = object.displacement();
+        let direction = if dx == 0 || dy == 0 {
+            SpaceshipDirection::Orthogonal
+        } else if dx.abs() == dy.abs() {
+            SpaceshipDirection::Diagonal
+        } else {
+            SpaceshipDirection::Oblique
+        };
+        let mut spaceships = self.spaceships.write().unwrap();
+        spaceships.push(Spaceship {
+            code: String::from(object.code()),
+            name: object.name().map(String::from),
+            period: object.period(),
+            displacement: (dx, dy),
+            velocity: velocity(dx.abs().max(dy.abs()) as usize, object.period()),
+            direction,
+            first_seen: self.generation - repeat,
+            removed_at: None,
+        });
+        Some(spaceships.len() - 1)
+    }
+}
+
+impl<T, U> SpaceshipDetector<T, U>
+where
+    T: Send + Sync + Clone,
+    U: Hash + Eq + FromPrimitive,
+{
+    /// Erases tracked spaceships within two cells of the edge two generations later, the
+    /// earliest the board can still erase cells in. Spaceships move at most one cell per
+    /// generation, so they do not wrap around before.
+    fn remove_at_edge(&mut self) {
+        let (shape, eraser) = match &self.edge {
+            Some(edge) => edge,
+            None => return,
+        };
+        let is_at_edge = |(x, y): &Cell| {
+            x - shape.x_idx_min() <= 2
+                || shape.x_idx_max() - x <= 2
+                || y - shape.y_idx_min() <= 2
+                || shape.y_idx_max() - y <= 2
+        };
+        let wrap = |(x, y): Cell| {
+            let width = shape.width() as i64;
+            let height = shape.height() as i64;
+            GridPoint2D::new(
+                U::from_i64((x - shape.x_idx_min()).rem_euclid(width) + shape.x_idx_min()).unwrap(),
+                U::from_i64((y - shape.y_idx_min()).rem_euclid(height) + shape.y_idx_min())
+                    .unwrap(),
+            )
+        };
+        let mut spaceships = self.spaceships.write().unwrap();
+        let census = &self.census;
+        let generation = self.generation;
+        let removing = &mut self.removing;
+        self.tracked.retain(|(id, cells)| {
+            if !cells.iter().any(is_at_edge) {
+                return true;
+            }
+            let next = census.step(cells);
+            let after_next = census.step(&next);
+            removing.push(next);
+            eraser.erase(generation + 2, after_next.into_iter().map(wrap));
+            spaceships[*id].removed_at = Some(generation + 2);
+            false
+        });
+    }
+}
+
+impl<T, U> BoardCallbackWithStates<T, GridPoint2D<U>> for SpaceshipDetector<T, U>
+where
+    T: Send + Sync + Clone + PartialEq,
+    U: Send + Sync + Hash + Eq + ToPrimitive + FromPrimitive,
+{
+    fn execute(&mut self, states: &[IndexedDataOwned<GridPoint2D<U>, T>]) {
+        let dead_state = &self.dead_state;
+        let cells: Vec<GridPoint2D<i64>> = states
+            .par_iter()
+            .filter(|(_, state)| state != dead_state)
+            .map(|(idx, _)| GridPoint2D::new(idx.x.to_i64().unwrap(), idx.y.to_i64().unwrap()))
+            .collect();
+        let islands = self.census.islands(&cells);
+        let island_ids: HashMap<Vec<Cell>, usize> = islands
+            .iter()
+            .enumerate()
+            .map(|(i, island)| (island.iter().map(|cell| (cell.x, cell.y)).collect(), i))
+            .collect();
+
+        // Tracked spaceships evolve exactly as predicted until they hit something.
+        let mut is_tracked = vec![false; islands.len()];
+        for cells in std::mem::take(&mut self.removing) {
+            let mut key: Vec<Cell> = cells.into_iter().collect();
+            key.sort_unstable();
+            if let Some(i) = island_ids.get(&key) {
+                is_tracked[*i] = true;
+            }
+        }
+        for (id, cells) in std::mem::take(&mut self.tracked) {
+            let next = self.census.step(&cells);
+            let mut key: Vec<Cell> = next.iter().cloned().collect();
+            key.sort_unstable();
+            if let Some(i) = island_ids.get(&key) {
+                is_tracked[*i] = true;
+                self.tracked.push((id, next));
+            }
+        }
+
+        let mut shapes: HashMap<Vec<Cell>, Vec<Cell>> = HashMap::new();
+        for (island, _) in islands
+            .iter()
+            .zip(is_tracked)
+            .filter(|(_, tracked)| !tracked)
+        {
+            let cells: HashSet<Cell> = island.iter().map(|cell| (cell.x, cell.y)).collect();
+            let (shape, origin) = normalize(&cells);
+            let id = self
+                .translated_repeat(&shape, origin)
+                .and_then(|repeat| self.push_spaceship(island, repeat));
+            match id {
+                Some(id) => self.tracked.push((id, cells)),
+                None => shapes.entry(shape).or_default().push(origin),
+            }
+        }
+        self.history.push_back(shapes);
+        if self.history.len() > self.census.max_period() {
+            self.history.pop_front();
+        }
+
+        self.remove_at_edge();
+        self.generation += 1;
+    }
+}
+
+/// Speed of `distance` cells per `period` generations in units of `c`, reduced.
+fn velocity(distance: usize, period: usize) -> String {
+    let gcd = gcd(distance, period);
+    match (distance / gcd, period / gcd) {
+        (1, 1) => String::from("c"),
+        (1, period) => format!("c/{}", period),
+        (distance, 1) => format!("{}c", distance),
+        (distance, period) => format!("{}c/{}", distance, period),
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[cfg(test)]
+mod spaceship_detector_test {
+    use super::{velocity, SpaceshipDirection};
+    use crate::util::grid_util::Shape2D;
+    use crate::{
+        Board, BoardCallback, CellEraser, DecayLifeLikeStrategy, GridPoint2D, ObjectCensus,
+        SpaceshipDetector, StandardBoard, StandardBoardFactory,
+    };
+    use std::collections::HashMap;
+
+    fn new_board(
+        shape: usize,
+        cells: &[(i32, i32)],
+        detector: SpaceshipDetector<u8, i32>,
+        is_donut: bool,
+    ) -> StandardBoard<u8, GridPoint2D<i32>, std::vec::IntoIter<GridPoint2D<i32>>> {
+        let initial_states: HashMap<GridPoint2D<i32>, u8> = cells
+            .iter()
+            .map(|(x, y)| (GridPoint2D::new(*x, *y), 1))
+            .collect();
+        StandardBoardFactory::new_standard_2d_grid(
+            (shape, shape),
+            0u8,
+            1,
+            initial_states,
+            Box::new(DecayLifeLikeStrategy::gol()),
+            vec![BoardCallback::WithStates(Box::new(detector))],
+            is_donut,
+        )
+    }
+
+    fn new_detector() -> SpaceshipDetector<u8, i32> {
+        SpaceshipDetector::new(
+            ObjectCensus::from_strategy(&DecayLifeLikeStrategy::gol()).unwrap(),
+            0u8,
+        )
+    }
+
+    #[test]
+    fn spaceship_detector_test_velocity() {
+        assert_eq!(velocity(1, 4), "c/4");
+        assert_eq!(velocity(2, 4), "c/2");
+        assert_eq!(velocity(1, 1), "c");
+        assert_eq!(velocity(2, 5), "2c/5");
+        assert_eq!(velocity(3, 1), "3c");
+    }
+
+    #[test]
+    fn spaceship_detector_test_glider_and_lwss() {
+        let detector = new_detector();
+        let spaceships = detector.clone_read_only();
+        // Glider, lightweight spaceship and a block.
+        let cells = [
+            (-10, 0),
+            (-9, 1),
+            (-11, 2),
+            (-10, 2),
+            (-9, 2),
+            (1, 10),
+            (4, 10),
+            (0, 11),
+            (0, 12),
+            (4, 12),
+            (0, 13),
+            (1, 13),
+            (2, 13),
+            (3, 13),
+            (-15, -15),
+            (-15, -14),
+            (-14, -15),
+            (-14, -14),
+        ];
+        let mut board = new_board(40, &cells, detector, false);
+        board.advance(Some(13));
+
+        let spaceships = spaceships.try_read().unwrap();
+        assert_eq!(spaceships.len(), 2);
+        let glider = spaceships
+            .iter()
+            .find(|ship| ship.name() == Some("glider"))
+            .unwrap();
+        assert_eq!(glider.code(), "xq4_153");
+        assert_eq!(glider.velocity(), "c/4");
+        assert_eq!(glider.direction(), SpaceshipDirection::Diagonal);
+        assert_eq!(glider.displacement(), (1, 1));
+        assert_eq!(glider.first_seen(), 0);
+        let lwss = spaceships
+            .iter()
+            .find(|ship| ship.name() == Some("lightweight spaceship"))
+            .unwrap();
+        assert_eq!(lwss.velocity(), "c/2");
+        assert_eq!(lwss.direction(), SpaceshipDirection::Orthogonal);
+        assert_eq!(lwss.removed_at(), None);
+    }
+
+    #[test]
+    fn spaceship_detector_test_edge_removal() {
+        let shape = 24;
+        let cells = [
+            (0, 0),
+            (1, 1),
+            (-1, 2),
+            (0, 2),
+            (1, 2),
+            (-8, -8),
+            (-8, -7),
+            (-7, -8),
+            (-7, -7),
+        ];
+        let run = || {
+            let eraser = CellEraser::new(0u8);
+            let detector =
+                new_detector().with_edge_removal(Shape2D::new(shape, shape), eraser.clone());
+            let spaceships = detector.clone_read_only();
+            let mut board = new_board(shape, &cells, detector, true).with_cell_eraser(eraser);
+            board.advance(Some(60));
+            let spaceships = spaceships.try_read().unwrap().clone();
+            let states = board.advance_one_generation();
+            let mut alive: Vec<_> = states
+                .iter()
+                .filter(|(_, state)| *state == 1)
+                .map(|(idx, _)| (idx.x, idx.y))
+                .collect();
+            alive.sort_unstable();
+            (spaceships, alive)
+        };
+
+        let (spaceships, alive) = run();
+        assert_eq!(spaceships.len(), 1);
+        assert!(spaceships[0].removed_at().is_some());
+        // Only the block is left.
+        assert_eq!(alive, vec![(-8, -8), (-8, -7), (-7, -8), (-7, -7)]);
+        // The spaceship is removed in the same generation every time.
+        assert_eq!(run(), (spaceships, alive));
+    }
+}
@@ -26,7 +26,9 @@ pub struct GridPoint3D<T> {
     pub z: T,
 }
 
-#[derive(Clone, Debug)]
+/// Serialized as a plain list of indices, one per dimension.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct GridPointND<T> {
     indices: Vec<T>,
 }
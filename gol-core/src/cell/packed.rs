@@ -0,0 +1,192 @@
+use crate::{GridPoint1D, GridPoint2D};
+use std::hash::Hash;
+
+/// Index packed into a single unsigned integer, cheaper to hash and compare than the index itself.
+/// Grid points are packed in Z-order, so cells close to each other get close keys.
+pub trait PackedIndex: Sized {
+    type Packed: Send + Sync + Copy + Ord + Hash;
+
+    fn pack(&self) -> Self::Packed;
+
+    fn unpack(packed: Self::Packed) -> Self;
+}
+
+impl PackedIndex for usize {
+    type Packed = usize;
+
+    fn pack(&self) -> Self::Packed {
+        *self
+    }
+
+    fn unpack(packed: Self::Packed) -> Self {
+        packed
+    }
+}
+
+impl PackedIndex for GridPoint1D<i32> {
+    type Packed = u32;
+
+    fn pack(&self) -> Self::Packed {
+        to_unsigned_32(self.x)
+    }
+
+    fn unpack(packed: Self::Packed) -> Self {
+        GridPoint1D::new(from_unsigned_32(packed))
+    }
+}
+
+impl PackedIndex for GridPoint1D<i64> {
+    type Packed = u64;
+
+    fn pack(&self) -> Self::Packed {
+        to_unsigned_64(self.x)
+    }
+
+    fn unpack(packed: Self::Packed) -> Self {
+        GridPoint1D::new(from_unsigned_64(packed))
+    }
+}
+
+impl PackedIndex for GridPoint2D<i32> {
+    type Packed = u64;
+
+    fn pack(&self) -> Self::Packed {
+        interleave_32(to_unsigned_32(self.x), to_unsigned_32(self.y))
+    }
+
+    fn unpack(packed: Self::Packed) -> Self {
+        let (x, y) = deinterleave_32(packed);
+        GridPoint2D::new(from_unsigned_32(x), from_unsigned_32(y))
+    }
+}
+
+impl PackedIndex for GridPoint2D<i64> {
+    type Packed = u128;
+
+    fn pack(&self) -> Self::Packed {
+        interleave_64(to_unsigned_64(self.x), to_unsigned_64(self.y))
+    }
+
+    fn unpack(packed: Self::Packed) -> Self {
+        let (x, y) = deinterleave_64(packed);
+        GridPoint2D::new(from_unsigned_64(x), from_unsigned_64(y))
+    }
+}
+
+/// Maps a signed coordinate to an unsigned one keeping the order.
+pub(crate) fn to_unsigned_32(val: i32) -> u32 {
+    (val as u32) ^ (1 << 31)
+}
+
+fn from_unsigned_32(val: u32) -> i32 {
+    (val ^ (1 << 31)) as i32
+}
+
+/// Maps a signed coordinate to an unsigned one keeping the order.
+pub(crate) fn to_unsigned_64(val: i64) -> u64 {
+    (val as u64) ^ (1 << 63)
+}
+
+fn from_unsigned_64(val: u64) -> i64 {
+    (val ^ (1 << 63)) as i64
+}
+
+/// Z-order code with the bits of `x` at the even positions and the ones of `y` at the odd ones.
+pub(crate) fn interleave_32(x: u32, y: u32) -> u64 {
+    spread_32(x) | (spread_32(y) << 1)
+}
+
+pub(crate) fn deinterleave_32(code: u64) -> (u32, u32) {
+    (compact_32(code), compact_32(code >> 1))
+}
+
+/// Z-order code with the bits of `x` at the even positions and the ones of `y` at the odd ones.
+pub(crate) fn interleave_64(x: u64, y: u64) -> u128 {
+    spread_64(x) | (spread_64(y) << 1)
+}
+
+pub(crate) fn deinterleave_64(code: u128) -> (u64, u64) {
+    (compact_64(code), compact_64(code >> 1))
+}
+
+/// Moves the bits of the value to the even bits.
+fn spread_32(val: u32) -> u64 {
+    let mut res = val as u64;
+    res = (res | (res << 16)) & 0x0000_FFFF_0000_FFFF;
+    res = (res | (res << 8)) & 0x00FF_00FF_00FF_00FF;
+    res = (res | (res << 4)) & 0x0F0F_0F0F_0F0F_0F0F;
+    res = (res | (res << 2)) & 0x3333_3333_3333_3333;
+    (res | (res << 1)) & 0x5555_5555_5555_5555
+}
+
+/// Reverse of `spread_32`, ignoring the odd bits.
+fn compact_32(code: u64) -> u32 {
+    let mut res = code & 0x5555_5555_5555_5555;
+    res = (res | (res >> 1)) & 0x3333_3333_3333_3333;
+    res = (res | (res >> 2)) & 0x0F0F_0F0F_0F0F_0F0F;
+    res = (res | (res >> 4)) & 0x00FF_00FF_00FF_00FF;
+    res = (res | (res >> 8)) & 0x0000_FFFF_0000_FFFF;
+    (res | (res >> 16)) as u32
+}
+
+/// Moves the bits of the value to the even bits.
+fn spread_64(val: u64) -> u128 {
+    let mut res = val as u128;
+    res = (res | (res << 32)) & 0x0000_0000_FFFF_FFFF_0000_0000_FFFF_FFFF;
+    res = (res | (res << 16)) & 0x0000_FFFF_0000_FFFF_0000_FFFF_0000_FFFF;
+    res = (res | (res << 8)) & 0x00FF_00FF_00FF_00FF_00FF_00FF_00FF_00FF;
+    res = (res | (res << 4)) & 0x0F0F_0F0F_0F0F_0F0F_0F0F_0F0F_0F0F_0F0F;
+    res = (res | (res << 2)) & 0x3333_3333_3333_3333_3333_3333_3333_3333;
+    (res | (res << 1)) & 0x5555_5555_5555_5555_5555_5555_5555_5555
+}
+
+/// Reverse of `spread_64`, ignoring the odd bits.
+fn compact_64(code: u128) -> u64 {
+    let mut res = code & 0x5555_5555_5555_5555_5555_5555_5555_5555;
+    res = (res | (res >> 1)) & 0x3333_3333_3333_3333_3333_3333_3333_3333;
+    res = (res | (res >> 2)) & 0x0F0F_0F0F_0F0F_0F0F_0F0F_0F0F_0F0F_0F0F;
+    res = (res | (res >> 4)) & 0x00FF_00FF_00FF_00FF_00FF_00FF_00FF_00FF;
+    res = (res | (res >> 8)) & 0x0000_FFFF_0000_FFFF_0000_FFFF_0000_FFFF;
+    res = (res | (res >> 16)) & 0x0000_0000_FFFF_FFFF_0000_0000_FFFF_FFFF;
+    (res | (res >> 32)) as u64
+}
+
+#[cfg(test)]
+mod packed_index_test {
+    use crate::{GridPoint1D, GridPoint2D, PackedIndex};
+
+    #[test]
+    fn packed_index_test_round_trip() {
+        let values_32 = [0, 1, -1, 12345, -98765, i32::MAX, i32::MIN];
+        for x in values_32.iter() {
+            let idx = GridPoint1D::new(*x);
+            assert_eq!(GridPoint1D::unpack(idx.pack()), idx);
+            for y in values_32.iter() {
+                let idx = GridPoint2D::new(*x, *y);
+                assert_eq!(GridPoint2D::unpack(idx.pack()), idx);
+            }
+        }
+
+        let values_64 = [0, 1, -1, 1 << 40, -(1 << 50) + 3, i64::MAX, i64::MIN];
+        for x in values_64.iter() {
+            let idx = GridPoint1D::new(*x);
+            assert_eq!(GridPoint1D::unpack(idx.pack()), idx);
+            for y in values_64.iter() {
+                let idx = GridPoint2D::new(*x, *y);
+                assert_eq!(GridPoint2D::unpack(idx.pack()), idx);
+            }
+        }
+    }
+
+    #[test]
+    fn packed_index_test_z_order() {
+        // Every aligned 2x2 block gets 4 consecutive keys, x changing first.
+        let base = GridPoint2D::new(-8i64, 6).pack();
+        assert_eq!(GridPoint2D::new(-7i64, 6).pack(), base + 1);
+        assert_eq!(GridPoint2D::new(-8i64, 7).pack(), base + 2);
+        assert_eq!(GridPoint2D::new(-7i64, 7).pack(), base + 3);
+
+        assert!(GridPoint1D::new(-3i32).pack() < GridPoint1D::new(2i32).pack());
+        assert!(GridPoint2D::new(-1i32, -1).pack() < GridPoint2D::new(0i32, 0).pack());
+    }
+}
@@ -0,0 +1,354 @@
+use super::protocol::{receive, send, Command, Hello, Setup, StripPartition};
+use crate::{
+    BoardCallback, BoardCallbackManager, GenerationBuffers, GenerationStates, GridPoint2D,
+    IndexedDataOwned,
+};
+use num_traits::{FromPrimitive, PrimInt, ToPrimitive};
+use rayon::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::io;
+use std::net::{TcpListener, TcpStream};
+use std::time::{Duration, Instant};
+
+/// Time workers have to connect to the coordinator.
+const ACCEPT_TIMEOUT: Duration = Duration::from_secs(30);
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// 2D grid split into strips evolved by `DistributedWorker`s in other processes. The coordinator
+/// only tells the workers when to advance, and collects the states for the callbacks.
+pub struct DistributedBoard<T, U>
+where
+    T: Send + Sync,
+    U: Send + Sync,
+{
+    workers: Vec<TcpStream>,
+    partition: StripPartition,
+    default_state: T,
+    initial_states: HashMap<GridPoint2D<U>, T>,
+    callback_manager: BoardCallbackManager<T, GridPoint2D<U>>,
+    has_callbacks: bool,
+    generation_buffers: GenerationBuffers<GridPoint2D<U>, T>,
+}
+
+impl<T, U> DistributedBoard<T, U>
+where
+    T: 'static + Send + Sync + Clone + PartialEq + Serialize + DeserializeOwned,
+    U: 'static
+        + Send
+        + Sync
+        + PrimInt
+        + FromPrimitive
+        + ToPrimitive
+        + Hash
+        + Serialize
+        + DeserializeOwned,
+{
+    /// Waits for one worker per strip to connect to the listener, sends all of them the payload
+    /// and assigns the strips in the order they connected. Fails if they do not connect within
+    /// `ACCEPT_TIMEOUT`, or as soon as `check_workers` does while waiting, e.g. because a worker
+    /// process exited.
+    pub fn new<F>(
+        listener: &TcpListener,
+        partition: StripPartition,
+        payload: &[u8],
+        default_state: T,
+        initial_states: HashMap<GridPoint2D<U>, T>,
+        callbacks: Vec<BoardCallback<T, GridPoint2D<U>>>,
+        check_workers: F,
+    ) -> io::Result<Self>
+    where
+        F: FnMut() -> io::Result<()>,
+    {
+        let strip_count = partition.strips().len();
+        let mut workers = Vec::with_capacity(strip_count);
+        let mut halo_addrs = Vec::with_capacity(strip_count);
+        let mut check_workers = check_workers;
+        let deadline = Instant::now() + ACCEPT_TIMEOUT;
+        listener.set_nonblocking(true)?;
+        for _ in 0..strip_count {
+            let stream = loop {
+                match listener.accept() {
+                    Ok((stream, _)) => break stream,
+                    Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                        check_workers()?;
+                        if Instant::now() >= deadline {
+                            return Err(io::Error::new(
+                                io::ErrorKind::TimedOut,
+                                "Workers did not connect in time.",
+                            ));
+                        }
+                        std::thread::sleep(ACCEPT_POLL_INTERVAL);
+                    }
+                    Err(err) => return Err(err),
+                }
+            };
+            stream.set_nonblocking(false)?;
+            stream.set_nodelay(true)?;
+            let hello: Hello = receive(&stream)?;
+            halo_addrs.push(hello.halo_addr);
+            workers.push(stream);
+        }
+        for worker in workers.iter() {
+            send(worker, payload)?;
+        }
+
+        let mut strip_states = vec![Vec::new(); strip_count];
+        for (idx, state) in initial_states.iter() {
+            let x = idx.x.to_i64().unwrap();
+            let (x_min, x_max) = (partition.shape().x_idx_min(), partition.shape().x_idx_max());
+            if state != &default_state && x >= x_min && x <= x_max {
+                strip_states[partition.strip_of(x)].push((idx.clone(), state.clone()));
+            }
+        }
+        for (strip, (worker, initial_states)) in workers.iter().zip(strip_states).enumerate() {
+            send(
+                worker,
+                &Setup {
+                    strip,
+                    partition: partition.clone(),
+                    halo_addrs: halo_addrs.clone(),
+                    default_state: default_state.clone(),
+                    initial_states,
+                },
+            )?;
+        }
+
+        Ok(Self {
+            workers,
+            partition,
+            default_state,
+            initial_states,
+            has_callbacks: !callbacks.is_empty(),
+            callback_manager: BoardCallbackManager::new(callbacks),
+            generation_buffers: GenerationBuffers::new(),
+        })
+    }
+
+    pub fn partition(&self) -> &StripPartition {
+        &self.partition
+    }
+
+    /// Same as `Board::advance`. States are only sent back to the coordinator if there are
+    /// callbacks.
+    pub fn advance(&mut self, max_iter: Option<usize>) -> io::Result<()> {
+        let mut cur_iter = 0usize;
+        self.callback_manager.setup_all();
+        if self.has_callbacks {
+            let initial_states = self.initial_generation();
+            self.callback_manager.call(initial_states);
+        }
+
+        loop {
+            let next_states = self.step(self.has_callbacks)?;
+            if let Some(next_states) = next_states {
+                self.callback_manager.call(next_states);
+            }
+
+            cur_iter += 1;
+            match max_iter {
+                Some(val) => {
+                    if cur_iter >= val - 1 {
+                        break;
+                    }
+                }
+                None => continue,
+            }
+        }
+        self.callback_manager.cleanup_all();
+        Ok(())
+    }
+
+    /// Evolves all strips by one generation and returns the states of the whole board, in the same
+    /// order as a `Grid` of the same shape.
+    pub fn advance_one_generation(&mut self) -> io::Result<GenerationStates<GridPoint2D<U>, T>> {
+        Ok(self.step(true)?.unwrap())
+    }
+
+    fn step(&mut self, report: bool) -> io::Result<Option<GenerationStates<GridPoint2D<U>, T>>> {
+        for worker in self.workers.iter() {
+            send(worker, &Command::Step { report })?;
+        }
+        let mut res = self.generation_buffers.take_spare();
+        // Strips are sent back in order, so the columns line up like the ones of a `Grid`.
+        for worker in self.workers.iter() {
+            let states: Option<Vec<IndexedDataOwned<GridPoint2D<U>, T>>> = receive(worker)?;
+            if let Some(states) = states {
+                res.extend(states);
+            }
+        }
+        Ok(if report {
+            Some(self.generation_buffers.push(res))
+        } else {
+            None
+        })
+    }
+
+    fn initial_generation(&mut self) -> GenerationStates<GridPoint2D<U>, T> {
+        let mut res = self.generation_buffers.take_spare();
+        let (initial_states, default_state) = (&self.initial_states, &self.default_state);
+        for strip in 0..self.partition.strips().len() {
+            let indices: Vec<GridPoint2D<U>> = self.partition.indices(strip);
+            res.par_extend(indices.into_par_iter().map(|idx| {
+                let state = match initial_states.get(&idx) {
+                    Some(state) => state.clone(),
+                    None => default_state.clone(),
+                };
+                (idx, state)
+            }));
+        }
+        self.generation_buffers.push(res)
+    }
+}
+
+impl<T, U> Drop for DistributedBoard<T, U>
+where
+    T: Send + Sync,
+    U: Send + Sync,
+{
+    fn drop(&mut self) {
+        // Workers of boards dropped halfway through a generation already closed or will fail.
+        for worker in self.workers.iter() {
+            let _ = send(worker, &Command::Stop);
+        }
+    }
+}
+
+#[cfg(test)]
+mod distributed_board_test {
+    use crate::{
+        util::grid_util::Shape2D, Board, BoardNeighborManager, DecayLifeLikeStrategy,
+        DistributedBoard, DistributedWorker, Grid, GridFactory, GridPoint2D, NeighborMoore,
+        NeighborMooreDonut, NeighborsGridSurround, SharedStrategyManager, SparseStates,
+        StandardBoard, StripPartition,
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::collections::{HashMap, HashSet};
+    use std::net::TcpListener;
+    use std::{io, thread};
+
+    type Point = GridPoint2D<i64>;
+
+    fn random_states(width: usize, height: usize) -> HashMap<Point, u8> {
+        let mut rng = StdRng::seed_from_u64(17);
+        let (x_min, y_min) = (-(width as i64) / 2, -(height as i64) / 2);
+        (0..width as i64)
+            .flat_map(|x| (0..height as i64).map(move |y| (x, y)))
+            .filter(|_| rng.gen_bool(0.35))
+            .map(|(x, y)| (GridPoint2D::new(x_min + x, y_min + y), 1))
+            .collect()
+    }
+
+    /// Runs the workers in threads, they only talk to each other over sockets like processes.
+    fn compare_with_standard(shape: Shape2D, margin: usize, is_donut: bool, worker_count: usize) {
+        let initial_states = random_states(shape.width(), shape.height());
+        let donut_shape = shape.clone();
+        let new_neighbor =
+            move || -> Box<dyn BoardNeighborManager<Point, std::vec::IntoIter<Point>>> {
+                match (margin, is_donut) {
+                    (1, true) => Box::new(NeighborMooreDonut::new(donut_shape.clone())),
+                    (1, false) => Box::new(NeighborMoore::new()),
+                    (_, false) => Box::new(NeighborsGridSurround::new(margin)),
+                    _ => panic!("Unsupported neighbors."),
+                }
+            };
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let handles: Vec<_> = (0..worker_count)
+            .map(|_| {
+                let new_neighbor = new_neighbor.clone();
+                thread::spawn(move || {
+                    let worker = DistributedWorker::connect(addr).unwrap();
+                    assert_eq!(worker.payload(), b"gol");
+                    worker
+                        .run::<u8, i64>(
+                            new_neighbor(),
+                            Box::new(SharedStrategyManager::new(Box::new(
+                                DecayLifeLikeStrategy::gol(),
+                            ))),
+                        )
+                        .unwrap();
+                })
+            })
+            .collect();
+
+        let partition = StripPartition::new(shape.clone(), is_donut, margin, worker_count).unwrap();
+        let mut distributed = DistributedBoard::new(
+            &listener,
+            partition,
+            b"gol",
+            0u8,
+            initial_states.clone(),
+            Vec::new(),
+            || Ok(()),
+        )
+        .unwrap();
+        let mut standard = StandardBoard::new(
+            Box::new(Grid::<Point>::new(
+                vec![shape.width(), shape.height()].into_iter(),
+            )),
+            new_neighbor(),
+            Box::new(SparseStates::new(0u8, initial_states)),
+            Box::new(SharedStrategyManager::new(Box::new(
+                DecayLifeLikeStrategy::gol(),
+            ))),
+            Vec::new(),
+        );
+        for _ in 0..15 {
+            let expected = standard.advance_one_generation();
+            let res = distributed.advance_one_generation().unwrap();
+            assert_eq!(res.as_slice(), expected.as_slice());
+        }
+        drop(distributed);
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn distributed_board_test_bounded() {
+        compare_with_standard(Shape2D::new(31, 20), 1, false, 3);
+    }
+
+    #[test]
+    fn distributed_board_test_donut() {
+        compare_with_standard(Shape2D::new(24, 17), 1, true, 2);
+        compare_with_standard(Shape2D::new(24, 17), 1, true, 1);
+    }
+
+    #[test]
+    fn distributed_board_test_wide_halo() {
+        compare_with_standard(Shape2D::new(40, 12), 3, false, 4);
+    }
+
+    #[test]
+    fn distributed_board_test_worker_exited() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let partition = StripPartition::new(Shape2D::new(10, 3), false, 1, 2).unwrap();
+        let res = DistributedBoard::new(
+            &listener,
+            partition,
+            b"gol",
+            0u8,
+            HashMap::<Point, u8>::new(),
+            Vec::new(),
+            || Err(io::Error::other("Worker exited.")),
+        );
+        assert_eq!(res.err().unwrap().to_string(), "Worker exited.");
+    }
+
+    #[test]
+    fn distributed_board_test_partition() {
+        let partition = StripPartition::new(Shape2D::new(10, 3), true, 2, 3).unwrap();
+        assert_eq!(partition.strips(), &[(-5, -3), (-2, 0), (1, 4)]);
+        assert_eq!(partition.strip_of(-3), 0);
+        assert_eq!(partition.strip_of(1), 2);
+        assert_eq!(partition.neighbors(0), (Some(2), Some(1)));
+        assert!(StripPartition::new(Shape2D::new(10, 3), false, 4, 3).is_err());
+        assert!(StripPartition::new(Shape2D::new(10, 3), false, 1, 11).is_err());
+
+        let indices: HashSet<Point> = (0..3).flat_map(|i| partition.indices(i)).collect();
+        assert_eq!(indices.len(), 30);
+    }
+}
@@ -0,0 +1,168 @@
+use crate::{util::grid_util::Shape2D, GridPoint2D, IndexedDataOwned};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+
+/// Split of a 2D grid into strips of whole columns, one per worker, from the smallest x to the
+/// largest.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StripPartition {
+    shape: Shape2D,
+    is_donut: bool,
+    halo: usize,
+    strips: Vec<(i64, i64)>,
+}
+
+impl StripPartition {
+    /// Splits the board into `strip_count` strips of about the same width. `halo` is the number of
+    /// columns on each side a cell needs to evolve, the neighbors across the edges of donut boards
+    /// are on the strips at the other end. Strips narrower than the halo are not supported.
+    pub fn new(
+        shape: Shape2D,
+        is_donut: bool,
+        halo: usize,
+        strip_count: usize,
+    ) -> io::Result<Self> {
+        if strip_count == 0 || strip_count > shape.width() {
+            return Err(invalid_input(format!(
+                "Cannot split {} columns into {} strips.",
+                shape.width(),
+                strip_count
+            )));
+        }
+        if strip_count > 1 && shape.width() / strip_count < halo {
+            return Err(invalid_input(format!(
+                "Strips of {} columns are narrower than the halo of {} columns.",
+                shape.width() / strip_count,
+                halo
+            )));
+        }
+        let (width, x_min) = (shape.width() as i64, shape.x_idx_min());
+        let count = strip_count as i64;
+        let strips = (0..count)
+            .map(|i| {
+                (
+                    x_min + width * i / count,
+                    x_min + width * (i + 1) / count - 1,
+                )
+            })
+            .collect();
+        Ok(Self {
+            shape,
+            is_donut,
+            halo,
+            strips,
+        })
+    }
+
+    pub fn shape(&self) -> &Shape2D {
+        &self.shape
+    }
+
+    pub fn is_donut(&self) -> bool {
+        self.is_donut
+    }
+
+    pub fn halo(&self) -> usize {
+        self.halo
+    }
+
+    /// Smallest and largest x of each strip, both included.
+    pub fn strips(&self) -> &[(i64, i64)] {
+        &self.strips
+    }
+
+    /// Strip holding the column.
+    pub fn strip_of(&self, x: i64) -> usize {
+        self.strips.partition_point(|strip| strip.1 < x)
+    }
+
+    /// Strips sending their columns to the strip, on the smaller and the larger x side.
+    pub fn neighbors(&self, strip: usize) -> (Option<usize>, Option<usize>) {
+        let count = self.strips.len();
+        if self.is_donut {
+            (Some((strip + count - 1) % count), Some((strip + 1) % count))
+        } else {
+            (
+                if strip > 0 { Some(strip - 1) } else { None },
+                if strip + 1 < count {
+                    Some(strip + 1)
+                } else {
+                    None
+                },
+            )
+        }
+    }
+
+    /// Cells of the strip, x changing slower than y like `Grid`.
+    pub fn indices<U>(&self, strip: usize) -> Vec<GridPoint2D<U>>
+    where
+        U: num_traits::FromPrimitive,
+    {
+        let (x_min, x_max) = self.strips[strip];
+        let (y_min, y_max) = (self.shape.y_idx_min(), self.shape.y_idx_max());
+        (x_min..=x_max)
+            .flat_map(|x| {
+                (y_min..=y_max).map(move |y| {
+                    GridPoint2D::new(U::from_i64(x).unwrap(), U::from_i64(y).unwrap())
+                })
+            })
+            .collect()
+    }
+}
+
+/// First message from a worker, with the address other workers send halos to.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Hello {
+    pub halo_addr: String,
+}
+
+/// Sent to each worker after all workers connected and the payload was sent.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Setup<U, T> {
+    pub strip: usize,
+    pub partition: StripPartition,
+    pub halo_addrs: Vec<String>,
+    pub default_state: T,
+    /// Non-default cells of the strip.
+    pub initial_states: Vec<IndexedDataOwned<GridPoint2D<U>, T>>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) enum Command {
+    /// Evolves one generation, workers answer with the states of their strip if `report` is set.
+    Step {
+        report: bool,
+    },
+    Stop,
+}
+
+/// Writes a message prefixed by its length.
+pub(crate) fn send<M>(mut stream: &TcpStream, message: &M) -> io::Result<()>
+where
+    M: Serialize + ?Sized,
+{
+    let bytes = bincode::serialize(message).map_err(invalid_data)?;
+    stream.write_all(&(bytes.len() as u64).to_le_bytes())?;
+    stream.write_all(&bytes)?;
+    stream.flush()
+}
+
+pub(crate) fn receive<M>(mut stream: &TcpStream) -> io::Result<M>
+where
+    M: DeserializeOwned,
+{
+    let mut len = [0u8; 8];
+    stream.read_exact(&mut len)?;
+    let mut bytes = vec![0u8; u64::from_le_bytes(len) as usize];
+    stream.read_exact(&mut bytes)?;
+    bincode::deserialize(&bytes).map_err(invalid_data)
+}
+
+fn invalid_input(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+fn invalid_data(err: bincode::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
@@ -0,0 +1,185 @@
+use super::protocol::{receive, send, Command, Hello, Setup};
+use crate::{
+    BoardNeighborManager, BoardStateManager, BoardStrategyManager, GridPoint2D, IndexedDataOwned,
+    SparseStates,
+};
+use num_traits::{FromPrimitive, PrimInt};
+use rayon::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::io;
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::thread;
+
+type NeighborManager<U> =
+    Box<dyn BoardNeighborManager<GridPoint2D<U>, std::vec::IntoIter<GridPoint2D<U>>>>;
+
+type StrategyManager<U, T> = Box<
+    dyn BoardStrategyManager<
+        GridPoint2D<U>,
+        T,
+        std::vec::IntoIter<IndexedDataOwned<GridPoint2D<U>, T>>,
+    >,
+>;
+
+/// Process evolving one strip of a `DistributedBoard`, swapping the columns at the edges of its
+/// strip with the workers of the strips next to it every generation.
+pub struct DistributedWorker {
+    coordinator: TcpStream,
+    halo_listener: TcpListener,
+    payload: Vec<u8>,
+}
+
+impl DistributedWorker {
+    /// Connects to the coordinator and waits until all workers joined.
+    pub fn connect<A: ToSocketAddrs>(coordinator_addr: A) -> io::Result<Self> {
+        let coordinator = TcpStream::connect(coordinator_addr)?;
+        coordinator.set_nodelay(true)?;
+        // Other workers reach this one on the interface it reaches the coordinator with.
+        let halo_listener = TcpListener::bind(SocketAddr::new(coordinator.local_addr()?.ip(), 0))?;
+        send(
+            &coordinator,
+            &Hello {
+                halo_addr: halo_listener.local_addr()?.to_string(),
+            },
+        )?;
+        let payload = receive(&coordinator)?;
+        Ok(Self {
+            coordinator,
+            halo_listener,
+            payload,
+        })
+    }
+
+    /// Bytes the coordinator sent to all workers, describing how to build their neighbor and
+    /// strategy managers.
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// Evolves the strip until the coordinator stops. The managers must match the ones the
+    /// coordinator expects, and strategies must only depend on the cell and its neighbors.
+    pub fn run<T, U>(
+        self,
+        neighbor_manager: NeighborManager<U>,
+        strategy_manager: StrategyManager<U, T>,
+    ) -> io::Result<()>
+    where
+        T: 'static + Send + Sync + Clone + PartialEq + Serialize + DeserializeOwned,
+        U: 'static + Send + Sync + PrimInt + FromPrimitive + Hash + Serialize + DeserializeOwned,
+    {
+        let Setup {
+            strip,
+            partition,
+            halo_addrs,
+            default_state,
+            initial_states,
+        }: Setup<U, T> = receive(&self.coordinator)?;
+        let (lower, upper) = partition.neighbors(strip);
+        // Connecting only needs the listener of the other side, accept after that.
+        let upper = match upper {
+            Some(other) => Some(connect_halo(&halo_addrs[other])?),
+            None => None,
+        };
+        let lower = match lower {
+            Some(_) => Some(self.halo_listener.accept()?.0),
+            None => None,
+        };
+        if let Some(stream) = &lower {
+            stream.set_nodelay(true)?;
+        }
+
+        let indices: Vec<GridPoint2D<U>> = partition.indices(strip);
+        let halo_len = partition.halo() * partition.shape().height();
+        let mut states = SparseStates::new(default_state.clone(), HashMap::new());
+        let initial_lookup: HashMap<GridPoint2D<U>, T> = initial_states.into_iter().collect();
+        let mut own: Vec<IndexedDataOwned<GridPoint2D<U>, T>> = indices
+            .par_iter()
+            .map(|idx| {
+                let state = match initial_lookup.get(idx) {
+                    Some(state) => state.clone(),
+                    None => default_state.clone(),
+                };
+                (idx.clone(), state)
+            })
+            .collect();
+        let mut cells = exchange_halos(&own, halo_len, &lower, &upper)?;
+        states.update_cell_states(&cells);
+
+        loop {
+            match receive(&self.coordinator)? {
+                Command::Step { report } => {
+                    let next: Vec<IndexedDataOwned<GridPoint2D<U>, T>> = indices
+                        .par_iter()
+                        .map(|idx| {
+                            let neighbors: Vec<IndexedDataOwned<GridPoint2D<U>, T>> =
+                                neighbor_manager
+                                    .get_neighbors_idx(idx)
+                                    .map(|neighbor| {
+                                        let state = states.get_cell_state(&neighbor);
+                                        (neighbor, state)
+                                    })
+                                    .collect();
+                            let state = strategy_manager
+                                .get_strategy_at_index(idx.clone())
+                                .next_state_with_previous(
+                                    idx.clone(),
+                                    states.get_cell_state(idx),
+                                    states.get_cell_previous_state(idx),
+                                    neighbors.into_iter(),
+                                );
+                            (idx.clone(), state)
+                        })
+                        .collect();
+                    own = next;
+                    cells = exchange_halos(&own, halo_len, &lower, &upper)?;
+                    states.update_cell_states(&cells);
+                    send(&self.coordinator, &if report { Some(&own) } else { None })?;
+                }
+                Command::Stop => return Ok(()),
+            }
+        }
+    }
+}
+
+fn connect_halo(addr: &str) -> io::Result<TcpStream> {
+    let res = TcpStream::connect(addr)?;
+    res.set_nodelay(true)?;
+    Ok(res)
+}
+
+/// Sends the columns at both edges of the strip to the workers next to it and returns the cells of
+/// the strip followed by the columns received from them.
+fn exchange_halos<CI, T>(
+    own: &[IndexedDataOwned<CI, T>],
+    halo_len: usize,
+    lower: &Option<TcpStream>,
+    upper: &Option<TcpStream>,
+) -> io::Result<Vec<IndexedDataOwned<CI, T>>>
+where
+    CI: Send + Sync + Clone + Serialize + DeserializeOwned,
+    T: Send + Sync + Clone + Serialize + DeserializeOwned,
+{
+    let halo_len = halo_len.min(own.len());
+    // Both sides send before they receive, so sending runs separately to never block on full
+    // socket buffers.
+    thread::scope(|scope| {
+        let sender = scope.spawn(|| -> io::Result<()> {
+            if let Some(stream) = lower {
+                send(stream, &own[..halo_len])?;
+            }
+            if let Some(stream) = upper {
+                send(stream, &own[own.len() - halo_len..])?;
+            }
+            Ok(())
+        });
+        let mut res = own.to_vec();
+        for stream in [lower, upper].iter().filter_map(|stream| stream.as_ref()) {
+            let halo: Vec<IndexedDataOwned<CI, T>> = receive(stream)?;
+            res.extend(halo);
+        }
+        sender.join().unwrap()?;
+        Ok(res)
+    })
+}
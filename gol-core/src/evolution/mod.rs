@@ -1,4 +1,9 @@
 pub mod strategy;
 pub mod strategy_discrete;
+pub mod strategy_gray_scott;
+pub mod strategy_lattice_gas;
 pub mod strategy_life_like;
 pub mod strategy_manager;
+pub mod strategy_sandpile;
+pub mod strategy_second_order;
+pub mod strategy_weighted_life_like;
@@ -1,8 +1,32 @@
-use crate::IndexedDataOwned;
+use crate::{IndexedDataOwned, TwoStateRule};
 
 pub trait EvolutionStrategy<CI, T, I>: Send + Sync
 where
     I: Iterator<Item = IndexedDataOwned<CI, T>>,
 {
     fn next_state(&self, idx: CI, cur_state: T, neighbors: I) -> T;
+
+    /// Same as `next_state`, but also receives the state of the cell in the previous generation.
+    /// Most strategies only depend on the current generation and do not need to override this.
+    fn next_state_with_previous(
+        &self,
+        idx: CI,
+        cur_state: T,
+        _previous_state: T,
+        neighbors: I,
+    ) -> T {
+        self.next_state(idx, cur_state, neighbors)
+    }
+
+    /// Whether `next_state_with_previous` depends on the previous state. Boards that skip cells
+    /// whose neighborhood did not change in the last generation cannot evolve such strategies.
+    fn uses_previous_state(&self) -> bool {
+        false
+    }
+
+    /// The strategy as a two-state outer-totalistic rule, if it is one. Boards use it to count
+    /// neighbors of many cells at once.
+    fn two_state_rule(&self) -> Option<TwoStateRule<T>> {
+        None
+    }
 }
@@ -0,0 +1,190 @@
+use crate::neighbors::util::PointPrimInt;
+use crate::{EvolutionStrategy, GridPoint2D, IndexedDataOwned, WeightedStencil2D};
+use serde::{Deserialize, Serialize};
+
+/// Concentrations of the two chemicals in a Gray-Scott reaction-diffusion system.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GrayScottState {
+    pub u: f64,
+    pub v: f64,
+}
+
+impl GrayScottState {
+    pub fn new(u: f64, v: f64) -> Self {
+        Self { u, v }
+    }
+}
+
+impl Default for GrayScottState {
+    /// Fully filled with U and no V, the trivial state of the system.
+    fn default() -> Self {
+        Self::new(1., 0.)
+    }
+}
+
+/// Named parameter sets that produce well known patterns.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GrayScottPreset {
+    Spots,
+    Stripes,
+    Mitosis,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GrayScottParams {
+    pub feed: f64,
+    pub kill: f64,
+    pub diffusion_u: f64,
+    pub diffusion_v: f64,
+}
+
+impl GrayScottParams {
+    pub fn new(feed: f64, kill: f64, diffusion_u: f64, diffusion_v: f64) -> Self {
+        Self {
+            feed,
+            kill,
+            diffusion_u,
+            diffusion_v,
+        }
+    }
+
+    pub fn from_preset(preset: GrayScottPreset) -> Self {
+        match preset {
+            GrayScottPreset::Spots => Self::spots(),
+            GrayScottPreset::Stripes => Self::stripes(),
+            GrayScottPreset::Mitosis => Self::mitosis(),
+        }
+    }
+
+    pub fn spots() -> Self {
+        Self::new(0.03, 0.062, 1., 0.5)
+    }
+
+    pub fn stripes() -> Self {
+        Self::new(0.055, 0.062, 1., 0.5)
+    }
+
+    pub fn mitosis() -> Self {
+        Self::new(0.0367, 0.0649, 1., 0.5)
+    }
+}
+
+/// Gray-Scott reaction-diffusion, the Laplacian of each concentration is the weighted sum given by
+/// the stencil, so the board should use the same stencil as its neighbor manager.
+pub struct GrayScottStrategy {
+    params: GrayScottParams,
+    delta_t: f64,
+    stencil: WeightedStencil2D,
+}
+
+impl GrayScottStrategy {
+    pub fn new(params: GrayScottParams, stencil: WeightedStencil2D) -> Self {
+        Self {
+            params,
+            delta_t: 1.,
+            stencil,
+        }
+    }
+
+    pub fn with_delta_t(self, delta_t: f64) -> Self {
+        let mut res = self;
+        res.delta_t = delta_t;
+        res
+    }
+
+    pub fn params(&self) -> &GrayScottParams {
+        &self.params
+    }
+
+    pub fn delta_t(&self) -> f64 {
+        self.delta_t
+    }
+}
+
+impl<U, I> EvolutionStrategy<GridPoint2D<U>, GrayScottState, I> for GrayScottStrategy
+where
+    U: PointPrimInt,
+    I: Iterator<Item = IndexedDataOwned<GridPoint2D<U>, GrayScottState>>,
+{
+    fn next_state(
+        &self,
+        idx: GridPoint2D<U>,
+        cur_state: GrayScottState,
+        neighbors: I,
+    ) -> GrayScottState {
+        let center_weight = self.stencil.center_weight();
+        let (mut laplacian_u, mut laplacian_v) =
+            (center_weight * cur_state.u, center_weight * cur_state.v);
+        for (neighbor_idx, state) in neighbors {
+            let weight = self.stencil.weight_of(&idx, &neighbor_idx);
+            laplacian_u += weight * state.u;
+            laplacian_v += weight * state.v;
+        }
+
+        let GrayScottState { u, v } = cur_state;
+        let GrayScottParams {
+            feed,
+            kill,
+            diffusion_u,
+            diffusion_v,
+        } = self.params;
+        let reaction = u * v * v;
+        let next_u = u + self.delta_t * (diffusion_u * laplacian_u - reaction + feed * (1. - u));
+        let next_v = v + self.delta_t * (diffusion_v * laplacian_v + reaction - (feed + kill) * v);
+        GrayScottState::new(next_u.clamp(0., 1.), next_v.clamp(0., 1.))
+    }
+}
+
+#[cfg(test)]
+mod gray_scott_strategy_test {
+    use crate::{
+        EvolutionStrategy, GrayScottParams, GrayScottState, GrayScottStrategy, GridPoint2D,
+        WeightedStencil2D,
+    };
+
+    fn adjacent_neighbors(state: GrayScottState) -> Vec<(GridPoint2D<i32>, GrayScottState)> {
+        [(1, 0), (-1, 0), (0, 1), (0, -1)]
+            .iter()
+            .map(|(x, y)| (GridPoint2D::new(*x, *y), state))
+            .collect()
+    }
+
+    #[test]
+    fn gray_scott_test_trivial_state() {
+        let strat = GrayScottStrategy::new(
+            GrayScottParams::mitosis(),
+            WeightedStencil2D::laplacian_5_point(),
+        );
+        let center = GridPoint2D::new(0, 0);
+        let neighbors = adjacent_neighbors(GrayScottState::default());
+        let next = strat.next_state(center, GrayScottState::default(), neighbors.into_iter());
+        assert_eq!(next, GrayScottState::default());
+    }
+
+    #[test]
+    fn gray_scott_test_diffusion() {
+        let params = GrayScottParams::new(0., 0., 1., 0.5);
+        let strat = GrayScottStrategy::new(params, WeightedStencil2D::laplacian_5_point());
+        let center = GridPoint2D::new(0, 0);
+        let neighbors = adjacent_neighbors(GrayScottState::new(0.5, 0.));
+        let next = strat.next_state(center, GrayScottState::new(1., 0.), neighbors.into_iter());
+        assert!((next.u - 0.5).abs() < 1e-9);
+        assert_eq!(next.v, 0.);
+    }
+
+    #[test]
+    fn gray_scott_test_reaction() {
+        let params = GrayScottParams::new(0.04, 0.06, 1., 0.5);
+        let strat = GrayScottStrategy::new(params, WeightedStencil2D::laplacian_9_point());
+        let center = GridPoint2D::new(0, 0);
+        let state = GrayScottState::new(0.5, 0.5);
+        let neighbors = adjacent_neighbors(state);
+        // Diagonal neighbors are missing, so the Laplacian only sums the adjacent weights.
+        let next = strat.next_state(center, state, neighbors.into_iter());
+        let laplacian = -1. * 0.5 + 0.2 * 4. * 0.5;
+        let expected_u = 0.5 + (laplacian - 0.125 + 0.04 * 0.5);
+        let expected_v = 0.5 + (0.5 * laplacian + 0.125 - 0.1 * 0.5);
+        assert!((next.u - expected_u).abs() < 1e-9);
+        assert!((next.v - expected_v).abs() < 1e-9);
+    }
+}
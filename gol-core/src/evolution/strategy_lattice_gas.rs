@@ -0,0 +1,354 @@
+use crate::neighbors::hex::hex_row_parity;
+use crate::neighbors::util::PointPrimInt;
+use crate::{EvolutionStrategy, GridPoint2D, IndexedDataOwned};
+use num_traits::PrimInt;
+
+/// Bit flag marking a cell as a solid obstacle, particles bounce back from obstacle cells.
+pub const LATTICE_GAS_OBSTACLE: u8 = 0b1000_0000;
+
+const STATE_OUT_OF_RANGE: &str = "Lattice gas states must fit in 8 bits.";
+
+/// Particle velocity channels of a lattice gas, each channel is one bit of the cell state.
+///
+/// HPP channels (square grid): 0 east, 1 north, 2 west, 3 south.
+/// FHP channels (hexagonal grid, see `NeighborHex`): channel `k` points at `k * 60` degrees,
+/// counter-clockwise from east.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LatticeGasModel {
+    Hpp,
+    Fhp,
+}
+
+pub struct LatticeGasStrategy {
+    model: LatticeGasModel,
+}
+
+impl LatticeGasModel {
+    pub fn channel_count(&self) -> usize {
+        match self {
+            Self::Hpp => 4,
+            Self::Fhp => 6,
+        }
+    }
+
+    pub fn channel_mask(&self) -> u8 {
+        (1u8 << self.channel_count()) - 1
+    }
+
+    pub fn opposite(&self, channel: usize) -> usize {
+        (channel + self.channel_count() / 2) % self.channel_count()
+    }
+
+    /// Unit velocity vector of a channel.
+    pub fn velocity(&self, channel: usize) -> (f64, f64) {
+        let angle = 2. * std::f64::consts::PI * channel as f64 / self.channel_count() as f64;
+        (angle.cos(), angle.sin())
+    }
+
+    pub fn density(&self, state: u8) -> usize {
+        (state & self.channel_mask()).count_ones() as usize
+    }
+
+    pub fn momentum(&self, state: u8) -> (f64, f64) {
+        (0..self.channel_count())
+            .filter(|channel| state & (1 << channel) != 0)
+            .map(|channel| self.velocity(channel))
+            .fold((0., 0.), |(x, y), (dx, dy)| (x + dx, y + dy))
+    }
+
+    pub fn is_obstacle(state: u8) -> bool {
+        state & LATTICE_GAS_OBSTACLE != 0
+    }
+
+    /// Collision step of a single cell, returns particles leaving the cell (without the obstacle
+    /// flag). `chirality` picks the rotation direction for non-deterministic FHP collisions.
+    pub fn collide(&self, state: u8, chirality: bool) -> u8 {
+        let particles = state & self.channel_mask();
+        if Self::is_obstacle(state) {
+            return (0..self.channel_count())
+                .filter(|channel| particles & (1 << channel) != 0)
+                .fold(0, |res, channel| res | (1 << self.opposite(channel)));
+        }
+        match self {
+            Self::Hpp => match particles {
+                0b0101 => 0b1010,
+                0b1010 => 0b0101,
+                _ => particles,
+            },
+            Self::Fhp => match particles {
+                0b01_0101 => 0b10_1010,
+                0b10_1010 => 0b01_0101,
+                0b00_1001 | 0b01_0010 | 0b10_0100 => {
+                    rotate_channels(particles, if chirality { 1 } else { 5 }, 6)
+                }
+                _ => particles,
+            },
+        }
+    }
+
+    /// Channel pointing from a cell towards its neighbor at offset `(dx, dy)`, `parity` is the
+    /// row parity of the cell, only used by the hexagonal lattice.
+    pub fn channel_of_offset(&self, dx: i64, dy: i64, parity: i64) -> Option<usize> {
+        match self {
+            Self::Hpp => match (dx, dy) {
+                (1, 0) => Some(0),
+                (0, 1) => Some(1),
+                (-1, 0) => Some(2),
+                (0, -1) => Some(3),
+                _ => None,
+            },
+            Self::Fhp => match dy {
+                0 => match dx {
+                    1 => Some(0),
+                    -1 => Some(3),
+                    _ => None,
+                },
+                1 if dx == parity => Some(1),
+                1 if dx == parity - 1 => Some(2),
+                -1 if dx == parity - 1 => Some(4),
+                -1 if dx == parity => Some(5),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl<T, U, I> EvolutionStrategy<GridPoint2D<U>, T, I> for LatticeGasStrategy
+where
+    T: PrimInt,
+    U: PointPrimInt,
+    I: Iterator<Item = IndexedDataOwned<GridPoint2D<U>, T>>,
+{
+    fn next_state(&self, idx: GridPoint2D<U>, cur_state: T, neighbors: I) -> T {
+        let cur_state = cur_state.to_u8().expect(STATE_OUT_OF_RANGE);
+        let parity = hex_row_parity(idx.y).to_i64().unwrap();
+        let mut res = cur_state & LATTICE_GAS_OBSTACLE;
+
+        // Streaming: pull particles moving towards this cell out of each neighbor's post-collision
+        // state.
+        for (neighbor_idx, neighbor_state) in neighbors {
+            let (dx, dy) = wrapped_offset(&idx, &neighbor_idx);
+            let channel = match self.model.channel_of_offset(dx, dy, parity) {
+                Some(val) => val,
+                None => continue,
+            };
+            let incoming = self.model.opposite(channel);
+            let collided = self.model.collide(
+                neighbor_state.to_u8().expect(STATE_OUT_OF_RANGE),
+                chirality(&neighbor_idx),
+            );
+            res |= collided & (1 << incoming);
+        }
+        T::from(res).unwrap()
+    }
+}
+
+impl LatticeGasStrategy {
+    pub fn new(model: LatticeGasModel) -> Self {
+        Self { model }
+    }
+
+    pub fn hpp() -> Self {
+        Self::new(LatticeGasModel::Hpp)
+    }
+
+    pub fn fhp() -> Self {
+        Self::new(LatticeGasModel::Fhp)
+    }
+
+    pub fn model(&self) -> LatticeGasModel {
+        self.model
+    }
+}
+
+fn rotate_channels(particles: u8, by: usize, channel_count: usize) -> u8 {
+    let mask = (1u8 << channel_count) - 1;
+    ((particles << by) | (particles >> (channel_count - by))) & mask
+}
+
+/// Offset from `idx` to one of its direct neighbors, undoing wrapping around board edges.
+fn wrapped_offset<U>(idx: &GridPoint2D<U>, neighbor: &GridPoint2D<U>) -> (i64, i64)
+where
+    U: PointPrimInt,
+{
+    let unwrap = |diff: i64| {
+        if diff.abs() > 1 {
+            -diff.signum()
+        } else {
+            diff
+        }
+    };
+    let dx = neighbor.x.to_i64().unwrap() - idx.x.to_i64().unwrap();
+    let dy = neighbor.y.to_i64().unwrap() - idx.y.to_i64().unwrap();
+    (unwrap(dx), unwrap(dy))
+}
+
+/// Deterministic pseudo-random chirality per site, used to break symmetry of FHP collisions.
+fn chirality<U>(idx: &GridPoint2D<U>) -> bool
+where
+    U: PointPrimInt,
+{
+    let (x, y) = (idx.x.to_i64().unwrap(), idx.y.to_i64().unwrap());
+    (x.wrapping_mul(73_856_093) ^ y.wrapping_mul(19_349_663)) & 1 == 0
+}
+
+#[cfg(test)]
+mod lattice_gas_strategy_test {
+    use crate::{
+        util::grid_util::Shape2D, Board, EvolutionStrategy, Grid, GridFactory, GridPoint2D,
+        IndexedDataOwned, LatticeGasModel, LatticeGasStrategy, NeighborHexDonut,
+        NeighborMooreDonut, SharedStrategyManager, SparseStates, StandardBoard,
+        LATTICE_GAS_OBSTACLE,
+    };
+    use std::collections::HashMap;
+
+    type Point = GridPoint2D<i32>;
+
+    fn next(
+        strat: &LatticeGasStrategy,
+        idx: Point,
+        cur_state: u8,
+        neighbors: Vec<IndexedDataOwned<Point, u8>>,
+    ) -> u8 {
+        strat.next_state(idx, cur_state, neighbors.into_iter())
+    }
+
+    #[test]
+    fn lattice_gas_hpp_streaming_test() {
+        let strat = LatticeGasStrategy::hpp();
+        // Particle moving east arrives from the west neighbor.
+        let res = next(
+            &strat,
+            GridPoint2D::new(0, 0),
+            0,
+            vec![(GridPoint2D::new(-1, 0), 0b0001)],
+        );
+        assert_eq!(res, 0b0001);
+        // Particle moving west does not arrive from the west neighbor.
+        let res = next(
+            &strat,
+            GridPoint2D::new(0, 0),
+            0,
+            vec![(GridPoint2D::new(-1, 0), 0b0100)],
+        );
+        assert_eq!(res, 0);
+    }
+
+    #[test]
+    fn lattice_gas_hpp_collision_test() {
+        let model = LatticeGasModel::Hpp;
+        assert_eq!(model.collide(0b0101, true), 0b1010);
+        assert_eq!(model.collide(0b1010, false), 0b0101);
+        assert_eq!(model.collide(0b0111, true), 0b0111);
+        assert_eq!(model.collide(LATTICE_GAS_OBSTACLE | 0b0001, true), 0b0100);
+    }
+
+    #[test]
+    fn lattice_gas_fhp_collision_test() {
+        let model = LatticeGasModel::Fhp;
+        assert_eq!(model.collide(0b01_0101, true), 0b10_1010);
+        assert_eq!(model.collide(0b00_1001, true), 0b01_0010);
+        assert_eq!(model.collide(0b00_1001, false), 0b10_0100);
+        assert_eq!(model.collide(0b00_0011, false), 0b00_0011);
+        assert_eq!(model.density(0b10_1011), 4);
+        let (x, y) = model.momentum(0b00_1001);
+        assert!(x.abs() < 1e-9 && y.abs() < 1e-9);
+    }
+
+    #[test]
+    fn lattice_gas_wrapping_test() {
+        let strat = LatticeGasStrategy::hpp();
+        // Neighbor on the other side of a wrapping board.
+        let res = next(
+            &strat,
+            GridPoint2D::new(-5, 0),
+            0,
+            vec![(GridPoint2D::new(4, 0), 0b0001)],
+        );
+        assert_eq!(res, 0b0001);
+    }
+
+    fn count_particles(
+        model: LatticeGasModel,
+        board: &StandardBoard<u8, Point, std::vec::IntoIter<Point>>,
+    ) -> usize {
+        board
+            .space_manager()
+            .indices_iter()
+            .map(|idx| model.density(board.state_manager().get_cell_state(&idx)))
+            .sum()
+    }
+
+    fn gen_board(
+        model: LatticeGasModel,
+        shape: Shape2D,
+        initial_states: HashMap<Point, u8>,
+    ) -> StandardBoard<u8, Point, std::vec::IntoIter<Point>> {
+        let space = Grid::<Point>::new(vec![shape.width(), shape.height()].into_iter());
+        let neighbor = match model {
+            LatticeGasModel::Hpp => Box::new(NeighborMooreDonut::new(shape))
+                as Box<dyn crate::BoardNeighborManager<Point, std::vec::IntoIter<Point>>>,
+            LatticeGasModel::Fhp => Box::new(NeighborHexDonut::new(shape)),
+        };
+        StandardBoard::new(
+            Box::new(space),
+            neighbor,
+            Box::new(SparseStates::new(0, initial_states)),
+            Box::new(SharedStrategyManager::new(Box::new(
+                LatticeGasStrategy::new(model),
+            ))),
+            Vec::new(),
+        )
+    }
+
+    #[test]
+    fn lattice_gas_conservation_test() {
+        for model in [LatticeGasModel::Hpp, LatticeGasModel::Fhp].iter() {
+            let mut initial_states = HashMap::new();
+            initial_states.insert(GridPoint2D::new(0, 0), model.channel_mask());
+            initial_states.insert(GridPoint2D::new(2, 1), 0b0101);
+            initial_states.insert(GridPoint2D::new(-3, -2), 0b1001);
+            initial_states.insert(GridPoint2D::new(1, -1), LATTICE_GAS_OBSTACLE);
+            let mut board = gen_board(*model, Shape2D::new(10, 8), initial_states);
+            let particle_count = count_particles(*model, &board);
+            for _ in 0..20 {
+                board.advance_one_generation();
+                assert_eq!(count_particles(*model, &board), particle_count);
+            }
+            assert_eq!(
+                board
+                    .state_manager()
+                    .get_cell_state(&GridPoint2D::new(1, -1))
+                    & LATTICE_GAS_OBSTACLE,
+                LATTICE_GAS_OBSTACLE
+            );
+        }
+    }
+
+    #[test]
+    fn lattice_gas_obstacle_bounce_test() {
+        let mut initial_states = HashMap::new();
+        initial_states.insert(GridPoint2D::new(0, 0), 0b0001);
+        initial_states.insert(GridPoint2D::new(2, 0), LATTICE_GAS_OBSTACLE);
+        let mut board = gen_board(LatticeGasModel::Hpp, Shape2D::new(10, 8), initial_states);
+        let states = board.state_manager();
+        assert_eq!(states.get_cell_state(&GridPoint2D::new(0, 0)), 0b0001);
+        board.advance_one_generation();
+        board.advance_one_generation();
+        // Particle entered the obstacle cell.
+        assert_eq!(
+            board
+                .state_manager()
+                .get_cell_state(&GridPoint2D::new(2, 0)),
+            LATTICE_GAS_OBSTACLE | 0b0001
+        );
+        board.advance_one_generation();
+        // Particle bounced back, now moving west.
+        assert_eq!(
+            board
+                .state_manager()
+                .get_cell_state(&GridPoint2D::new(1, 0)),
+            0b0100
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{EvolutionStrategy, IndexedDataOwned};
+use crate::{EvolutionStrategy, IndexedDataOwned, TwoStateRule};
 use num_traits::{FromPrimitive, PrimInt, Unsigned};
 use std::collections::HashSet;
 
@@ -33,6 +33,19 @@ where
             cur_state
         }
     }
+
+    fn two_state_rule(&self) -> Option<TwoStateRule<T>> {
+        if self.state_count != 2 {
+            return None;
+        }
+        Some(TwoStateRule::new(
+            &self.alive_surive_counts,
+            &self.newborn_counts,
+            T::zero(),
+            T::one(),
+            |state| *state == T::one(),
+        ))
+    }
 }
 
 impl DecayLifeLikeStrategy {
@@ -48,6 +61,20 @@ impl DecayLifeLikeStrategy {
         }
     }
 
+    pub fn state_count(&self) -> usize {
+        self.state_count
+    }
+
+    /// Alive neighbor counts alive cells survive with.
+    pub fn survive_counts(&self) -> &HashSet<usize> {
+        &self.alive_surive_counts
+    }
+
+    /// Alive neighbor counts dead cells are born with.
+    pub fn born_counts(&self) -> &HashSet<usize> {
+        &self.newborn_counts
+    }
+
     pub fn gol() -> Self {
         let survive: HashSet<usize> = vec![2, 3].into_iter().collect();
         let born: HashSet<usize> = vec![3].into_iter().collect();
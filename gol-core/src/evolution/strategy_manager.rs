@@ -14,6 +14,10 @@ where
     fn get_strategy_at_index(&self, _: CI) -> &dyn EvolutionStrategy<CI, T, I> {
         &*self.strategy
     }
+
+    fn uses_previous_state(&self) -> bool {
+        self.strategy.uses_previous_state()
+    }
 }
 
 impl<CI, T, I> SharedStrategyManager<CI, T, I>
@@ -0,0 +1,106 @@
+use crate::{EvolutionStrategy, IndexedDataOwned};
+use num_traits::{FromPrimitive, PrimInt, Unsigned};
+
+/// Bak-Tang-Wiesenfeld sandpile: a cell holding at least `threshold` grains topples, giving one
+/// grain to each neighbor. All unstable cells topple at the same time every generation. Grains
+/// toppled off the edge of the board are lost.
+pub struct SandpileStrategy {
+    threshold: usize,
+}
+
+impl SandpileStrategy {
+    pub fn new(threshold: usize) -> Self {
+        assert!(threshold > 0);
+        Self { threshold }
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+}
+
+impl Default for SandpileStrategy {
+    /// Classic sandpile on a square grid with 4 neighbors.
+    fn default() -> Self {
+        Self::new(4)
+    }
+}
+
+impl<CI, T, I> EvolutionStrategy<CI, T, I> for SandpileStrategy
+where
+    T: PrimInt + Unsigned + FromPrimitive,
+    I: Iterator<Item = IndexedDataOwned<CI, T>>,
+{
+    fn next_state(&self, _: CI, cur_state: T, neighbors: I) -> T {
+        let threshold = T::from_usize(self.threshold).unwrap();
+        let incoming = neighbors.filter(|(_, state)| state >= &threshold).count();
+        let remaining = if cur_state >= threshold {
+            cur_state - threshold
+        } else {
+            cur_state
+        };
+        remaining
+            .checked_add(&T::from_usize(incoming).unwrap())
+            .expect("Sandpile state overflow, try using a wider state type.")
+    }
+}
+
+#[cfg(test)]
+mod sandpile_strategy_test {
+    use crate::{
+        Board, EvolutionStrategy, Grid, GridFactory, GridPoint2D, NeighborVonNeumann,
+        SandpileStrategy, SharedStrategyManager, SparseStates, StandardBoard,
+    };
+    use std::collections::HashMap;
+
+    #[test]
+    fn sandpile_test_topple() {
+        let strat = SandpileStrategy::default();
+        let neighbors = vec![
+            (GridPoint2D::new(1, 0), 4u16),
+            (GridPoint2D::new(-1, 0), 3),
+            (GridPoint2D::new(0, 1), 300),
+            (GridPoint2D::new(0, -1), 0),
+        ];
+        assert_eq!(
+            strat.next_state(GridPoint2D::new(0, 0), 5u16, neighbors.into_iter()),
+            3
+        );
+    }
+
+    #[test]
+    fn sandpile_test_conservation() {
+        let mut initial_states = HashMap::new();
+        initial_states.insert(GridPoint2D::new(0, 0), 64u32);
+        let mut board = StandardBoard::new(
+            Box::new(Grid::<GridPoint2D<i32>>::new(vec![21usize, 21].into_iter())),
+            Box::new(NeighborVonNeumann::new()),
+            Box::new(SparseStates::new(0, initial_states)),
+            Box::new(SharedStrategyManager::new(Box::new(
+                SandpileStrategy::default(),
+            ))),
+            Vec::new(),
+        );
+        for _ in 0..199 {
+            board.advance_one_generation();
+        }
+        let states = board.advance_one_generation();
+        // Too few grains to reach the edge, so none should be lost.
+        assert_eq!(states.iter().map(|ele| ele.1).sum::<u32>(), 64);
+        assert!(states.iter().all(|ele| ele.1 < 4));
+        let center = states
+            .iter()
+            .find(|ele| ele.0 == GridPoint2D::new(0, 0))
+            .unwrap();
+        let mirrored = states
+            .iter()
+            .find(|ele| ele.0 == GridPoint2D::new(0, -2))
+            .unwrap();
+        let original = states
+            .iter()
+            .find(|ele| ele.0 == GridPoint2D::new(0, 2))
+            .unwrap();
+        assert!(center.1 < 4);
+        assert_eq!(mirrored.1, original.1);
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{DecayLifeLikeStrategy, EvolutionStrategy, IndexedDataOwned};
+use num_traits::{FromPrimitive, PrimInt, ToPrimitive, Unsigned};
+
+/// Second-order version of another strategy: the next state is the state given by the inner
+/// strategy minus the previous state, modulo the number of states (XOR for two states). Any rule
+/// becomes reversible this way, swapping the current and previous generations runs it backwards.
+pub struct SecondOrderStrategy<S> {
+    inner: S,
+    state_count: usize,
+}
+
+impl<S> SecondOrderStrategy<S> {
+    pub fn new(inner: S, state_count: usize) -> Self {
+        assert!(state_count >= 2);
+        Self { inner, state_count }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn state_count(&self) -> usize {
+        self.state_count
+    }
+}
+
+impl SecondOrderStrategy<DecayLifeLikeStrategy> {
+    /// Reversible version of a life-like rule.
+    /// ```rust
+    /// use gol_core::{DecayLifeLikeStrategy, EvolutionStrategy, GridPoint2D, SecondOrderStrategy};
+    ///
+    /// let strat = SecondOrderStrategy::from_life_like(DecayLifeLikeStrategy::gol());
+    /// let neighbors: Vec<(GridPoint2D<i32>, u8)> = (0..3)
+    ///     .map(|x| (GridPoint2D::new(x, 1), 1))
+    ///     .collect();
+    /// // Birth XOR alive in the previous generation.
+    /// let next = strat.next_state_with_previous(GridPoint2D::new(1, 0), 0, 1, neighbors.into_iter());
+    /// assert_eq!(next, 0);
+    /// ```
+    pub fn from_life_like(inner: DecayLifeLikeStrategy) -> Self {
+        let state_count = inner.state_count();
+        Self::new(inner, state_count)
+    }
+}
+
+impl<CI, T, I, S> EvolutionStrategy<CI, T, I> for SecondOrderStrategy<S>
+where
+    S: EvolutionStrategy<CI, T, I>,
+    T: PrimInt + Unsigned + FromPrimitive + ToPrimitive,
+    I: Iterator<Item = IndexedDataOwned<CI, T>>,
+{
+    /// Without history the previous generation is assumed to be all zero states.
+    fn next_state(&self, idx: CI, cur_state: T, neighbors: I) -> T {
+        self.next_state_with_previous(idx, cur_state, T::zero(), neighbors)
+    }
+
+    fn next_state_with_previous(
+        &self,
+        idx: CI,
+        cur_state: T,
+        previous_state: T,
+        neighbors: I,
+    ) -> T {
+        let state_count = self.state_count;
+        let first_order = self
+            .inner
+            .next_state(idx, cur_state, neighbors)
+            .to_usize()
+            .unwrap();
+        let previous = previous_state.to_usize().unwrap() % state_count;
+        T::from_usize((first_order + state_count - previous) % state_count).unwrap()
+    }
+
+    fn uses_previous_state(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod second_order_strategy_test {
+    use crate::{
+        util::grid_util::Shape2D, Board, DecayLifeLikeStrategy, Grid, GridFactory, GridPoint2D,
+        NeighborMooreDonut, SecondOrderStrategy, SharedStrategyManager, SparseStates,
+        StandardBoard,
+    };
+    use std::collections::{HashMap, HashSet};
+
+    fn collect_states(
+        board: &StandardBoard<u8, GridPoint2D<i32>, std::vec::IntoIter<GridPoint2D<i32>>>,
+    ) -> HashMap<GridPoint2D<i32>, u8> {
+        let mut res = HashMap::new();
+        for x in -5..5 {
+            for y in -5..5 {
+                let idx = GridPoint2D::new(x, y);
+                let state = board.state_manager().get_cell_state(&idx);
+                res.insert(idx, state);
+            }
+        }
+        res
+    }
+
+    fn run_forward_and_backward(strat: DecayLifeLikeStrategy, max_state: u8) {
+        let shape = Shape2D::new(10, 10);
+        let initial: HashMap<GridPoint2D<i32>, u8> =
+            [(0, 0), (1, 0), (-1, 0), (0, 1), (2, 2), (-3, 4)]
+                .iter()
+                .map(|(x, y)| (GridPoint2D::new(*x, *y), max_state))
+                .collect();
+        let previous: HashMap<GridPoint2D<i32>, u8> = [(0, -1), (4, 4)]
+            .iter()
+            .map(|(x, y)| (GridPoint2D::new(*x, *y), max_state))
+            .collect();
+        let mut board = StandardBoard::new(
+            Box::new(Grid::<GridPoint2D<i32>>::new(vec![10usize, 10].into_iter())),
+            Box::new(NeighborMooreDonut::new(shape)),
+            Box::new(SparseStates::new(0u8, initial).with_previous_states(previous)),
+            Box::new(SharedStrategyManager::new(Box::new(
+                SecondOrderStrategy::from_life_like(strat),
+            ))),
+            Vec::new(),
+        );
+        let start = collect_states(&board);
+
+        let generations = 25;
+        for _ in 0..generations {
+            board.advance_one_generation();
+        }
+        assert_ne!(collect_states(&board), start);
+
+        board.reverse();
+        for _ in 0..generations - 1 {
+            board.advance_one_generation();
+        }
+        assert_eq!(collect_states(&board), start);
+    }
+
+    #[test]
+    fn second_order_test_gol_reversible() {
+        run_forward_and_backward(DecayLifeLikeStrategy::gol(), 1);
+    }
+
+    #[test]
+    fn second_order_test_decay_reversible() {
+        let survive: HashSet<usize> = vec![3, 4, 5].into_iter().collect();
+        let born: HashSet<usize> = vec![2].into_iter().collect();
+        run_forward_and_backward(DecayLifeLikeStrategy::new(4, survive, born), 3);
+    }
+}
@@ -0,0 +1,138 @@
+use crate::neighbors::util::PointPrimInt;
+use crate::{EvolutionStrategy, GridPoint2D, IndexedDataOwned, NeighborsWeightedKernel};
+use num_traits::{FromPrimitive, PrimInt, Unsigned};
+
+/// Weighted sums closer than this to the end of a range are inside the range.
+const SUM_TOLERANCE: f64 = 1e-9;
+
+/// Life-like rule with decay where alive neighbors are summed with the weights of a kernel
+/// instead of counted, the board should use the same kernel as its neighbor manager. Survival
+/// and birth are given as inclusive ranges of weighted sums, use `(x, x)` for a single sum.
+pub struct WeightedLifeLikeStrategy {
+    state_count: usize,
+    kernel: NeighborsWeightedKernel,
+    alive_survive_sums: Vec<(f64, f64)>,
+    newborn_sums: Vec<(f64, f64)>,
+}
+
+impl WeightedLifeLikeStrategy {
+    pub fn new(
+        state_count: usize,
+        kernel: NeighborsWeightedKernel,
+        alive_survive_sums: Vec<(f64, f64)>,
+        newborn_sums: Vec<(f64, f64)>,
+    ) -> Self {
+        Self {
+            state_count,
+            kernel,
+            alive_survive_sums,
+            newborn_sums,
+        }
+    }
+
+    pub fn state_count(&self) -> usize {
+        self.state_count
+    }
+
+    pub fn kernel(&self) -> &NeighborsWeightedKernel {
+        &self.kernel
+    }
+}
+
+fn in_ranges(ranges: &[(f64, f64)], sum: f64) -> bool {
+    ranges
+        .iter()
+        .any(|(min, max)| sum >= min - SUM_TOLERANCE && sum <= max + SUM_TOLERANCE)
+}
+
+impl<U, T, I> EvolutionStrategy<GridPoint2D<U>, T, I> for WeightedLifeLikeStrategy
+where
+    U: PointPrimInt,
+    T: PrimInt + Unsigned + FromPrimitive + std::ops::Sub<Output = T>,
+    I: Iterator<Item = IndexedDataOwned<GridPoint2D<U>, T>>,
+{
+    fn next_state(&self, idx: GridPoint2D<U>, cur_state: T, neighbors: I) -> T {
+        let alive_state = T::from_usize(self.state_count - 1).unwrap();
+        let alive_sum: f64 = neighbors
+            .filter(|(_, state)| state == &alive_state)
+            .map(|(neighbor_idx, _)| self.kernel.weight_of(&idx, &neighbor_idx))
+            .sum();
+
+        let is_alive = cur_state == alive_state;
+        let is_zero = cur_state == T::zero();
+        if !is_alive && !is_zero || is_alive && !in_ranges(&self.alive_survive_sums, alive_sum) {
+            cur_state - T::one()
+        } else if is_zero && in_ranges(&self.newborn_sums, alive_sum) {
+            alive_state
+        } else {
+            cur_state
+        }
+    }
+}
+
+#[cfg(test)]
+mod weighted_life_like_strategy_test {
+    use crate::{
+        EvolutionStrategy, GridPoint2D, NeighborsWeightedKernel, WeightedLifeLikeStrategy,
+    };
+
+    fn neighbors(alive: &[(i32, i32)], state: u8) -> Vec<(GridPoint2D<i32>, u8)> {
+        alive
+            .iter()
+            .map(|(x, y)| (GridPoint2D::new(*x, *y), state))
+            .collect()
+    }
+
+    #[test]
+    fn weighted_life_like_test_sums() {
+        // Orthogonal neighbors count 2 and diagonal ones 1, born on exactly 5.
+        let strat = WeightedLifeLikeStrategy::new(
+            2,
+            NeighborsWeightedKernel::orthogonal_diagonal(2., 1.),
+            vec![(4., 6.)],
+            vec![(5., 5.)],
+        );
+        let center = GridPoint2D::new(0, 0);
+        let born = neighbors(&[(1, 0), (0, 1), (1, 1)], 1);
+        assert_eq!(strat.next_state(center.clone(), 0u8, born.into_iter()), 1);
+        let not_born = neighbors(&[(1, 0), (0, 1), (1, 1), (-1, -1)], 1);
+        assert_eq!(
+            strat.next_state(center.clone(), 0u8, not_born.into_iter()),
+            0
+        );
+        let survive = neighbors(&[(1, 0), (-1, 0), (1, 1)], 1);
+        assert_eq!(
+            strat.next_state(center.clone(), 1u8, survive.into_iter()),
+            1
+        );
+        let die = neighbors(&[(1, 0), (-1, 0), (0, 1), (0, -1)], 1);
+        assert_eq!(strat.next_state(center.clone(), 1u8, die.into_iter()), 0);
+    }
+
+    #[test]
+    fn weighted_life_like_test_decay() {
+        let strat = WeightedLifeLikeStrategy::new(
+            4,
+            NeighborsWeightedKernel::orthogonal_diagonal(2., 1.),
+            vec![(2., 3.)],
+            vec![(2., 2.)],
+        );
+        let center = GridPoint2D::new(5, 5);
+        // Decaying cells are not alive and do not add to the sum.
+        let decaying = neighbors(&[(5, 6), (6, 6)], 2);
+        assert_eq!(
+            strat.next_state(center.clone(), 0u8, decaying.into_iter()),
+            0
+        );
+        let alive = neighbors(&[(5, 6)], 3);
+        assert_eq!(
+            strat.next_state(center.clone(), 0u8, alive.clone().into_iter()),
+            3
+        );
+        assert_eq!(strat.next_state(center.clone(), 3u8, alive.into_iter()), 3);
+        assert_eq!(
+            strat.next_state(center.clone(), 2u8, Vec::new().into_iter()),
+            1
+        );
+    }
+}
@@ -1,31 +1,69 @@
+pub mod analysis;
 pub mod board;
 pub mod board_states;
 pub mod callback;
 pub mod cell;
+pub mod distributed;
 pub mod evolution;
 pub mod neighbors;
 pub mod space;
 pub mod util;
 
+pub use analysis::census::{Census, CensusEntry, CensusObject, ObjectCensus, ObjectKind};
+pub use analysis::methuselah_search::{Methuselah, MethuselahRank, MethuselahSearch};
+pub use analysis::pattern_search::{PatternSearch, PatternSearchProgress};
+pub use analysis::rle::to_rle;
+pub use analysis::soup_search::{RareFind, SearchResults, SoupResult, SoupSearch};
+pub use board::bit_life::{BitLife, TwoStateRule};
 pub use board::board::Board;
 pub use board::board_callback::{
     BoardCallback, BoardCallbackManager, BoardCallbackWithStates, BoardCallbackWithoutStates,
 };
 pub use board::board_neighbor::BoardNeighborManager;
 pub use board::board_space::BoardSpaceManager;
-pub use board::board_state::BoardStateManager;
+pub use board::board_state::{BoardStateManager, StateChunk};
 pub use board::board_strategy::BoardStrategyManager;
+pub use board::cell_eraser::CellEraser;
+pub use board::generation::{GenerationBuffers, GenerationStates};
+pub use board::grain_dropper::GrainDropper;
 pub use board::standard::{StandardBoard, StandardBoardFactory};
-pub use board_states::sparse::SparseStates;
-pub use callback::{model_states::StatesCallback, model_states::StatesReadOnly};
+pub use board_states::{
+    chunked::ChunkedStates, mapped::MappedBitStates, packed_sparse::PackedSparseStates,
+    quadtree::QuadtreeStates, sparse::SparseStates,
+};
+pub use callback::{
+    avalanche::AvalancheHistogram,
+    census::CensusCallback,
+    model_states::StatesCallback,
+    model_states::StatesReadOnly,
+    spaceship::{Spaceship, SpaceshipDetector, SpaceshipDirection},
+};
 pub use cell::common::IndexedDataOwned;
 pub use cell::index::{GridPoint1D, GridPoint2D, GridPoint3D, GridPointND};
+pub use cell::packed::PackedIndex;
+pub use distributed::{
+    coordinator::DistributedBoard, protocol::StripPartition, worker::DistributedWorker,
+};
 pub use evolution::strategy::EvolutionStrategy;
 pub use evolution::strategy_discrete::DecayMultiAliveStrategy;
+pub use evolution::strategy_gray_scott::{
+    GrayScottParams, GrayScottPreset, GrayScottState, GrayScottStrategy,
+};
+pub use evolution::strategy_lattice_gas::{
+    LatticeGasModel, LatticeGasStrategy, LATTICE_GAS_OBSTACLE,
+};
 pub use evolution::strategy_life_like::DecayLifeLikeStrategy;
 pub use evolution::strategy_manager::SharedStrategyManager;
+pub use evolution::strategy_sandpile::SandpileStrategy;
+pub use evolution::strategy_second_order::SecondOrderStrategy;
+pub use evolution::strategy_weighted_life_like::WeightedLifeLikeStrategy;
 pub use neighbors::{
-    grid_donut::NeighborsGridDonut, grid_surround::NeighborsGridSurround, moore::NeighborMoore,
-    moore_donut::NeighborMooreDonut, moore_triangle::NeighborMooreTriangle,
+    graph::NeighborsGraph, grid_donut::NeighborsGridDonut, grid_surround::NeighborsGridSurround,
+    hex::NeighborHex, hex_donut::NeighborHexDonut, moore::NeighborMoore,
+    moore_donut::NeighborMooreDonut, moore_triangle::NeighborMooreTriangle, table::NeighborTable,
+    von_neumann::NeighborVonNeumann, weighted_kernel::NeighborsWeightedKernel,
+    weighted_stencil::WeightedStencil2D,
 };
+pub use space::graph::Graph;
 pub use space::grid::{Grid, GridFactory, GridOrigin};
+pub use space::tiling::Tiling;
@@ -0,0 +1,60 @@
+use crate::{BoardNeighborManager, Graph};
+
+/// Neighbors are the nodes sharing an edge with the cell.
+pub struct NeighborsGraph {
+    graph: Graph,
+}
+
+impl NeighborsGraph {
+    pub fn new(graph: Graph) -> Self {
+        Self { graph }
+    }
+}
+
+impl BoardNeighborManager<usize, std::vec::IntoIter<usize>> for NeighborsGraph {
+    fn get_neighbors_idx(&self, idx: &usize) -> std::vec::IntoIter<usize> {
+        self.graph.neighbors(*idx).to_vec().into_iter()
+    }
+}
+
+#[cfg(test)]
+mod graph_neighbors_test {
+    use crate::{
+        Board, BoardNeighborManager, DecayLifeLikeStrategy, Graph, NeighborsGraph,
+        SharedStrategyManager, SparseStates, StandardBoard,
+    };
+    use std::collections::HashSet;
+
+    #[test]
+    fn graph_neighbors_test_edges() {
+        let neighbors = NeighborsGraph::new(Graph::from_edges(4, vec![(0, 1), (2, 0)]));
+        let res: Vec<usize> = neighbors.get_neighbors_idx(&0).collect();
+        assert_eq!(res, vec![1, 2]);
+        assert_eq!(neighbors.get_neighbors_idx(&3).count(), 0);
+    }
+
+    #[test]
+    fn graph_neighbors_test_board() {
+        // Ring of 6 nodes, cells are born with exactly one alive neighbor and never survive.
+        let graph = Graph::watts_strogatz(6, 2, 0., 0);
+        let strat = DecayLifeLikeStrategy::new(
+            2,
+            HashSet::new(),
+            vec![1].into_iter().collect::<HashSet<usize>>(),
+        );
+        let mut board = StandardBoard::new(
+            Box::new(graph.clone()),
+            Box::new(NeighborsGraph::new(graph)),
+            Box::new(SparseStates::new(0u8, vec![(0, 1)].into_iter().collect())),
+            Box::new(SharedStrategyManager::new(Box::new(strat))),
+            Vec::new(),
+        );
+        let states = board.advance_one_generation();
+        let alive: HashSet<usize> = states
+            .iter()
+            .filter(|ele| ele.1 == 1)
+            .map(|ele| ele.0)
+            .collect();
+        assert_eq!(alive, vec![1, 5].into_iter().collect());
+    }
+}
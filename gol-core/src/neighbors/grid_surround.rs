@@ -1,10 +1,12 @@
 use super::util::{MarginPrimInt, PointPrimInt};
+use crate::cell::index::ToGridPointND;
 use crate::{
     BoardNeighborManager, GridPoint1D, GridPoint2D, GridPoint3D, GridPointND, NeighborMoore,
 };
 use itertools::izip;
 use std::convert::TryFrom;
 
+#[derive(Clone, Debug)]
 pub struct NeighborsGridSurround<T> {
     should_repeat_margin: bool,
     margins: Vec<(T, T)>,
@@ -182,7 +184,7 @@ where
         let (mut y_left, mut y_right) = self.margins.first().unwrap();
         let (z_left, z_right) = self.margins.last().unwrap();
         if !self.should_repeat_margin {
-            let y_margin = self.margins[2];
+            let y_margin = self.margins[1];
             y_left = y_margin.0;
             y_right = y_margin.1;
         }
@@ -193,34 +195,11 @@ where
         {
             return NeighborMoore::new().get_neighbors_idx(idx);
         }
-        let x_left_u = match U::try_from(*x_left) {
-            Ok(val) => val,
-            Err(_) => panic!("Error casting number."),
-        };
-        let y_left_u = match U::try_from(y_left) {
-            Ok(val) => val,
-            Err(_) => panic!("Error casting number."),
-        };
-        let z_left_u = match U::try_from(*z_left) {
-            Ok(val) => val,
-            Err(_) => panic!("Error casting number."),
-        };
-        let mut res = Vec::new();
-        let width = (*x_left + one_t + *x_right).to_usize().unwrap();
-        let height = (y_left + one_t + y_right).to_usize().unwrap();
-        let depth = (*z_left + one_t + *z_right).to_usize().unwrap();
-        let skip_idx = x_left.to_usize().unwrap()
-            + width * y_left.to_usize().unwrap()
-            + width * height * z_left.to_usize().unwrap();
-        for i in 0..(width * height * depth) {
-            if i == skip_idx {
-                continue;
-            }
-            let cur_x = x_left_u + U::from_usize(i % width).unwrap();
-            let cur_y = y_left_u + U::from_usize(i / width).unwrap();
-            let cur_z = z_left_u + U::from_usize(i / (width * height)).unwrap();
-            res.push(GridPoint3D::new(cur_x, cur_y, cur_z));
-        }
+        let res: Vec<GridPoint3D<U>> = self
+            .calc_grid_point_surrounding(&idx.to_nd())
+            .iter()
+            .map(|ele| ele.to_3d().unwrap())
+            .collect();
         res.into_iter()
     }
 }
@@ -257,8 +236,8 @@ where
             if i == skip_idx {
                 continue;
             }
-            let cur_x = x_left_u + U::from_usize(i % width).unwrap();
-            let cur_y = y_left_u + U::from_usize(i / width).unwrap();
+            let cur_x = idx.x - x_left_u + U::from_usize(i % width).unwrap();
+            let cur_y = idx.y - y_left_u + U::from_usize(i / width).unwrap();
             res.push(GridPoint2D::new(cur_x, cur_y));
         }
         res.into_iter()
@@ -354,6 +333,19 @@ mod grid_surrounding_neighbor_test {
         assert!(!neighbors.contains(&point));
     }
 
+    #[test]
+    fn grid_surrounding_test_2d_4() {
+        let margins = [(2usize, 1), (1usize, 2)];
+        let neighbor_calc = NeighborsGridSurround::new_with_variable_margin(margins.iter());
+        let point = GridPoint2D { x: 10, y: -5 };
+        let neighbors: Vec<GridPoint2D<i32>> = neighbor_calc.get_neighbors_idx(&point).collect();
+        assert_eq!(neighbors.len(), 15);
+        assert!(!neighbors.contains(&point));
+        assert!(neighbors.contains(&GridPoint2D { x: 8, y: -6 }));
+        assert!(neighbors.contains(&GridPoint2D { x: 11, y: -3 }));
+        assert!(!neighbors.contains(&GridPoint2D { x: 12, y: -5 }));
+    }
+
     #[test]
     fn grid_surrounding_test_3d_1() {
         let neighbor_calc = NeighborsGridSurround::new(1usize);
@@ -414,4 +406,16 @@ mod grid_surrounding_neighbor_test {
         assert_eq!(neighbors.len(), 696279);
         assert!(!neighbors.contains(&point));
     }
+
+    #[test]
+    fn grid_surrounding_test_3d_4() {
+        let neighbor_calc = NeighborsGridSurround::new(2usize);
+        let point = GridPoint3D::new(10, -4, 3);
+        let neighbors: Vec<GridPoint3D<i32>> = neighbor_calc.get_neighbors_idx(&point).collect();
+        assert_eq!(neighbors.len(), 124);
+        assert!(neighbors.contains(&GridPoint3D::new(12, -6, 1)));
+        assert!(neighbors.contains(&GridPoint3D::new(8, -2, 5)));
+        assert!(!neighbors.contains(&GridPoint3D::new(13, -4, 3)));
+        assert!(!neighbors.contains(&point));
+    }
 }
@@ -0,0 +1,86 @@
+use super::util::PointPrimInt;
+use crate::{BoardNeighborManager, GridPoint2D};
+
+/// Six neighbors of a hexagonal lattice laid out in "odd-r" offset coordinates: every odd row is
+/// shifted half a cell to the right, so the board can still be stored as a rectangular grid.
+pub struct NeighborHex {}
+
+impl NeighborHex {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Default for NeighborHex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> BoardNeighborManager<GridPoint2D<T>, std::vec::IntoIter<GridPoint2D<T>>> for NeighborHex
+where
+    T: PointPrimInt,
+{
+    fn get_neighbors_idx(&self, idx: &GridPoint2D<T>) -> std::vec::IntoIter<GridPoint2D<T>> {
+        hex_neighbors(idx).into_iter()
+    }
+}
+
+/// Returns 1 for odd rows and 0 for even rows, works with negative row indices.
+pub fn hex_row_parity<T>(y: T) -> T
+where
+    T: PointPrimInt,
+{
+    let two_t = T::one() + T::one();
+    ((y % two_t) + two_t) % two_t
+}
+
+pub(crate) fn hex_neighbors<T>(idx: &GridPoint2D<T>) -> Vec<GridPoint2D<T>>
+where
+    T: PointPrimInt,
+{
+    let one_t = T::one();
+    let parity = hex_row_parity(idx.y);
+    vec![
+        GridPoint2D::new(idx.x + one_t, idx.y),
+        GridPoint2D::new(idx.x + parity, idx.y + one_t),
+        GridPoint2D::new(idx.x + parity - one_t, idx.y + one_t),
+        GridPoint2D::new(idx.x - one_t, idx.y),
+        GridPoint2D::new(idx.x + parity - one_t, idx.y - one_t),
+        GridPoint2D::new(idx.x + parity, idx.y - one_t),
+    ]
+}
+
+#[cfg(test)]
+mod hex_neighbor_test {
+    use crate::{BoardNeighborManager, GridPoint2D, NeighborHex};
+
+    #[test]
+    fn hex_neighbor_test_even_row() {
+        let neighbor_calc = NeighborHex::new();
+        let point = GridPoint2D { x: 0, y: 0 };
+        let neighbors: Vec<GridPoint2D<i32>> = neighbor_calc.get_neighbors_idx(&point).collect();
+        assert_eq!(neighbors.len(), 6);
+        assert!(!neighbors.contains(&point));
+        assert!(neighbors.contains(&GridPoint2D { x: 1, y: 0 }));
+        assert!(neighbors.contains(&GridPoint2D { x: -1, y: 0 }));
+        assert!(neighbors.contains(&GridPoint2D { x: 0, y: 1 }));
+        assert!(neighbors.contains(&GridPoint2D { x: -1, y: 1 }));
+        assert!(neighbors.contains(&GridPoint2D { x: 0, y: -1 }));
+        assert!(neighbors.contains(&GridPoint2D { x: -1, y: -1 }));
+    }
+
+    #[test]
+    fn hex_neighbor_test_odd_negative_row() {
+        let neighbor_calc = NeighborHex::new();
+        let point = GridPoint2D { x: 3, y: -3 };
+        let neighbors: Vec<GridPoint2D<i64>> = neighbor_calc.get_neighbors_idx(&point).collect();
+        assert_eq!(neighbors.len(), 6);
+        assert!(neighbors.contains(&GridPoint2D { x: 4, y: -3 }));
+        assert!(neighbors.contains(&GridPoint2D { x: 2, y: -3 }));
+        assert!(neighbors.contains(&GridPoint2D { x: 4, y: -2 }));
+        assert!(neighbors.contains(&GridPoint2D { x: 3, y: -2 }));
+        assert!(neighbors.contains(&GridPoint2D { x: 4, y: -4 }));
+        assert!(neighbors.contains(&GridPoint2D { x: 3, y: -4 }));
+    }
+}
@@ -0,0 +1,72 @@
+use super::hex::hex_neighbors;
+use super::util::PointPrimInt;
+use crate::{util::grid_util::Shape2D, BoardNeighborManager, GridPoint2D};
+
+/// Same as `NeighborHex`, but wraps around the board edges. Board height must be even so that row
+/// parity stays consistent across the top and bottom edges.
+pub struct NeighborHexDonut {
+    grid_size: Shape2D,
+}
+
+impl NeighborHexDonut {
+    pub fn new(grid_size: Shape2D) -> Self {
+        assert!(grid_size.width() >= 3);
+        assert!(grid_size.height() >= 2);
+        assert_eq!(
+            grid_size.height() % 2,
+            0,
+            "Wrapping hexagonal board must have even height."
+        );
+        Self { grid_size }
+    }
+}
+
+impl<T> BoardNeighborManager<GridPoint2D<T>, std::vec::IntoIter<GridPoint2D<T>>>
+    for NeighborHexDonut
+where
+    T: PointPrimInt,
+{
+    fn get_neighbors_idx(&self, idx: &GridPoint2D<T>) -> std::vec::IntoIter<GridPoint2D<T>> {
+        let (x_min, x_max) = (
+            T::from_i64(self.grid_size.x_idx_min()).unwrap(),
+            T::from_i64(self.grid_size.x_idx_max()).unwrap(),
+        );
+        let (y_min, y_max) = (
+            T::from_i64(self.grid_size.y_idx_min()).unwrap(),
+            T::from_i64(self.grid_size.y_idx_max()).unwrap(),
+        );
+        let mut res = hex_neighbors(idx);
+        for point in res.iter_mut() {
+            if point.x < x_min {
+                point.x = x_max;
+            } else if point.x > x_max {
+                point.x = x_min;
+            }
+            if point.y < y_min {
+                point.y = y_max;
+            } else if point.y > y_max {
+                point.y = y_min;
+            }
+        }
+        res.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod hex_donut_neighbor_test {
+    use crate::{util::grid_util::Shape2D, BoardNeighborManager, GridPoint2D, NeighborHexDonut};
+
+    #[test]
+    fn hex_donut_test_corner() {
+        let neighbor_calc = NeighborHexDonut::new(Shape2D::new(5, 4));
+        let point = GridPoint2D { x: -2, y: -2 };
+        let neighbors: Vec<GridPoint2D<i32>> = neighbor_calc.get_neighbors_idx(&point).collect();
+        assert_eq!(neighbors.len(), 6);
+        assert!(neighbors.contains(&GridPoint2D { x: -1, y: -2 }));
+        assert!(neighbors.contains(&GridPoint2D { x: 2, y: -2 }));
+        assert!(neighbors.contains(&GridPoint2D { x: -2, y: -1 }));
+        assert!(neighbors.contains(&GridPoint2D { x: 2, y: -1 }));
+        assert!(neighbors.contains(&GridPoint2D { x: -2, y: 1 }));
+        assert!(neighbors.contains(&GridPoint2D { x: 2, y: 1 }));
+    }
+}
@@ -1,6 +1,13 @@
+pub mod graph;
 pub mod grid_donut;
 pub mod grid_surround;
+pub mod hex;
+pub mod hex_donut;
 pub mod moore;
 pub mod moore_donut;
 pub mod moore_triangle;
+pub mod table;
 pub mod util;
+pub mod von_neumann;
+pub mod weighted_kernel;
+pub mod weighted_stencil;
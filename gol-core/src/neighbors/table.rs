@@ -0,0 +1,176 @@
+use crate::{BoardNeighborManager, BoardSpaceManager};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Neighbors of every cell of a bounded board compiled once into flat integer IDs, stored in
+/// compressed sparse row form: the neighbors of cell `id` are
+/// `neighbors[offsets[id]..offsets[id + 1]]`. Cells of the board get IDs from 0 to
+/// `cell_count() - 1` in the order of the space manager. Neighbors outside of the board, e.g.
+/// past the edges of a bounded grid, get the IDs after them.
+#[derive(Clone, Debug)]
+pub struct NeighborTable<CI> {
+    indices: Vec<CI>,
+    cell_count: usize,
+    offsets: Vec<usize>,
+    neighbors: Vec<usize>,
+}
+
+impl<CI> NeighborTable<CI>
+where
+    CI: Send + Sync + Clone + Hash + Eq,
+{
+    pub fn new<I>(
+        space_manager: &dyn BoardSpaceManager<CI, I, rayon::vec::IntoIter<CI>>,
+        neighbor_manager: &dyn BoardNeighborManager<CI, I>,
+    ) -> Self
+    where
+        I: Iterator<Item = CI>,
+    {
+        let mut indices: Vec<CI> = space_manager.indices_iter().collect();
+        let cell_count = indices.len();
+        let mut ids: HashMap<CI, usize> = indices
+            .iter()
+            .enumerate()
+            .map(|(id, idx)| (idx.clone(), id))
+            .collect();
+        let mut offsets = Vec::with_capacity(cell_count + 1);
+        let mut neighbors = Vec::new();
+        offsets.push(0);
+        for id in 0..cell_count {
+            for neighbor_idx in neighbor_manager.get_neighbors_idx(&indices[id]) {
+                let next_id = indices.len();
+                let neighbor_id = *ids.entry(neighbor_idx.clone()).or_insert(next_id);
+                if neighbor_id == next_id {
+                    indices.push(neighbor_idx);
+                }
+                neighbors.push(neighbor_id);
+            }
+            offsets.push(neighbors.len());
+        }
+        Self {
+            indices,
+            cell_count,
+            offsets,
+            neighbors,
+        }
+    }
+}
+
+impl<CI> NeighborTable<CI> {
+    /// Number of cells on the board.
+    pub fn cell_count(&self) -> usize {
+        self.cell_count
+    }
+
+    /// Number of cells on the board plus neighbors outside of it.
+    pub fn index_count(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn idx(&self, id: usize) -> &CI {
+        &self.indices[id]
+    }
+
+    pub fn neighbors(&self, id: usize) -> &[usize] {
+        &self.neighbors[self.offsets[id]..self.offsets[id + 1]]
+    }
+
+    pub fn offsets(&self) -> &[usize] {
+        &self.offsets
+    }
+}
+
+#[cfg(test)]
+mod neighbor_table_test {
+    use crate::{
+        util::grid_util::Shape2D, Board, BoardNeighborManager, DecayLifeLikeStrategy, Grid,
+        GridFactory, GridPoint2D, NeighborMoore, NeighborMooreDonut, NeighborTable,
+        SecondOrderStrategy, SharedStrategyManager, SparseStates, StandardBoard,
+    };
+    use std::collections::{HashMap, HashSet};
+
+    #[test]
+    fn neighbor_table_test_bounded() {
+        let space = Grid::<GridPoint2D<i32>>::new(vec![3usize, 3].into_iter());
+        let neighbor = NeighborMoore::new();
+        let table = NeighborTable::new(&space, &neighbor);
+        assert_eq!(table.cell_count(), 9);
+        // The ring of 16 cells around the board.
+        assert_eq!(table.index_count(), 25);
+        assert_eq!(table.offsets().len(), 10);
+        for id in 0..table.cell_count() {
+            let expected: HashSet<GridPoint2D<i32>> =
+                neighbor.get_neighbors_idx(table.idx(id)).collect();
+            let res: HashSet<GridPoint2D<i32>> = table
+                .neighbors(id)
+                .iter()
+                .map(|neighbor_id| table.idx(*neighbor_id).clone())
+                .collect();
+            assert_eq!(res, expected);
+        }
+    }
+
+    #[test]
+    fn neighbor_table_test_same_generations() {
+        let glider: HashMap<GridPoint2D<i32>, u8> = vec![(0, -1), (1, 0), (-1, 1), (0, 1), (1, 1)]
+            .into_iter()
+            .map(|(x, y)| (GridPoint2D::new(x, y), 1))
+            .collect();
+        let new_board = || {
+            StandardBoard::new(
+                Box::new(Grid::<GridPoint2D<i32>>::new(vec![6usize, 6].into_iter())),
+                Box::new(NeighborMooreDonut::new(Shape2D::new(6, 6))),
+                Box::new(SparseStates::new(0u8, glider.clone())),
+                Box::new(SharedStrategyManager::new(Box::new(
+                    DecayLifeLikeStrategy::gol(),
+                ))),
+                Vec::new(),
+            )
+        };
+        let mut board = new_board();
+        let mut board_with_table = new_board().with_neighbor_table();
+        for _ in 0..30 {
+            let mut states = board.advance_one_generation().to_vec();
+            let mut states_with_table = board_with_table.advance_one_generation().to_vec();
+            states.sort_by_key(|(idx, _)| (idx.x, idx.y));
+            states_with_table.sort_by_key(|(idx, _)| (idx.x, idx.y));
+            assert_eq!(states, states_with_table);
+        }
+    }
+
+    #[test]
+    fn neighbor_table_test_reverse() {
+        let initial_states: HashMap<GridPoint2D<i32>, u8> =
+            vec![(0, -1), (1, 0), (-1, 1), (0, 1), (1, 1), (3, 3)]
+                .into_iter()
+                .map(|(x, y)| (GridPoint2D::new(x, y), 1))
+                .collect();
+        let mut board = StandardBoard::new(
+            Box::new(Grid::<GridPoint2D<i32>>::new(vec![10usize, 10].into_iter())),
+            Box::new(NeighborMoore::new()),
+            Box::new(SparseStates::new(0u8, initial_states.clone())),
+            Box::new(SharedStrategyManager::new(Box::new(
+                SecondOrderStrategy::from_life_like(DecayLifeLikeStrategy::gol()),
+            ))),
+            Vec::new(),
+        )
+        .with_neighbor_table();
+        let alive = |states: &[(GridPoint2D<i32>, u8)]| -> HashSet<GridPoint2D<i32>> {
+            states
+                .iter()
+                .filter(|(_, state)| *state == 1)
+                .map(|(idx, _)| idx.clone())
+                .collect()
+        };
+        for _ in 0..12 {
+            board.advance_one_generation();
+        }
+        board.reverse();
+        let mut states = board.advance_one_generation();
+        // The first generation backwards is the one before the last.
+        for _ in 0..10 {
+            states = board.advance_one_generation();
+        }
+        assert_eq!(alive(&states), initial_states.keys().cloned().collect());
+    }
+}
@@ -0,0 +1,78 @@
+use super::util::PointPrimInt;
+use crate::{BoardNeighborManager, GridPoint2D, GridPoint3D};
+
+/// Orthogonally adjacent cells only, 4 neighbors in 2D and 6 neighbors in 3D.
+pub struct NeighborVonNeumann {}
+
+impl NeighborVonNeumann {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Default for NeighborVonNeumann {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> BoardNeighborManager<GridPoint3D<T>, std::vec::IntoIter<GridPoint3D<T>>>
+    for NeighborVonNeumann
+where
+    T: PointPrimInt,
+{
+    fn get_neighbors_idx(&self, idx: &GridPoint3D<T>) -> std::vec::IntoIter<GridPoint3D<T>> {
+        let one_t = T::one();
+        vec![
+            GridPoint3D::new(idx.x - one_t, idx.y, idx.z),
+            GridPoint3D::new(idx.x + one_t, idx.y, idx.z),
+            GridPoint3D::new(idx.x, idx.y - one_t, idx.z),
+            GridPoint3D::new(idx.x, idx.y + one_t, idx.z),
+            GridPoint3D::new(idx.x, idx.y, idx.z - one_t),
+            GridPoint3D::new(idx.x, idx.y, idx.z + one_t),
+        ]
+        .into_iter()
+    }
+}
+
+impl<T> BoardNeighborManager<GridPoint2D<T>, std::vec::IntoIter<GridPoint2D<T>>>
+    for NeighborVonNeumann
+where
+    T: PointPrimInt,
+{
+    fn get_neighbors_idx(&self, idx: &GridPoint2D<T>) -> std::vec::IntoIter<GridPoint2D<T>> {
+        let one_t = T::one();
+        vec![
+            GridPoint2D::new(idx.x - one_t, idx.y),
+            GridPoint2D::new(idx.x + one_t, idx.y),
+            GridPoint2D::new(idx.x, idx.y - one_t),
+            GridPoint2D::new(idx.x, idx.y + one_t),
+        ]
+        .into_iter()
+    }
+}
+
+#[cfg(test)]
+mod von_neumann_neighbor_test {
+    use crate::{BoardNeighborManager, GridPoint2D, GridPoint3D, NeighborVonNeumann};
+
+    #[test]
+    fn von_neumann_test_2d() {
+        let neighbor_calc = NeighborVonNeumann::new();
+        let point = GridPoint2D { x: 10, y: 5 };
+        let neighbors: Vec<GridPoint2D<i32>> = neighbor_calc.get_neighbors_idx(&point).collect();
+        assert_eq!(neighbors.len(), 4);
+        assert!(!neighbors.contains(&point));
+        assert!(neighbors.contains(&GridPoint2D { x: 10, y: 6 }));
+        assert!(!neighbors.contains(&GridPoint2D { x: 11, y: 6 }));
+    }
+
+    #[test]
+    fn von_neumann_test_3d() {
+        let neighbor_calc = NeighborVonNeumann::new();
+        let point = GridPoint3D { x: 0, y: 0, z: 0 };
+        let neighbors: Vec<GridPoint3D<i32>> = neighbor_calc.get_neighbors_idx(&point).collect();
+        assert_eq!(neighbors.len(), 6);
+        assert!(neighbors.contains(&GridPoint3D { x: 0, y: 0, z: -1 }));
+    }
+}
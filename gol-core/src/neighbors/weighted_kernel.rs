@@ -0,0 +1,207 @@
+use super::util::PointPrimInt;
+use super::weighted_stencil::{shortest_offset, wrap_idx};
+use crate::{util::grid_util::Shape2D, BoardNeighborManager, GridPoint2D, NeighborsGridSurround};
+use std::convert::TryFrom;
+
+/// Neighborhood given by a matrix of weights centered on the cell, for totalistic rules that
+/// count some neighbors more than others. Row `i` and column `j` of the matrix are the offsets
+/// `i - rows / 2` along y and `j - columns / 2` along x, cells with zero weight are not
+/// neighbors and the center weight is ignored.
+#[derive(Clone, Debug)]
+pub struct NeighborsWeightedKernel {
+    weights: Vec<Vec<f64>>,
+    surround: NeighborsGridSurround<usize>,
+    wrap_shape: Option<Shape2D>,
+}
+
+impl NeighborsWeightedKernel {
+    /// Panics if the rows have different lengths or if either side of the matrix is even.
+    /// ```rust
+    /// use gol_core::{BoardNeighborManager, GridPoint2D, NeighborsWeightedKernel};
+    ///
+    /// let kernel = NeighborsWeightedKernel::new(vec![
+    ///     vec![0., 1., 0.],
+    ///     vec![1., 0., 1.],
+    ///     vec![0., 1., 0.],
+    /// ]);
+    /// let center = GridPoint2D::new(3, 3);
+    /// let neighbors: Vec<GridPoint2D<i32>> = kernel.get_neighbors_idx(&center).collect();
+    /// assert_eq!(neighbors.len(), 4);
+    /// ```
+    pub fn new(weights: Vec<Vec<f64>>) -> Self {
+        let height = weights.len();
+        let width = weights.first().map_or(0, |row| row.len());
+        assert!(
+            height % 2 == 1 && width % 2 == 1,
+            "Kernel sides must be odd to have a center cell."
+        );
+        assert!(
+            weights.iter().all(|row| row.len() == width),
+            "Kernel rows must have the same length."
+        );
+        let margins = [(width / 2, width / 2), (height / 2, height / 2)];
+        Self {
+            weights,
+            surround: NeighborsGridSurround::new_with_variable_margin(margins.iter()),
+            wrap_shape: None,
+        }
+    }
+
+    /// Moore neighborhood where adjacent and diagonal cells have different weights.
+    pub fn orthogonal_diagonal(orthogonal: f64, diagonal: f64) -> Self {
+        Self::new(vec![
+            vec![diagonal, orthogonal, diagonal],
+            vec![orthogonal, 0., orthogonal],
+            vec![diagonal, orthogonal, diagonal],
+        ])
+    }
+
+    /// Cells within `radius` weighted by a Gaussian of their distance, the closest neighbors
+    /// have a weight of about one.
+    pub fn gaussian(radius: usize, sigma: f64) -> Self {
+        let radius_i = radius as i64;
+        let weights = (-radius_i..=radius_i)
+            .map(|dy| {
+                (-radius_i..=radius_i)
+                    .map(|dx| {
+                        let dist_sq = (dx * dx + dy * dy) as f64;
+                        if dist_sq > (radius * radius) as f64 {
+                            0.
+                        } else {
+                            (-(dist_sq - 1.) / (2. * sigma * sigma)).exp()
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        Self::new(weights)
+    }
+
+    /// Wraps neighbors around the edges of a board with the given shape.
+    pub fn with_wrap(self, board_shape: Shape2D) -> Self {
+        let mut res = self;
+        res.wrap_shape = Some(board_shape);
+        res
+    }
+
+    pub fn weights(&self) -> &Vec<Vec<f64>> {
+        &self.weights
+    }
+
+    /// Weight of `neighbor` relative to `center`, zero if it is outside of the kernel.
+    pub fn weight_of<T>(&self, center: &GridPoint2D<T>, neighbor: &GridPoint2D<T>) -> f64
+    where
+        T: PointPrimInt,
+    {
+        let mut dx = neighbor.x.to_i64().unwrap() - center.x.to_i64().unwrap();
+        let mut dy = neighbor.y.to_i64().unwrap() - center.y.to_i64().unwrap();
+        if let Some(shape) = &self.wrap_shape {
+            dx = shortest_offset(dx, shape.width() as i64);
+            dy = shortest_offset(dy, shape.height() as i64);
+        }
+        if dx == 0 && dy == 0 {
+            return 0.;
+        }
+        let row = usize::try_from(dy + (self.weights.len() / 2) as i64).ok();
+        let col = usize::try_from(dx + (self.weights[0].len() / 2) as i64).ok();
+        match (row, col) {
+            (Some(row), Some(col)) => self
+                .weights
+                .get(row)
+                .and_then(|weights| weights.get(col))
+                .cloned()
+                .unwrap_or(0.),
+            _ => 0.,
+        }
+    }
+}
+
+impl<T> BoardNeighborManager<GridPoint2D<T>, std::vec::IntoIter<GridPoint2D<T>>>
+    for NeighborsWeightedKernel
+where
+    T: PointPrimInt + TryFrom<usize>,
+{
+    fn get_neighbors_idx(&self, idx: &GridPoint2D<T>) -> std::vec::IntoIter<GridPoint2D<T>> {
+        let mut res: Vec<GridPoint2D<T>> = Vec::new();
+        for point in self.surround.get_neighbors_idx(idx) {
+            if self.weight_of(idx, &point) == 0. {
+                continue;
+            }
+            let point = match &self.wrap_shape {
+                Some(shape) => GridPoint2D::new(
+                    T::from_i64(wrap_idx(
+                        point.x.to_i64().unwrap(),
+                        shape.x_idx_min(),
+                        shape.width() as i64,
+                    ))
+                    .unwrap(),
+                    T::from_i64(wrap_idx(
+                        point.y.to_i64().unwrap(),
+                        shape.y_idx_min(),
+                        shape.height() as i64,
+                    ))
+                    .unwrap(),
+                ),
+                None => point,
+            };
+            // Kernels wider than a small board can wrap onto the same cell more than once.
+            if &point != idx && !res.contains(&point) {
+                res.push(point);
+            }
+        }
+        res.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod weighted_kernel_test {
+    use crate::{
+        util::grid_util::Shape2D, BoardNeighborManager, GridPoint2D, NeighborsWeightedKernel,
+    };
+
+    #[test]
+    fn weighted_kernel_test_offsets() {
+        let kernel = NeighborsWeightedKernel::new(vec![
+            vec![0., 0., 3., 0., 0.],
+            vec![0., 0., 9., 0., 0.],
+            vec![1., 0., 0., 2., 0.],
+        ]);
+        let center = GridPoint2D::new(10, -4);
+        let neighbors: Vec<GridPoint2D<i32>> = kernel.get_neighbors_idx(&center).collect();
+        assert_eq!(neighbors.len(), 3);
+        assert!(neighbors.contains(&GridPoint2D::new(10, -5)));
+        assert!(neighbors.contains(&GridPoint2D::new(8, -3)));
+        assert!(neighbors.contains(&GridPoint2D::new(11, -3)));
+        assert_eq!(kernel.weight_of(&center, &GridPoint2D::new(10, -5)), 3.);
+        assert_eq!(kernel.weight_of(&center, &GridPoint2D::new(11, -3)), 2.);
+        assert_eq!(kernel.weight_of(&center, &center), 0.);
+        assert_eq!(kernel.weight_of(&center, &GridPoint2D::new(13, -4)), 0.);
+    }
+
+    #[test]
+    fn weighted_kernel_test_wrap() {
+        let kernel =
+            NeighborsWeightedKernel::orthogonal_diagonal(2., 1.).with_wrap(Shape2D::new(4, 4));
+        let center = GridPoint2D::new(-2, 1);
+        let neighbors: Vec<GridPoint2D<i32>> = kernel.get_neighbors_idx(&center).collect();
+        assert_eq!(neighbors.len(), 8);
+        assert!(neighbors.contains(&GridPoint2D::new(1, 1)));
+        assert!(neighbors.contains(&GridPoint2D::new(1, -2)));
+        assert_eq!(kernel.weight_of(&center, &GridPoint2D::new(1, 1)), 2.);
+        assert_eq!(kernel.weight_of(&center, &GridPoint2D::new(1, -2)), 1.);
+    }
+
+    #[test]
+    fn weighted_kernel_test_gaussian() {
+        let kernel = NeighborsWeightedKernel::gaussian(3, 1.5);
+        let center = GridPoint2D::new(0, 0);
+        let neighbors: Vec<GridPoint2D<i64>> = kernel.get_neighbors_idx(&center).collect();
+        // Cells of the 7x7 square within distance 3, without the center.
+        assert_eq!(neighbors.len(), 28);
+        let weight = |x, y| kernel.weight_of(&center, &GridPoint2D::new(x, y));
+        assert!((weight(1, 0) - 1.).abs() < 1e-9);
+        assert_eq!(weight(0, -2), weight(2, 0));
+        assert!(weight(1, 1) < weight(1, 0) && weight(3, 0) < weight(1, 1));
+        assert_eq!(weight(3, 1), 0.);
+    }
+}
@@ -0,0 +1,165 @@
+use super::util::PointPrimInt;
+use crate::{util::grid_util::Shape2D, BoardNeighborManager, GridPoint2D};
+
+/// Neighbors with a weight for each relative offset, used by strategies that need a weighted sum
+/// over the neighborhood (e.g. a discrete Laplacian). The center cell is not a neighbor, its
+/// weight is kept separately.
+#[derive(Clone, Debug)]
+pub struct WeightedStencil2D {
+    center_weight: f64,
+    offsets: Vec<((i64, i64), f64)>,
+    wrap_shape: Option<Shape2D>,
+}
+
+impl WeightedStencil2D {
+    /// Creates a stencil with weights for relative offsets, offsets with zero weight are dropped.
+    /// ```rust
+    /// use gol_core::{BoardNeighborManager, GridPoint2D, WeightedStencil2D};
+    ///
+    /// let stencil = WeightedStencil2D::new(-2., vec![((-1, 0), 1.), ((1, 0), 1.), ((0, 1), 0.)]);
+    /// let neighbors: Vec<GridPoint2D<i32>> =
+    ///     stencil.get_neighbors_idx(&GridPoint2D::new(3, 3)).collect();
+    /// assert_eq!(neighbors.len(), 2);
+    /// ```
+    pub fn new(center_weight: f64, offsets: Vec<((i64, i64), f64)>) -> Self {
+        let offsets: Vec<((i64, i64), f64)> = offsets
+            .into_iter()
+            .filter(|(offset, weight)| offset != &(0, 0) && weight != &0.)
+            .collect();
+        Self {
+            center_weight,
+            offsets,
+            wrap_shape: None,
+        }
+    }
+
+    /// Five-point discrete Laplacian.
+    pub fn laplacian_5_point() -> Self {
+        Self::new(
+            -1.,
+            vec![
+                ((1, 0), 0.25),
+                ((-1, 0), 0.25),
+                ((0, 1), 0.25),
+                ((0, -1), 0.25),
+            ],
+        )
+    }
+
+    /// Nine-point discrete Laplacian, 0.2 for adjacent cells and 0.05 for diagonal cells.
+    pub fn laplacian_9_point() -> Self {
+        Self::new(
+            -1.,
+            vec![
+                ((1, 0), 0.2),
+                ((-1, 0), 0.2),
+                ((0, 1), 0.2),
+                ((0, -1), 0.2),
+                ((1, 1), 0.05),
+                ((1, -1), 0.05),
+                ((-1, 1), 0.05),
+                ((-1, -1), 0.05),
+            ],
+        )
+    }
+
+    /// Wraps neighbors around the edges of a board with the given shape.
+    pub fn with_wrap(self, board_shape: Shape2D) -> Self {
+        let mut res = self;
+        res.wrap_shape = Some(board_shape);
+        res
+    }
+
+    pub fn center_weight(&self) -> f64 {
+        self.center_weight
+    }
+
+    pub fn offsets(&self) -> &Vec<((i64, i64), f64)> {
+        &self.offsets
+    }
+
+    /// Weight of `neighbor` relative to `center`, zero if it is not part of the stencil.
+    pub fn weight_of<T>(&self, center: &GridPoint2D<T>, neighbor: &GridPoint2D<T>) -> f64
+    where
+        T: PointPrimInt,
+    {
+        let mut dx = neighbor.x.to_i64().unwrap() - center.x.to_i64().unwrap();
+        let mut dy = neighbor.y.to_i64().unwrap() - center.y.to_i64().unwrap();
+        if let Some(shape) = &self.wrap_shape {
+            dx = shortest_offset(dx, shape.width() as i64);
+            dy = shortest_offset(dy, shape.height() as i64);
+        }
+        self.offsets
+            .iter()
+            .find(|(offset, _)| offset == &(dx, dy))
+            .map_or(0., |(_, weight)| *weight)
+    }
+}
+
+impl<T> BoardNeighborManager<GridPoint2D<T>, std::vec::IntoIter<GridPoint2D<T>>>
+    for WeightedStencil2D
+where
+    T: PointPrimInt,
+{
+    fn get_neighbors_idx(&self, idx: &GridPoint2D<T>) -> std::vec::IntoIter<GridPoint2D<T>> {
+        let (x, y) = (idx.x.to_i64().unwrap(), idx.y.to_i64().unwrap());
+        let mut res: Vec<GridPoint2D<T>> = Vec::with_capacity(self.offsets.len());
+        for ((dx, dy), _) in self.offsets.iter() {
+            let (mut cur_x, mut cur_y) = (x + dx, y + dy);
+            if let Some(shape) = &self.wrap_shape {
+                cur_x = wrap_idx(cur_x, shape.x_idx_min(), shape.width() as i64);
+                cur_y = wrap_idx(cur_y, shape.y_idx_min(), shape.height() as i64);
+            }
+            let point = GridPoint2D::new(T::from_i64(cur_x).unwrap(), T::from_i64(cur_y).unwrap());
+            // Stencils wider than a small board can wrap onto the same cell more than once.
+            if &point != idx && !res.contains(&point) {
+                res.push(point);
+            }
+        }
+        res.into_iter()
+    }
+}
+
+pub(super) fn wrap_idx(idx: i64, idx_min: i64, len: i64) -> i64 {
+    (idx - idx_min).rem_euclid(len) + idx_min
+}
+
+pub(super) fn shortest_offset(offset: i64, len: i64) -> i64 {
+    let res = offset.rem_euclid(len);
+    if res > len / 2 {
+        res - len
+    } else {
+        res
+    }
+}
+
+#[cfg(test)]
+mod weighted_stencil_test {
+    use crate::{util::grid_util::Shape2D, BoardNeighborManager, GridPoint2D, WeightedStencil2D};
+
+    #[test]
+    fn weighted_stencil_test_1() {
+        let stencil = WeightedStencil2D::laplacian_9_point();
+        let center = GridPoint2D::new(0, 0);
+        let neighbors: Vec<GridPoint2D<i32>> = stencil.get_neighbors_idx(&center).collect();
+        assert_eq!(neighbors.len(), 8);
+        let total: f64 = neighbors
+            .iter()
+            .map(|ele| stencil.weight_of(&center, ele))
+            .sum();
+        assert!((total + stencil.center_weight()).abs() < 1e-9);
+        assert_eq!(stencil.weight_of(&center, &GridPoint2D::new(2, 0)), 0.);
+    }
+
+    #[test]
+    fn weighted_stencil_test_wrap() {
+        let stencil = WeightedStencil2D::laplacian_5_point().with_wrap(Shape2D::new(4, 4));
+        let center = GridPoint2D::new(-2, 1);
+        let neighbors: Vec<GridPoint2D<i32>> = stencil.get_neighbors_idx(&center).collect();
+        assert_eq!(neighbors.len(), 4);
+        assert!(neighbors.contains(&GridPoint2D::new(1, 1)));
+        assert!(neighbors.contains(&GridPoint2D::new(-2, -2)));
+        assert_eq!(stencil.weight_of(&center, &GridPoint2D::new(1, 1)), 0.25);
+        assert_eq!(stencil.weight_of(&center, &GridPoint2D::new(-2, -2)), 0.25);
+    }
+}
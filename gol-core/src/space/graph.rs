@@ -0,0 +1,228 @@
+use crate::BoardSpaceManager;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rayon::prelude::*;
+use serde::de::Error;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+/// Undirected graph whose nodes are the cells of a board, node IDs go from 0 to
+/// `node_count() - 1`. Self loops and duplicated edges are dropped.
+#[derive(Clone, Debug)]
+pub struct Graph {
+    adjacency: Arc<Vec<Vec<usize>>>,
+    nodes: Arc<Vec<usize>>,
+}
+
+impl Graph {
+    /// Panics if a neighbor ID is not smaller than the number of nodes.
+    pub fn from_adjacency(adjacency: Vec<Vec<usize>>) -> Self {
+        let node_count = adjacency.len();
+        let edges = adjacency
+            .into_iter()
+            .enumerate()
+            .flat_map(|(node, neighbors)| neighbors.into_iter().map(move |other| (node, other)));
+        Self::from_edges(node_count, edges)
+    }
+
+    /// Panics if an edge has a node ID not smaller than `node_count`.
+    pub fn from_edges<I>(node_count: usize, edges: I) -> Self
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+    {
+        let mut sets = vec![BTreeSet::new(); node_count];
+        for (from, to) in edges {
+            assert!(
+                from < node_count && to < node_count,
+                "Edge ({}, {}) out of range for graph with {} nodes.",
+                from,
+                to,
+                node_count
+            );
+            if from != to {
+                sets[from].insert(to);
+                sets[to].insert(from);
+            }
+        }
+        Self::from_sets(sets)
+    }
+
+    fn from_sets(sets: Vec<BTreeSet<usize>>) -> Self {
+        Self {
+            nodes: Arc::new((0..sets.len()).collect()),
+            adjacency: Arc::new(
+                sets.into_iter()
+                    .map(|set| set.into_iter().collect())
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Parses a list of `[from, to]` pairs, the node count is the largest ID plus one.
+    pub fn from_edge_list_json(json: &str) -> Result<Self, serde_json::Error> {
+        let edges: Vec<(usize, usize)> = serde_json::from_str(json)?;
+        let node_count = edges
+            .iter()
+            .map(|(from, to)| from.max(to) + 1)
+            .max()
+            .unwrap_or(0);
+        Ok(Self::from_edges(node_count, edges))
+    }
+
+    /// Parses a list with the neighbor IDs of every node.
+    pub fn from_adjacency_json(json: &str) -> Result<Self, serde_json::Error> {
+        let adjacency: Vec<Vec<usize>> = serde_json::from_str(json)?;
+        let node_count = adjacency.len();
+        if let Some(id) = adjacency.iter().flatten().find(|id| **id >= node_count) {
+            return Err(serde_json::Error::custom(format!(
+                "neighbor {} out of range for graph with {} nodes",
+                id, node_count
+            )));
+        }
+        Ok(Self::from_adjacency(adjacency))
+    }
+
+    /// Small-world graph: a ring where every node links to its `degree` nearest nodes, then each
+    /// edge is rewired to a random node with probability `rewire_probability`.
+    pub fn watts_strogatz(
+        node_count: usize,
+        degree: usize,
+        rewire_probability: f64,
+        seed: u64,
+    ) -> Self {
+        assert!(
+            degree & 1 == 0 && degree < node_count,
+            "Watts-Strogatz degree must be even and smaller than the number of nodes."
+        );
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut sets = vec![BTreeSet::new(); node_count];
+        for node in 0..node_count {
+            for offset in 1..=degree / 2 {
+                let other = (node + offset) % node_count;
+                sets[node].insert(other);
+                sets[other].insert(node);
+            }
+        }
+        for offset in 1..=degree / 2 {
+            for node in 0..node_count {
+                let other = (node + offset) % node_count;
+                // Skip when the node is already linked to every other node.
+                if !rng.gen_bool(rewire_probability) || sets[node].len() + 1 >= node_count {
+                    continue;
+                }
+                let new_other = loop {
+                    let candidate = rng.gen_range(0..node_count);
+                    if candidate != node && !sets[node].contains(&candidate) {
+                        break candidate;
+                    }
+                };
+                sets[node].remove(&other);
+                sets[other].remove(&node);
+                sets[node].insert(new_other);
+                sets[new_other].insert(node);
+            }
+        }
+        Self::from_sets(sets)
+    }
+
+    /// Random graph where every pair of nodes is linked with probability `edge_probability`.
+    pub fn erdos_renyi(node_count: usize, edge_probability: f64, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut edges = Vec::new();
+        for from in 0..node_count {
+            for to in from + 1..node_count {
+                if rng.gen_bool(edge_probability) {
+                    edges.push((from, to));
+                }
+            }
+        }
+        Self::from_edges(node_count, edges)
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.adjacency.len()
+    }
+
+    /// Sorted IDs of the nodes linked to `node`.
+    pub fn neighbors(&self, node: usize) -> &[usize] {
+        &self.adjacency[node]
+    }
+
+    /// Every edge once, with the smaller node ID first.
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        self.adjacency
+            .iter()
+            .enumerate()
+            .flat_map(|(node, neighbors)| {
+                neighbors
+                    .iter()
+                    .filter(move |other| **other > node)
+                    .map(move |other| (node, *other))
+            })
+            .collect()
+    }
+}
+
+impl BoardSpaceManager<usize, std::vec::IntoIter<usize>, rayon::vec::IntoIter<usize>> for Graph {
+    fn indices_iter(&self) -> std::vec::IntoIter<usize> {
+        (0..self.node_count()).collect::<Vec<usize>>().into_iter()
+    }
+
+    fn indices_par_iter(&self) -> rayon::vec::IntoIter<usize> {
+        (0..self.node_count())
+            .collect::<Vec<usize>>()
+            .into_par_iter()
+    }
+
+    fn indices(&self) -> &[usize] {
+        &self.nodes
+    }
+}
+
+#[cfg(test)]
+mod graph_test {
+    use crate::{BoardSpaceManager, Graph};
+
+    #[test]
+    fn graph_test_edge_list_json() {
+        let graph = Graph::from_edge_list_json("[[0, 1], [1, 2], [2, 1], [3, 3]]").unwrap();
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.neighbors(1), &[0, 2]);
+        assert!(graph.neighbors(3).is_empty());
+        assert_eq!(graph.edges(), vec![(0, 1), (1, 2)]);
+        assert_eq!(graph.indices_iter().count(), 4);
+    }
+
+    #[test]
+    fn graph_test_adjacency_json() {
+        let graph = Graph::from_adjacency_json("[[1, 2], [], [3], []]").unwrap();
+        assert_eq!(graph.neighbors(0), &[1, 2]);
+        assert_eq!(graph.neighbors(3), &[2]);
+        assert!(Graph::from_adjacency_json("[[1], [5]]").is_err());
+    }
+
+    #[test]
+    fn graph_test_watts_strogatz() {
+        let ring = Graph::watts_strogatz(10, 4, 0., 0);
+        assert_eq!(ring.neighbors(0), &[1, 2, 8, 9]);
+        assert_eq!(ring.edges().len(), 20);
+
+        let graph = Graph::watts_strogatz(100, 4, 0.3, 42);
+        assert_eq!(graph.edges().len(), 200);
+        assert_ne!(graph.edges(), Graph::watts_strogatz(100, 4, 0., 42).edges());
+        assert_eq!(
+            graph.edges(),
+            Graph::watts_strogatz(100, 4, 0.3, 42).edges()
+        );
+    }
+
+    #[test]
+    fn graph_test_erdos_renyi() {
+        let graph = Graph::erdos_renyi(200, 0.05, 7);
+        let edge_count = graph.edges().len();
+        // Expected 995 edges.
+        assert!(edge_count > 850 && edge_count < 1150);
+        assert_eq!(Graph::erdos_renyi(200, 0.05, 7).edges().len(), edge_count);
+        assert!(Graph::erdos_renyi(20, 0., 7).edges().is_empty());
+        assert_eq!(Graph::erdos_renyi(20, 1., 7).edges().len(), 190);
+    }
+}
@@ -32,6 +32,10 @@ where
     fn indices_par_iter(&self) -> rayon::vec::IntoIter<T> {
         self.indices.clone().into_par_iter()
     }
+
+    fn indices(&self) -> &[T] {
+        &self.indices
+    }
 }
 
 impl<T, U, I> GridFactory<GridPointND<T>, U, I> for Grid<GridPointND<T>>
@@ -74,13 +78,13 @@ impl<T> Grid<GridPointND<T>> {
                         Ok(val) => val,
                         Err(_) => panic!("Cannot convert size to index type."),
                     };
-                    let dim_idx = cur_t / dim_t
+                    let dim_idx = cur_t % dim_t
                         - match &origin {
                             GridOrigin::Center => dim_t / two_t,
                             GridOrigin::Zero => zero_t,
                         };
                     res.push(dim_idx);
-                    cur = cur % *dim;
+                    cur = cur / *dim;
                 }
                 GridPointND::new(res.iter())
             })
@@ -192,6 +196,7 @@ mod grid_tests {
         GridPointND,
     };
     use rayon::prelude::*;
+    use std::collections::HashSet;
 
     #[test]
     fn grid_1d_test_1() {
@@ -316,4 +321,14 @@ mod grid_tests {
         assert_eq!(indices_2.len(), indices_1.len());
         assert_eq!(indices_par_2.len(), indices_1.len());
     }
+
+    #[test]
+    fn grid_nd_test_unique() {
+        type Point = GridPointND<i32>;
+        let grid = Grid::<Point>::new(vec![3usize, 4, 2].into_iter());
+        let indices: HashSet<Point> = grid.indices_iter().collect();
+        assert_eq!(indices.len(), 24);
+        assert!(indices.contains(&GridPointND::new(vec![-1, -2, -1].iter())));
+        assert!(indices.contains(&GridPointND::new(vec![1, 1, 0].iter())));
+    }
 }
@@ -1 +1,3 @@
+pub mod graph;
 pub mod grid;
+pub mod tiling;
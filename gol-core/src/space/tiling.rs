@@ -0,0 +1,343 @@
+use crate::{BoardSpaceManager, Graph};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Vertices closer than this are treated as the same point when matching tiles.
+const VERTEX_TOLERANCE: f64 = 1e-9;
+
+type Point = (f64, f64);
+/// Rounded end points of an edge, the smaller one first.
+type EdgeKey = ((i64, i64), (i64, i64));
+/// Half of a Penrose rhombus: whether it is thick, then its corners.
+type RobinsonTriangle = (bool, Point, Point, Point);
+/// Corner of a Voronoi cell with the site sharing the edge that starts at it, if any.
+type CellCorner = (Point, Option<usize>);
+
+/// Plane covered by convex polygon tiles, tile IDs go from 0 to `tile_count() - 1`. Neighbors
+/// are tiles sharing an edge (`edge_graph`) or at least a corner (`vertex_graph`).
+#[derive(Clone, Debug)]
+pub struct Tiling {
+    tiles: Arc<Vec<Vec<(f64, f64)>>>,
+    edge_graph: Graph,
+    vertex_graph: Graph,
+}
+
+impl Tiling {
+    /// Tiles are convex polygons with vertices in order, adjacency is found by matching vertices.
+    pub fn from_polygons(tiles: Vec<Vec<(f64, f64)>>) -> Self {
+        let mut vertex_tiles: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+        let mut edge_tiles: HashMap<EdgeKey, Vec<usize>> = HashMap::new();
+        for (tile, polygon) in tiles.iter().enumerate() {
+            for (i, point) in polygon.iter().enumerate() {
+                let (from, to) = (
+                    vertex_key(point),
+                    vertex_key(&polygon[(i + 1) % polygon.len()]),
+                );
+                vertex_tiles.entry(from).or_default().push(tile);
+                edge_tiles
+                    .entry((from.min(to), from.max(to)))
+                    .or_default()
+                    .push(tile);
+            }
+        }
+        let pairs = |groups: Vec<Vec<usize>>| -> Vec<(usize, usize)> {
+            groups
+                .into_iter()
+                .flat_map(|group| {
+                    let mut res = Vec::new();
+                    for i in 0..group.len() {
+                        for j in i + 1..group.len() {
+                            res.push((group[i], group[j]));
+                        }
+                    }
+                    res
+                })
+                .collect()
+        };
+        let tile_count = tiles.len();
+        Self {
+            edge_graph: Graph::from_edges(tile_count, pairs(edge_tiles.into_values().collect())),
+            vertex_graph: Graph::from_edges(
+                tile_count,
+                pairs(vertex_tiles.into_values().collect()),
+            ),
+            tiles: Arc::new(tiles),
+        }
+    }
+
+    /// Penrose P3 tiling of thin and thick rhombi inside the unit circle, built by subdividing a
+    /// wheel of Robinson triangles `subdivisions` times. Rhombi cut by the border are dropped.
+    pub fn penrose_rhombus(subdivisions: usize) -> Self {
+        let golden_ratio = (1. + 5f64.sqrt()) / 2.;
+        let lerp = |from: (f64, f64), to: (f64, f64), ratio: f64| {
+            (
+                from.0 + (to.0 - from.0) * ratio,
+                from.1 + (to.1 - from.1) * ratio,
+            )
+        };
+        let point_at = |angle: f64| (angle.cos(), angle.sin());
+
+        // Each triangle is half of a rhombus, mirrored across its edge from `b` to `c`.
+        let mut triangles: Vec<RobinsonTriangle> = (0..10)
+            .map(|i| {
+                let angle = std::f64::consts::PI / 10.;
+                let (mut b, mut c) = (
+                    point_at((2 * i - 1) as f64 * angle),
+                    point_at((2 * i + 1) as f64 * angle),
+                );
+                if i % 2 == 0 {
+                    std::mem::swap(&mut b, &mut c);
+                }
+                (false, (0., 0.), b, c)
+            })
+            .collect();
+        for _ in 0..subdivisions {
+            triangles = triangles
+                .into_iter()
+                .flat_map(|(is_thick, a, b, c)| {
+                    if !is_thick {
+                        let p = lerp(a, b, 1. / golden_ratio);
+                        vec![(false, c, p, b), (true, p, c, a)]
+                    } else {
+                        let q = lerp(b, a, 1. / golden_ratio);
+                        let r = lerp(b, c, 1. / golden_ratio);
+                        vec![(true, r, c, a), (true, q, r, b), (false, r, q, a)]
+                    }
+                })
+                .collect();
+        }
+
+        let mut halves: HashMap<EdgeKey, Vec<usize>> = HashMap::new();
+        for (i, (_, _, b, c)) in triangles.iter().enumerate() {
+            let (b_key, c_key) = (vertex_key(b), vertex_key(c));
+            halves
+                .entry((b_key.min(c_key), b_key.max(c_key)))
+                .or_default()
+                .push(i);
+        }
+        let mut rhombi: Vec<(usize, Vec<(f64, f64)>)> = halves
+            .into_values()
+            .filter(|group| group.len() == 2)
+            .map(|group| {
+                let (_, a, b, c) = triangles[group[0]];
+                let mirrored = triangles[group[1]].1;
+                (group[0].min(group[1]), vec![a, b, mirrored, c])
+            })
+            .collect();
+        // Hash map order is random, keep tile IDs stable.
+        rhombi.sort_by_key(|(first_half, _)| *first_half);
+        Self::from_polygons(rhombi.into_iter().map(|(_, rhombus)| rhombus).collect())
+    }
+
+    /// Voronoi cells of `tile_count` random sites in the square from -1 to 1. Cells meet three at
+    /// a corner, so the vertex graph is the same as the edge graph.
+    pub fn voronoi(tile_count: usize, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let sites: Vec<(f64, f64)> = (0..tile_count)
+            .map(|_| (rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)))
+            .collect();
+        let cells: Vec<Vec<CellCorner>> = (0..tile_count)
+            .into_par_iter()
+            .map(|site| voronoi_cell(&sites, site))
+            .collect();
+        let edges: Vec<(usize, usize)> = cells
+            .iter()
+            .enumerate()
+            .flat_map(|(site, cell)| {
+                cell.iter()
+                    .filter_map(move |(_, other)| other.map(|other| (site, other)))
+            })
+            .collect();
+        let graph = Graph::from_edges(tile_count, edges);
+        Self {
+            tiles: Arc::new(
+                cells
+                    .into_iter()
+                    .map(|cell| cell.into_iter().map(|(point, _)| point).collect())
+                    .collect(),
+            ),
+            edge_graph: graph.clone(),
+            vertex_graph: graph,
+        }
+    }
+
+    pub fn tile_count(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// Vertices of a tile in order around it.
+    pub fn tile(&self, idx: usize) -> &[(f64, f64)] {
+        &self.tiles[idx]
+    }
+
+    /// Tiles sharing an edge.
+    pub fn edge_graph(&self) -> &Graph {
+        &self.edge_graph
+    }
+
+    /// Tiles sharing at least one corner.
+    pub fn vertex_graph(&self) -> &Graph {
+        &self.vertex_graph
+    }
+}
+
+impl BoardSpaceManager<usize, std::vec::IntoIter<usize>, rayon::vec::IntoIter<usize>> for Tiling {
+    fn indices_iter(&self) -> std::vec::IntoIter<usize> {
+        self.edge_graph.indices_iter()
+    }
+
+    fn indices_par_iter(&self) -> rayon::vec::IntoIter<usize> {
+        self.edge_graph.indices_par_iter()
+    }
+
+    fn indices(&self) -> &[usize] {
+        self.edge_graph.indices()
+    }
+}
+
+fn vertex_key(point: &(f64, f64)) -> (i64, i64) {
+    (
+        (point.0 / VERTEX_TOLERANCE).round() as i64,
+        (point.1 / VERTEX_TOLERANCE).round() as i64,
+    )
+}
+
+/// Cell of `site` as vertices, each paired with the site across the edge starting at it, `None`
+/// for edges on the border of the square.
+fn voronoi_cell(sites: &[(f64, f64)], site: usize) -> Vec<CellCorner> {
+    let center = sites[site];
+    let mut cell = vec![
+        ((-1., -1.), None),
+        ((1., -1.), None),
+        ((1., 1.), None),
+        ((-1., 1.), None),
+    ];
+    let dist_sq = |point: (f64, f64)| (point.0 - center.0).powi(2) + (point.1 - center.1).powi(2);
+    let mut others: Vec<usize> = (0..sites.len()).filter(|other| *other != site).collect();
+    others.sort_by(|a, b| dist_sq(sites[*a]).partial_cmp(&dist_sq(sites[*b])).unwrap());
+    for other in others {
+        let radius_sq = cell
+            .iter()
+            .map(|(point, _)| dist_sq(*point))
+            .fold(0., f64::max);
+        // Bisectors of farther sites cannot reach the cell anymore.
+        if dist_sq(sites[other]) > 4. * radius_sq {
+            break;
+        }
+        cell = clip_cell(&cell, center, sites[other], other);
+    }
+    cell
+}
+
+/// Keeps the part of `cell` closer to `center` than to `other_site`.
+fn clip_cell(
+    cell: &[CellCorner],
+    center: (f64, f64),
+    other_site: (f64, f64),
+    other: usize,
+) -> Vec<CellCorner> {
+    let normal = (other_site.0 - center.0, other_site.1 - center.1);
+    let mid = (
+        (center.0 + other_site.0) / 2.,
+        (center.1 + other_site.1) / 2.,
+    );
+    let side = |point: (f64, f64)| (point.0 - mid.0) * normal.0 + (point.1 - mid.1) * normal.1;
+    let mut res = Vec::new();
+    for (i, (cur, label)) in cell.iter().enumerate() {
+        let next = cell[(i + 1) % cell.len()].0;
+        let (cur_side, next_side) = (side(*cur), side(next));
+        let crossing = || {
+            let ratio = cur_side / (cur_side - next_side);
+            (
+                cur.0 + (next.0 - cur.0) * ratio,
+                cur.1 + (next.1 - cur.1) * ratio,
+            )
+        };
+        if cur_side <= 0. {
+            res.push((*cur, *label));
+            if next_side > 0. {
+                res.push((crossing(), Some(other)));
+            }
+        } else if next_side <= 0. {
+            res.push((crossing(), *label));
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tiling_test {
+    use crate::{BoardSpaceManager, Tiling};
+
+    fn area(polygon: &[(f64, f64)]) -> f64 {
+        let mut res = 0.;
+        for (i, (x, y)) in polygon.iter().enumerate() {
+            let (next_x, next_y) = polygon[(i + 1) % polygon.len()];
+            res += x * next_y - next_x * y;
+        }
+        res.abs() / 2.
+    }
+
+    #[test]
+    fn tiling_test_from_polygons() {
+        // 2x2 squares, diagonal squares only share a corner.
+        let square = |x: f64, y: f64| vec![(x, y), (x + 1., y), (x + 1., y + 1.), (x, y + 1.)];
+        let tiling = Tiling::from_polygons(vec![
+            square(0., 0.),
+            square(1., 0.),
+            square(0., 1.),
+            square(1., 1.),
+        ]);
+        assert_eq!(tiling.tile_count(), 4);
+        assert_eq!(tiling.edge_graph().neighbors(0), &[1, 2]);
+        assert_eq!(tiling.vertex_graph().neighbors(0), &[1, 2, 3]);
+        assert_eq!(tiling.indices_iter().count(), 4);
+    }
+
+    #[test]
+    fn tiling_test_penrose() {
+        let tiling = Tiling::penrose_rhombus(5);
+        assert!(tiling.tile_count() > 100);
+        let side = (0..4)
+            .map(|i| {
+                let (a, b) = (tiling.tile(0)[i], tiling.tile(0)[(i + 1) % 4]);
+                ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+            })
+            .collect::<Vec<f64>>();
+        assert!(side.iter().all(|len| (len - side[0]).abs() < 1e-9));
+        for tile in 0..tiling.tile_count() {
+            // Thin and thick rhombi have angles of 36 and 72 degrees.
+            let ratio = area(tiling.tile(tile)) / side[0].powi(2);
+            let thin = (36f64).to_radians().sin();
+            let thick = (72f64).to_radians().sin();
+            assert!((ratio - thin).abs() < 1e-6 || (ratio - thick).abs() < 1e-6);
+            assert!(tiling.edge_graph().neighbors(tile).len() <= 4);
+            assert!(tiling.vertex_graph().neighbors(tile).len() <= 11);
+        }
+        // Most inner rhombi share all four edges.
+        let full = (0..tiling.tile_count())
+            .filter(|tile| tiling.edge_graph().neighbors(*tile).len() == 4)
+            .count();
+        assert!(full * 2 > tiling.tile_count());
+    }
+
+    #[test]
+    fn tiling_test_voronoi() {
+        let tiling = Tiling::voronoi(200, 11);
+        assert_eq!(tiling.tile_count(), 200);
+        let total: f64 = (0..200).map(|tile| area(tiling.tile(tile))).sum();
+        assert!((total - 4.).abs() < 1e-9);
+        let degrees: Vec<usize> = (0..200)
+            .map(|tile| tiling.edge_graph().neighbors(tile).len())
+            .collect();
+        // Average degree of a planar Voronoi diagram is just below six.
+        let mean = degrees.iter().sum::<usize>() as f64 / 200.;
+        assert!(mean > 5. && mean < 6.);
+        assert_eq!(
+            tiling.edge_graph().edges(),
+            Tiling::voronoi(200, 11).edge_graph().edges()
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use super::grid_2d::{make_transform, run_shapes_window, ColorRGBA, PushConstants};
+use crate::{
+    renderer::{
+        board_info::RendererBoardInfo, graph_layout::force_directed_layout,
+        keyboard_control::KeyboardControl,
+    },
+    CellularAutomatonRenderer, StateVisualMapping,
+};
+use gfx_hal::{window::Extent2D, UnsupportedBackend};
+use gol_core::{Graph, StatesReadOnly};
+use rgb::RGBA16;
+
+const LAYOUT_ITERATIONS: usize = 100;
+const EDGE_COLOR: [f32; 4] = [0.3, 0.3, 0.3, 1.0];
+const NODE_COLOR: [f32; 4] = [0.15, 0.15, 0.15, 1.0];
+
+/// Draws a graph board with a force-directed layout, edges as lines and nodes as squares colored
+/// by state.
+///
+/// Drag with the mouse to pan, scroll to zoom and double click to reset the view.
+pub struct GraphicalRendererGraph<T> {
+    info: RendererBoardInfo<usize>,
+    graph: Graph,
+    control: Option<KeyboardControl>,
+    states_read_only: StatesReadOnly<usize, T>,
+}
+
+impl<T> GraphicalRendererGraph<T>
+where
+    T: 'static + Send + Sync + Clone,
+{
+    pub fn new(
+        graph: Graph,
+        states_storage: StatesReadOnly<usize, T>,
+    ) -> Result<Self, UnsupportedBackend> {
+        let info = RendererBoardInfo::new(graph.node_count());
+        Ok(Self {
+            info,
+            graph,
+            control: None,
+            states_read_only: states_storage,
+        })
+    }
+
+    pub fn with_title(self, title: String) -> Self {
+        let mut res = self;
+        res.info.set_title(title);
+        res
+    }
+
+    pub fn with_keyboard_control(self, control: KeyboardControl) -> Self {
+        let mut res = self;
+        res.control = Some(control);
+        res
+    }
+}
+
+impl<T> CellularAutomatonRenderer<T, RGBA16> for GraphicalRendererGraph<T>
+where
+    T: 'static + Send + Sync + Clone,
+{
+    fn need_run_on_main(&self) -> bool {
+        true
+    }
+
+    fn run(&mut self, visual_mapping: Box<dyn StateVisualMapping<T, RGBA16>>) {
+        let positions = force_directed_layout(&self.graph, LAYOUT_ITERATIONS);
+        let edges = self.graph.edges();
+        let node_count = self.graph.node_count();
+        let states_read_only = self.states_read_only.clone();
+
+        let gen_shapes = move |surface_extent: &Extent2D, zoom: f32, dx: f32, dy: f32| {
+            let view = GraphView::new(surface_extent, node_count, zoom, dx, dy);
+            let lookup = loop {
+                if let Ok(val) = states_read_only.try_read() {
+                    break val.1.clone();
+                }
+            };
+
+            let mut res: Vec<PushConstants> = edges
+                .iter()
+                .map(|(from, to)| view.edge(positions[*from], positions[*to]))
+                .collect();
+            for (node, pos) in positions.iter().enumerate() {
+                let color = match lookup.get(&node) {
+                    Some(state) => {
+                        ColorRGBA::from_rgba16(&visual_mapping.to_visual(state)).to_array()
+                    }
+                    None => NODE_COLOR,
+                };
+                res.push(view.node(*pos, color));
+            }
+            res
+        };
+
+        run_shapes_window(
+            self.info.title().clone(),
+            self.control.clone(),
+            false,
+            |_| None,
+            gen_shapes,
+        );
+    }
+}
+
+/// Maps layout positions in [-1, 1] to normalized device coordinates of the current frame.
+struct GraphView {
+    scale_x: f32,
+    scale_y: f32,
+    dx: f32,
+    dy: f32,
+    node_len: f32,
+    edge_width: f32,
+}
+
+impl GraphView {
+    fn new(surface_extent: &Extent2D, node_count: usize, zoom: f32, dx: f32, dy: f32) -> Self {
+        let (width, height) = (surface_extent.width as f32, surface_extent.height as f32);
+        let min_len = width.min(height);
+        // Leave a margin so nodes on the border are fully visible.
+        let fit = 0.9 * zoom;
+        Self {
+            scale_x: min_len / width * fit,
+            scale_y: min_len / height * fit,
+            dx,
+            dy,
+            node_len: (0.5 / (node_count.max(1) as f32).sqrt()).min(0.05) * zoom,
+            edge_width: 1.5 / min_len,
+        }
+    }
+
+    fn to_screen(&self, pos: (f32, f32)) -> (f32, f32) {
+        (
+            pos.0 * self.scale_x + self.dx,
+            -pos.1 * self.scale_y + self.dy,
+        )
+    }
+
+    fn node(&self, pos: (f32, f32), color: [f32; 4]) -> PushConstants {
+        let (x, y) = self.to_screen(pos);
+        let (len_x, len_y) = (self.node_len * self.scale_x, self.node_len * self.scale_y);
+        PushConstants {
+            color,
+            transform: make_transform(x - len_x / 2., y - len_y / 2., len_x, len_y, 0.),
+        }
+    }
+
+    /// Thin quad from `from` to `to`, its sides are the edge direction and the normal turned
+    /// counterclockwise from it, which keeps the same winding as squares.
+    fn edge(&self, from: (f32, f32), to: (f32, f32)) -> PushConstants {
+        let (from_x, from_y) = self.to_screen(from);
+        let (to_x, to_y) = self.to_screen(to);
+        let (dir_x, dir_y) = (to_x - from_x, to_y - from_y);
+        let len = (dir_x * dir_x + dir_y * dir_y).sqrt().max(f32::EPSILON);
+        let (normal_x, normal_y) = (
+            -dir_y / len * self.edge_width,
+            dir_x / len * self.edge_width,
+        );
+        PushConstants {
+            color: EDGE_COLOR,
+            transform: [
+                [dir_x, dir_y, 0., 0.],
+                [normal_x, normal_y, 0., 0.],
+                [0., 0., 1., 0.],
+                [from_x - normal_x / 2., from_y - normal_y / 2., 0., 1.],
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod graph_renderer_test {
+    use crate::graphics::graph::GraphView;
+    use gfx_hal::window::Extent2D;
+
+    #[test]
+    fn graph_view_test_edge_winding() {
+        let extent = Extent2D {
+            width: 800,
+            height: 600,
+        };
+        let view = GraphView::new(&extent, 100, 1., 0., 0.);
+        for (from, to) in vec![((0., 0.), (1., 0.5)), ((0.3, 0.2), (-0.7, -0.9))] {
+            let transform = view.edge(from, to).transform;
+            let det = transform[0][0] * transform[1][1] - transform[0][1] * transform[1][0];
+            assert!(det > 0.);
+        }
+        let square = view.node((0., 0.), [1.; 4]).transform;
+        assert!(square[0][0] * square[1][1] > 0.);
+    }
+}
@@ -3,7 +3,7 @@ use crate::{
     renderer::{
         board_info::RendererBoardInfo, fps_counter::FPSCounter, keyboard_control::KeyboardControl,
     },
-    CellularAutomatonRenderer, StateVisualMapping,
+    CellularAutomatonRenderer, RenderIndex2D, SliceView, StateVisualMapping,
 };
 use gfx_hal::{
     adapter::PhysicalDevice,
@@ -25,14 +25,16 @@ use gfx_hal::{
     window::{Extent2D, PresentationSurface, Surface, SwapchainConfig},
     Instance, UnsupportedBackend,
 };
-use gol_core::{util::grid_util::Shape2D, GridPoint2D, StatesReadOnly};
-use num_traits::{CheckedSub, FromPrimitive, ToPrimitive};
+use gol_core::{util::grid_util::Shape2D, StatesReadOnly};
+use num_traits::ToPrimitive;
 use rayon::prelude::*;
 use rgb::RGBA16;
 use shaderc::ShaderKind;
 use std::borrow::Borrow;
+use std::cell::RefCell;
 use std::hash::Hash;
 use std::mem::ManuallyDrop;
+use std::rc::Rc;
 use std::time::Instant;
 use winit::{
     dpi::{LogicalSize, PhysicalPosition, PhysicalSize},
@@ -49,17 +51,19 @@ where
     fps_counter: FPSCounter, // TODO: show FPS
     states_read_only: StatesReadOnly<CI, T>,
     is_triangle: bool,
+    is_hex_offset: bool,
+    slice_view: Option<SliceView>,
 }
 
-impl<T, U> GraphicalRendererGrid2D<GridPoint2D<U>, T>
+impl<T, CI> GraphicalRendererGrid2D<CI, T>
 where
     T: 'static + Send + Sync + Clone,
-    U: 'static + Send + Sync + Clone + Ord + CheckedSub + ToPrimitive + FromPrimitive + Hash,
+    CI: 'static + Send + Sync + RenderIndex2D<T>,
 {
     pub fn new(
         board_width: usize,
         board_height: usize,
-        states_storage: StatesReadOnly<GridPoint2D<U>, T>,
+        states_storage: StatesReadOnly<CI, T>,
     ) -> Result<Self, UnsupportedBackend> {
         let info = RendererBoardInfo::new(Shape2D::new(board_width, board_height));
         Ok(Self {
@@ -68,6 +72,8 @@ where
             fps_counter: FPSCounter::new(240),
             states_read_only: states_storage,
             is_triangle: false,
+            is_hex_offset: false,
+            slice_view: None,
         })
     }
 
@@ -94,531 +100,592 @@ where
         res.is_triangle = true;
         res
     }
+
+    /// Shift every odd row half a cell to the right, used to render hexagonal boards stored in
+    /// "odd-r" offset coordinates.
+    pub fn with_hex_offset_rows(self) -> Self {
+        let mut res = self;
+        res.is_hex_offset = true;
+        res
+    }
+
+    /// Show a 2D slice of a board with more than two dimensions, the board size passed to `new`
+    /// is replaced by the slice display size.
+    pub fn with_slice_view(self, view: SliceView) -> Self {
+        let mut res = self;
+        res.info.set_board_shape(view.display_shape());
+        res.slice_view = Some(view);
+        res
+    }
 }
 
-impl<T, U> CellularAutomatonRenderer<T, RGBA16> for GraphicalRendererGrid2D<GridPoint2D<U>, T>
+impl<T, CI> CellularAutomatonRenderer<T, RGBA16> for GraphicalRendererGrid2D<CI, T>
 where
-    T: 'static + Send + Sync + Clone + Hash,
-    U: 'static + Send + Sync + Clone + Ord + CheckedSub + ToPrimitive + FromPrimitive + Hash,
+    T: 'static + Send + Sync + Clone,
+    CI: 'static + Send + Sync + RenderIndex2D<T>,
 {
     fn need_run_on_main(&self) -> bool {
         true
     }
 
     fn run(&mut self, visual_mapping: Box<dyn StateVisualMapping<T, RGBA16>>) {
-        let event_loop = EventLoop::new();
         let title = self.info.title().clone();
-
         let board_shape = self.info.board_shape().clone();
         let states_read_only = self.states_read_only.clone();
-        let mut control = self.control.clone();
-
-        let desired_aspect_ratio = 1.0;
+        let is_triangle = self.is_triangle;
+        let is_hex_offset = self.is_hex_offset;
+
+        // Shared between the key handler and the shape generator, both run on the event loop.
+        let slice_view = Rc::new(RefCell::new(self.slice_view.clone()));
+        let key_slice_view = Rc::clone(&slice_view);
+        let key_title = title.clone();
+        let on_key = move |ch: VirtualKeyCode| {
+            let slice_key = match ch {
+                VirtualKeyCode::X => 'x',
+                VirtualKeyCode::Y => 'y',
+                VirtualKeyCode::Z => 'z',
+                VirtualKeyCode::LBracket => '[',
+                VirtualKeyCode::RBracket => ']',
+                VirtualKeyCode::P => 'p',
+                _ => return None,
+            };
+            let mut view = key_slice_view.borrow_mut();
+            let view = view.as_mut()?;
+            if view.execute_user_input(slice_key) {
+                Some(window_title(&key_title, Some(view)))
+            } else {
+                None
+            }
+        };
 
-        let (logical_window_size, physical_window_size) =
-            get_window_size(&event_loop, desired_aspect_ratio, 0.8);
+        let gen_shapes = move |surface_extent: &Extent2D, zoom, translate_x, translate_y| {
+            let lookup = loop {
+                if let Ok(val) = states_read_only.try_read() {
+                    break CI::lookup_2d(&val.1, RefCell::borrow(&slice_view).as_ref());
+                }
+            };
 
-        let mut surface_extent = Extent2D {
-            width: physical_window_size.width,
-            height: physical_window_size.height,
+            let constants: Vec<((u32, u32), ColorRGBA)> = lookup
+                .par_iter()
+                .map(|(idx, state)| {
+                    let (x_min, y_min) = (board_shape.x_idx_min(), board_shape.y_idx_min());
+                    let ele_res: ((u32, u32), ColorRGBA) = (
+                        (
+                            (idx.x - x_min).to_u32().unwrap(),
+                            (idx.y - y_min).to_u32().unwrap(),
+                        ),
+                        ColorRGBA::from_rgba16(&visual_mapping.to_visual(&state)),
+                    );
+                    ele_res
+                })
+                .collect::<Vec<((u32, u32), ColorRGBA)>>();
+
+            if is_triangle {
+                create_triangles(
+                    surface_extent,
+                    board_shape.width() as u32,
+                    board_shape.height() as u32,
+                    constants,
+                    zoom,
+                    translate_x,
+                    translate_y,
+                )
+            } else {
+                create_squares(
+                    surface_extent,
+                    &board_shape,
+                    constants,
+                    zoom,
+                    translate_x,
+                    translate_y,
+                    is_hex_offset,
+                )
+            }
         };
 
-        let window = winit::window::WindowBuilder::new()
-            .with_title(title.as_str())
-            .with_inner_size(logical_window_size)
-            .build(&event_loop)
-            .expect("Failed to create window");
+        run_shapes_window(
+            window_title(&title, self.slice_view.as_ref()),
+            self.control.clone(),
+            is_triangle,
+            on_key,
+            gen_shapes,
+        );
+    }
+}
 
-        let (instance, surface, adapter) = {
-            let instance =
-                backend::Instance::create(title.as_str(), 1).expect("Backend not supported");
+/// Opens a window and draws the shapes from `gen_shapes` every frame, either all triangles or
+/// all squares. Dragging pans, scrolling zooms and double clicking resets the view; released
+/// keys go to `on_key`, which may return a new window title, then to the keyboard control.
+pub(super) fn run_shapes_window<K, S>(
+    title: String,
+    control: Option<KeyboardControl>,
+    is_triangle: bool,
+    mut on_key: K,
+    mut gen_shapes: S,
+) -> !
+where
+    K: 'static + FnMut(VirtualKeyCode) -> Option<String>,
+    S: 'static + FnMut(&Extent2D, f32, f32, f32) -> Vec<PushConstants>,
+{
+    let event_loop = EventLoop::new();
+    let mut control = control;
 
-            let surface = unsafe {
-                instance
-                    .create_surface(&window)
-                    .expect("Failed to create surface for window")
-            };
+    let desired_aspect_ratio = 1.0;
 
-            let adapter = instance.enumerate_adapters().remove(0);
+    let (logical_window_size, physical_window_size) =
+        get_window_size(&event_loop, desired_aspect_ratio, 0.8);
 
-            (instance, surface, adapter)
-        };
+    let mut surface_extent = Extent2D {
+        width: physical_window_size.width,
+        height: physical_window_size.height,
+    };
 
-        let (device, mut queue_group) = {
-            let queue_family = adapter
-                .queue_families
-                .iter()
-                .find(|family| {
-                    surface.supports_queue_family(family) && family.queue_type().supports_graphics()
-                })
-                .expect("No compatible queue family found");
+    let window = winit::window::WindowBuilder::new()
+        .with_title(&title)
+        .with_inner_size(logical_window_size)
+        .build(&event_loop)
+        .expect("Failed to create window");
 
-            let mut gpu = unsafe {
-                adapter
-                    .physical_device
-                    .open(&[(queue_family, &[1.0])], gfx_hal::Features::empty())
-                    .expect("Failed to open device")
-            };
+    let (instance, surface, adapter) = {
+        let instance = backend::Instance::create(title.as_str(), 1).expect("Backend not supported");
 
-            (gpu.device, gpu.queue_groups.pop().unwrap())
+        let surface = unsafe {
+            instance
+                .create_surface(&window)
+                .expect("Failed to create surface for window")
         };
 
-        let (command_pool, mut command_buffer) = unsafe {
-            let mut command_pool = device
-                .create_command_pool(queue_group.family, CommandPoolCreateFlags::empty())
-                .expect("Out of memory");
+        let adapter = instance.enumerate_adapters().remove(0);
 
-            let command_buffer = command_pool.allocate_one(Level::Primary);
+        (instance, surface, adapter)
+    };
 
-            (command_pool, command_buffer)
+    let (device, mut queue_group) = {
+        let queue_family = adapter
+            .queue_families
+            .iter()
+            .find(|family| {
+                surface.supports_queue_family(family) && family.queue_type().supports_graphics()
+            })
+            .expect("No compatible queue family found");
+
+        let mut gpu = unsafe {
+            adapter
+                .physical_device
+                .open(&[(queue_family, &[1.0])], gfx_hal::Features::empty())
+                .expect("Failed to open device")
         };
 
-        let surface_color_format = {
-            let supported_formats = surface
-                .supported_formats(&adapter.physical_device)
-                .unwrap_or(vec![]);
+        (gpu.device, gpu.queue_groups.pop().unwrap())
+    };
 
-            let default_format = *supported_formats.get(0).unwrap_or(&Format::Rgba8Srgb);
+    let (command_pool, mut command_buffer) = unsafe {
+        let mut command_pool = device
+            .create_command_pool(queue_group.family, CommandPoolCreateFlags::empty())
+            .expect("Out of memory");
 
-            supported_formats
-                .into_iter()
-                .find(|format| format.base_format().1 == ChannelType::Srgb)
-                .unwrap_or(default_format)
-        };
+        let command_buffer = command_pool.allocate_one(Level::Primary);
 
-        let render_pass = {
-            let color_attachment = Attachment {
-                format: Some(surface_color_format),
-                samples: 1,
-                ops: AttachmentOps::new(AttachmentLoadOp::Clear, AttachmentStoreOp::Store),
-                stencil_ops: AttachmentOps::DONT_CARE,
-                layouts: Layout::Undefined..Layout::Present,
-            };
+        (command_pool, command_buffer)
+    };
 
-            let subpass = SubpassDesc {
-                colors: &[(0, Layout::ColorAttachmentOptimal)],
-                depth_stencil: None,
-                inputs: &[],
-                resolves: &[],
-                preserves: &[],
-            };
+    let surface_color_format = {
+        let supported_formats = surface
+            .supported_formats(&adapter.physical_device)
+            .unwrap_or(vec![]);
 
-            unsafe {
-                device
-                    .create_render_pass(
-                        vec![color_attachment].into_iter(),
-                        vec![subpass].into_iter(),
-                        vec![].into_iter(),
-                    )
-                    .expect("Out of memory")
-            }
+        let default_format = *supported_formats.get(0).unwrap_or(&Format::Rgba8Srgb);
+
+        supported_formats
+            .into_iter()
+            .find(|format| format.base_format().1 == ChannelType::Srgb)
+            .unwrap_or(default_format)
+    };
+
+    let render_pass = {
+        let color_attachment = Attachment {
+            format: Some(surface_color_format),
+            samples: 1,
+            ops: AttachmentOps::new(AttachmentLoadOp::Clear, AttachmentStoreOp::Store),
+            stencil_ops: AttachmentOps::DONT_CARE,
+            layouts: Layout::Undefined..Layout::Present,
         };
 
-        let pipeline_layout = unsafe {
-            let push_constant_bytes = std::mem::size_of::<PushConstants>() as u32;
+        let subpass = SubpassDesc {
+            colors: &[(0, Layout::ColorAttachmentOptimal)],
+            depth_stencil: None,
+            inputs: &[],
+            resolves: &[],
+            preserves: &[],
+        };
 
-            // The second slice passed here defines the ranges of push constants
-            // available to each shader stage. In this example, we're going to give
-            // one `PushConstants` struct worth of bytes to the vertex shader.
-            //
-            // Out data _could_ be offset, which is why we pass a range of bytes,
-            // but here we can start at zero since there's no data before our
-            // struct.
+        unsafe {
             device
-                .create_pipeline_layout(
+                .create_render_pass(
+                    vec![color_attachment].into_iter(),
+                    vec![subpass].into_iter(),
                     vec![].into_iter(),
-                    vec![(ShaderStageFlags::VERTEX, 0..push_constant_bytes)].into_iter(),
                 )
                 .expect("Out of memory")
-        };
-
-        let is_triangle = self.is_triangle;
-        let vertex_shader = if is_triangle {
-            include_str!("shaders/triangle.vert")
-        } else {
-            include_str!("shaders/square.vert")
-        };
-        let fragment_shader = if is_triangle {
-            include_str!("shaders/triangle.frag")
-        } else {
-            include_str!("shaders/square.frag")
-        };
+        }
+    };
 
-        let pipeline = unsafe {
-            make_pipeline::<backend::Backend>(
-                &device,
-                &render_pass,
-                &pipeline_layout,
-                vertex_shader,
-                fragment_shader,
+    let pipeline_layout = unsafe {
+        let push_constant_bytes = std::mem::size_of::<PushConstants>() as u32;
+
+        // The second slice passed here defines the ranges of push constants
+        // available to each shader stage. In this example, we're going to give
+        // one `PushConstants` struct worth of bytes to the vertex shader.
+        //
+        // Out data _could_ be offset, which is why we pass a range of bytes,
+        // but here we can start at zero since there's no data before our
+        // struct.
+        device
+            .create_pipeline_layout(
+                vec![].into_iter(),
+                vec![(ShaderStageFlags::VERTEX, 0..push_constant_bytes)].into_iter(),
             )
-        };
+            .expect("Out of memory")
+    };
 
-        let submission_complete_fence = device.create_fence(true).expect("Out of memory");
-        let rendering_complete_semaphore = device.create_semaphore().expect("Out of memory");
+    let vertex_shader = if is_triangle {
+        include_str!("shaders/triangle.vert")
+    } else {
+        include_str!("shaders/square.vert")
+    };
+    let fragment_shader = if is_triangle {
+        include_str!("shaders/triangle.frag")
+    } else {
+        include_str!("shaders/square.frag")
+    };
 
-        let mut resource_holder: ResourceHolder<backend::Backend> =
-            ResourceHolder(ManuallyDrop::new(Resources {
-                instance,
-                surface,
-                device,
-                command_pool,
-                render_passes: vec![render_pass],
-                pipeline_layouts: vec![pipeline_layout],
-                pipelines: vec![pipeline],
-                submission_complete_fence,
-                rendering_complete_semaphore,
-            }));
-
-        let mut should_configure_swapchain = true;
-
-        let (mut zoom, mut translate_x, mut translate_y) = (1., 0., 0.);
-        let mut click_start_pos: Option<PhysicalPosition<f64>> = None;
-        let mut cur_cursor_pos: Option<PhysicalPosition<f64>> = None;
-        let mut click_start_translates = None;
-        let mut last_mouse_click_time = None;
-
-        event_loop.run(move |event, _, control_flow| {
-            let mut cur_iter: Option<usize> = None;
-            match event {
-                Event::WindowEvent { event, .. } => match event {
-                    WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
-                    WindowEvent::Resized(dims) => {
-                        surface_extent = Extent2D {
-                            width: dims.width,
-                            height: dims.height,
-                        };
-                        should_configure_swapchain = true;
-                    }
-                    WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
-                        surface_extent = Extent2D {
-                            width: new_inner_size.width,
-                            height: new_inner_size.height,
-                        };
-                        should_configure_swapchain = true;
+    let pipeline = unsafe {
+        make_pipeline::<backend::Backend>(
+            &device,
+            &render_pass,
+            &pipeline_layout,
+            vertex_shader,
+            fragment_shader,
+        )
+    };
+
+    let submission_complete_fence = device.create_fence(true).expect("Out of memory");
+    let rendering_complete_semaphore = device.create_semaphore().expect("Out of memory");
+
+    let mut resource_holder: ResourceHolder<backend::Backend> =
+        ResourceHolder(ManuallyDrop::new(Resources {
+            instance,
+            surface,
+            device,
+            command_pool,
+            render_passes: vec![render_pass],
+            pipeline_layouts: vec![pipeline_layout],
+            pipelines: vec![pipeline],
+            submission_complete_fence,
+            rendering_complete_semaphore,
+        }));
+
+    let mut should_configure_swapchain = true;
+
+    let (mut zoom, mut translate_x, mut translate_y) = (1., 0., 0.);
+    let mut click_start_pos: Option<PhysicalPosition<f64>> = None;
+    let mut cur_cursor_pos: Option<PhysicalPosition<f64>> = None;
+    let mut click_start_translates = None;
+    let mut last_mouse_click_time = None;
+
+    event_loop.run(move |event, _, control_flow| {
+        match event {
+            Event::WindowEvent { event, .. } => match event {
+                WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
+                WindowEvent::Resized(dims) => {
+                    surface_extent = Extent2D {
+                        width: dims.width,
+                        height: dims.height,
+                    };
+                    should_configure_swapchain = true;
+                }
+                WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
+                    surface_extent = Extent2D {
+                        width: new_inner_size.width,
+                        height: new_inner_size.height,
+                    };
+                    should_configure_swapchain = true;
+                }
+                WindowEvent::MouseInput { state, .. } => match state {
+                    ElementState::Pressed => {
+                        click_start_pos = cur_cursor_pos;
+                        click_start_translates = Some((translate_x, translate_y));
                     }
-                    WindowEvent::MouseInput { state, .. } => match state {
-                        ElementState::Pressed => {
-                            click_start_pos = cur_cursor_pos;
-                            click_start_translates = Some((translate_x, translate_y));
-                        }
-                        ElementState::Released => {
-                            click_start_pos = None;
-                            click_start_translates = None;
-                            if last_mouse_click_time.is_some() {
-                                let last_click: Instant = last_mouse_click_time.unwrap();
-                                let duration = Instant::now() - last_click;
-                                if duration.as_millis() < 250 {
-                                    zoom = 1.;
-                                    translate_x = 0.;
-                                    translate_y = 0.;
-                                }
+                    ElementState::Released => {
+                        click_start_pos = None;
+                        click_start_translates = None;
+                        if last_mouse_click_time.is_some() {
+                            let last_click: Instant = last_mouse_click_time.unwrap();
+                            let duration = Instant::now() - last_click;
+                            if duration.as_millis() < 250 {
+                                zoom = 1.;
+                                translate_x = 0.;
+                                translate_y = 0.;
                             }
-                            last_mouse_click_time = Some(Instant::now());
-                        }
-                    },
-                    WindowEvent::CursorMoved { position, .. } => {
-                        cur_cursor_pos = Some(position);
-                        if click_start_pos.is_some() {
-                            let pxl_x = position.x - click_start_pos.unwrap().x;
-                            let pxl_y = position.y - click_start_pos.unwrap().y;
-                            translate_x = click_start_translates.unwrap().0
-                                + pxl_x as f32 / surface_extent.width as f32;
-                            translate_y = click_start_translates.unwrap().1
-                                + pxl_y as f32 / surface_extent.height as f32;
                         }
+                        last_mouse_click_time = Some(Instant::now());
                     }
-                    WindowEvent::MouseWheel { delta, .. } => match delta {
-                        MouseScrollDelta::PixelDelta(position) => {
-                            let double_per_position = 512.;
-                            let ratio = position.y as f32 / double_per_position;
-                            zoom = if ratio >= 0. {
-                                zoom * (1. + ratio)
-                            } else {
-                                zoom / (1. - ratio)
-                            };
-                        }
-                        MouseScrollDelta::LineDelta(_, line_count) => {
-                            // Zoom 1.05x everytime scroll 1 line.
-                            let line_zoom_factor = 1. + (line_count * 0.05);
-                            zoom *= line_zoom_factor;
+                },
+                WindowEvent::CursorMoved { position, .. } => {
+                    cur_cursor_pos = Some(position);
+                    if click_start_pos.is_some() {
+                        let pxl_x = position.x - click_start_pos.unwrap().x;
+                        let pxl_y = position.y - click_start_pos.unwrap().y;
+                        translate_x = click_start_translates.unwrap().0
+                            + pxl_x as f32 / surface_extent.width as f32;
+                        translate_y = click_start_translates.unwrap().1
+                            + pxl_y as f32 / surface_extent.height as f32;
+                    }
+                }
+                WindowEvent::MouseWheel { delta, .. } => match delta {
+                    MouseScrollDelta::PixelDelta(position) => {
+                        let double_per_position = 512.;
+                        let ratio = position.y as f32 / double_per_position;
+                        zoom = if ratio >= 0. {
+                            zoom * (1. + ratio)
+                        } else {
+                            zoom / (1. - ratio)
+                        };
+                    }
+                    MouseScrollDelta::LineDelta(_, line_count) => {
+                        // Zoom 1.05x everytime scroll 1 line.
+                        let line_zoom_factor = 1. + (line_count * 0.05);
+                        zoom *= line_zoom_factor;
+                    }
+                },
+                WindowEvent::KeyboardInput { input, .. } => {
+                    if input.state == ElementState::Released {
+                        if let Some(new_title) = input.virtual_keycode.and_then(&mut on_key) {
+                            window.set_title(&new_title);
                         }
-                    },
-                    WindowEvent::KeyboardInput { input, .. } => {
-                        if input.state == ElementState::Released {
-                            if let Some(control) = control.as_mut() {
-                                match input.virtual_keycode {
-                                    Some(ch) => match ch {
-                                        VirtualKeyCode::Q => {
-                                            control.broadcast('q');
-                                        }
-                                        VirtualKeyCode::H => {
-                                            control.broadcast('h');
-                                        }
-                                        VirtualKeyCode::Left => {
-                                            control.broadcast('h');
-                                        }
-                                        VirtualKeyCode::J => {
-                                            control.broadcast('j');
-                                        }
-                                        VirtualKeyCode::Down => {
-                                            control.broadcast('j');
-                                        }
-                                        VirtualKeyCode::K => {
-                                            control.broadcast('k');
-                                        }
-                                        VirtualKeyCode::Up => {
-                                            control.broadcast('k');
-                                        }
-                                        VirtualKeyCode::L => {
-                                            control.broadcast('l');
-                                        }
-                                        VirtualKeyCode::Right => {
-                                            control.broadcast('l');
-                                        }
-                                        VirtualKeyCode::Space => {
-                                            control.broadcast(' ');
-                                        }
-                                        _ => (),
-                                    },
+                        if let Some(control) = control.as_mut() {
+                            match input.virtual_keycode {
+                                Some(ch) => match ch {
+                                    VirtualKeyCode::Q => {
+                                        control.broadcast('q');
+                                    }
+                                    VirtualKeyCode::H => {
+                                        control.broadcast('h');
+                                    }
+                                    VirtualKeyCode::Left => {
+                                        control.broadcast('h');
+                                    }
+                                    VirtualKeyCode::J => {
+                                        control.broadcast('j');
+                                    }
+                                    VirtualKeyCode::Down => {
+                                        control.broadcast('j');
+                                    }
+                                    VirtualKeyCode::K => {
+                                        control.broadcast('k');
+                                    }
+                                    VirtualKeyCode::Up => {
+                                        control.broadcast('k');
+                                    }
+                                    VirtualKeyCode::L => {
+                                        control.broadcast('l');
+                                    }
+                                    VirtualKeyCode::Right => {
+                                        control.broadcast('l');
+                                    }
+                                    VirtualKeyCode::Space => {
+                                        control.broadcast(' ');
+                                    }
                                     _ => (),
-                                }
+                                },
+                                _ => (),
                             }
                         }
                     }
-                    _ => (),
-                },
-                Event::MainEventsCleared => window.request_redraw(),
-                Event::RedrawRequested(_) => {
-                    let res: &mut Resources<_> = &mut resource_holder.0;
-                    let render_pass = &res.render_passes[0];
-                    let pipeline_layout = &res.pipeline_layouts[0];
-                    let pipeline = &res.pipelines[0];
-
-                    let (grid_width, grid_height) =
-                        (board_shape.width() as u32, board_shape.height() as u32);
-
-                    let lookup;
-                    loop {
-                        match states_read_only.try_read() {
-                            Ok(val) => {
-                                if cur_iter.is_none() || cur_iter.unwrap() != val.0 {
-                                    lookup = val.1.clone();
-                                    cur_iter = Some(val.0); // TODO: show current iteration.
-                                    break;
-                                }
-                            }
-                            Err(_) => continue,
-                        }
+                }
+                _ => (),
+            },
+            Event::MainEventsCleared => window.request_redraw(),
+            Event::RedrawRequested(_) => {
+                let res: &mut Resources<_> = &mut resource_holder.0;
+                let render_pass = &res.render_passes[0];
+                let pipeline_layout = &res.pipeline_layouts[0];
+                let pipeline = &res.pipelines[0];
+
+                unsafe {
+                    // We refuse to wait more than a second, to avoid hanging.
+                    let render_timeout_ns = 1_000_000_000;
+
+                    res.device
+                        .wait_for_fence(&res.submission_complete_fence, render_timeout_ns)
+                        .expect("Out of memory or device lost");
+
+                    res.device
+                        .reset_fence(&mut res.submission_complete_fence)
+                        .expect("Out of memory");
+
+                    res.command_pool.reset(false);
+                }
+
+                let caps = res.surface.capabilities(&adapter.physical_device);
+                let mut swapchain_config =
+                    SwapchainConfig::from_caps(&caps, surface_color_format, surface_extent);
+                let mut framebuffer_attachment = swapchain_config.framebuffer_attachment();
+                if should_configure_swapchain {
+                    // This seems to fix some fullscreen slowdown on macOS.
+                    if caps.image_count.contains(&3) {
+                        swapchain_config.image_count = 3;
                     }
 
-                    let constants: Vec<((u32, u32), ColorRGBA)> = lookup
-                        .par_iter()
-                        .map(|(idx, state)| {
-                            let color = visual_mapping.to_visual(&state);
-                            let max_color = u16::MAX as f32;
-                            let (x_min, y_min) = (
-                                board_shape.x_idx_min() as i32,
-                                board_shape.y_idx_min() as i32,
-                            );
-                            let ele_res: ((u32, u32), ColorRGBA) = (
-                                (
-                                    (idx.x.to_i32().unwrap() - x_min).to_u32().unwrap(),
-                                    (idx.y.to_i32().unwrap() - y_min).to_u32().unwrap(),
-                                ),
-                                ColorRGBA {
-                                    r: color.r as f32 / max_color,
-                                    g: color.g as f32 / max_color,
-                                    b: color.b as f32 / max_color,
-                                    a: color.a as f32 / max_color,
-                                },
-                            );
-                            ele_res
-                        })
-                        .collect::<Vec<((u32, u32), ColorRGBA)>>();
+                    // Uncomment to allow resizing to other aspects:
+                    surface_extent = swapchain_config.extent;
+                    framebuffer_attachment = swapchain_config.framebuffer_attachment();
 
                     unsafe {
-                        // We refuse to wait more than a second, to avoid hanging.
-                        let render_timeout_ns = 1_000_000_000;
-
-                        res.device
-                            .wait_for_fence(&res.submission_complete_fence, render_timeout_ns)
-                            .expect("Out of memory or device lost");
+                        res.surface
+                            .configure_swapchain(&res.device, swapchain_config)
+                            .expect("Failed to configure swapchain");
+                    };
 
-                        res.device
-                            .reset_fence(&mut res.submission_complete_fence)
-                            .expect("Out of memory");
+                    should_configure_swapchain = false;
+                }
 
-                        res.command_pool.reset(false);
-                    }
+                let surface_image = unsafe {
+                    // We refuse to wait more than a second, to avoid hanging.
+                    let acquire_timeout_ns = 1_000_000_000;
 
-                    let caps = res.surface.capabilities(&adapter.physical_device);
-                    let mut swapchain_config =
-                        SwapchainConfig::from_caps(&caps, surface_color_format, surface_extent);
-                    let mut framebuffer_attachment = swapchain_config.framebuffer_attachment();
-                    if should_configure_swapchain {
-                        // This seems to fix some fullscreen slowdown on macOS.
-                        if caps.image_count.contains(&3) {
-                            swapchain_config.image_count = 3;
+                    match res.surface.acquire_image(acquire_timeout_ns) {
+                        Ok((image, _)) => image,
+                        Err(_) => {
+                            should_configure_swapchain = true;
+                            return;
                         }
-
-                        // Uncomment to allow resizing to other aspects:
-                        surface_extent = swapchain_config.extent;
-                        framebuffer_attachment = swapchain_config.framebuffer_attachment();
-
-                        unsafe {
-                            res.surface
-                                .configure_swapchain(&res.device, swapchain_config)
-                                .expect("Failed to configure swapchain");
-                        };
-
-                        should_configure_swapchain = false;
                     }
+                };
 
-                    let surface_image = unsafe {
-                        // We refuse to wait more than a second, to avoid hanging.
-                        let acquire_timeout_ns = 1_000_000_000;
-
-                        match res.surface.acquire_image(acquire_timeout_ns) {
-                            Ok((image, _)) => image,
-                            Err(_) => {
-                                should_configure_swapchain = true;
-                                return;
-                            }
-                        }
-                    };
-
-                    let framebuffer = unsafe {
-                        res.device
-                            .create_framebuffer(
-                                render_pass,
-                                vec![framebuffer_attachment].into_iter(),
-                                Extent {
-                                    width: surface_extent.width,
-                                    height: surface_extent.height,
-                                    depth: 1,
-                                },
-                            )
-                            .unwrap()
-                    };
-
-                    let viewport = {
-                        Viewport {
-                            rect: Rect {
-                                x: 0,
-                                y: 0,
-                                w: surface_extent.width as i16,
-                                h: surface_extent.height as i16,
-                            },
-                            depth: 0.0..1.0,
-                        }
-                    };
-
-                    // Each `PushConstants` struct in this slice represents the
-                    // color, position, and scale of a triangle. This allows us to
-                    // efficiently draw the same thing multiple times with varying
-                    // parameters.
-
-                    let render_attachment_info = RenderAttachmentInfo {
-                        image_view: surface_image.borrow(),
-                        clear_value: ClearValue {
-                            color: ClearColor {
-                                float32: [0.0, 0.0, 0.0, 1.0],
+                let framebuffer = unsafe {
+                    res.device
+                        .create_framebuffer(
+                            render_pass,
+                            vec![framebuffer_attachment].into_iter(),
+                            Extent {
+                                width: surface_extent.width,
+                                height: surface_extent.height,
+                                depth: 1,
                             },
+                        )
+                        .unwrap()
+                };
+
+                let viewport = {
+                    Viewport {
+                        rect: Rect {
+                            x: 0,
+                            y: 0,
+                            w: surface_extent.width as i16,
+                            h: surface_extent.height as i16,
                         },
-                    };
-                    unsafe {
-                        command_buffer.begin_primary(CommandBufferFlags::ONE_TIME_SUBMIT);
-
-                        command_buffer.set_viewports(0, vec![viewport.clone()].into_iter());
-                        command_buffer.set_scissors(0, vec![viewport.rect].into_iter());
-
-                        command_buffer.begin_render_pass(
-                            render_pass,
-                            &framebuffer,
-                            viewport.rect,
-                            vec![render_attachment_info].into_iter(),
-                            SubpassContents::Inline,
+                        depth: 0.0..1.0,
+                    }
+                };
+
+                // Each `PushConstants` struct in this slice represents the
+                // color, position, and scale of a triangle. This allows us to
+                // efficiently draw the same thing multiple times with varying
+                // parameters.
+
+                let render_attachment_info = RenderAttachmentInfo {
+                    image_view: surface_image.borrow(),
+                    clear_value: ClearValue {
+                        color: ClearColor {
+                            float32: [0.0, 0.0, 0.0, 1.0],
+                        },
+                    },
+                };
+                unsafe {
+                    command_buffer.begin_primary(CommandBufferFlags::ONE_TIME_SUBMIT);
+
+                    command_buffer.set_viewports(0, vec![viewport.clone()].into_iter());
+                    command_buffer.set_scissors(0, vec![viewport.rect].into_iter());
+
+                    command_buffer.begin_render_pass(
+                        render_pass,
+                        &framebuffer,
+                        viewport.rect,
+                        vec![render_attachment_info].into_iter(),
+                        SubpassContents::Inline,
+                    );
+
+                    command_buffer.bind_graphics_pipeline(pipeline);
+
+                    let shapes = gen_shapes(&surface_extent, zoom, translate_x, translate_y);
+                    for shape in shapes.as_slice() {
+                        // This encodes the actual push constants themselves
+                        // into the command buffer. The vertex shader will be
+                        // able to access these properties.
+                        command_buffer.push_graphics_constants(
+                            pipeline_layout,
+                            ShaderStageFlags::VERTEX,
+                            0,
+                            push_constant_bytes(shape),
                         );
 
-                        command_buffer.bind_graphics_pipeline(pipeline);
-
-                        let shapes = if is_triangle {
-                            create_triangles(
-                                &surface_extent,
-                                grid_width,
-                                grid_height,
-                                constants,
-                                zoom,
-                                translate_x,
-                                translate_y,
-                            )
-                        } else {
-                            create_squares(
-                                &surface_extent,
-                                grid_width,
-                                grid_height,
-                                constants,
-                                zoom,
-                                translate_x,
-                                translate_y,
-                            )
-                        };
-                        for shape in shapes.as_slice() {
-                            // This encodes the actual push constants themselves
-                            // into the command buffer. The vertex shader will be
-                            // able to access these properties.
-                            command_buffer.push_graphics_constants(
-                                pipeline_layout,
-                                ShaderStageFlags::VERTEX,
-                                0,
-                                push_constant_bytes(shape),
-                            );
-
-                            command_buffer.draw(0..if is_triangle { 3 } else { 4 }, 0..1);
-                        }
-
-                        command_buffer.end_render_pass();
-                        command_buffer.finish();
+                        command_buffer.draw(0..if is_triangle { 3 } else { 4 }, 0..1);
                     }
 
-                    unsafe {
-                        let command_buffer = vec![&command_buffer].into_iter();
-                        let wait_semaphores = vec![].into_iter();
-                        let signal_semaphores = vec![&res.rendering_complete_semaphore].into_iter();
-
-                        queue_group.queues[0].submit(
-                            command_buffer,
-                            wait_semaphores,
-                            signal_semaphores,
-                            Some(&mut res.submission_complete_fence),
-                        );
+                    command_buffer.end_render_pass();
+                    command_buffer.finish();
+                }
 
-                        let result = queue_group.queues[0].present(
-                            &mut res.surface,
-                            surface_image,
-                            Some(&mut res.rendering_complete_semaphore),
-                        );
+                unsafe {
+                    let command_buffer = vec![&command_buffer].into_iter();
+                    let wait_semaphores = vec![].into_iter();
+                    let signal_semaphores = vec![&res.rendering_complete_semaphore].into_iter();
 
-                        should_configure_swapchain |= result.is_err();
+                    queue_group.queues[0].submit(
+                        command_buffer,
+                        wait_semaphores,
+                        signal_semaphores,
+                        Some(&mut res.submission_complete_fence),
+                    );
 
-                        res.device.destroy_framebuffer(framebuffer);
-                    }
+                    let result = queue_group.queues[0].present(
+                        &mut res.surface,
+                        surface_image,
+                        Some(&mut res.rendering_complete_semaphore),
+                    );
+
+                    should_configure_swapchain |= result.is_err();
+
+                    res.device.destroy_framebuffer(framebuffer);
                 }
-                _ => (),
             }
-        });
-    }
+            _ => (),
+        }
+    });
 }
 
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
-struct PushConstants {
-    color: [f32; 4],
-    transform: [[f32; 4]; 4],
+pub(super) struct PushConstants {
+    pub(super) color: [f32; 4],
+    pub(super) transform: [[f32; 4]; 4],
+}
+
+fn window_title(title: &String, slice_view: Option<&SliceView>) -> String {
+    match slice_view {
+        Some(view) => format!("{} ({})", title, view.description()),
+        None => title.clone(),
+    }
 }
 
-fn make_transform(dx: f32, dy: f32, scale_x: f32, scale_y: f32, angle: f32) -> [[f32; 4]; 4] {
+pub(super) fn make_transform(
+    dx: f32,
+    dy: f32,
+    scale_x: f32,
+    scale_y: f32,
+    angle: f32,
+) -> [[f32; 4]; 4] {
     let (sin, cos) = (angle.sin(), angle.cos());
     let (dz, scale_z) = (0., 1.);
 
@@ -656,7 +723,7 @@ where
     }
 }
 
-fn get_window_size(
+pub(super) fn get_window_size(
     event_loop: &EventLoop<()>,
     aspect_ratio: f32,
     scale: f32,
@@ -686,17 +753,41 @@ pub struct ColorRGBA {
     a: f32,
 }
 
+impl ColorRGBA {
+    pub(super) fn from_rgba16(color: &RGBA16) -> Self {
+        let max_color = u16::MAX as f32;
+        Self {
+            r: color.r as f32 / max_color,
+            g: color.g as f32 / max_color,
+            b: color.b as f32 / max_color,
+            a: color.a as f32 / max_color,
+        }
+    }
+
+    pub(super) fn to_array(&self) -> [f32; 4] {
+        [self.r, self.g, self.b, self.a]
+    }
+}
+
 fn create_squares(
     surface_extent: &Extent2D,
-    grid_width: u32,
-    grid_height: u32,
+    board_shape: &Shape2D,
     states: Vec<((u32, u32), ColorRGBA)>,
     zoom: f32,
     dx: f32,
     dy: f32,
+    is_hex_offset: bool,
 ) -> Vec<PushConstants> {
+    let (grid_width, grid_height) = (board_shape.width() as u32, board_shape.height() as u32);
+    // Parity of the first row, so that shifted rows match odd row indices of the board.
+    let first_row_parity = board_shape.y_idx_min().rem_euclid(2) as u32;
+    let row_width = if is_hex_offset {
+        grid_width as f32 + 0.5
+    } else {
+        grid_width as f32
+    };
     let (square_width, square_height) = (
-        surface_extent.width as f32 / grid_width as f32,
+        surface_extent.width as f32 / row_width,
         surface_extent.height as f32 / grid_height as f32,
     );
     let square_len = square_width.min(square_height);
@@ -705,15 +796,18 @@ fn create_squares(
         square_len / surface_extent.height as f32 * 2.0 * zoom,
     );
 
-    let left_padding = 1.0 - scale_x / 2.0 * grid_width as f32;
+    let left_padding = 1.0 - scale_x / 2.0 * row_width;
     let top_padding = 1.0 - scale_y / 2.0 * grid_height as f32;
 
     let scale = cell_scale_for_gap(scale_x, scale_y);
 
     let mut res = Vec::new();
     for (idx, color) in states.iter() {
-        let x_transform =
+        let mut x_transform =
             -1.0 + left_padding + idx.0 as f32 * scale_x + (1.0 - scale) / 2.0 * scale_x + dx;
+        if is_hex_offset && (idx.1 + first_row_parity) % 2 == 1 {
+            x_transform += scale_x / 2.0;
+        }
         let y_transform = -1.0
             + top_padding
             + (grid_height - idx.1 - 1) as f32 * scale_y
@@ -722,7 +816,7 @@ fn create_squares(
         let rotation = 0.0;
 
         res.push(PushConstants {
-            color: [color.r, color.g, color.b, color.a],
+            color: color.to_array(),
             transform: make_transform(
                 x_transform,
                 y_transform,
@@ -791,7 +885,7 @@ fn create_triangles(
         }
 
         res.push(PushConstants {
-            color: [color.r, color.g, color.b, color.a],
+            color: color.to_array(),
             transform: make_transform(
                 x_transform,
                 y_transform,
@@ -830,7 +924,7 @@ fn cell_scale_for_gap(scale_x: f32, scale_y: f32) -> f32 {
 /// representation: SPIR-V. This is more easily interpreted and optimized
 /// by your graphics card. As an added bonus, this allows us to use the
 /// same shader code across different backends.
-fn compile_shader(glsl: &str, shader_kind: ShaderKind) -> Vec<u32> {
+pub(super) fn compile_shader(glsl: &str, shader_kind: ShaderKind) -> Vec<u32> {
     let mut compiler = shaderc::Compiler::new().unwrap();
 
     let compiled_shader = compiler
@@ -912,7 +1006,7 @@ unsafe fn make_pipeline<B: gfx_hal::Backend>(
 /// Note that this assumes the struct divides evenly into
 /// 4-byte chunks. If the contents are all `f32`s, which they
 /// often are, then this will always be the case.
-unsafe fn push_constant_bytes<T>(push_constants: &T) -> &[u32] {
+pub(super) unsafe fn push_constant_bytes<T>(push_constants: &T) -> &[u32] {
     let size_in_bytes = std::mem::size_of::<T>();
     let size_in_u32s = size_in_bytes / std::mem::size_of::<u32>();
     let start_ptr = push_constants as *const T as *const u32;
@@ -0,0 +1,1046 @@
+use super::grid_2d::{compile_shader, get_window_size, push_constant_bytes};
+use crate::{
+    renderer::{board_info::RendererBoardInfo, keyboard_control::KeyboardControl},
+    CellularAutomatonRenderer, StateVisualMapping,
+};
+use gfx_hal::{
+    adapter::{MemoryType, PhysicalDevice},
+    buffer::{self, SubRange},
+    command::{
+        ClearColor, ClearDepthStencil, ClearValue, CommandBuffer, CommandBufferFlags, Level,
+        RenderAttachmentInfo, SubpassContents,
+    },
+    device::Device,
+    format::{Aspects, ChannelType, Format, Swizzle},
+    image::{
+        self, Extent, FramebufferAttachment, Kind, Layout, SubresourceRange, Tiling,
+        ViewCapabilities, ViewKind,
+    },
+    memory::{Properties, Segment},
+    pass::{Attachment, AttachmentLoadOp, AttachmentOps, AttachmentStoreOp, Subpass, SubpassDesc},
+    pool::{CommandPool, CommandPoolCreateFlags},
+    pso::{
+        AttributeDesc, BlendState, ColorBlendDesc, ColorMask, Comparison, DepthStencilDesc,
+        DepthTest, Element, EntryPoint, GraphicsPipelineDesc, InputAssemblerDesc, Primitive,
+        PrimitiveAssemblerDesc, Rasterizer, Rect, ShaderStageFlags, Specialization,
+        VertexBufferDesc, VertexInputRate, Viewport,
+    },
+    queue::{CommandQueue, QueueFamily},
+    window::{Extent2D, PresentationSurface, Surface, SwapchainConfig},
+    Instance, MemoryTypeId, UnsupportedBackend,
+};
+use gol_core::{util::grid_util::Shape3D, GridPoint3D, StatesReadOnly};
+use num_traits::ToPrimitive;
+use rayon::prelude::*;
+use rgb::RGBA16;
+use shaderc::ShaderKind;
+use std::borrow::Borrow;
+use std::f32::consts::PI;
+use std::hash::Hash;
+use std::mem::ManuallyDrop;
+use std::time::Instant;
+use winit::{
+    dpi::PhysicalPosition,
+    event::{ElementState, Event, MouseScrollDelta, VirtualKeyCode, WindowEvent},
+    event_loop::{ControlFlow, EventLoop},
+};
+
+const DEPTH_FORMAT: Format = Format::D32Sfloat;
+const CUBE_VERTEX_COUNT: u32 = 36;
+
+/// Draws every non-trivial cell of a 3D board as a cube, viewed through an orbit camera.
+///
+/// Drag with the mouse to orbit, scroll to zoom and double click to reset the camera.
+pub struct GraphicalRendererGrid3D<CI, T>
+where
+    CI: Hash,
+{
+    info: RendererBoardInfo<Shape3D>,
+    control: Option<KeyboardControl>,
+    states_read_only: StatesReadOnly<CI, T>,
+}
+
+impl<T, U> GraphicalRendererGrid3D<GridPoint3D<U>, T>
+where
+    T: 'static + Send + Sync + Clone,
+    U: 'static + Send + Sync + Hash + ToPrimitive,
+{
+    pub fn new(
+        board_width: usize,
+        board_height: usize,
+        board_depth: usize,
+        states_storage: StatesReadOnly<GridPoint3D<U>, T>,
+    ) -> Result<Self, UnsupportedBackend> {
+        let info = RendererBoardInfo::new(Shape3D::new(board_width, board_height, board_depth));
+        Ok(Self {
+            info,
+            control: None,
+            states_read_only: states_storage,
+        })
+    }
+
+    pub fn with_title(self, title: String) -> Self {
+        let mut res = self;
+        res.info.set_title(title);
+        res
+    }
+
+    pub fn with_keyboard_control(self, control: KeyboardControl) -> Self {
+        let mut res = self;
+        res.control = Some(control);
+        res
+    }
+}
+
+impl<T, U> CellularAutomatonRenderer<T, RGBA16> for GraphicalRendererGrid3D<GridPoint3D<U>, T>
+where
+    T: 'static + Send + Sync + Clone,
+    U: 'static + Send + Sync + Hash + ToPrimitive,
+{
+    fn need_run_on_main(&self) -> bool {
+        true
+    }
+
+    fn run(&mut self, visual_mapping: Box<dyn StateVisualMapping<T, RGBA16>>) {
+        let event_loop = EventLoop::new();
+        let title = self.info.title().clone();
+
+        let board_shape = self.info.board_shape().clone();
+        let states_read_only = self.states_read_only.clone();
+        let mut control = self.control.clone();
+
+        let (logical_window_size, physical_window_size) = get_window_size(&event_loop, 1.0, 0.8);
+
+        let mut surface_extent = Extent2D {
+            width: physical_window_size.width,
+            height: physical_window_size.height,
+        };
+
+        let window = winit::window::WindowBuilder::new()
+            .with_title(title.as_str())
+            .with_inner_size(logical_window_size)
+            .build(&event_loop)
+            .expect("Failed to create window");
+
+        let (instance, surface, adapter) = {
+            let instance =
+                backend::Instance::create(title.as_str(), 1).expect("Backend not supported");
+
+            let surface = unsafe {
+                instance
+                    .create_surface(&window)
+                    .expect("Failed to create surface for window")
+            };
+
+            let adapter = instance.enumerate_adapters().remove(0);
+
+            (instance, surface, adapter)
+        };
+
+        let memory_types = adapter.physical_device.memory_properties().memory_types;
+
+        let (device, mut queue_group) = {
+            let queue_family = adapter
+                .queue_families
+                .iter()
+                .find(|family| {
+                    surface.supports_queue_family(family) && family.queue_type().supports_graphics()
+                })
+                .expect("No compatible queue family found");
+
+            let mut gpu = unsafe {
+                adapter
+                    .physical_device
+                    .open(&[(queue_family, &[1.0])], gfx_hal::Features::empty())
+                    .expect("Failed to open device")
+            };
+
+            (gpu.device, gpu.queue_groups.pop().unwrap())
+        };
+
+        let (command_pool, mut command_buffer) = unsafe {
+            let mut command_pool = device
+                .create_command_pool(queue_group.family, CommandPoolCreateFlags::empty())
+                .expect("Out of memory");
+
+            let command_buffer = command_pool.allocate_one(Level::Primary);
+
+            (command_pool, command_buffer)
+        };
+
+        let surface_color_format = {
+            let supported_formats = surface
+                .supported_formats(&adapter.physical_device)
+                .unwrap_or(vec![]);
+
+            let default_format = *supported_formats.first().unwrap_or(&Format::Rgba8Srgb);
+
+            supported_formats
+                .into_iter()
+                .find(|format| format.base_format().1 == ChannelType::Srgb)
+                .unwrap_or(default_format)
+        };
+
+        let render_pass = {
+            let color_attachment = Attachment {
+                format: Some(surface_color_format),
+                samples: 1,
+                ops: AttachmentOps::new(AttachmentLoadOp::Clear, AttachmentStoreOp::Store),
+                stencil_ops: AttachmentOps::DONT_CARE,
+                layouts: Layout::Undefined..Layout::Present,
+            };
+
+            let depth_attachment = Attachment {
+                format: Some(DEPTH_FORMAT),
+                samples: 1,
+                ops: AttachmentOps::new(AttachmentLoadOp::Clear, AttachmentStoreOp::DontCare),
+                stencil_ops: AttachmentOps::DONT_CARE,
+                layouts: Layout::Undefined..Layout::DepthStencilAttachmentOptimal,
+            };
+
+            let subpass = SubpassDesc {
+                colors: &[(0, Layout::ColorAttachmentOptimal)],
+                depth_stencil: Some(&(1, Layout::DepthStencilAttachmentOptimal)),
+                inputs: &[],
+                resolves: &[],
+                preserves: &[],
+            };
+
+            unsafe {
+                device
+                    .create_render_pass(
+                        vec![color_attachment, depth_attachment].into_iter(),
+                        vec![subpass].into_iter(),
+                        vec![].into_iter(),
+                    )
+                    .expect("Out of memory")
+            }
+        };
+
+        let pipeline_layout = unsafe {
+            let push_constant_bytes = std::mem::size_of::<CubePushConstants>() as u32;
+            device
+                .create_pipeline_layout(
+                    vec![].into_iter(),
+                    vec![(ShaderStageFlags::VERTEX, 0..push_constant_bytes)].into_iter(),
+                )
+                .expect("Out of memory")
+        };
+
+        let pipeline = unsafe {
+            make_cube_pipeline::<backend::Backend>(
+                &device,
+                &render_pass,
+                &pipeline_layout,
+                include_str!("shaders/cube.vert"),
+                include_str!("shaders/cube.frag"),
+            )
+        };
+
+        let submission_complete_fence = device.create_fence(true).expect("Out of memory");
+        let rendering_complete_semaphore = device.create_semaphore().expect("Out of memory");
+
+        let mut resource_holder: ResourceHolder<backend::Backend> =
+            ResourceHolder(ManuallyDrop::new(Resources {
+                instance,
+                surface,
+                device,
+                command_pool,
+                render_pass,
+                pipeline_layout,
+                pipeline,
+                submission_complete_fence,
+                rendering_complete_semaphore,
+                depth_image: None,
+                instance_buffer: None,
+            }));
+
+        let mut should_configure_swapchain = true;
+
+        let mut camera = OrbitCamera::new();
+        let mut click_start_pos: Option<PhysicalPosition<f64>> = None;
+        let mut cur_cursor_pos: Option<PhysicalPosition<f64>> = None;
+        let mut click_start_camera = None;
+        let mut last_mouse_click_time: Option<Instant> = None;
+
+        event_loop.run(move |event, _, control_flow| match event {
+            Event::WindowEvent { event, .. } => match event {
+                WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
+                WindowEvent::Resized(dims) => {
+                    surface_extent = Extent2D {
+                        width: dims.width,
+                        height: dims.height,
+                    };
+                    should_configure_swapchain = true;
+                }
+                WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
+                    surface_extent = Extent2D {
+                        width: new_inner_size.width,
+                        height: new_inner_size.height,
+                    };
+                    should_configure_swapchain = true;
+                }
+                WindowEvent::MouseInput { state, .. } => match state {
+                    ElementState::Pressed => {
+                        click_start_pos = cur_cursor_pos;
+                        click_start_camera = Some(camera.clone());
+                    }
+                    ElementState::Released => {
+                        click_start_pos = None;
+                        click_start_camera = None;
+                        if let Some(last_click) = last_mouse_click_time {
+                            let duration = Instant::now() - last_click;
+                            if duration.as_millis() < 250 {
+                                camera = OrbitCamera::new();
+                            }
+                        }
+                        last_mouse_click_time = Some(Instant::now());
+                    }
+                },
+                WindowEvent::CursorMoved { position, .. } => {
+                    cur_cursor_pos = Some(position);
+                    if let (Some(start_pos), Some(start_camera)) =
+                        (click_start_pos, click_start_camera.as_ref())
+                    {
+                        // Dragging across the whole window turns the camera half way around.
+                        let pxl_x = position.x - start_pos.x;
+                        let pxl_y = position.y - start_pos.y;
+                        camera = start_camera.rotated(
+                            -pxl_x as f32 / surface_extent.width as f32 * PI,
+                            pxl_y as f32 / surface_extent.height as f32 * PI,
+                        );
+                    }
+                }
+                WindowEvent::MouseWheel { delta, .. } => match delta {
+                    MouseScrollDelta::PixelDelta(position) => {
+                        let double_per_position = 512.;
+                        let ratio = position.y as f32 / double_per_position;
+                        camera.zoom = if ratio >= 0. {
+                            camera.zoom * (1. + ratio)
+                        } else {
+                            camera.zoom / (1. - ratio)
+                        };
+                    }
+                    MouseScrollDelta::LineDelta(_, line_count) => {
+                        // Zoom 1.05x everytime scroll 1 line.
+                        let line_zoom_factor = 1. + (line_count * 0.05);
+                        camera.zoom *= line_zoom_factor;
+                    }
+                },
+                WindowEvent::KeyboardInput { input, .. }
+                    if input.state == ElementState::Released =>
+                {
+                    if let (Some(control), Some(key)) = (control.as_mut(), input.virtual_keycode) {
+                        let ch = match key {
+                            VirtualKeyCode::Q => Some('q'),
+                            VirtualKeyCode::H | VirtualKeyCode::Left => Some('h'),
+                            VirtualKeyCode::J | VirtualKeyCode::Down => Some('j'),
+                            VirtualKeyCode::K | VirtualKeyCode::Up => Some('k'),
+                            VirtualKeyCode::L | VirtualKeyCode::Right => Some('l'),
+                            VirtualKeyCode::Space => Some(' '),
+                            _ => None,
+                        };
+                        if let Some(ch) = ch {
+                            control.broadcast(ch);
+                        }
+                    }
+                }
+                _ => (),
+            },
+            Event::MainEventsCleared => window.request_redraw(),
+            Event::RedrawRequested(_) => {
+                let res: &mut Resources<_> = &mut resource_holder.0;
+
+                let lookup = loop {
+                    if let Ok(val) = states_read_only.try_read() {
+                        break val;
+                    }
+                };
+
+                let max_color = u16::MAX as f32;
+                let instances: Vec<CubeInstance> = lookup
+                    .1
+                    .par_iter()
+                    .map(|(idx, state)| {
+                        let color = visual_mapping.to_visual(state);
+                        CubeInstance {
+                            position: [
+                                idx.x.to_f32().unwrap(),
+                                idx.y.to_f32().unwrap(),
+                                idx.z.to_f32().unwrap(),
+                            ],
+                            color: [
+                                color.r as f32 / max_color,
+                                color.g as f32 / max_color,
+                                color.b as f32 / max_color,
+                                color.a as f32 / max_color,
+                            ],
+                        }
+                    })
+                    .collect();
+                drop(lookup);
+
+                unsafe {
+                    // We refuse to wait more than a second, to avoid hanging.
+                    let render_timeout_ns = 1_000_000_000;
+
+                    res.device
+                        .wait_for_fence(&res.submission_complete_fence, render_timeout_ns)
+                        .expect("Out of memory or device lost");
+
+                    res.device
+                        .reset_fence(&mut res.submission_complete_fence)
+                        .expect("Out of memory");
+
+                    res.command_pool.reset(false);
+                }
+
+                let caps = res.surface.capabilities(&adapter.physical_device);
+                let mut swapchain_config =
+                    SwapchainConfig::from_caps(&caps, surface_color_format, surface_extent);
+                let mut framebuffer_attachment = swapchain_config.framebuffer_attachment();
+                if should_configure_swapchain {
+                    // This seems to fix some fullscreen slowdown on macOS.
+                    if caps.image_count.contains(&3) {
+                        swapchain_config.image_count = 3;
+                    }
+
+                    surface_extent = swapchain_config.extent;
+                    framebuffer_attachment = swapchain_config.framebuffer_attachment();
+
+                    unsafe {
+                        res.surface
+                            .configure_swapchain(&res.device, swapchain_config)
+                            .expect("Failed to configure swapchain");
+
+                        if let Some(depth_image) = res.depth_image.take() {
+                            depth_image.destroy(&res.device);
+                        }
+                        res.depth_image =
+                            Some(DepthImage::new(&res.device, &memory_types, &surface_extent));
+                    };
+
+                    should_configure_swapchain = false;
+                }
+
+                unsafe {
+                    let required_capacity = instances.len().max(1);
+                    let has_capacity = match &res.instance_buffer {
+                        Some(buffer) => buffer.capacity >= required_capacity,
+                        None => false,
+                    };
+                    if !has_capacity {
+                        if let Some(buffer) = res.instance_buffer.take() {
+                            buffer.destroy(&res.device);
+                        }
+                        res.instance_buffer = Some(InstanceBuffer::new(
+                            &res.device,
+                            &memory_types,
+                            required_capacity.next_power_of_two(),
+                        ));
+                    }
+                    res.instance_buffer
+                        .as_mut()
+                        .unwrap()
+                        .write(&res.device, &instances);
+                }
+
+                let surface_image = unsafe {
+                    // We refuse to wait more than a second, to avoid hanging.
+                    let acquire_timeout_ns = 1_000_000_000;
+
+                    match res.surface.acquire_image(acquire_timeout_ns) {
+                        Ok((image, _)) => image,
+                        Err(_) => {
+                            should_configure_swapchain = true;
+                            return;
+                        }
+                    }
+                };
+
+                let depth_framebuffer_attachment = FramebufferAttachment {
+                    usage: image::Usage::DEPTH_STENCIL_ATTACHMENT,
+                    view_caps: ViewCapabilities::empty(),
+                    format: DEPTH_FORMAT,
+                };
+
+                let framebuffer = unsafe {
+                    res.device
+                        .create_framebuffer(
+                            &res.render_pass,
+                            vec![framebuffer_attachment, depth_framebuffer_attachment].into_iter(),
+                            Extent {
+                                width: surface_extent.width,
+                                height: surface_extent.height,
+                                depth: 1,
+                            },
+                        )
+                        .unwrap()
+                };
+
+                let viewport = Viewport {
+                    rect: Rect {
+                        x: 0,
+                        y: 0,
+                        w: surface_extent.width as i16,
+                        h: surface_extent.height as i16,
+                    },
+                    depth: 0.0..1.0,
+                };
+
+                let push_constants = CubePushConstants {
+                    view_projection: camera.view_projection(
+                        &board_shape,
+                        surface_extent.width as f32 / surface_extent.height as f32,
+                    ),
+                    cell_scale: cell_scale_for_count(instances.len()),
+                };
+
+                let attachment_infos = vec![
+                    RenderAttachmentInfo {
+                        image_view: surface_image.borrow(),
+                        clear_value: ClearValue {
+                            color: ClearColor {
+                                float32: [0.0, 0.0, 0.0, 1.0],
+                            },
+                        },
+                    },
+                    RenderAttachmentInfo {
+                        image_view: &res.depth_image.as_ref().unwrap().view,
+                        clear_value: ClearValue {
+                            depth_stencil: ClearDepthStencil {
+                                depth: 1.0,
+                                stencil: 0,
+                            },
+                        },
+                    },
+                ];
+
+                unsafe {
+                    command_buffer.begin_primary(CommandBufferFlags::ONE_TIME_SUBMIT);
+
+                    command_buffer.set_viewports(0, vec![viewport.clone()].into_iter());
+                    command_buffer.set_scissors(0, vec![viewport.rect].into_iter());
+
+                    command_buffer.begin_render_pass(
+                        &res.render_pass,
+                        &framebuffer,
+                        viewport.rect,
+                        attachment_infos.into_iter(),
+                        SubpassContents::Inline,
+                    );
+
+                    command_buffer.bind_graphics_pipeline(&res.pipeline);
+
+                    if !instances.is_empty() {
+                        command_buffer.bind_vertex_buffers(
+                            0,
+                            vec![(
+                                &res.instance_buffer.as_ref().unwrap().buffer,
+                                SubRange::WHOLE,
+                            )]
+                            .into_iter(),
+                        );
+                        command_buffer.push_graphics_constants(
+                            &res.pipeline_layout,
+                            ShaderStageFlags::VERTEX,
+                            0,
+                            push_constant_bytes(&push_constants),
+                        );
+                        // One instance per cell, the cube itself is generated by the vertex shader.
+                        command_buffer.draw(0..CUBE_VERTEX_COUNT, 0..instances.len() as u32);
+                    }
+
+                    command_buffer.end_render_pass();
+                    command_buffer.finish();
+                }
+
+                unsafe {
+                    let command_buffer = vec![&command_buffer].into_iter();
+                    let wait_semaphores = vec![].into_iter();
+                    let signal_semaphores = vec![&res.rendering_complete_semaphore].into_iter();
+
+                    queue_group.queues[0].submit(
+                        command_buffer,
+                        wait_semaphores,
+                        signal_semaphores,
+                        Some(&mut res.submission_complete_fence),
+                    );
+
+                    let result = queue_group.queues[0].present(
+                        &mut res.surface,
+                        surface_image,
+                        Some(&mut res.rendering_complete_semaphore),
+                    );
+
+                    should_configure_swapchain |= result.is_err();
+
+                    res.device.destroy_framebuffer(framebuffer);
+                }
+            }
+            _ => (),
+        });
+    }
+}
+
+/// Camera looking at the center of the board from a point on a sphere around it.
+#[derive(Clone)]
+struct OrbitCamera {
+    yaw: f32,
+    pitch: f32,
+    zoom: f32,
+}
+
+impl OrbitCamera {
+    fn new() -> Self {
+        Self {
+            yaw: PI / 4.,
+            pitch: PI / 6.,
+            zoom: 1.,
+        }
+    }
+
+    fn rotated(&self, d_yaw: f32, d_pitch: f32) -> Self {
+        let max_pitch = PI / 2. - 0.01;
+        Self {
+            yaw: self.yaw + d_yaw,
+            pitch: (self.pitch + d_pitch).max(-max_pitch).min(max_pitch),
+            zoom: self.zoom,
+        }
+    }
+
+    fn eye(&self) -> [f32; 3] {
+        // The board is scaled to fit in a unit cube, this distance shows all of it.
+        let distance = 2.2 / self.zoom;
+        [
+            distance * self.pitch.cos() * self.yaw.sin(),
+            distance * self.pitch.sin(),
+            distance * self.pitch.cos() * self.yaw.cos(),
+        ]
+    }
+
+    fn view_projection(&self, board_shape: &Shape3D, aspect_ratio: f32) -> [[f32; 4]; 4] {
+        let board_len = board_shape
+            .width()
+            .max(board_shape.height())
+            .max(board_shape.depth()) as f32;
+        let center = [
+            (board_shape.x_idx_min() + board_shape.x_idx_max()) as f32 / 2.,
+            (board_shape.y_idx_min() + board_shape.y_idx_max()) as f32 / 2.,
+            (board_shape.z_idx_min() + board_shape.z_idx_max()) as f32 / 2.,
+        ];
+        let scale = 1. / board_len;
+        let model = [
+            [scale, 0., 0., 0.],
+            [0., scale, 0., 0.],
+            [0., 0., scale, 0.],
+            [
+                -center[0] * scale,
+                -center[1] * scale,
+                -center[2] * scale,
+                1.,
+            ],
+        ];
+
+        let eye = self.eye();
+        let distance = (eye[0] * eye[0] + eye[1] * eye[1] + eye[2] * eye[2]).sqrt();
+        let near = (distance - 1.).max(0.01);
+        let far = distance + 1.;
+        let view = look_at_origin(eye);
+        let projection = perspective(PI / 4., aspect_ratio, near, far);
+        mat_mul(&projection, &mat_mul(&view, &model))
+    }
+}
+
+/// Right-handed view matrix with the y axis pointing up.
+fn look_at_origin(eye: [f32; 3]) -> [[f32; 4]; 4] {
+    let forward = normalize([-eye[0], -eye[1], -eye[2]]);
+    let side = normalize(cross(forward, [0., 1., 0.]));
+    let up = cross(side, forward);
+    [
+        [side[0], up[0], -forward[0], 0.],
+        [side[1], up[1], -forward[1], 0.],
+        [side[2], up[2], -forward[2], 0.],
+        [-dot(side, eye), -dot(up, eye), dot(forward, eye), 1.],
+    ]
+}
+
+/// Perspective projection into clip space with depth in [0, 1] and y pointing down.
+fn perspective(fov_y: f32, aspect_ratio: f32, near: f32, far: f32) -> [[f32; 4]; 4] {
+    let focal = 1. / (fov_y / 2.).tan();
+    [
+        [focal / aspect_ratio, 0., 0., 0.],
+        [0., -focal, 0., 0.],
+        [0., 0., far / (near - far), -1.],
+        [0., 0., near * far / (near - far), 0.],
+    ]
+}
+
+/// Product of two column-major matrices.
+fn mat_mul(lhs: &[[f32; 4]; 4], rhs: &[[f32; 4]; 4]) -> [[f32; 4]; 4] {
+    let mut res = [[0.; 4]; 4];
+    for col in 0..4 {
+        for row in 0..4 {
+            res[col][row] = (0..4).map(|k| lhs[k][row] * rhs[col][k]).sum();
+        }
+    }
+    res
+}
+
+fn cross(lhs: [f32; 3], rhs: [f32; 3]) -> [f32; 3] {
+    [
+        lhs[1] * rhs[2] - lhs[2] * rhs[1],
+        lhs[2] * rhs[0] - lhs[0] * rhs[2],
+        lhs[0] * rhs[1] - lhs[1] * rhs[0],
+    ]
+}
+
+fn dot(lhs: [f32; 3], rhs: [f32; 3]) -> f32 {
+    lhs[0] * rhs[0] + lhs[1] * rhs[1] + lhs[2] * rhs[2]
+}
+
+fn normalize(vec: [f32; 3]) -> [f32; 3] {
+    let len = dot(vec, vec).sqrt();
+    [vec[0] / len, vec[1] / len, vec[2] / len]
+}
+
+/// Leave gaps between cubes of sparse boards so single cells are easy to tell apart.
+fn cell_scale_for_count(count: usize) -> f32 {
+    if count < 4096 {
+        0.85
+    } else {
+        0.95
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct CubePushConstants {
+    view_projection: [[f32; 4]; 4],
+    cell_scale: f32,
+}
+
+/// Per-instance vertex data, matches the inputs of `cube.vert`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct CubeInstance {
+    position: [f32; 3],
+    color: [f32; 4],
+}
+
+fn find_memory_type(
+    memory_types: &[MemoryType],
+    type_mask: u32,
+    properties: Properties,
+) -> MemoryTypeId {
+    memory_types
+        .iter()
+        .enumerate()
+        .position(|(id, memory_type)| {
+            type_mask & (1 << id) != 0 && memory_type.properties.contains(properties)
+        })
+        .map(MemoryTypeId)
+        .expect("No suitable memory type found")
+}
+
+struct InstanceBuffer<B: gfx_hal::Backend> {
+    buffer: B::Buffer,
+    memory: B::Memory,
+    capacity: usize,
+}
+
+impl<B: gfx_hal::Backend> InstanceBuffer<B> {
+    unsafe fn new(device: &B::Device, memory_types: &[MemoryType], capacity: usize) -> Self {
+        let size = (capacity * std::mem::size_of::<CubeInstance>()) as u64;
+        let mut buffer = device
+            .create_buffer(size, buffer::Usage::VERTEX)
+            .expect("Failed to create instance buffer");
+        let requirements = device.get_buffer_requirements(&buffer);
+        let memory_type = find_memory_type(
+            memory_types,
+            requirements.type_mask,
+            Properties::CPU_VISIBLE | Properties::COHERENT,
+        );
+        let memory = device
+            .allocate_memory(memory_type, requirements.size)
+            .expect("Out of memory");
+        device
+            .bind_buffer_memory(&memory, 0, &mut buffer)
+            .expect("Failed to bind instance buffer memory");
+        Self {
+            buffer,
+            memory,
+            capacity,
+        }
+    }
+
+    unsafe fn write(&mut self, device: &B::Device, instances: &[CubeInstance]) {
+        if instances.is_empty() {
+            return;
+        }
+        let mapped = device
+            .map_memory(&mut self.memory, Segment::ALL)
+            .expect("Failed to map instance buffer");
+        std::ptr::copy_nonoverlapping(
+            instances.as_ptr() as *const u8,
+            mapped,
+            std::mem::size_of_val(instances),
+        );
+        device.unmap_memory(&mut self.memory);
+    }
+
+    unsafe fn destroy(self, device: &B::Device) {
+        device.destroy_buffer(self.buffer);
+        device.free_memory(self.memory);
+    }
+}
+
+struct DepthImage<B: gfx_hal::Backend> {
+    image: B::Image,
+    memory: B::Memory,
+    view: B::ImageView,
+}
+
+impl<B: gfx_hal::Backend> DepthImage<B> {
+    unsafe fn new(device: &B::Device, memory_types: &[MemoryType], extent: &Extent2D) -> Self {
+        let mut image = device
+            .create_image(
+                Kind::D2(extent.width, extent.height, 1, 1),
+                1,
+                DEPTH_FORMAT,
+                Tiling::Optimal,
+                image::Usage::DEPTH_STENCIL_ATTACHMENT,
+                ViewCapabilities::empty(),
+            )
+            .expect("Failed to create depth image");
+        let requirements = device.get_image_requirements(&image);
+        let memory_type = find_memory_type(
+            memory_types,
+            requirements.type_mask,
+            Properties::DEVICE_LOCAL,
+        );
+        let memory = device
+            .allocate_memory(memory_type, requirements.size)
+            .expect("Out of memory");
+        device
+            .bind_image_memory(&memory, 0, &mut image)
+            .expect("Failed to bind depth image memory");
+        let view = device
+            .create_image_view(
+                &image,
+                ViewKind::D2,
+                DEPTH_FORMAT,
+                Swizzle::NO,
+                SubresourceRange {
+                    aspects: Aspects::DEPTH,
+                    ..Default::default()
+                },
+            )
+            .expect("Failed to create depth image view");
+        Self {
+            image,
+            memory,
+            view,
+        }
+    }
+
+    unsafe fn destroy(self, device: &B::Device) {
+        device.destroy_image_view(self.view);
+        device.destroy_image(self.image);
+        device.free_memory(self.memory);
+    }
+}
+
+/// Pipeline drawing one depth tested cube per instance.
+unsafe fn make_cube_pipeline<B: gfx_hal::Backend>(
+    device: &B::Device,
+    render_pass: &B::RenderPass,
+    pipeline_layout: &B::PipelineLayout,
+    vertex_shader: &str,
+    fragment_shader: &str,
+) -> B::GraphicsPipeline {
+    let vertex_shader_module = device
+        .create_shader_module(&compile_shader(vertex_shader, ShaderKind::Vertex))
+        .expect("Failed to create vertex shader module");
+
+    let fragment_shader_module = device
+        .create_shader_module(&compile_shader(fragment_shader, ShaderKind::Fragment))
+        .expect("Failed to create fragment shader module");
+
+    let (vs_entry, fs_entry) = (
+        EntryPoint {
+            entry: "main",
+            module: &vertex_shader_module,
+            specialization: Specialization::default(),
+        },
+        EntryPoint {
+            entry: "main",
+            module: &fragment_shader_module,
+            specialization: Specialization::default(),
+        },
+    );
+    let primitive_assembler = PrimitiveAssemblerDesc::Vertex {
+        buffers: &[VertexBufferDesc {
+            binding: 0,
+            stride: std::mem::size_of::<CubeInstance>() as u32,
+            rate: VertexInputRate::Instance(1),
+        }],
+        attributes: &[
+            AttributeDesc {
+                location: 0,
+                binding: 0,
+                element: Element {
+                    format: Format::Rgb32Sfloat,
+                    offset: 0,
+                },
+            },
+            AttributeDesc {
+                location: 1,
+                binding: 0,
+                element: Element {
+                    format: Format::Rgba32Sfloat,
+                    offset: std::mem::size_of::<[f32; 3]>() as u32,
+                },
+            },
+        ],
+        input_assembler: InputAssemblerDesc::new(Primitive::TriangleList),
+        vertex: vs_entry,
+        tessellation: None,
+        geometry: None,
+    };
+    let mut pipeline_desc = GraphicsPipelineDesc::new(
+        primitive_assembler,
+        Rasterizer::FILL,
+        Some(fs_entry),
+        pipeline_layout,
+        Subpass {
+            index: 0,
+            main_pass: render_pass,
+        },
+    );
+
+    pipeline_desc.depth_stencil = DepthStencilDesc {
+        depth: Some(DepthTest {
+            fun: Comparison::LessEqual,
+            write: true,
+        }),
+        depth_bounds: false,
+        stencil: None,
+    };
+    pipeline_desc.blender.targets.push(ColorBlendDesc {
+        mask: ColorMask::ALL,
+        blend: Some(BlendState::ALPHA),
+    });
+    let pipeline = device
+        .create_graphics_pipeline(&pipeline_desc, None)
+        .expect("Failed to create graphics pipeline");
+
+    device.destroy_shader_module(vertex_shader_module);
+    device.destroy_shader_module(fragment_shader_module);
+
+    pipeline
+}
+
+struct Resources<B: gfx_hal::Backend> {
+    instance: B::Instance,
+    surface: B::Surface,
+    device: B::Device,
+    render_pass: B::RenderPass,
+    pipeline_layout: B::PipelineLayout,
+    pipeline: B::GraphicsPipeline,
+    command_pool: B::CommandPool,
+    submission_complete_fence: B::Fence,
+    rendering_complete_semaphore: B::Semaphore,
+    depth_image: Option<DepthImage<B>>,
+    instance_buffer: Option<InstanceBuffer<B>>,
+}
+
+struct ResourceHolder<B: gfx_hal::Backend>(ManuallyDrop<Resources<B>>);
+
+impl<B: gfx_hal::Backend> Drop for ResourceHolder<B> {
+    fn drop(&mut self) {
+        unsafe {
+            let Resources {
+                instance,
+                mut surface,
+                device,
+                command_pool,
+                render_pass,
+                pipeline_layout,
+                pipeline,
+                submission_complete_fence,
+                rendering_complete_semaphore,
+                depth_image,
+                instance_buffer,
+            } = ManuallyDrop::take(&mut self.0);
+
+            if let Some(depth_image) = depth_image {
+                depth_image.destroy(&device);
+            }
+            if let Some(instance_buffer) = instance_buffer {
+                instance_buffer.destroy(&device);
+            }
+            device.destroy_semaphore(rendering_complete_semaphore);
+            device.destroy_fence(submission_complete_fence);
+            device.destroy_graphics_pipeline(pipeline);
+            device.destroy_pipeline_layout(pipeline_layout);
+            device.destroy_render_pass(render_pass);
+            device.destroy_command_pool(command_pool);
+            surface.unconfigure_swapchain(&device);
+            instance.destroy_surface(surface);
+        }
+    }
+}
+
+#[cfg(test)]
+mod grid_3d_test {
+    use super::{mat_mul, OrbitCamera};
+    use gol_core::util::grid_util::Shape3D;
+
+    fn transform(mat: &[[f32; 4]; 4], point: [f32; 3]) -> [f32; 4] {
+        let mut res = [0.; 4];
+        for row in 0..4 {
+            res[row] = mat[0][row] * point[0]
+                + mat[1][row] * point[1]
+                + mat[2][row] * point[2]
+                + mat[3][row];
+        }
+        res
+    }
+
+    #[test]
+    fn orbit_camera_test_center_in_view() {
+        let camera = OrbitCamera::new().rotated(1.0, -0.3);
+        let shape = Shape3D::new(24, 24, 24);
+        let clip = transform(&camera.view_projection(&shape, 1.5), [-0.5, -0.5, -0.5]);
+        let (x, y, z) = (clip[0] / clip[3], clip[1] / clip[3], clip[2] / clip[3]);
+        assert!(x.abs() < 1e-4 && y.abs() < 1e-4);
+        assert!(z > 0. && z < 1.);
+    }
+
+    #[test]
+    fn orbit_camera_test_pitch_clamped() {
+        let camera = OrbitCamera::new().rotated(0., 10.);
+        assert!(camera.pitch < std::f32::consts::PI / 2.);
+        let eye = camera.eye();
+        assert!(eye[1] > 0.);
+    }
+
+    #[test]
+    fn mat_mul_test_identity() {
+        let identity = [
+            [1., 0., 0., 0.],
+            [0., 1., 0., 0.],
+            [0., 0., 1., 0.],
+            [0., 0., 0., 1.],
+        ];
+        let mat = [
+            [1., 2., 3., 4.],
+            [5., 6., 7., 8.],
+            [9., 10., 11., 12.],
+            [13., 14., 15., 16.],
+        ];
+        assert_eq!(mat_mul(&identity, &mat), mat);
+        assert_eq!(mat_mul(&mat, &identity), mat);
+    }
+}
@@ -1 +1,4 @@
+pub mod graph;
 pub mod grid_2d;
+pub mod grid_3d;
+pub mod tiling;
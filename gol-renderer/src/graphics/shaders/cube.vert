@@ -0,0 +1,51 @@
+#version 450
+#extension GL_ARB_separate_shader_objects : enable
+
+layout(push_constant) uniform PushConstants {
+    mat4 view_projection;
+    float cell_scale;
+} push_constants;
+
+layout(location = 0) in vec3 instance_position;
+layout(location = 1) in vec4 instance_color;
+
+layout(location = 0) out vec4 vertex_color;
+
+vec3 corners[8] = vec3[](
+    vec3(-0.5, -0.5, -0.5),
+    vec3(0.5, -0.5, -0.5),
+    vec3(0.5, 0.5, -0.5),
+    vec3(-0.5, 0.5, -0.5),
+    vec3(-0.5, -0.5, 0.5),
+    vec3(0.5, -0.5, 0.5),
+    vec3(0.5, 0.5, 0.5),
+    vec3(-0.5, 0.5, 0.5)
+);
+
+// Two triangles per face, faces ordered as -z, +z, -x, +x, -y, +y.
+int corner_indices[36] = int[](
+    0, 1, 2, 2, 3, 0,
+    4, 5, 6, 6, 7, 4,
+    0, 3, 7, 7, 4, 0,
+    1, 2, 6, 6, 5, 1,
+    0, 1, 5, 5, 4, 0,
+    3, 2, 6, 6, 7, 3
+);
+
+vec3 normals[6] = vec3[](
+    vec3(0.0, 0.0, -1.0),
+    vec3(0.0, 0.0, 1.0),
+    vec3(-1.0, 0.0, 0.0),
+    vec3(1.0, 0.0, 0.0),
+    vec3(0.0, -1.0, 0.0),
+    vec3(0.0, 1.0, 0.0)
+);
+
+void main() {
+    vec3 corner = corners[corner_indices[gl_VertexIndex]];
+    vec3 normal = normals[gl_VertexIndex / 6];
+    float shade = 0.35 + 0.65 * max(dot(normal, normalize(vec3(0.4, 1.0, 0.6))), 0.0);
+    vertex_color = vec4(instance_color.rgb * shade, instance_color.a);
+    vec3 pos = instance_position + corner * push_constants.cell_scale;
+    gl_Position = push_constants.view_projection * vec4(pos, 1.0);
+}
@@ -0,0 +1,207 @@
+use super::grid_2d::{run_shapes_window, ColorRGBA, PushConstants};
+use crate::{
+    renderer::{board_info::RendererBoardInfo, keyboard_control::KeyboardControl},
+    CellularAutomatonRenderer, StateVisualMapping,
+};
+use gfx_hal::{window::Extent2D, UnsupportedBackend};
+use gol_core::{StatesReadOnly, Tiling};
+use rgb::RGBA16;
+
+const TILE_COLOR: [f32; 4] = [0.15, 0.15, 0.15, 1.0];
+/// Tiles are shrunk towards their center by this ratio to leave a gap between them.
+const TILE_SCALE: f32 = 0.9;
+/// Second and third vertices of the unit triangle in `triangle.vert`, relative to the first.
+const UNIT_TRIANGLE_EDGES: [(f32, f32); 2] = [(1.0, 0.0), (0.5, -0.8660254)];
+
+/// Draws every tile of a tiling board as a convex polygon colored by state.
+///
+/// Drag with the mouse to pan, scroll to zoom and double click to reset the view.
+pub struct GraphicalRendererTiling<T> {
+    info: RendererBoardInfo<usize>,
+    tiling: Tiling,
+    control: Option<KeyboardControl>,
+    states_read_only: StatesReadOnly<usize, T>,
+}
+
+impl<T> GraphicalRendererTiling<T>
+where
+    T: 'static + Send + Sync + Clone,
+{
+    pub fn new(
+        tiling: Tiling,
+        states_storage: StatesReadOnly<usize, T>,
+    ) -> Result<Self, UnsupportedBackend> {
+        let info = RendererBoardInfo::new(tiling.tile_count());
+        Ok(Self {
+            info,
+            tiling,
+            control: None,
+            states_read_only: states_storage,
+        })
+    }
+
+    pub fn with_title(self, title: String) -> Self {
+        let mut res = self;
+        res.info.set_title(title);
+        res
+    }
+
+    pub fn with_keyboard_control(self, control: KeyboardControl) -> Self {
+        let mut res = self;
+        res.control = Some(control);
+        res
+    }
+}
+
+impl<T> CellularAutomatonRenderer<T, RGBA16> for GraphicalRendererTiling<T>
+where
+    T: 'static + Send + Sync + Clone,
+{
+    fn need_run_on_main(&self) -> bool {
+        true
+    }
+
+    fn run(&mut self, visual_mapping: Box<dyn StateVisualMapping<T, RGBA16>>) {
+        let polygons = fitted_polygons(&self.tiling);
+        let states_read_only = self.states_read_only.clone();
+
+        let gen_shapes = move |surface_extent: &Extent2D, zoom: f32, dx: f32, dy: f32| {
+            let (width, height) = (surface_extent.width as f32, surface_extent.height as f32);
+            let min_len = width.min(height);
+            let (scale_x, scale_y) = (min_len / width * zoom, min_len / height * zoom);
+            let to_screen = |(x, y): (f32, f32)| (x * scale_x + dx, -y * scale_y + dy);
+
+            let lookup = loop {
+                if let Ok(val) = states_read_only.try_read() {
+                    break val.1.clone();
+                }
+            };
+            let mut res = Vec::new();
+            for (tile, polygon) in polygons.iter().enumerate() {
+                let color = match lookup.get(&tile) {
+                    Some(state) => {
+                        ColorRGBA::from_rgba16(&visual_mapping.to_visual(state)).to_array()
+                    }
+                    None => TILE_COLOR,
+                };
+                let points: Vec<(f32, f32)> = polygon.iter().cloned().map(to_screen).collect();
+                for i in 1..points.len() - 1 {
+                    res.push(PushConstants {
+                        color,
+                        transform: triangle_transform(points[0], points[i], points[i + 1]),
+                    });
+                }
+            }
+            res
+        };
+
+        run_shapes_window(
+            self.info.title().clone(),
+            self.control.clone(),
+            true,
+            |_| None,
+            gen_shapes,
+        );
+    }
+}
+
+/// Tiles scaled to fit in [-0.9, 0.9] and shrunk towards their centers.
+fn fitted_polygons(tiling: &Tiling) -> Vec<Vec<(f32, f32)>> {
+    let points = (0..tiling.tile_count()).flat_map(|tile| tiling.tile(tile).iter());
+    let (mut x_min, mut x_max, mut y_min, mut y_max) = (f64::MAX, f64::MIN, f64::MAX, f64::MIN);
+    for (x, y) in points {
+        x_min = x_min.min(*x);
+        x_max = x_max.max(*x);
+        y_min = y_min.min(*y);
+        y_max = y_max.max(*y);
+    }
+    let half_len = ((x_max - x_min).max(y_max - y_min) / 2.).max(f64::EPSILON) / 0.9;
+    let (x_center, y_center) = ((x_min + x_max) / 2., (y_min + y_max) / 2.);
+    (0..tiling.tile_count())
+        .map(|tile| {
+            let polygon = tiling.tile(tile);
+            let len = polygon.len() as f64;
+            let (cx, cy) = polygon
+                .iter()
+                .fold((0., 0.), |(sx, sy), (x, y)| (sx + x / len, sy + y / len));
+            polygon
+                .iter()
+                .map(|(x, y)| {
+                    let (x, y) = (
+                        cx + (x - cx) * TILE_SCALE as f64,
+                        cy + (y - cy) * TILE_SCALE as f64,
+                    );
+                    (
+                        ((x - x_center) / half_len) as f32,
+                        ((y - y_center) / half_len) as f32,
+                    )
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Affine transform taking the triangle of `triangle.vert` to the given corners. Corners are
+/// swapped if needed so the winding matches the unit triangle and back-face culling keeps it.
+fn triangle_transform(first: (f32, f32), second: (f32, f32), third: (f32, f32)) -> [[f32; 4]; 4] {
+    let cross =
+        (second.0 - first.0) * (third.1 - first.1) - (second.1 - first.1) * (third.0 - first.0);
+    let (second, third) = if cross > 0. {
+        (third, second)
+    } else {
+        (second, third)
+    };
+    let [_, (half, height)] = UNIT_TRIANGLE_EDGES;
+    let col_x = (second.0 - first.0, second.1 - first.1);
+    let to_third = (third.0 - first.0, third.1 - first.1);
+    let col_y = (
+        (half * col_x.0 - to_third.0) / -height,
+        (half * col_x.1 - to_third.1) / -height,
+    );
+    // The first corner of the unit triangle is (0, 1).
+    [
+        [col_x.0, col_x.1, 0., 0.],
+        [col_y.0, col_y.1, 0., 0.],
+        [0., 0., 1., 0.],
+        [first.0 - col_y.0, first.1 - col_y.1, 0., 1.],
+    ]
+}
+
+#[cfg(test)]
+mod tiling_renderer_test {
+    use crate::graphics::tiling::{triangle_transform, UNIT_TRIANGLE_EDGES};
+
+    fn apply(transform: &[[f32; 4]; 4], (x, y): (f32, f32)) -> (f32, f32) {
+        (
+            transform[0][0] * x + transform[1][0] * y + transform[3][0],
+            transform[0][1] * x + transform[1][1] * y + transform[3][1],
+        )
+    }
+
+    fn close(a: (f32, f32), b: (f32, f32)) -> bool {
+        (a.0 - b.0).abs() < 1e-5 && (a.1 - b.1).abs() < 1e-5
+    }
+
+    #[test]
+    fn triangle_transform_test_corners_and_winding() {
+        let unit = [
+            (0., 1.),
+            (1., 1.),
+            (UNIT_TRIANGLE_EDGES[1].0, 1. + UNIT_TRIANGLE_EDGES[1].1),
+        ];
+        for corners in vec![
+            [(0.1, 0.2), (0.5, -0.3), (-0.4, 0.6)],
+            [(0.1, 0.2), (-0.4, 0.6), (0.5, -0.3)],
+        ] {
+            let transform = triangle_transform(corners[0], corners[1], corners[2]);
+            let mapped: Vec<(f32, f32)> =
+                unit.iter().map(|point| apply(&transform, *point)).collect();
+            assert!(close(mapped[0], corners[0]));
+            assert!(corners
+                .iter()
+                .all(|corner| mapped.iter().any(|point| close(*point, *corner))));
+            let det = transform[0][0] * transform[1][1] - transform[0][1] * transform[1][0];
+            assert!(det > 0.);
+        }
+    }
+}
@@ -5,10 +5,18 @@ pub mod graphics;
 #[cfg(feature = "ascii")]
 pub mod text;
 
+pub use graphics::graph::GraphicalRendererGraph;
 pub use graphics::grid_2d::GraphicalRendererGrid2D;
+pub use graphics::grid_3d::GraphicalRendererGrid3D;
+pub use graphics::tiling::GraphicalRendererTiling;
 #[cfg(feature = "ascii")]
 pub use text::grid_2d::TextRendererGrid2D;
 
+pub use renderer::graph_layout::force_directed_layout;
 pub use renderer::renderer::CellularAutomatonRenderer;
+pub use renderer::slice_2d::{RenderIndex2D, SliceIndex, SliceView};
 
-pub use state_visual::mapping::{DiscreteStateCharMap, DiscreteStateColorMap, StateVisualMapping};
+pub use state_visual::mapping::{
+    DiscreteStateCharMap, DiscreteStateColorMap, GrayScottCharMap, GrayScottColorMap,
+    LatticeGasCharMap, LatticeGasColorMap, SandpileCharMap, SandpileColorMap, StateVisualMapping,
+};
@@ -18,6 +18,10 @@ impl<T> RendererBoardInfo<T> {
         &self.board_shape
     }
 
+    pub fn set_board_shape(&mut self, board_shape: T) {
+        self.board_shape = board_shape;
+    }
+
     pub fn title(&self) -> &String {
         &self.title
     }
@@ -0,0 +1,109 @@
+use gol_core::Graph;
+use rayon::prelude::*;
+
+/// Fruchterman-Reingold force-directed layout, returns node positions fitted into [-1, 1] on
+/// both axes. Nodes start on a spiral so the result only depends on the graph.
+pub fn force_directed_layout(graph: &Graph, iterations: usize) -> Vec<(f32, f32)> {
+    let node_count = graph.node_count();
+    if node_count == 0 {
+        return Vec::new();
+    }
+    // Golden angle spiral, spreads nodes evenly over the unit disk.
+    let golden_angle = std::f32::consts::PI * (3. - 5f32.sqrt());
+    let mut positions: Vec<(f32, f32)> = (0..node_count)
+        .map(|node| {
+            let radius = ((node as f32 + 0.5) / node_count as f32).sqrt();
+            let angle = node as f32 * golden_angle;
+            (radius * angle.cos(), radius * angle.sin())
+        })
+        .collect();
+
+    // Ideal edge length for nodes spread over an area of 4.
+    let k = (4. / node_count as f32).sqrt();
+    let min_dist = k / 100.;
+    for iter in 0..iterations {
+        let temperature = 0.1 * (1. - iter as f32 / iterations as f32);
+        let prev = &positions;
+        positions = (0..node_count)
+            .into_par_iter()
+            .map(|node| {
+                let (x, y) = prev[node];
+                let (mut dx, mut dy) = (0., 0.);
+                for (other, (ox, oy)) in prev.iter().enumerate() {
+                    if other == node {
+                        continue;
+                    }
+                    let (diff_x, diff_y) = (x - ox, y - oy);
+                    let dist = (diff_x * diff_x + diff_y * diff_y).sqrt().max(min_dist);
+                    let repulsion = k * k / dist;
+                    dx += diff_x / dist * repulsion;
+                    dy += diff_y / dist * repulsion;
+                }
+                for other in graph.neighbors(node) {
+                    let (ox, oy) = prev[*other];
+                    let (diff_x, diff_y) = (x - ox, y - oy);
+                    let dist = (diff_x * diff_x + diff_y * diff_y).sqrt().max(min_dist);
+                    let attraction = dist * dist / k;
+                    dx -= diff_x / dist * attraction;
+                    dy -= diff_y / dist * attraction;
+                }
+                let len = (dx * dx + dy * dy).sqrt().max(min_dist);
+                let step = len.min(temperature);
+                (x + dx / len * step, y + dy / len * step)
+            })
+            .collect();
+    }
+    fit_to_square(positions)
+}
+
+fn fit_to_square(positions: Vec<(f32, f32)>) -> Vec<(f32, f32)> {
+    let (mut x_min, mut x_max, mut y_min, mut y_max) = (f32::MAX, f32::MIN, f32::MAX, f32::MIN);
+    for (x, y) in positions.iter() {
+        x_min = x_min.min(*x);
+        x_max = x_max.max(*x);
+        y_min = y_min.min(*y);
+        y_max = y_max.max(*y);
+    }
+    let half_len = ((x_max - x_min).max(y_max - y_min) / 2.).max(f32::EPSILON);
+    let (x_center, y_center) = ((x_min + x_max) / 2., (y_min + y_max) / 2.);
+    positions
+        .into_iter()
+        .map(|(x, y)| ((x - x_center) / half_len, (y - y_center) / half_len))
+        .collect()
+}
+
+#[cfg(test)]
+mod graph_layout_test {
+    use crate::renderer::graph_layout::force_directed_layout;
+    use gol_core::Graph;
+
+    fn dist(a: (f32, f32), b: (f32, f32)) -> f32 {
+        ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+    }
+
+    #[test]
+    fn graph_layout_test_bounds_and_determinism() {
+        let graph = Graph::watts_strogatz(50, 4, 0.1, 3);
+        let positions = force_directed_layout(&graph, 50);
+        assert_eq!(positions.len(), 50);
+        assert!(positions
+            .iter()
+            .all(|(x, y)| x.abs() <= 1.0001 && y.abs() <= 1.0001));
+        assert!(positions
+            .iter()
+            .any(|(x, y)| x.abs() > 0.999 || y.abs() > 0.999));
+        assert_eq!(positions, force_directed_layout(&graph, 50));
+    }
+
+    #[test]
+    fn graph_layout_test_clusters() {
+        // Two triangles joined by a single edge, linked nodes should end up closer.
+        let graph = Graph::from_edges(
+            6,
+            vec![(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)],
+        );
+        let positions = force_directed_layout(&graph, 100);
+        assert!(dist(positions[0], positions[1]) < dist(positions[0], positions[5]));
+        assert!(dist(positions[4], positions[5]) < dist(positions[1], positions[4]));
+    }
+}
@@ -1,4 +1,6 @@
 pub mod board_info;
 pub mod fps_counter;
+pub mod graph_layout;
 pub mod keyboard_control;
 pub mod renderer;
+pub mod slice_2d;
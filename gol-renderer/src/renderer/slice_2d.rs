@@ -0,0 +1,309 @@
+use gol_core::{util::grid_util::Shape2D, GridPoint2D, GridPoint3D, GridPointND};
+use num_traits::ToPrimitive;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Indices of boards with more than two dimensions.
+pub trait SliceIndex {
+    fn dim_count(&self) -> usize;
+    fn coord(&self, axis: usize) -> i64;
+}
+
+impl<T> SliceIndex for GridPoint3D<T>
+where
+    T: ToPrimitive,
+{
+    fn dim_count(&self) -> usize {
+        3
+    }
+
+    fn coord(&self, axis: usize) -> i64 {
+        match axis {
+            0 => self.x.to_i64().unwrap(),
+            1 => self.y.to_i64().unwrap(),
+            2 => self.z.to_i64().unwrap(),
+            _ => panic!("Axis {} out of range for 3D index.", axis),
+        }
+    }
+}
+
+impl<T> SliceIndex for GridPointND<T>
+where
+    T: ToPrimitive,
+{
+    fn dim_count(&self) -> usize {
+        self.indices().len()
+    }
+
+    fn coord(&self, axis: usize) -> i64 {
+        self.indices().nth(axis).unwrap().to_i64().unwrap()
+    }
+}
+
+/// Indices 2D grid renderers can draw, either directly or through a `SliceView`.
+pub trait RenderIndex2D<T>: Hash + Eq + Sized {
+    /// Non-trivial states on the displayed plane, `view` is only used by higher-dimensional indices.
+    fn lookup_2d(
+        lookup: &HashMap<Self, T>,
+        view: Option<&SliceView>,
+    ) -> HashMap<GridPoint2D<i64>, T>;
+}
+
+impl<T, U> RenderIndex2D<T> for GridPoint2D<U>
+where
+    T: Clone,
+    U: Hash + Eq + ToPrimitive,
+{
+    fn lookup_2d(
+        lookup: &HashMap<Self, T>,
+        _view: Option<&SliceView>,
+    ) -> HashMap<GridPoint2D<i64>, T> {
+        lookup
+            .iter()
+            .map(|(idx, state)| {
+                (
+                    GridPoint2D::new(idx.x.to_i64().unwrap(), idx.y.to_i64().unwrap()),
+                    state.clone(),
+                )
+            })
+            .collect()
+    }
+}
+
+impl<T, U> RenderIndex2D<T> for GridPoint3D<U>
+where
+    T: Send + Sync + Clone + Ord,
+    U: Send + Sync + Hash + Eq + ToPrimitive,
+{
+    fn lookup_2d(
+        lookup: &HashMap<Self, T>,
+        view: Option<&SliceView>,
+    ) -> HashMap<GridPoint2D<i64>, T> {
+        view.expect("3D boards need a slice view to be rendered in 2D.")
+            .slice(lookup)
+    }
+}
+
+impl<T, U> RenderIndex2D<T> for GridPointND<U>
+where
+    T: Send + Sync + Clone + Ord,
+    U: Send + Sync + Hash + Eq + ToPrimitive,
+{
+    fn lookup_2d(
+        lookup: &HashMap<Self, T>,
+        view: Option<&SliceView>,
+    ) -> HashMap<GridPoint2D<i64>, T> {
+        view.expect("N-dimensional boards need a slice view to be rendered in 2D.")
+            .slice(lookup)
+    }
+}
+
+/// The 2D plane of a higher-dimensional grid shown by 2D renderers.
+///
+/// Keys: `x`/`y` cycle the horizontal/vertical axis, `z` picks the hidden axis to move along,
+/// `[`/`]` move the slice along it and `p` toggles max projection over it.
+#[derive(Clone, Debug)]
+pub struct SliceView {
+    shape: Vec<usize>,
+    x_axis: usize,
+    y_axis: usize,
+    hidden_axis: usize,
+    positions: Vec<i64>,
+    is_max_projection: bool,
+}
+
+impl SliceView {
+    /// Starts at the slice through the origin spanned by the first two axes.
+    pub fn new(shape: Vec<usize>) -> Self {
+        assert!(
+            shape.len() > 2,
+            "Slice views need a board with more than two dimensions."
+        );
+        let positions = vec![0; shape.len()];
+        Self {
+            shape,
+            x_axis: 0,
+            y_axis: 1,
+            hidden_axis: 2,
+            positions,
+            is_max_projection: false,
+        }
+    }
+
+    /// Square large enough to show any pair of axes.
+    pub fn display_shape(&self) -> Shape2D {
+        let max_len = *self.shape.iter().max().unwrap();
+        Shape2D::new(max_len, max_len)
+    }
+
+    pub fn is_max_projection(&self) -> bool {
+        self.is_max_projection
+    }
+
+    /// Applies a slice key, returns true if the view changed.
+    pub fn execute_user_input(&mut self, ch: char) -> bool {
+        match ch {
+            'x' => {
+                self.x_axis = self.next_axis(self.x_axis, &[self.y_axis]);
+                self.fix_hidden_axis();
+            }
+            'y' => {
+                self.y_axis = self.next_axis(self.y_axis, &[self.x_axis]);
+                self.fix_hidden_axis();
+            }
+            'z' => self.hidden_axis = self.next_axis(self.hidden_axis, &[self.x_axis, self.y_axis]),
+            '[' => return self.move_slice(-1),
+            ']' => return self.move_slice(1),
+            'p' => self.is_max_projection = !self.is_max_projection,
+            _ => return false,
+        }
+        true
+    }
+
+    /// Where `idx` is drawn, `None` if it is not on the displayed plane.
+    pub fn project<CI>(&self, idx: &CI) -> Option<GridPoint2D<i64>>
+    where
+        CI: SliceIndex,
+    {
+        for axis in 0..idx.dim_count() {
+            if axis == self.x_axis
+                || axis == self.y_axis
+                || (self.is_max_projection && axis == self.hidden_axis)
+            {
+                continue;
+            }
+            if idx.coord(axis) != self.positions[axis] {
+                return None;
+            }
+        }
+        Some(GridPoint2D::new(
+            idx.coord(self.x_axis),
+            idx.coord(self.y_axis),
+        ))
+    }
+
+    /// States on the displayed plane, the maximum state along the hidden axis in max projection.
+    pub fn slice<CI, T>(&self, lookup: &HashMap<CI, T>) -> HashMap<GridPoint2D<i64>, T>
+    where
+        CI: Send + Sync + SliceIndex,
+        T: Send + Sync + Clone + Ord,
+    {
+        let projected: Vec<(GridPoint2D<i64>, T)> = lookup
+            .par_iter()
+            .filter_map(|(idx, state)| self.project(idx).map(|point| (point, state.clone())))
+            .collect();
+        let mut res: HashMap<GridPoint2D<i64>, T> = HashMap::new();
+        for (point, state) in projected {
+            let cur = res.entry(point).or_insert_with(|| state.clone());
+            if state > *cur {
+                *cur = state;
+            }
+        }
+        res
+    }
+
+    pub fn description(&self) -> String {
+        let hidden: Vec<String> = (0..self.shape.len())
+            .filter(|axis| *axis != self.x_axis && *axis != self.y_axis)
+            .map(|axis| {
+                let marker = if axis == self.hidden_axis { "*" } else { "" };
+                if self.is_max_projection && axis == self.hidden_axis {
+                    format!("{}{}=max", axis, marker)
+                } else {
+                    format!("{}{}={}", axis, marker, self.positions[axis])
+                }
+            })
+            .collect();
+        format!(
+            "Axes x={} y={}, hidden {}",
+            self.x_axis,
+            self.y_axis,
+            hidden.join(" ")
+        )
+    }
+
+    fn next_axis(&self, axis: usize, excluded: &[usize]) -> usize {
+        let mut res = axis;
+        loop {
+            res = (res + 1) % self.shape.len();
+            if !excluded.contains(&res) {
+                return res;
+            }
+        }
+    }
+
+    fn fix_hidden_axis(&mut self) {
+        if self.hidden_axis == self.x_axis || self.hidden_axis == self.y_axis {
+            self.hidden_axis = self.next_axis(self.hidden_axis, &[self.x_axis, self.y_axis]);
+        }
+    }
+
+    fn move_slice(&mut self, step: i64) -> bool {
+        let len = self.shape[self.hidden_axis] as i64;
+        let (idx_min, idx_max) = (-(len / 2), len - len / 2 - 1);
+        let cur = self.positions[self.hidden_axis];
+        let new_pos = (cur + step).max(idx_min).min(idx_max);
+        self.positions[self.hidden_axis] = new_pos;
+        new_pos != cur
+    }
+}
+
+#[cfg(test)]
+mod slice_2d_test {
+    use crate::renderer::slice_2d::SliceView;
+    use gol_core::{GridPoint2D, GridPoint3D, GridPointND};
+    use std::collections::HashMap;
+
+    #[test]
+    fn slice_view_test_3d_default() {
+        let view = SliceView::new(vec![4, 4, 4]);
+        let lookup: HashMap<GridPoint3D<i32>, u8> = vec![
+            (GridPoint3D::new(1, -1, 0), 1),
+            (GridPoint3D::new(1, -1, 1), 2),
+        ]
+        .into_iter()
+        .collect();
+        let res = view.slice(&lookup);
+        assert_eq!(res.len(), 1);
+        assert_eq!(res.get(&GridPoint2D::new(1, -1)), Some(&1));
+    }
+
+    #[test]
+    fn slice_view_test_3d_move_and_switch() {
+        let mut view = SliceView::new(vec![4, 6, 4]);
+        assert!(view.execute_user_input(']'));
+        assert!(!view.execute_user_input(']'));
+        assert_eq!(view.project(&GridPoint3D::new(0, 0, 0)), None);
+        assert_eq!(
+            view.project(&GridPoint3D::new(-2, 2, 1)),
+            Some(GridPoint2D::new(-2, 2))
+        );
+
+        // Show x and z, y becomes the hidden axis.
+        assert!(view.execute_user_input('y'));
+        assert_eq!(
+            view.project(&GridPoint3D::new(-2, 0, 1)),
+            Some(GridPoint2D::new(-2, 1))
+        );
+        assert_eq!(view.project(&GridPoint3D::new(-2, 2, 1)), None);
+        assert_eq!(view.display_shape().width(), 6);
+    }
+
+    #[test]
+    fn slice_view_test_nd_max_projection() {
+        let mut view = SliceView::new(vec![3, 3, 3, 3]);
+        assert!(view.execute_user_input('p'));
+        let lookup: HashMap<GridPointND<i32>, u8> = vec![
+            (GridPointND::new(vec![0, 0, -1, 0].iter()), 1),
+            (GridPointND::new(vec![0, 0, 1, 0].iter()), 2),
+            (GridPointND::new(vec![1, 0, 1, 1].iter()), 2),
+        ]
+        .into_iter()
+        .collect();
+        let res = view.slice(&lookup);
+        assert_eq!(res.len(), 1);
+        assert_eq!(res.get(&GridPoint2D::new(0, 0)), Some(&2));
+        assert_eq!(view.description(), "Axes x=0 y=1, hidden 2*=max 3=0");
+    }
+}
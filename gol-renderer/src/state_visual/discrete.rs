@@ -46,7 +46,7 @@ where
     }
 }
 
-fn hsl_to_rbg(h: f64, s: f64, l: f64, a: f64) -> RGBA16 {
+pub(crate) fn hsl_to_rbg(h: f64, s: f64, l: f64, a: f64) -> RGBA16 {
     let (mut r, mut g, mut b) = (l, l, l);
     if s != 0. {
         let q = if l < 0.5 { l * (1. + s) } else { l + s - l * s };
@@ -0,0 +1,27 @@
+use super::discrete::hsl_to_rbg;
+use super::mapping::{GrayScottCharMap, GrayScottColorMap, StateVisualMapping};
+use gol_core::GrayScottState;
+use rgb::RGBA16;
+
+const CONCENTRATION_CHARS: [char; 10] = [' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'];
+
+impl StateVisualMapping<GrayScottState, RGBA16> for GrayScottColorMap {
+    fn to_visual(&self, state: &GrayScottState) -> RGBA16 {
+        let ratio = concentration(state, self.should_color_u());
+        // From dark blue at low concentration to bright yellow at high concentration.
+        hsl_to_rbg(0.66 - 0.5 * ratio, 0.8, 0.05 + 0.5 * ratio, 1.)
+    }
+}
+
+impl StateVisualMapping<GrayScottState, char> for GrayScottCharMap {
+    fn to_visual(&self, state: &GrayScottState) -> char {
+        let ratio = concentration(state, self.should_color_u());
+        let max_idx = CONCENTRATION_CHARS.len() - 1;
+        CONCENTRATION_CHARS[((ratio * max_idx as f64).round() as usize).min(max_idx)]
+    }
+}
+
+fn concentration(state: &GrayScottState, should_use_u: bool) -> f64 {
+    let res = if should_use_u { state.u } else { state.v };
+    res.clamp(0., 1.)
+}
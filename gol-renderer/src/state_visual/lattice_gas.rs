@@ -0,0 +1,74 @@
+use super::discrete::hsl_to_rbg;
+use super::mapping::{LatticeGasCharMap, LatticeGasColorMap, StateVisualMapping};
+use gol_core::LatticeGasModel;
+use num_traits::PrimInt;
+use rgb::RGBA16;
+
+const OBSTACLE_CHAR: char = '#';
+const EMPTY_CHAR: char = ' ';
+
+impl<T> StateVisualMapping<T, RGBA16> for LatticeGasColorMap
+where
+    T: PrimInt,
+{
+    fn to_visual(&self, state: &T) -> RGBA16 {
+        let state = state.to_u8().unwrap();
+        if LatticeGasModel::is_obstacle(state) {
+            return RGBA16 {
+                r: u16::MAX / 4 * 3,
+                g: u16::MAX / 4 * 3,
+                b: u16::MAX / 4 * 3,
+                a: u16::MAX,
+            };
+        }
+
+        let model = self.model();
+        let density = model.density(state);
+        if density == 0 {
+            return RGBA16 {
+                r: 0,
+                g: 0,
+                b: 0,
+                a: 0,
+            };
+        }
+
+        if self.should_color_momentum() {
+            let (x, y) = model.momentum(state);
+            let magnitude = (x * x + y * y).sqrt();
+            let ratio = (magnitude / self.max_momentum()).min(1.);
+            let angle = y.atan2(x) / (2. * std::f64::consts::PI);
+            let hue = if angle < 0. { angle + 1. } else { angle };
+            // Particles without net momentum (e.g. head-on pairs) still show up dimly.
+            hsl_to_rbg(hue, ratio, 0.15 + 0.35 * ratio, 0.3 + 0.7 * ratio)
+        } else {
+            let ratio = density as f64 / model.channel_count() as f64;
+            hsl_to_rbg(0.6, 0.8, 0.1 + 0.4 * ratio, ratio)
+        }
+    }
+}
+
+impl<T> StateVisualMapping<T, char> for LatticeGasCharMap
+where
+    T: PrimInt,
+{
+    fn to_visual(&self, state: &T) -> char {
+        let state = state.to_u8().unwrap();
+        if LatticeGasModel::is_obstacle(state) {
+            return OBSTACLE_CHAR;
+        }
+        match self.model().density(state) {
+            0 => EMPTY_CHAR,
+            density => std::char::from_digit(density as u32, 10).unwrap(),
+        }
+    }
+}
+
+pub(crate) fn max_momentum(model: LatticeGasModel) -> f64 {
+    (0..=model.channel_mask())
+        .map(|state| {
+            let (x, y) = model.momentum(state);
+            (x * x + y * y).sqrt()
+        })
+        .fold(0., f64::max)
+}
@@ -1,3 +1,6 @@
+use super::lattice_gas::max_momentum;
+use gol_core::LatticeGasModel;
+
 pub trait StateVisualMapping<T, U>: Send + Sync {
     fn to_visual(&self, state: &T) -> U;
 }
@@ -13,6 +16,38 @@ pub struct DiscreteStateColorMap {
     should_decay_alpha: bool,
 }
 
+#[derive(Clone)]
+pub struct LatticeGasColorMap {
+    model: LatticeGasModel,
+    should_color_momentum: bool,
+    max_momentum: f64,
+}
+
+#[derive(Clone)]
+pub struct LatticeGasCharMap {
+    model: LatticeGasModel,
+}
+
+#[derive(Clone)]
+pub struct GrayScottColorMap {
+    should_color_u: bool,
+}
+
+#[derive(Clone)]
+pub struct GrayScottCharMap {
+    should_color_u: bool,
+}
+
+#[derive(Clone)]
+pub struct SandpileColorMap {
+    threshold: usize,
+}
+
+#[derive(Clone)]
+pub struct SandpileCharMap {
+    threshold: usize,
+}
+
 impl DiscreteStateCharMap {
     pub fn new(state_count: usize) -> Self {
         Self { state_count }
@@ -45,3 +80,116 @@ impl DiscreteStateColorMap {
         self.should_decay_alpha
     }
 }
+
+impl LatticeGasColorMap {
+    /// Colors cells by particle density, brighter cells have more particles.
+    pub fn new(model: LatticeGasModel) -> Self {
+        Self {
+            model,
+            should_color_momentum: false,
+            max_momentum: max_momentum(model),
+        }
+    }
+
+    /// Colors cells by momentum instead, hue is the direction and brightness is the magnitude.
+    pub fn with_momentum(self) -> Self {
+        let mut res = self;
+        res.should_color_momentum = true;
+        res
+    }
+
+    pub fn model(&self) -> LatticeGasModel {
+        self.model
+    }
+
+    pub fn should_color_momentum(&self) -> bool {
+        self.should_color_momentum
+    }
+
+    pub fn max_momentum(&self) -> f64 {
+        self.max_momentum
+    }
+}
+
+impl LatticeGasCharMap {
+    pub fn new(model: LatticeGasModel) -> Self {
+        Self { model }
+    }
+
+    pub fn model(&self) -> LatticeGasModel {
+        self.model
+    }
+}
+
+impl GrayScottColorMap {
+    /// Colors cells by the concentration of V.
+    pub fn new() -> Self {
+        Self {
+            should_color_u: false,
+        }
+    }
+
+    /// Colors cells by the concentration of U instead.
+    pub fn with_u_concentration(self) -> Self {
+        let mut res = self;
+        res.should_color_u = true;
+        res
+    }
+
+    pub fn should_color_u(&self) -> bool {
+        self.should_color_u
+    }
+}
+
+impl Default for GrayScottColorMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GrayScottCharMap {
+    /// Shows the concentration of V.
+    pub fn new() -> Self {
+        Self {
+            should_color_u: false,
+        }
+    }
+
+    /// Shows the concentration of U instead.
+    pub fn with_u_concentration(self) -> Self {
+        let mut res = self;
+        res.should_color_u = true;
+        res
+    }
+
+    pub fn should_color_u(&self) -> bool {
+        self.should_color_u
+    }
+}
+
+impl Default for GrayScottCharMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SandpileColorMap {
+    /// Colors stable cells by grain count, cells at or above `threshold` are about to topple.
+    pub fn new(threshold: usize) -> Self {
+        Self { threshold }
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+}
+
+impl SandpileCharMap {
+    pub fn new(threshold: usize) -> Self {
+        Self { threshold }
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+}
@@ -1,2 +1,5 @@
 pub mod discrete;
+pub mod gray_scott;
+pub mod lattice_gas;
 pub mod mapping;
+pub mod sandpile;
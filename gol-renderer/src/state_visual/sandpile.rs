@@ -0,0 +1,46 @@
+use super::discrete::hsl_to_rbg;
+use super::mapping::{SandpileCharMap, SandpileColorMap, StateVisualMapping};
+use num_traits::{PrimInt, ToPrimitive, Unsigned};
+use rgb::RGBA16;
+
+const TOPPLING_CHAR: char = '#';
+const GRAIN_CHARS: [char; 4] = [' ', '.', ':', '*'];
+
+impl<T> StateVisualMapping<T, RGBA16> for SandpileColorMap
+where
+    T: PrimInt + Unsigned + ToPrimitive,
+{
+    fn to_visual(&self, state: &T) -> RGBA16 {
+        let grains = state.to_usize().unwrap_or(usize::MAX);
+        if grains == 0 {
+            RGBA16 {
+                r: 0,
+                g: 0,
+                b: 0,
+                a: 0,
+            }
+        } else if grains >= self.threshold() {
+            hsl_to_rbg(0., 0., 1., 1.)
+        } else {
+            // From blue for a single grain to red right below the threshold.
+            let ratio = (grains - 1) as f64 / (self.threshold() - 1).max(1) as f64;
+            hsl_to_rbg(0.66 * (1. - ratio), 0.9, 0.5, 1.)
+        }
+    }
+}
+
+impl<T> StateVisualMapping<T, char> for SandpileCharMap
+where
+    T: PrimInt + Unsigned + ToPrimitive,
+{
+    fn to_visual(&self, state: &T) -> char {
+        let grains = state.to_usize().unwrap_or(usize::MAX);
+        if grains >= self.threshold() {
+            TOPPLING_CHAR
+        } else {
+            let max_idx = GRAIN_CHARS.len() - 1;
+            let idx = grains * max_idx / (self.threshold() - 1).max(1);
+            GRAIN_CHARS[idx.min(max_idx)]
+        }
+    }
+}
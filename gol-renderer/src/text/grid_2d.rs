@@ -4,17 +4,16 @@ use crate::{
     renderer::{
         board_info::RendererBoardInfo, fps_counter::FPSCounter, keyboard_control::KeyboardControl,
     },
-    CellularAutomatonRenderer, StateVisualMapping,
+    CellularAutomatonRenderer, RenderIndex2D, SliceView, StateVisualMapping,
 };
-use gol_core::{util::grid_util::Shape2D, GridPoint2D, StatesReadOnly};
+use gol_core::{util::grid_util::Shape2D, StatesReadOnly};
 use ncurses::*;
-use num_traits::{FromPrimitive, ToPrimitive};
 use std::char;
-use std::hash::Hash;
 use std::io::{self, Read};
 
 const TITLE_ROW: i32 = 1;
 const GENERATION_ROW: i32 = 3;
+const SLICE_ROW: i32 = 4;
 
 pub struct TextRendererGrid2D<S> {
     info: RendererBoardInfo<Shape2D>,
@@ -22,17 +21,15 @@ pub struct TextRendererGrid2D<S> {
     fps_counter: FPSCounter,
     screen_size: Option<Shape2D>,
     states_read_only: S,
+    slice_view: Option<SliceView>,
+    is_slice_changed: bool,
 }
 
-impl<T, U> TextRendererGrid2D<StatesReadOnly<GridPoint2D<U>, T>>
+impl<T, CI> TextRendererGrid2D<StatesReadOnly<CI, T>>
 where
-    U: Clone + Hash + FromPrimitive + ToPrimitive + std::ops::Sub<Output = U>,
+    CI: RenderIndex2D<T>,
 {
-    pub fn new(
-        board_width: usize,
-        board_height: usize,
-        states: StatesReadOnly<GridPoint2D<U>, T>,
-    ) -> Self {
+    pub fn new(board_width: usize, board_height: usize, states: StatesReadOnly<CI, T>) -> Self {
         let info = RendererBoardInfo::new(Shape2D::new(board_width, board_height));
         Self {
             info,
@@ -40,9 +37,20 @@ where
             fps_counter: FPSCounter::new(240),
             screen_size: None,
             states_read_only: states,
+            slice_view: None,
+            is_slice_changed: false,
         }
     }
 
+    /// Show a 2D slice of a board with more than two dimensions, the board size passed to `new`
+    /// is replaced by the slice display size.
+    pub fn with_slice_view(self, view: SliceView) -> Self {
+        let mut res = self;
+        res.info.set_board_shape(view.display_shape());
+        res.slice_view = Some(view);
+        res
+    }
+
     pub fn with_title(self, title: String) -> Self {
         let mut res = self;
         res.info.set_title(title);
@@ -86,6 +94,10 @@ where
     fn execute_user_input(&mut self, ch: char) {
         if ch == 'q' {
             self.cleanup();
+        } else if let Some(view) = self.slice_view.as_mut() {
+            if view.execute_user_input(ch) {
+                self.is_slice_changed = true;
+            }
         }
     }
 
@@ -116,6 +128,20 @@ where
         refresh();
     }
 
+    fn print_slice(&self) {
+        if let (Some(view), Some(screen_size)) = (&self.slice_view, &self.screen_size) {
+            mv(SLICE_ROW, 0);
+            clrtoeol();
+            let slice_msg = view.description();
+            mvprintw(
+                SLICE_ROW,
+                (screen_size.width() - slice_msg.len()) as i32 / 2,
+                slice_msg.as_str(),
+            );
+            refresh();
+        }
+    }
+
     fn setup_if_not_ready(&mut self) {
         if self.screen_size.is_none() {
             initscr();
@@ -135,7 +161,11 @@ where
                 self.info.title().as_str(),
             );
 
-            let message = "SPACE: play/pause, k/j: speed up/down, q: exit.";
+            let message = if self.slice_view.is_some() {
+                "SPACE: play/pause, k/j: speed up/down, x/y/z: axes, [/]: move slice, p: max projection, q: exit."
+            } else {
+                "SPACE: play/pause, k/j: speed up/down, q: exit."
+            };
             mvprintw(
                 screen_size.height() as i32 - 2,
                 (screen_size.width() - message.len()) as i32 / 2,
@@ -143,11 +173,12 @@ where
             );
 
             refresh();
+            self.print_slice();
         }
     }
 
     fn draw(&mut self, char_map: &dyn StateVisualMapping<T, char>) {
-        let board_shape = self.info.board_shape();
+        let board_shape = self.info.board_shape().clone();
         let (win_width, win_height) = (board_shape.width(), board_shape.height());
 
         if self.screen_size.is_none() {
@@ -159,37 +190,51 @@ where
         let start_x = ((screen_size.width() - win_width) / 2) as i32;
 
         loop {
-            match self.states_read_only.try_read() {
+            let is_drawn = match self.states_read_only.try_read() {
                 Ok(val) => {
-                    if self.info.iter_count().is_none() || self.info.iter_count().unwrap() != val.0
+                    if self.is_slice_changed
+                        || self.info.iter_count().is_none()
+                        || self.info.iter_count().unwrap() != val.0
                     {
+                        let lookup = CI::lookup_2d(&val.1, self.slice_view.as_ref());
                         let win = create_win(start_y, start_x, win_height as i32, win_width as i32);
-                        for (idx, state) in val.1.iter() {
+                        for (idx, state) in lookup.iter() {
                             let x_min = board_shape.x_idx_min();
                             let y_max = board_shape.y_idx_max();
-                            let cur_x = (idx.x.clone() - U::from_i64(x_min).unwrap())
-                                .to_i32()
-                                .unwrap();
-                            let cur_y = (y_max - idx.y.to_i64().unwrap()).to_i32().unwrap();
+                            let cur_x = (idx.x - x_min) as i32;
+                            let cur_y = (y_max - idx.y) as i32;
                             let ch: char = char_map.to_visual(state);
                             mvwprintw(win, cur_y, cur_x, ch.to_string().as_str());
                         }
                         wrefresh(win);
                         self.info.set_iter_count(val.0);
-                        break;
+                        true
+                    } else {
+                        false
                     }
                 }
-                Err(_) => continue,
+                Err(_) => false,
+            };
+            if is_drawn {
+                break;
             }
+            // Slice keys need to redraw even if the board is paused.
+            self.check_user_input(false);
+            if self.screen_size.is_none() {
+                return;
+            }
+        }
+        if self.is_slice_changed {
+            self.is_slice_changed = false;
+            self.print_slice();
         }
     }
 }
 
-impl<T, U> CellularAutomatonRenderer<T, char>
-    for TextRendererGrid2D<StatesReadOnly<GridPoint2D<U>, T>>
+impl<T, CI> CellularAutomatonRenderer<T, char> for TextRendererGrid2D<StatesReadOnly<CI, T>>
 where
     T: Send + Sync,
-    U: Send + Sync + Clone + Hash + FromPrimitive + ToPrimitive + std::ops::Sub<Output = U>,
+    CI: Send + Sync + RenderIndex2D<T>,
 {
     fn need_run_on_main(&self) -> bool {
         false